@@ -315,9 +315,9 @@ impl ConnectionManager {
         if self.ip_has_permanent_connection(ip).await {
             return;
         }
-        for peer in self.p2p_adaptor.active_peers() {
-            if peer.net_address().ip() == ip {
-                self.p2p_adaptor.terminate(peer.key()).await;
+        for peer in self.p2p_adaptor.peer_infos() {
+            if peer.net_address.ip() == ip {
+                self.p2p_adaptor.terminate(peer.key).await;
             }
         }
         self.address_manager.lock().ban(ip.into());
@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use kaspa_consensus_core::api::ConsensusApi;
+use kaspa_consensusmanager::{ConsensusCtl, ConsensusManager, SessionPool, DEFAULT_SESSION_POOL_MAX_AGE};
+use std::{sync::Arc, thread::JoinHandle};
+
+struct ConsensusStub;
+
+impl ConsensusApi for ConsensusStub {}
+
+impl ConsensusCtl for ConsensusStub {
+    fn start(&self) -> Vec<JoinHandle<()>> {
+        vec![]
+    }
+
+    fn stop(&self) {}
+
+    fn make_active(&self) {}
+}
+
+/// The number of concurrent readers, emulating RPC handlers serving queries in parallel
+const READERS: usize = 64;
+
+fn consensus_manager() -> Arc<ConsensusManager> {
+    Arc::new(ConsensusManager::from_consensus(Arc::new(ConsensusStub)))
+}
+
+pub fn bench_session_acquisition(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let mut group = c.benchmark_group("session acquisition");
+
+    // Mirrors the usual pattern of resolving the current consensus from the manager on every acquisition
+    let consensus_manager = consensus_manager();
+    group.bench_function("direct", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let sessions =
+                    futures::future::join_all((0..READERS).map(|_| async { consensus_manager.consensus().session().await })).await;
+                black_box(sessions)
+            })
+        })
+    });
+
+    let pool = SessionPool::new(consensus_manager(), DEFAULT_SESSION_POOL_MAX_AGE);
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let sessions = futures::future::join_all((0..READERS).map(|_| pool.session())).await;
+                black_box(sessions)
+            })
+        })
+    });
+
+    group.finish();
+}
+
+// `cargo bench --package kaspa-consensusmanager --bench session`
+criterion_group!(benches, bench_session_acquisition);
+criterion_main!(benches);
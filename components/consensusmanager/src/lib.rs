@@ -5,9 +5,11 @@ use parking_lot::RwLock;
 use std::{collections::VecDeque, ops::Deref, sync::Arc, thread::JoinHandle};
 
 mod batch;
+mod pool;
 mod session;
 
 pub use batch::BlockProcessingBatch;
+pub use pool::{SessionPool, DEFAULT_SESSION_POOL_MAX_AGE};
 pub use session::{
     spawn_blocking, ConsensusInstance, ConsensusProxy, ConsensusSessionBlocking, ConsensusSessionOwned, SessionLock, SessionReadGuard,
     SessionWriteGuard,
@@ -0,0 +1,164 @@
+use crate::{
+    session::{ConsensusInstance, ConsensusProxy, WeakConsensusSession},
+    ConsensusManager,
+};
+use parking_lot::Mutex;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Default maximum age of a session shared by [`SessionPool`]. Kept well below the ~2 seconds lifetime budget of a session
+pub const DEFAULT_SESSION_POOL_MAX_AGE: Duration = Duration::from_millis(200);
+
+/// A pool of read-only guarded consensus sessions, reducing session acquisition churn under high query load (e.g. RPC).
+///
+/// Concurrent callers share the most recently acquired session as long as it is still alive (held by another caller)
+/// and younger than `max_age`, instead of each re-acquiring the session lock. The pool itself only holds a weak reference,
+/// so once all callers drop their sessions the lock is released, and the age bound prevents a continuous stream of
+/// overlapping callers from holding the lock indefinitely and thus starving writers (e.g. pruning or a consensus switch).
+///
+/// The current consensus is resolved from the consensus manager on every acquisition, so following a consensus switch
+/// sessions of the replaced consensus are never shared, nor kept alive by the pool.
+///
+/// Sessions obtained from the pool must only be used for reading consensus data, where callers sharing the same view is
+/// harmless
+pub struct SessionPool {
+    consensus_manager: Arc<ConsensusManager>,
+    max_age: Duration,
+    shared: Mutex<Option<(Instant, WeakConsensusSession)>>,
+}
+
+impl SessionPool {
+    pub fn new(consensus_manager: Arc<ConsensusManager>, max_age: Duration) -> Self {
+        Self { consensus_manager, max_age, shared: Mutex::new(None) }
+    }
+
+    /// Returns a guarded session of the current consensus, shared with other callers when possible.
+    /// See [`ConsensusInstance::session`]
+    pub async fn session(&self) -> ConsensusProxy {
+        let consensus = self.consensus_manager.consensus();
+        if let Some(session) = self.try_share(&consensus) {
+            return session;
+        }
+        let session = consensus.session().await;
+        *self.shared.lock() = session.downgrade().map(|weak| (Instant::now(), weak));
+        session
+    }
+
+    fn try_share(&self, consensus: &ConsensusInstance) -> Option<ConsensusProxy> {
+        let mut shared = self.shared.lock();
+        let session = shared
+            .as_ref()
+            .filter(|(acquired, weak)| acquired.elapsed() < self.max_age && weak.is_session_of(consensus))
+            .and_then(|(_, weak)| weak.upgrade());
+        if session.is_none() {
+            // Drop the stale entry, be it expired, released or of a replaced consensus
+            *shared = None;
+        }
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsensusCtl, ConsensusFactory, DynConsensusCtl, SessionLock};
+    use kaspa_consensus_core::api::ConsensusApi;
+    use std::{sync::Weak, thread::JoinHandle};
+    use tokio::time::{sleep, timeout};
+
+    struct ConsensusStub;
+
+    impl ConsensusApi for ConsensusStub {}
+
+    impl ConsensusCtl for ConsensusStub {
+        fn start(&self) -> Vec<JoinHandle<()>> {
+            vec![]
+        }
+
+        fn stop(&self) {}
+
+        fn make_active(&self) {}
+    }
+
+    /// Creates a new stub consensus for every active or staging request, all sharing the same session lock
+    struct StubFactory {
+        session_lock: SessionLock,
+        created: Mutex<Vec<Weak<ConsensusStub>>>,
+    }
+
+    impl StubFactory {
+        fn new(session_lock: SessionLock) -> Self {
+            Self { session_lock, created: Default::default() }
+        }
+
+        fn new_consensus(&self) -> (ConsensusInstance, DynConsensusCtl) {
+            let consensus = Arc::new(ConsensusStub);
+            self.created.lock().push(Arc::downgrade(&consensus));
+            (ConsensusInstance::new(self.session_lock.clone(), consensus.clone()), consensus)
+        }
+    }
+
+    impl ConsensusFactory for StubFactory {
+        fn new_active_consensus(&self) -> (ConsensusInstance, DynConsensusCtl) {
+            self.new_consensus()
+        }
+
+        fn new_staging_consensus(&self) -> (ConsensusInstance, DynConsensusCtl) {
+            self.new_consensus()
+        }
+
+        fn close(&self) {}
+
+        fn delete_inactive_consensus_entries(&self) {}
+
+        fn delete_staging_entry(&self) {}
+    }
+
+    #[tokio::test]
+    async fn test_session_pool() {
+        let session_lock = SessionLock::new();
+        let consensus_manager = Arc::new(ConsensusManager::new(Arc::new(StubFactory::new(session_lock.clone()))));
+        let pool = SessionPool::new(consensus_manager, Duration::from_millis(100));
+
+        // Live sessions are shared
+        let first = pool.session().await;
+        let second = pool.session().await;
+        assert!(first.shares_guard_with(&second));
+
+        // Once all sessions are dropped the lock is released and a new session is acquired
+        drop((first, second));
+        let lock = session_lock.clone();
+        timeout(Duration::from_secs(1), tokio::task::spawn_blocking(move || drop(lock.blocking_write()))).await.unwrap().unwrap();
+        let third = pool.session().await;
+        let fourth = pool.session().await;
+        assert!(third.shares_guard_with(&fourth));
+
+        // Live sessions older than the max age are not shared
+        sleep(Duration::from_millis(150)).await;
+        let fifth = pool.session().await;
+        assert!(!fifth.shares_guard_with(&third));
+        assert!(fifth.shares_guard_with(&pool.session().await));
+    }
+
+    #[tokio::test]
+    async fn test_session_pool_consensus_switch() {
+        let factory = Arc::new(StubFactory::new(SessionLock::new()));
+        let consensus_manager = Arc::new(ConsensusManager::new(factory.clone()));
+        let pool = SessionPool::new(consensus_manager.clone(), Duration::from_secs(60));
+
+        let prev_session = pool.session().await;
+        let prev_consensus = factory.created.lock()[0].clone();
+
+        // Once the staging consensus is committed, live sessions of the replaced consensus are no longer shared
+        consensus_manager.new_staging_consensus().commit();
+        let session = pool.session().await;
+        assert!(!session.shares_guard_with(&prev_session));
+        assert!(session.shares_guard_with(&pool.session().await));
+
+        // The pool does not keep the replaced consensus alive
+        drop(prev_session);
+        assert!(prev_consensus.upgrade().is_none());
+    }
+}
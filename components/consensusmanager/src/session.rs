@@ -3,8 +3,11 @@
 //! We use newtypes in order to simplify changing the underlying lock in the future
 
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
-    api::{BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats, DynConsensus},
+    acceptance_data::{AcceptanceData, AcceptedTransactionsData},
+    api::{
+        BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats, DifficultyInfo, DynConsensus, SelfCheckReport, TipsInfo,
+        ValidationResult,
+    },
     block::Block,
     blockstatus::BlockStatus,
     daa_score_timestamp::DaaScoreTimestamp,
@@ -13,12 +16,15 @@ use kaspa_consensus_core::{
     mass::{ContextualMasses, NonContextualMasses},
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
     utxo::utxo_inquirer::UtxoInquirerError,
     BlockHashSet, BlueWorkType, ChainPath, Hash,
 };
 use kaspa_utils::sync::rwlock::*;
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ops::Deref,
+    sync::{Arc, Weak},
+};
 
 pub use tokio::task::spawn_blocking;
 
@@ -158,6 +164,20 @@ impl ConsensusSessionOwned {
         Self { _session_guard: None, consensus }
     }
 
+    /// Returns a weak reference to this session which neither keeps the session lock acquired nor the consensus alive,
+    /// or `None` if the session is unguarded
+    pub(crate) fn downgrade(&self) -> Option<WeakConsensusSession> {
+        self._session_guard
+            .as_ref()
+            .map(|guard| WeakConsensusSession { session_guard: Arc::downgrade(&guard.0), consensus: Arc::downgrade(&self.consensus) })
+    }
+
+    /// Indicates whether both sessions hold the very same session guard
+    #[cfg(test)]
+    pub(crate) fn shares_guard_with(&self, other: &Self) -> bool {
+        matches!((&self._session_guard, &other._session_guard), (Some(a), Some(b)) if Arc::ptr_eq(&a.0, &b.0))
+    }
+
     /// Uses [`tokio::task::spawn_blocking`] to run the provided consensus closure on a thread where blocking is acceptable.
     /// Note that this function is only available on the *owned* session, and requires cloning the session. In fact this
     /// function is the main motivation for a separate session type.
@@ -170,6 +190,26 @@ impl ConsensusSessionOwned {
     }
 }
 
+/// A weak reference to a guarded [`ConsensusSessionOwned`]. Can be upgraded back to a session as long as
+/// some clone of the original session is still alive
+#[derive(Clone)]
+pub(crate) struct WeakConsensusSession {
+    session_guard: Weak<RfRwLockOwnedReadGuard>,
+    consensus: Weak<dyn ConsensusApi>,
+}
+
+impl WeakConsensusSession {
+    /// Indicates whether this session was acquired from `instance`
+    pub(crate) fn is_session_of(&self, instance: &ConsensusInstance) -> bool {
+        std::ptr::addr_eq(self.consensus.as_ptr(), Arc::as_ptr(&instance.consensus))
+    }
+
+    pub(crate) fn upgrade(&self) -> Option<ConsensusSessionOwned> {
+        let consensus = self.consensus.upgrade()?;
+        self.session_guard.upgrade().map(|guard| ConsensusSessionOwned::new(SessionOwnedReadGuard(guard), consensus))
+    }
+}
+
 impl ConsensusSessionOwned {
     pub fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
         self.consensus.validate_and_insert_block(block)
@@ -178,12 +218,11 @@ impl ConsensusSessionOwned {
     pub fn validate_and_insert_block_batch(&self, mut batch: Vec<Block>) -> BlockProcessingBatch {
         // Sort by blue work in order to ensure topological order
         batch.sort_by(|a, b| a.header.blue_work.partial_cmp(&b.header.blue_work).unwrap());
-        let (block_tasks, virtual_state_tasks) = batch
-            .iter()
-            .map(|b| {
-                let BlockValidationFutures { block_task, virtual_state_task } = self.consensus.validate_and_insert_block(b.clone());
-                (block_task, virtual_state_task)
-            })
+        let (block_tasks, virtual_state_tasks) = self
+            .consensus
+            .validate_and_insert_blocks(batch.clone())
+            .into_iter()
+            .map(|BlockValidationFutures { block_task, virtual_state_task }| (block_task, virtual_state_task))
             .unzip();
         BlockProcessingBatch::new(batch, block_tasks, virtual_state_tasks)
     }
@@ -258,6 +297,22 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_current_block_color(hash)).await
     }
 
+    pub async fn async_past_median_time(&self, hash: Hash) -> Option<u64> {
+        self.clone().spawn_blocking(move |c| c.past_median_time(hash)).await
+    }
+
+    pub async fn async_acceptance_depth(&self, hash: Hash) -> Option<u64> {
+        self.clone().spawn_blocking(move |c| c.acceptance_depth(hash)).await
+    }
+
+    pub async fn async_get_block_accepted_transaction_ids(&self, hash: Hash) -> Option<Vec<TransactionId>> {
+        self.clone().spawn_blocking(move |c| c.get_block_accepted_transaction_ids(hash)).await
+    }
+
+    pub async fn async_accepting_block_of(&self, tx_id: TransactionId, depth: u64) -> Option<Hash> {
+        self.clone().spawn_blocking(move |c| c.accepting_block_of(tx_id, depth)).await
+    }
+
     /// retention period root refers to the earliest block from which the current node has full header & block data  
     pub async fn async_get_retention_period_root(&self) -> Hash {
         self.clone().spawn_blocking(|c| c.get_retention_period_root()).await
@@ -284,10 +339,26 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_virtual_utxos(from_outpoint, chunk_size, skip_first)).await
     }
 
+    pub async fn async_get_utxo_entries(&self, outpoints: Vec<TransactionOutpoint>) -> Vec<Option<UtxoEntry>> {
+        self.clone().spawn_blocking(move |c| c.get_utxo_entries(&outpoints)).await
+    }
+
+    pub async fn async_coinbase_maturity_daa(&self) -> u64 {
+        self.clone().spawn_blocking(|c| c.coinbase_maturity_daa()).await
+    }
+
+    pub async fn async_is_coinbase_spendable(&self, outpoint: TransactionOutpoint, at_daa_score: u64) -> Option<bool> {
+        self.clone().spawn_blocking(move |c| c.is_coinbase_spendable(outpoint, at_daa_score)).await
+    }
+
     pub async fn async_get_tips(&self) -> Vec<Hash> {
         self.clone().spawn_blocking(|c| c.get_tips()).await
     }
 
+    pub async fn async_dag_tips_info(&self) -> TipsInfo {
+        self.clone().spawn_blocking(|c| c.dag_tips_info()).await
+    }
+
     pub async fn async_get_tips_len(&self) -> usize {
         self.clone().spawn_blocking(|c| c.get_tips_len()).await
     }
@@ -300,10 +371,30 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_hashes_between(low, high, max_blocks)).await
     }
 
+    pub async fn async_backward_chain_iterator_to(&self, from: Hash, stop: Hash) -> ConsensusResult<Vec<Hash>> {
+        self.clone().spawn_blocking(move |c| c.backward_chain_iterator_to(from, stop)).await
+    }
+
+    pub async fn async_difficulty_info(&self, hash: Hash) -> ConsensusResult<DifficultyInfo> {
+        self.clone().spawn_blocking(move |c| c.difficulty_info(hash)).await
+    }
+
     pub async fn async_get_header(&self, hash: Hash) -> ConsensusResult<Arc<Header>> {
         self.clone().spawn_blocking(move |c| c.get_header(hash)).await
     }
 
+    pub async fn async_get_headers(&self, hashes: Vec<Hash>) -> Vec<Option<Arc<Header>>> {
+        self.clone().spawn_blocking(move |c| c.get_headers(&hashes)).await
+    }
+
+    pub async fn async_self_check(&self, depth: usize) -> SelfCheckReport {
+        self.clone().spawn_blocking(move |c| c.self_check(depth)).await
+    }
+
+    pub async fn async_validate_dag_integrity(&self, depth: usize) -> ValidationResult {
+        self.clone().spawn_blocking(move |c| c.validate_dag_integrity(depth)).await
+    }
+
     pub async fn async_get_headers_selected_tip(&self) -> Hash {
         self.clone().spawn_blocking(|c| c.get_headers_selected_tip()).await
     }
@@ -373,6 +464,15 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_ghostdag_data(hash)).await
     }
 
+    /// Computes the GHOSTDAG data of a hypothetical block with the given parents, see [`ConsensusApi::compute_ghostdag`]
+    pub async fn async_compute_ghostdag(&self, parents: Vec<Hash>) -> ConsensusResult<ExternalGhostdagData> {
+        self.clone().spawn_blocking(move |c| c.compute_ghostdag(&parents)).await
+    }
+
+    pub async fn async_estimate_past_size(&self, hash: Hash) -> ConsensusResult<u64> {
+        self.clone().spawn_blocking(move |c| c.estimate_past_size(hash)).await
+    }
+
     pub async fn async_get_block_children(&self, hash: Hash) -> Option<Vec<Hash>> {
         self.clone().spawn_blocking(move |c| c.get_block_children(hash)).await
     }
@@ -389,6 +489,10 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_block_acceptance_data(hash)).await
     }
 
+    pub async fn async_get_block_accepted_transactions_data(&self, hash: Hash) -> Option<AcceptedTransactionsData> {
+        self.clone().spawn_blocking(move |c| c.get_block_accepted_transactions_data(hash)).await
+    }
+
     /// Returns acceptance data for a set of blocks belonging to the selected parent chain.
     ///
     /// See `self::get_virtual_chain`
@@ -1,7 +1,7 @@
 use kaspa_hashes::Hash;
 use serde::{Deserialize, Serialize};
 
-use crate::tx::TransactionId;
+use crate::tx::{Transaction, TransactionId};
 
 pub type AcceptanceData = Vec<MergesetBlockAcceptanceData>;
 
@@ -16,3 +16,19 @@ pub struct AcceptedTxEntry {
     pub transaction_id: TransactionId,
     pub index_within_block: u32,
 }
+
+/// Acceptance data of a chain block with the accepted transactions resolved from the bodies of the merged blocks
+pub type AcceptedTransactionsData = Vec<MergesetBlockAcceptedTransactions>;
+
+/// The transactions accepted from a single mergeset block, in the order they appear in its acceptance data
+#[derive(Debug, Clone)]
+pub struct MergesetBlockAcceptedTransactions {
+    pub block_hash: Hash,
+    pub accepted_transactions: Vec<AcceptedTransaction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AcceptedTransaction {
+    pub index_within_block: u32,
+    pub transaction: Transaction,
+}
@@ -18,13 +18,26 @@ impl TransactionValidationArgs {
 /// A struct provided to consensus for transactions validation batch processing calls
 pub struct TransactionValidationBatchArgs {
     tx_args: HashMap<TransactionId, TransactionValidationArgs>,
+    /// Optional max number of threads concurrently validating the batch
+    max_parallelism: Option<usize>,
 }
 
 impl TransactionValidationBatchArgs {
     const DEFAULT_ARGS: TransactionValidationArgs = TransactionValidationArgs { feerate_threshold: None };
 
     pub fn new() -> Self {
-        Self { tx_args: HashMap::new() }
+        Self { tx_args: HashMap::new(), max_parallelism: None }
+    }
+
+    /// Bounds the number of threads concurrently validating the batch, leaving the remaining
+    /// processing threads available for other tasks (e.g. block processing)
+    pub fn with_max_parallelism(mut self, max_parallelism: Option<usize>) -> Self {
+        self.max_parallelism = max_parallelism.map(|n| n.max(1));
+        self
+    }
+
+    pub fn max_parallelism(&self) -> Option<usize> {
+        self.max_parallelism
     }
 
     /// Set some fee/mass threshold for transaction `transaction_id`.
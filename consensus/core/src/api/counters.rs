@@ -1,3 +1,4 @@
+use crate::utxo::utxo_diff::UtxoDiffApplyCounts;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Default)]
@@ -11,6 +12,12 @@ pub struct ProcessingCounters {
     pub chain_block_counts: AtomicU64,
     pub chain_disqualified_counts: AtomicU64,
     pub mass_counts: AtomicU64,
+    pub utxo_diff_add_counts: AtomicU64,
+    pub utxo_diff_remove_counts: AtomicU64,
+    pub utxo_diff_conflict_counts: AtomicU64,
+    pub reindex_counts: AtomicU64,
+    pub reindexed_block_counts: AtomicU64,
+    pub reindex_root_advance_counts: AtomicU64,
 }
 
 impl ProcessingCounters {
@@ -25,8 +32,21 @@ impl ProcessingCounters {
             chain_block_counts: self.chain_block_counts.load(Ordering::Relaxed),
             chain_disqualified_counts: self.chain_disqualified_counts.load(Ordering::Relaxed),
             mass_counts: self.mass_counts.load(Ordering::Relaxed),
+            utxo_diff_add_counts: self.utxo_diff_add_counts.load(Ordering::Relaxed),
+            utxo_diff_remove_counts: self.utxo_diff_remove_counts.load(Ordering::Relaxed),
+            utxo_diff_conflict_counts: self.utxo_diff_conflict_counts.load(Ordering::Relaxed),
+            reindex_counts: self.reindex_counts.load(Ordering::Relaxed),
+            reindexed_block_counts: self.reindexed_block_counts.load(Ordering::Relaxed),
+            reindex_root_advance_counts: self.reindex_root_advance_counts.load(Ordering::Relaxed),
         }
     }
+
+    /// Accumulates the counts of a UTXO diff application
+    pub fn add_utxo_diff_counts(&self, counts: UtxoDiffApplyCounts) {
+        self.utxo_diff_add_counts.fetch_add(counts.adds, Ordering::Relaxed);
+        self.utxo_diff_remove_counts.fetch_add(counts.removes, Ordering::Relaxed);
+        self.utxo_diff_conflict_counts.fetch_add(counts.conflicts, Ordering::Relaxed);
+    }
 }
 
 #[derive(Default, Debug, PartialEq, Eq)]
@@ -40,6 +60,12 @@ pub struct ProcessingCountersSnapshot {
     pub chain_block_counts: u64,
     pub chain_disqualified_counts: u64,
     pub mass_counts: u64,
+    pub utxo_diff_add_counts: u64,
+    pub utxo_diff_remove_counts: u64,
+    pub utxo_diff_conflict_counts: u64,
+    pub reindex_counts: u64,
+    pub reindexed_block_counts: u64,
+    pub reindex_root_advance_counts: u64,
 }
 
 impl core::ops::Sub for &ProcessingCountersSnapshot {
@@ -56,6 +82,12 @@ impl core::ops::Sub for &ProcessingCountersSnapshot {
             chain_block_counts: self.chain_block_counts.saturating_sub(rhs.chain_block_counts),
             chain_disqualified_counts: self.chain_disqualified_counts.saturating_sub(rhs.chain_disqualified_counts),
             mass_counts: self.mass_counts.saturating_sub(rhs.mass_counts),
+            utxo_diff_add_counts: self.utxo_diff_add_counts.saturating_sub(rhs.utxo_diff_add_counts),
+            utxo_diff_remove_counts: self.utxo_diff_remove_counts.saturating_sub(rhs.utxo_diff_remove_counts),
+            utxo_diff_conflict_counts: self.utxo_diff_conflict_counts.saturating_sub(rhs.utxo_diff_conflict_counts),
+            reindex_counts: self.reindex_counts.saturating_sub(rhs.reindex_counts),
+            reindexed_block_counts: self.reindexed_block_counts.saturating_sub(rhs.reindexed_block_counts),
+            reindex_root_advance_counts: self.reindex_root_advance_counts.saturating_sub(rhs.reindex_root_advance_counts),
         }
     }
 }
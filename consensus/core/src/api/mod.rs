@@ -3,7 +3,7 @@ use kaspa_muhash::MuHash;
 use std::sync::Arc;
 
 use crate::{
-    acceptance_data::AcceptanceData,
+    acceptance_data::{AcceptanceData, AcceptedTransactionsData},
     api::args::{TransactionValidationArgs, TransactionValidationBatchArgs},
     block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     blockstatus::BlockStatus,
@@ -20,16 +20,18 @@ use crate::{
     mass::{ContextualMasses, NonContextualMasses},
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList, PruningProofMetadata},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
     utxo::utxo_inquirer::UtxoInquirerError,
-    BlockHashSet, BlueWorkType, ChainPath,
+    BlockHashSet, BlueWorkType, ChainPath, ReorgInfo,
 };
 use kaspa_hashes::Hash;
 
-pub use self::stats::{BlockCount, ConsensusStats};
+pub use self::self_check::{DagIntegrityError, SelfCheckIssue, SelfCheckReport, ValidationResult};
+pub use self::stats::{BlockCount, ConsensusStats, DagStats, DifficultyInfo, TipsInfo, UtxoSetSummary};
 
 pub mod args;
 pub mod counters;
+pub mod self_check;
 pub mod stats;
 
 pub type BlockValidationFuture = BoxFuture<'static, BlockProcessResult<BlockStatus>>;
@@ -61,6 +63,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Submits a batch of blocks for validation in a single pass. The returned futures are ordered
+    /// as the input blocks and carry the same semantics as those returned by [`Self::validate_and_insert_block`].
+    /// Callers are expected to provide the blocks in topological order.
+    fn validate_and_insert_blocks(&self, blocks: Vec<Block>) -> Vec<BlockValidationFutures> {
+        blocks.into_iter().map(|block| self.validate_and_insert_block(block)).collect()
+    }
+
     fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
         unimplemented!()
     }
@@ -99,11 +108,24 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the overall consensus mass of the transaction, which fees should be computed against.
+    /// See [`crate::mass::MassCalculator::calc_overall_mass`] for the handling of partially populated transactions
+    /// and for the cases where the mass is incomputable, in which `None` is returned
+    fn calculate_transaction_mass(&self, transaction: &MutableTransaction) -> Option<u64> {
+        unimplemented!()
+    }
+
     /// Returns an aggregation of consensus stats. Designed to be a fast call.
     fn get_stats(&self) -> ConsensusStats {
         unimplemented!()
     }
 
+    /// Returns the mean mergeset blues, mergeset reds, direct parents and transactions over the given blocks.
+    /// Blocks with no stored transactions are counted as having zero transactions, and unknown blocks are skipped
+    fn dag_stats(&self, hashes: &[Hash]) -> DagStats {
+        unimplemented!()
+    }
+
     fn get_virtual_daa_score(&self) -> u64 {
         unimplemented!()
     }
@@ -131,6 +153,12 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns information about the most recent change of the virtual selected chain, or `None`
+    /// if the chain has not changed since consensus was started
+    fn last_reorg_info(&self) -> Option<ReorgInfo> {
+        unimplemented!()
+    }
+
     fn get_sink_timestamp(&self) -> u64 {
         unimplemented!()
     }
@@ -143,6 +171,34 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the past median time of `hash`, i.e., the timestamp lower bound for its children (which must be strictly
+    /// greater). Returns `None` if the block is unknown or out of the retention period
+    fn past_median_time(&self, hash: Hash) -> Option<u64> {
+        unimplemented!()
+    }
+
+    /// Returns the number of chain blocks added on top of the chain block accepting `hash` (i.e., the chain block
+    /// whose mergeset contains `hash`), where a depth of 0 means `hash` is accepted by the sink. Returns `None`
+    /// if the block is not yet accepted by a chain block, or if it is unknown or out of the retention period
+    fn acceptance_depth(&self, hash: Hash) -> Option<u64> {
+        unimplemented!()
+    }
+
+    /// Returns the ids of the transactions of block `hash` which were accepted into the virtual state by the block
+    /// merging it (either a chain block or virtual itself). Returns `None` if the block has no body, is not yet merged,
+    /// or is unknown or out of the retention period
+    fn get_block_accepted_transaction_ids(&self, hash: Hash) -> Option<Vec<TransactionId>> {
+        unimplemented!()
+    }
+
+    /// Returns the selected chain block which accepted transaction `tx_id`, scanning the acceptance data of the `depth`
+    /// chain blocks ending at the sink. Returns `None` if the transaction is not accepted by any of the scanned blocks,
+    /// including when it is only accepted by virtual or when its accepting block was pruned. Since the scan holds the pruning
+    /// lock, `depth` is capped at 10 times the mergeset size limit, which is the chain batch size served by RPC
+    fn accepting_block_of(&self, tx_id: TransactionId, depth: u64) -> Option<Hash> {
+        unimplemented!()
+    }
+
     fn get_virtual_state_approx_id(&self) -> VirtualStateApproxId {
         unimplemented!()
     }
@@ -192,6 +248,25 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the virtual UTXO entries of the given outpoints, in the order they were provided.
+    /// Outpoints which are spent or do not exist are mapped to `None`
+    fn get_utxo_entries(&self, outpoints: &[TransactionOutpoint]) -> Vec<Option<UtxoEntry>> {
+        unimplemented!()
+    }
+
+    /// Returns the number of DAA scores a coinbase output must wait after its block DAA score before
+    /// it can be spent, as applicable at the current virtual DAA score
+    fn coinbase_maturity_daa(&self) -> u64 {
+        unimplemented!()
+    }
+
+    /// Returns whether the virtual UTXO at `outpoint` can be spent by a transaction at `at_daa_score`
+    /// with respect to coinbase maturity. Non-coinbase entries are always considered spendable.
+    /// Returns `None` if the outpoint is not in the virtual UTXO set
+    fn is_coinbase_spendable(&self, outpoint: TransactionOutpoint, at_daa_score: u64) -> Option<bool> {
+        unimplemented!()
+    }
+
     fn get_tips(&self) -> Vec<Hash> {
         unimplemented!()
     }
@@ -200,10 +275,21 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the sink, virtual parents, body tips and headers selected tip in a single consistent read
+    fn dag_tips_info(&self) -> TipsInfo {
+        unimplemented!()
+    }
+
     fn modify_coinbase_payload(&self, payload: Vec<u8>, miner_data: &MinerData) -> CoinbaseResult<Vec<u8>> {
         unimplemented!()
     }
 
+    /// Returns the max allowed coinbase payload length. Miner data can be checked against it
+    /// via [`MinerData::coinbase_payload_len`] before building a block template
+    fn max_coinbase_payload_len(&self) -> usize {
+        unimplemented!()
+    }
+
     fn calc_transaction_hash_merkle_root(&self, txs: &[Transaction], pov_daa_score: u64) -> Hash {
         unimplemented!()
     }
@@ -236,10 +322,46 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the selected chain blocks walking backward from `from` (inclusive) down to `stop` (exclusive).
+    /// Returns an empty vector if `stop == from`, and an error if `stop` is not a chain ancestor of `from`
+    fn backward_chain_iterator_to(&self, from: Hash, stop: Hash) -> ConsensusResult<Vec<Hash>> {
+        unimplemented!()
+    }
+
     fn get_header(&self, hash: Hash) -> ConsensusResult<Arc<Header>> {
         unimplemented!()
     }
 
+    /// Returns the headers of `hashes` in input order, where unknown or pruned headers are reported as `None`
+    fn get_headers(&self, hashes: &[Hash]) -> Vec<Option<Arc<Header>>> {
+        unimplemented!()
+    }
+
+    /// Runs read-only consistency checks (block data, relations and reachability) over the blocks within
+    /// `depth` parent steps from the DAG tips and reports any inconsistency found
+    fn self_check(&self, depth: usize) -> SelfCheckReport {
+        unimplemented!()
+    }
+
+    /// Re-verifies stored invariants in-place over the blocks within `depth` parent steps from the DAG tips. On top of the
+    /// [`Self::self_check`] checks, GHOSTDAG data is recomputed and chain blocks are checked against their stored UTXO
+    /// multisets and acceptance data. This is a lighter alternative to re-importing the DAG into a fresh consensus.
+    /// Returns the number of validated blocks if no inconsistency was found
+    fn validate_dag_integrity(&self, depth: usize) -> ValidationResult {
+        unimplemented!()
+    }
+
+    /// Returns the difficulty figures of the block `hash`. Returns an error if the block header is unknown or was pruned
+    fn difficulty_info(&self, hash: Hash) -> ConsensusResult<DifficultyInfo> {
+        unimplemented!()
+    }
+
+    /// Returns the headers of selected chain blocks with blue work in the inclusive range `[low, high]`, in ascending
+    /// blue-work order and bounded by `limit`. Returns an empty vector if `low > high`
+    fn headers_by_blue_work_range(&self, low: BlueWorkType, high: BlueWorkType, limit: usize) -> Vec<Arc<Header>> {
+        unimplemented!()
+    }
+
     fn get_headers_selected_tip(&self) -> Hash {
         unimplemented!()
     }
@@ -288,6 +410,21 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Runs GHOSTDAG over a hypothetical block with the given `parents` and returns the resulting data (selected parent,
+    /// mergeset blues and reds, blue score and blue work) without inserting anything. The parents must be known valid blocks
+    /// in the future of the pruning point which are mutually in anticone, otherwise an error is returned
+    fn compute_ghostdag(&self, parents: &[Hash]) -> ConsensusResult<ExternalGhostdagData> {
+        unimplemented!()
+    }
+
+    /// Returns an estimate of the inclusive past size of `hash` (the number of blocks in its past, itself and genesis included)
+    /// in constant time, i.e., without traversing the past. The estimate is based on the DAA score, which counts the past blocks
+    /// which are not DAA-excluded, and is lower-bounded by the blue score. Hence it is exact unless the past contains DAA-excluded
+    /// blocks, in which case it underestimates. Returns an error if the block header is unknown or was pruned
+    fn estimate_past_size(&self, hash: Hash) -> ConsensusResult<u64> {
+        unimplemented!()
+    }
+
     fn get_block_children(&self, hash: Hash) -> Option<Vec<Hash>> {
         unimplemented!()
     }
@@ -304,6 +441,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the acceptance data of the chain block `hash` with each accepted transaction resolved from the body
+    /// of the mergeset block it was merged from. Returns `None` if the acceptance data or any of the merged
+    /// block bodies is missing, e.g. if the block is not a chain block or was pruned
+    fn get_block_accepted_transactions_data(&self, hash: Hash) -> Option<AcceptedTransactionsData> {
+        unimplemented!()
+    }
+
     /// Returns acceptance data for a set of blocks belonging to the selected parent chain.
     ///
     /// See `self::get_virtual_chain`
@@ -329,6 +473,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Iterates the pruning point UTXO set once and returns its entry count, total value and estimated byte size.
+    /// The set is streamed in chunks, so the pruning utxoset lock is only ever held briefly. Returns
+    /// `ConsensusError::UnexpectedPruningPoint` if the set is still being advanced to the current pruning point.
+    fn pruning_point_utxo_set_summary(&self) -> ConsensusResult<UtxoSetSummary> {
+        unimplemented!()
+    }
+
     fn get_missing_block_body_hashes(&self, high: Hash) -> ConsensusResult<Vec<Hash>> {
         unimplemented!()
     }
@@ -0,0 +1,87 @@
+use kaspa_hashes::Hash;
+use std::fmt::Display;
+use thiserror::Error;
+
+/// A single inconsistency found by a consensus self-check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelfCheckIssue {
+    /// The block has no status entry
+    MissingStatus(Hash),
+
+    /// The block has no header entry
+    MissingHeader(Hash),
+
+    /// The block has no (level 0) GHOSTDAG data
+    MissingGhostdagData(Hash),
+
+    /// The block has no reachability data
+    MissingReachabilityData(Hash),
+
+    /// The block has no reachability relations entry
+    MissingRelations(Hash),
+
+    /// `child` lists `parent` as a parent, but `parent` does not list `child` as a child
+    MissingChildLink { parent: Hash, child: Hash },
+
+    /// `parent` is a parent of `child` but is not in its past according to reachability
+    ParentNotInPast { parent: Hash, child: Hash },
+
+    /// The reachability tree parent interval does not strictly contain the interval of `child`
+    TreeIntervalNotContained { parent: Hash, child: Hash },
+
+    /// The stored GHOSTDAG data of the block differs from the data recomputed from its parents
+    GhostdagMismatch(Hash),
+
+    /// The stored UTXO multiset of the chain block does not match the UTXO commitment of its header
+    UtxoCommitmentMismatch(Hash),
+
+    /// The stored acceptance data of the chain block does not match the accepted ID merkle root of its header
+    AcceptedIdMerkleRootMismatch(Hash),
+}
+
+impl Display for SelfCheckIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingStatus(hash) => write!(f, "block {hash} has no status"),
+            Self::MissingHeader(hash) => write!(f, "block {hash} has no header"),
+            Self::MissingGhostdagData(hash) => write!(f, "block {hash} has no GHOSTDAG data"),
+            Self::MissingReachabilityData(hash) => write!(f, "block {hash} has no reachability data"),
+            Self::MissingRelations(hash) => write!(f, "block {hash} has no reachability relations"),
+            Self::MissingChildLink { parent, child } => write!(f, "parent {parent} is missing a child link to {child}"),
+            Self::ParentNotInPast { parent, child } => write!(f, "parent {parent} is not in the past of {child}"),
+            Self::TreeIntervalNotContained { parent, child } => {
+                write!(f, "reachability interval of {child} is not contained in the interval of its tree parent {parent}")
+            }
+            Self::GhostdagMismatch(hash) => write!(f, "stored GHOSTDAG data of block {hash} differs from its recomputation"),
+            Self::UtxoCommitmentMismatch(hash) => write!(f, "UTXO multiset of block {hash} does not match its UTXO commitment"),
+            Self::AcceptedIdMerkleRootMismatch(hash) => {
+                write!(f, "acceptance data of block {hash} does not match its accepted ID merkle root")
+            }
+        }
+    }
+}
+
+/// The outcome of a bounded consensus self-check
+#[derive(Clone, Debug, Default)]
+pub struct SelfCheckReport {
+    /// The number of blocks which were checked
+    pub checked_blocks: usize,
+
+    /// The inconsistencies found, in traversal order
+    pub issues: Vec<SelfCheckIssue>,
+}
+
+impl SelfCheckReport {
+    /// Returns whether no inconsistency was found
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Returned by a DAG integrity validation which found inconsistencies. Holds the full report of the validated blocks
+#[derive(Error, Clone, Debug)]
+#[error("DAG integrity validation found {} inconsistencies over {} blocks", .0.issues.len(), .0.checked_blocks)]
+pub struct DagIntegrityError(pub SelfCheckReport);
+
+/// The result of a DAG integrity validation, holding the number of validated blocks on success
+pub type ValidationResult = Result<usize, DagIntegrityError>;
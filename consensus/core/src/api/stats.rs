@@ -1,3 +1,9 @@
+use crate::{
+    tx::{utxo_record_mem_bytes, UtxoEntry},
+    BlueWorkType,
+};
+use kaspa_hashes::Hash;
+use kaspa_math::Uint256;
 use serde::{Deserialize, Serialize};
 use workflow_serializer::prelude::*;
 
@@ -53,3 +59,131 @@ pub struct ConsensusStats {
     /// Virtual-related stats
     pub virtual_stats: VirtualStateStats,
 }
+
+/// Difficulty figures of a single block, expanded from its compact target bits
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DifficultyInfo {
+    /// The compact target bits as found in the block header
+    pub bits: u32,
+    /// The expanded target
+    pub target: Uint256,
+    /// The expected number of hashes required for mining a block with this target
+    pub work: BlueWorkType,
+    /// The network hashrate implied by the target, i.e., the work expected per second given the target time per block
+    pub estimated_network_hps: f64,
+}
+
+/// Aggregated figures over a full UTXO set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoSetSummary {
+    /// Number of UTXO entries
+    pub count: u64,
+    /// Total value held by all entries
+    pub total_sompi: u64,
+    /// Estimated byte size of the set (outpoints, entries and their script payloads)
+    pub bytes: u64,
+}
+
+impl UtxoSetSummary {
+    /// Accounts for a single UTXO entry
+    pub fn add(&mut self, entry: &UtxoEntry) {
+        self.count += 1;
+        self.total_sompi += entry.amount;
+        self.bytes += Self::estimated_entry_bytes(entry) as u64;
+    }
+
+    /// Estimates the byte size of a single UTXO record, including its outpoint key
+    pub fn estimated_entry_bytes(entry: &UtxoEntry) -> usize {
+        utxo_record_mem_bytes(entry)
+    }
+}
+
+impl<'a> FromIterator<&'a UtxoEntry> for UtxoSetSummary {
+    fn from_iter<I: IntoIterator<Item = &'a UtxoEntry>>(iter: I) -> Self {
+        let mut summary = Self::default();
+        iter.into_iter().for_each(|entry| summary.add(entry));
+        summary
+    }
+}
+
+/// The tip-related hashes of the DAG, read together in order to provide a consistent view
+#[derive(Clone, Debug)]
+pub struct TipsInfo {
+    /// The virtual selected parent
+    pub sink: Hash,
+    /// The direct parents of virtual, i.e., the subset of body tips which were merged by virtual
+    pub virtual_parents: Vec<Hash>,
+    /// The tips of the DAG of blocks with a body
+    pub body_tips: Vec<Hash>,
+    /// The selected tip of the headers DAG, which might be ahead of the body tips while syncing.
+    /// Note that only the selected tip is tracked for headers, hence the full set of header tips is not available
+    pub headers_selected_tip: Hash,
+}
+
+/// Average structural figures over a set of blocks
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DagStats {
+    pub blues_mean: f64,
+    pub reds_mean: f64,
+    pub parents_mean: f64,
+    pub txs_mean: f64,
+    /// Overall number of transactions in all blocks
+    pub num_txs: usize,
+}
+
+impl DagStats {
+    /// Aggregates per-block `(mergeset blues, mergeset reds, direct parents, txs)` counts into means.
+    /// An empty input yields all-zero stats
+    pub fn from_block_counts(counts: impl IntoIterator<Item = (usize, usize, usize, usize)>) -> Self {
+        let (mut blues, mut reds, mut parents, mut num_txs, mut len) = (0usize, 0usize, 0usize, 0usize, 0usize);
+        for (block_blues, block_reds, block_parents, block_txs) in counts {
+            blues += block_blues;
+            reds += block_reds;
+            parents += block_parents;
+            num_txs += block_txs;
+            len += 1;
+        }
+        if len == 0 {
+            return Self::default();
+        }
+        let mean = |sum: usize| sum as f64 / len as f64;
+        Self { blues_mean: mean(blues), reds_mean: mean(reds), parents_mean: mean(parents), txs_mean: mean(num_txs), num_txs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::{ScriptPublicKey, TransactionOutpoint};
+
+    #[test]
+    fn test_utxo_set_summary() {
+        let entries = [(100u64, 34usize), (2_500, 35), (0, 0), (7, 67)]
+            .into_iter()
+            .map(|(amount, script_len)| UtxoEntry::new(amount, ScriptPublicKey::from_vec(0, vec![0; script_len]), 0, false))
+            .collect::<Vec<_>>();
+        let summary: UtxoSetSummary = entries.iter().collect();
+        let base = size_of::<TransactionOutpoint>() + size_of::<UtxoEntry>();
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.total_sompi, 2_607);
+        // Only the script exceeding the inline script vector capacity is accounted for separately
+        assert_eq!(summary.bytes, (4 * base + 67) as u64);
+        assert_eq!(UtxoSetSummary::default(), std::iter::empty::<&UtxoEntry>().collect());
+    }
+
+    #[test]
+    fn test_dag_stats() {
+        let stats = DagStats::from_block_counts([(1, 0, 1, 1), (3, 1, 2, 4), (2, 2, 3, 0), (2, 1, 2, 3)]);
+        assert_eq!(stats, DagStats { blues_mean: 2.0, reds_mean: 1.0, parents_mean: 2.0, txs_mean: 2.0, num_txs: 8 });
+
+        let stats = DagStats::from_block_counts([(1, 0, 1, 1), (2, 1, 1, 0), (2, 0, 2, 0)]);
+        assert_eq!(stats.num_txs, 1);
+        assert!((stats.blues_mean - 5.0 / 3.0).abs() < 1e-12);
+        assert!((stats.reds_mean - 1.0 / 3.0).abs() < 1e-12);
+        assert!((stats.parents_mean - 4.0 / 3.0).abs() < 1e-12);
+        assert!((stats.txs_mean - 1.0 / 3.0).abs() < 1e-12);
+
+        assert_eq!(DagStats::from_block_counts([]), DagStats::default());
+    }
+}
@@ -39,10 +39,22 @@ impl BlockStatus {
         matches!(self, Self::StatusUTXOValid | Self::StatusUTXOPendingVerification | Self::StatusDisqualifiedFromChain)
     }
 
+    pub fn is_utxo_valid(self) -> bool {
+        self == Self::StatusUTXOValid
+    }
+
+    pub fn is_utxo_pending_verification(self) -> bool {
+        self == Self::StatusUTXOPendingVerification
+    }
+
     pub fn is_utxo_valid_or_pending(self) -> bool {
         matches!(self, Self::StatusUTXOValid | Self::StatusUTXOPendingVerification)
     }
 
+    pub fn is_disqualified_from_chain(self) -> bool {
+        self == Self::StatusDisqualifiedFromChain
+    }
+
     pub fn is_valid(self) -> bool {
         self != BlockStatus::StatusInvalid
     }
@@ -51,3 +63,39 @@ impl BlockStatus {
         self == BlockStatus::StatusInvalid
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BlockStatus::{self, *};
+
+    #[test]
+    fn test_predicates() {
+        // Columns: has_block_header, is_header_only, has_block_body, is_utxo_valid, is_utxo_pending_verification,
+        //          is_utxo_valid_or_pending, is_disqualified_from_chain, is_valid, is_invalid
+        let expected: [(BlockStatus, [bool; 9]); 5] = [
+            (StatusInvalid, [false, false, false, false, false, false, false, false, true]),
+            (StatusUTXOValid, [true, false, true, true, false, true, false, true, false]),
+            (StatusUTXOPendingVerification, [true, false, true, false, true, true, false, true, false]),
+            (StatusDisqualifiedFromChain, [true, false, true, false, false, false, true, true, false]),
+            (StatusHeaderOnly, [true, true, false, false, false, false, false, true, false]),
+        ];
+        for (status, predicates) in expected {
+            // Make sure the table covers all variants (this match fails to compile if a variant is added)
+            match status {
+                StatusInvalid | StatusUTXOValid | StatusUTXOPendingVerification | StatusDisqualifiedFromChain | StatusHeaderOnly => {}
+            }
+            let actual = [
+                status.has_block_header(),
+                status.is_header_only(),
+                status.has_block_body(),
+                status.is_utxo_valid(),
+                status.is_utxo_pending_verification(),
+                status.is_utxo_valid_or_pending(),
+                status.is_disqualified_from_chain(),
+                status.is_valid(),
+                status.is_invalid(),
+            ];
+            assert_eq!(predicates, actual, "unexpected predicates for {status:?}");
+        }
+    }
+}
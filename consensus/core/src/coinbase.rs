@@ -1,18 +1,153 @@
-use crate::tx::{ScriptPublicKey, Transaction};
-use serde::{Deserialize, Serialize};
+use crate::{
+    errors::coinbase::{CoinbaseError, CoinbaseResult},
+    tx::{ScriptPublicKey, ScriptVec, Transaction},
+};
+use kaspa_utils::serde_bytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+/// The length of the fixed coinbase payload fields: blue score (u64), subsidy (u64),
+/// script public key version (u16) and script public key length (u8)
+pub const COINBASE_PAYLOAD_FIXED_LEN: usize = size_of::<u64>() + size_of::<u64>() + size_of::<u16>() + size_of::<u8>();
+
+#[derive(Debug, Clone)]
 pub struct MinerData<T: AsRef<[u8]> = Vec<u8>> {
-    pub script_public_key: ScriptPublicKey,
-    pub extra_data: T,
+    script_public_key: ScriptPublicKey,
+    extra_data: T,
+    /// A hash of the content of both fields, used as a fast-path for rejecting unequal instances.
+    /// The fields are immutable, so the hash is computed once on construction
+    content_hash: u64,
 }
 
 impl<T: AsRef<[u8]>> MinerData<T> {
     pub fn new(script_public_key: ScriptPublicKey, extra_data: T) -> Self {
-        Self { script_public_key, extra_data }
+        let mut hasher = DefaultHasher::new();
+        script_public_key.hash(&mut hasher);
+        extra_data.as_ref().hash(&mut hasher);
+        let content_hash = hasher.finish();
+        Self { script_public_key, extra_data, content_hash }
+    }
+
+    pub fn script_public_key(&self) -> &ScriptPublicKey {
+        &self.script_public_key
+    }
+
+    pub fn extra_data(&self) -> &[u8] {
+        self.extra_data.as_ref()
+    }
+
+    /// Returns the hash of the content of both fields
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
+    /// Consumes the miner data, returning the script public key and the extra data
+    pub fn into_parts(self) -> (ScriptPublicKey, T) {
+        (self.script_public_key, self.extra_data)
+    }
+
+    /// Returns the length of the coinbase payload this miner data serializes into, i.e., the fixed blue score
+    /// and subsidy prefix followed by the script public key (with version and length) and the extra data
+    pub fn coinbase_payload_len(&self) -> usize {
+        COINBASE_PAYLOAD_FIXED_LEN + self.script_public_key.script().len() + self.extra_data.as_ref().len()
+    }
+
+    /// Encodes the miner data in a compact binary format: the script public key version (u16), the length-prefixed
+    /// (u32) script and the length-prefixed (u32) extra data, with all integers in little-endian order
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let script = self.script_public_key.script();
+        let extra_data = self.extra_data.as_ref();
+        let mut bytes = Vec::with_capacity(MINER_DATA_COMPACT_FIXED_LEN + script.len() + extra_data.len());
+        bytes.extend(self.script_public_key.version().to_le_bytes());
+        bytes.extend((script.len() as u32).to_le_bytes());
+        bytes.extend(script);
+        bytes.extend((extra_data.len() as u32).to_le_bytes());
+        bytes.extend(extra_data);
+        bytes
     }
 }
 
+/// The length of the fixed fields of the compact miner data encoding: script public key version (u16),
+/// script length (u32) and extra data length (u32)
+const MINER_DATA_COMPACT_FIXED_LEN: usize = size_of::<u16>() + size_of::<u32>() + size_of::<u32>();
+
+impl MinerData {
+    /// Decodes miner data from the compact binary format produced by [`MinerData::to_compact_bytes`]
+    pub fn from_compact_bytes(bytes: &[u8]) -> CoinbaseResult<Self> {
+        let mut required_len = MINER_DATA_COMPACT_FIXED_LEN;
+        if bytes.len() < required_len {
+            return Err(CoinbaseError::MinerDataEncodingTruncated(bytes.len(), required_len));
+        }
+        let version = u16::from_le_bytes(bytes[..2].try_into().unwrap());
+        let script_len = u32::from_le_bytes(bytes[2..6].try_into().unwrap()) as usize;
+        // The declared lengths are attacker-chosen, so on 32-bit targets the offsets below might wrap
+        let script_end = 6usize.checked_add(script_len).ok_or(CoinbaseError::MinerDataEncodingLengthOverflow)?;
+        required_len = required_len.checked_add(script_len).ok_or(CoinbaseError::MinerDataEncodingLengthOverflow)?;
+        if bytes.len() < required_len {
+            return Err(CoinbaseError::MinerDataEncodingTruncated(bytes.len(), required_len));
+        }
+        let script = &bytes[6..script_end];
+        // Cannot overflow since it is bounded by the required length checked above
+        let extra_data_start = script_end + size_of::<u32>();
+        let extra_data_len = u32::from_le_bytes(bytes[script_end..extra_data_start].try_into().unwrap()) as usize;
+        required_len = required_len.checked_add(extra_data_len).ok_or(CoinbaseError::MinerDataEncodingLengthOverflow)?;
+        if bytes.len() < required_len {
+            return Err(CoinbaseError::MinerDataEncodingTruncated(bytes.len(), required_len));
+        }
+        if bytes.len() > required_len {
+            return Err(CoinbaseError::MinerDataEncodingTrailingBytes(bytes.len(), required_len));
+        }
+        let script_public_key = ScriptPublicKey::new(version, ScriptVec::from_slice(script));
+        Ok(Self::new(script_public_key, bytes[extra_data_start..].to_vec()))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "MinerData", rename_all = "camelCase")]
+struct MinerDataRef<'a> {
+    script_public_key: &'a ScriptPublicKey,
+    #[serde(with = "serde_bytes")]
+    extra_data: &'a [u8],
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "MinerData", rename_all = "camelCase")]
+struct MinerDataOwned {
+    script_public_key: ScriptPublicKey,
+    #[serde(with = "serde_bytes")]
+    extra_data: Vec<u8>,
+}
+
+/// Serializes the borrowed content, so any extra data container can be serialized. The content hash is
+/// not part of the format
+impl<T: AsRef<[u8]>> Serialize for MinerData<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MinerDataRef { script_public_key: &self.script_public_key, extra_data: self.extra_data.as_ref() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MinerData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let MinerDataOwned { script_public_key, extra_data } = MinerDataOwned::deserialize(deserializer)?;
+        Ok(Self::new(script_public_key, extra_data))
+    }
+}
+
+impl<T: AsRef<[u8]>> PartialEq for MinerData<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // Differing hashes imply differing content, so large payloads are only compared in full on a hash match
+        if self.content_hash != other.content_hash {
+            return false;
+        }
+        self.script_public_key == other.script_public_key && self.extra_data.as_ref() == other.extra_data.as_ref()
+    }
+}
+
+impl<T: AsRef<[u8]>> Eq for MinerData<T> {}
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct CoinbaseData<T: AsRef<[u8]> = Vec<u8>> {
     pub blue_score: u64,
@@ -31,6 +166,28 @@ impl BlockRewardData {
     pub fn new(subsidy: u64, total_fees: u64, script_public_key: ScriptPublicKey) -> Self {
         Self { subsidy, total_fees, script_public_key }
     }
+
+    /// Returns the reward this (merged) block contributes to the coinbase of the merging block. The split is all-or-nothing:
+    /// if the block was `merged_as_red`, its entire reward is collected by the red reward output (which is the last coinbase
+    /// output, see [`CoinbaseTransactionTemplate`]), otherwise it is entirely paid to the script public key reported by the block.
+    /// Blocks outside the DAA window of the merging block are not rewarded with subsidy, only with their fees.
+    ///
+    /// Note: this is the rule applied by consensus when building the expected coinbase transaction
+    pub fn split_for_template(&self, merged_as_red: bool, in_daa_window: bool) -> RewardSplit {
+        let total = if in_daa_window { self.subsidy + self.total_fees } else { self.total_fees };
+        if merged_as_red {
+            RewardSplit { miner_amount: 0, red_amount: total }
+        } else {
+            RewardSplit { miner_amount: total, red_amount: 0 }
+        }
+    }
+}
+
+/// The division of a merged block reward between the block miner and the red reward output of the merging block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RewardSplit {
+    pub miner_amount: u64,
+    pub red_amount: u64,
 }
 
 /// Holds a coinbase transaction along with meta-data obtained during creation
@@ -38,3 +195,87 @@ pub struct CoinbaseTransactionTemplate {
     pub tx: Transaction,
     pub has_red_reward: bool, // Does the last output contain reward for red blocks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::ScriptVec;
+
+    #[test]
+    fn test_miner_data_equality_fast_path() {
+        let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&[1u8; 34]));
+        let a = MinerData::new(script_public_key.clone(), vec![7u8; 10_000]);
+        let mut extra_data = vec![7u8; 10_000];
+        *extra_data.last_mut().unwrap() = 8;
+        let b = MinerData::new(script_public_key.clone(), extra_data);
+
+        // Large but different miner datas are told apart by the hash
+        assert_ne!(a.content_hash(), b.content_hash());
+        assert_ne!(a, b);
+
+        // Equal content hashes equally, and clones carry the same hash
+        let c = MinerData::new(script_public_key.clone(), vec![7u8; 10_000]);
+        assert_eq!(a.content_hash(), c.content_hash());
+        assert_eq!(a, c);
+        assert_eq!(a.clone().content_hash(), a.content_hash());
+
+        // The hash covers the script public key as well, and borrowed extra data hashes like owned data
+        let d = MinerData::new(ScriptPublicKey::new(0, ScriptVec::from_slice(&[2u8; 34])), vec![7u8; 10_000]);
+        assert_ne!(a.content_hash(), d.content_hash());
+        assert_ne!(a, d);
+        assert_eq!(a.content_hash(), MinerData::new(script_public_key, a.extra_data()).content_hash());
+    }
+
+    #[test]
+    fn test_reward_split() {
+        let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&[1u8; 34]));
+        let reward_data = BlockRewardData::new(44000000000, 12345, script_public_key.clone());
+
+        // A blue block is paid its entire reward
+        assert_eq!(reward_data.split_for_template(false, true), RewardSplit { miner_amount: 44000012345, red_amount: 0 });
+        // The reward of a red block is entirely collected by the red reward output
+        assert_eq!(reward_data.split_for_template(true, true), RewardSplit { miner_amount: 0, red_amount: 44000012345 });
+        // A red block outside the DAA window contributes its fees only
+        assert_eq!(reward_data.split_for_template(true, false), RewardSplit { miner_amount: 0, red_amount: 12345 });
+
+        // Fees alone, e.g. once the subsidy schedule ended
+        let reward_data = BlockRewardData::new(0, 5000, script_public_key);
+        assert_eq!(reward_data.split_for_template(false, true), RewardSplit { miner_amount: 5000, red_amount: 0 });
+        assert_eq!(reward_data.split_for_template(true, true), RewardSplit { miner_amount: 0, red_amount: 5000 });
+    }
+
+    #[test]
+    fn test_miner_data_round_trip() {
+        let script_public_key = ScriptPublicKey::new(0, ScriptVec::from_slice(&[1u8; 34]));
+        for extra_data_len in [0, 1, 80, 255, 256, 10_000] {
+            let extra_data = (0..extra_data_len).map(|i| i as u8).collect::<Vec<_>>();
+            let miner_data = MinerData::new(script_public_key.clone(), extra_data.clone());
+
+            // Serde, through both a human-readable and a binary format
+            let json = serde_json::to_string(&miner_data).unwrap();
+            assert_eq!(serde_json::from_str::<MinerData>(&json).unwrap(), miner_data);
+            let bin = bincode::serialize(&miner_data).unwrap();
+            assert_eq!(bincode::deserialize::<MinerData>(&bin).unwrap(), miner_data);
+
+            // Borrowed extra data serializes exactly like owned extra data
+            let borrowed = MinerData::new(script_public_key.clone(), extra_data.as_slice());
+            assert_eq!(serde_json::to_string(&borrowed).unwrap(), json);
+            assert_eq!(bincode::serialize(&borrowed).unwrap(), bin);
+
+            // Compact encoding
+            let compact = miner_data.to_compact_bytes();
+            assert_eq!(compact.len(), MINER_DATA_COMPACT_FIXED_LEN + 34 + extra_data_len);
+            assert_eq!(borrowed.to_compact_bytes(), compact);
+            assert_eq!(MinerData::from_compact_bytes(&compact).unwrap(), miner_data);
+        }
+
+        // Malformed compact encodings are rejected
+        let compact = MinerData::new(script_public_key, vec![7u8; 10]).to_compact_bytes();
+        for len in [0, MINER_DATA_COMPACT_FIXED_LEN - 1, 6 + 34, compact.len() - 1] {
+            assert!(matches!(MinerData::from_compact_bytes(&compact[..len]), Err(CoinbaseError::MinerDataEncodingTruncated(..))));
+        }
+        let mut trailing = compact.clone();
+        trailing.push(0);
+        assert!(matches!(MinerData::from_compact_bytes(&trailing), Err(CoinbaseError::MinerDataEncodingTrailingBytes(..))));
+    }
+}
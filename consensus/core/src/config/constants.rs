@@ -123,6 +123,10 @@ pub mod perf {
     const BASELINE_BLOCK_WINDOW_CACHE_SIZE: usize = 2_000;
     const BASELINE_UTXOSET_CACHE_SIZE: usize = 10_000;
 
+    /// The fraction of available memory which the cache byte budgets may occupy when tuned to available memory.
+    /// The rest is left for low-level allocation overhead, the mempool and other node components
+    pub const CACHE_MEMORY_FRACTION: f64 = 0.5;
+
     #[derive(Clone, Debug)]
     pub struct PerfParams {
         //
@@ -142,6 +146,15 @@ pub mod perf {
         /// Preferred cache size for block-window-related data
         pub block_window_cache_size: usize,
 
+        /// The right-shift applied per block level to the relations-store cache budgets, i.e., the
+        /// cache of level `l` is sized `budget >> (l * level_cache_shrink_shift)`. Sizes are floored
+        /// at the level lower bound (`2 * pruning_proof_m`) regardless of the shift. Defaults to 1
+        pub level_cache_shrink_shift: u8,
+
+        /// A scale factor applied to all cache byte budgets on top of the RAM scale. Defaults to 1.0
+        /// and is lowered when tuning to available memory (see [`crate::config::ConfigBuilder::adjust_perf_params_to_available_memory`])
+        pub cache_budget_scale: f64,
+
         //
         // Thread-pools
         //
@@ -159,6 +172,8 @@ pub mod perf {
         block_data_cache_size: BASELINE_BLOCK_DATA_CACHE_SIZE,
         utxo_set_cache_size: BASELINE_UTXOSET_CACHE_SIZE,
         block_window_cache_size: BASELINE_BLOCK_WINDOW_CACHE_SIZE,
+        level_cache_shrink_shift: 1,
+        cache_budget_scale: 1.0,
         block_processors_num_threads: 0,
         virtual_processor_num_threads: 0,
     };
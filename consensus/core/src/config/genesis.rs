@@ -1,4 +1,7 @@
-use crate::{block::Block, header::Header, subnets::SUBNETWORK_ID_COINBASE, tx::Transaction};
+use crate::{
+    block::Block, errors::config::ConfigError, header::Header, merkle::calc_hash_merkle_root, subnets::SUBNETWORK_ID_COINBASE,
+    tx::Transaction,
+};
 use kaspa_hashes::{Hash, ZERO_HASH};
 use kaspa_muhash::EMPTY_MUHASH;
 
@@ -63,6 +66,37 @@ impl From<(&Header, &'static [u8])> for GenesisBlock {
     }
 }
 
+impl TryFrom<&Block> for GenesisBlock {
+    type Error = ConfigError;
+
+    /// Validates that `block` is a well-formed genesis, i.e., it has no parents, contains only a coinbase transaction
+    /// and its header is fully determined by the genesis constants. Note that on success the coinbase payload is leaked in
+    /// order to comply with the static lifetime of genesis constants, hence this is meant to be called once per process
+    fn try_from(block: &Block) -> Result<Self, Self::Error> {
+        let hash = block.hash();
+        if block.header.parents_by_level.iter().any(|parents| !parents.is_empty()) {
+            return Err(ConfigError::GenesisHasParents(hash));
+        }
+        let coinbase = match block.transactions.as_slice() {
+            [tx] if tx.is_coinbase() && tx.inputs.is_empty() && tx.outputs.is_empty() => tx,
+            _ => return Err(ConfigError::GenesisNotCoinbaseOnly(hash)),
+        };
+        if calc_hash_merkle_root(block.transactions.iter(), false) != block.header.hash_merkle_root {
+            return Err(ConfigError::GenesisMerkleRootMismatch(hash));
+        }
+        // The header does not depend on the payload (which is committed to by the merkle root checked above),
+        // so the payload is only leaked once the block is known to be valid
+        let mut genesis = GenesisBlock::from((block.header.as_ref(), &[][..]));
+        // Fields such as blue work or the pruning point are implied for genesis, so the rebuilt header must hash the same
+        let expected_hash = Header::from(&genesis).hash;
+        if expected_hash != hash {
+            return Err(ConfigError::GenesisHeaderMismatch(hash, expected_hash));
+        }
+        genesis.coinbase_payload = Box::leak(coinbase.payload.clone().into_boxed_slice());
+        Ok(genesis)
+    }
+}
+
 /// The genesis block of the block-DAG which serves as the public transaction ledger for the main network.
 pub const GENESIS: GenesisBlock = GenesisBlock {
     hash: Hash::from_bytes([
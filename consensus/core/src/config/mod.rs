@@ -12,11 +12,51 @@ use std::sync::Arc;
 
 use std::ops::Deref;
 
+use crate::{
+    block::Block,
+    errors::config::{ConfigError, ConfigResult},
+    BlockLevel,
+};
 use {
-    constants::perf::{PerfParams, PERF_PARAMS},
-    params::Params,
+    constants::perf::{PerfParams, CACHE_MEMORY_FRACTION, PERF_PARAMS},
+    genesis::GenesisBlock,
+    params::{Params, DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET_PARAMS},
 };
 
+/// The highest allowed `max_block_level`, i.e., the highest max block level of the predefined networks. A store is
+/// created per level up to `max_block_level`, so higher values only allocate stores for levels no network reaches
+pub const MAX_BLOCK_LEVEL_UPPER_BOUND: BlockLevel = networks_max_block_level();
+
+const fn networks_max_block_level() -> BlockLevel {
+    let levels =
+        [MAINNET_PARAMS.max_block_level, TESTNET_PARAMS.max_block_level, SIMNET_PARAMS.max_block_level, DEVNET_PARAMS.max_block_level];
+    let mut max = 0;
+    let mut i = 0;
+    while i < levels.len() {
+        if levels[i] > max {
+            max = levels[i];
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Upper bound on the number of headers a pruning proof can hold, i.e., `2 * pruning_proof_m` headers per level.
+/// Level-related stores and caches are sized accordingly, so this bounds the resources allocated for them
+pub const MAX_PRUNING_PROOF_HEADERS: u64 = 1 << 20;
+
+/// Controls the noise added to consensus cache sizes on storage initialization
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CacheSizeNoise {
+    /// Random noise drawn from the thread-local RNG. Avoids predictable and equal cache sizes across network nodes
+    #[default]
+    Random,
+    /// Deterministic noise derived from the given seed, for reproducible runs
+    Seeded(u64),
+    /// No noise at all, cache sizes are exactly as computed
+    Disabled,
+}
+
 /// Various consensus configurations all bundled up under a single struct. Use `Config::new` for directly building from
 /// a `Params` instance. For anything more complex it is recommended to use `ConfigBuilder`. NOTE: this struct can be
 /// implicitly de-refed into `Params`
@@ -71,6 +111,8 @@ pub struct Config {
 
     /// The number of days to keep data for
     pub retention_period_days: Option<f64>,
+
+    pub cache_size_noise: CacheSizeNoise,
 }
 
 impl Config {
@@ -99,11 +141,12 @@ impl Config {
             disable_upnp: false,
             ram_scale: 1.0,
             retention_period_days: None,
+            cache_size_noise: Default::default(),
         }
     }
 
     pub fn to_builder(&self) -> ConfigBuilder {
-        ConfigBuilder { config: self.clone() }
+        ConfigBuilder { config: self.clone(), custom_genesis: None }
     }
 }
 
@@ -123,11 +166,12 @@ impl Deref for Config {
 
 pub struct ConfigBuilder {
     config: Config,
+    custom_genesis: Option<Block>,
 }
 
 impl ConfigBuilder {
     pub fn new(params: Params) -> Self {
-        Self { config: Config::new(params) }
+        Self { config: Config::new(params), custom_genesis: None }
     }
 
     pub fn set_perf_params(mut self, perf: PerfParams) -> Self {
@@ -140,6 +184,35 @@ impl ConfigBuilder {
         self
     }
 
+    /// Scales down the cache byte budgets so that their sum fits within [`CACHE_MEMORY_FRACTION`] of `total_bytes` of
+    /// available memory. Budgets are never scaled up. `cache_bytes_budget` computes the sum of the budgets of the actual
+    /// store cache policies under a config (see `ConsensusStorage::cache_bytes_budget`). Since minimum item floors and
+    /// item count bounds do not scale, the largest fitting scale is searched for. The budgets are bounded under the current
+    /// RAM scale, hence this should be called after setting it
+    pub fn adjust_perf_params_to_available_memory(mut self, total_bytes: u64, cache_bytes_budget: impl Fn(&Config) -> usize) -> Self {
+        const SEARCH_ROUNDS: usize = 32;
+        let target = total_bytes as f64 * CACHE_MEMORY_FRACTION;
+        let fits = |config: &mut Config, scale: f64| {
+            config.perf.cache_budget_scale = scale;
+            cache_bytes_budget(config) as f64 <= target
+        };
+        let (mut low, mut high) = (0.0, self.config.perf.cache_budget_scale);
+        if fits(&mut self.config, high) {
+            return self;
+        }
+        // The budgets are monotone in the scale, so bisect for the largest fitting scale
+        for _ in 0..SEARCH_ROUNDS {
+            let mid = (low + high) / 2.0;
+            if fits(&mut self.config, mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        self.config.perf.cache_budget_scale = low;
+        self
+    }
+
     pub fn edit_consensus_params<F>(mut self, edit_func: F) -> Self
     where
         F: Fn(&mut Params),
@@ -176,7 +249,140 @@ impl ConfigBuilder {
         self
     }
 
+    /// Replaces the genesis of the consensus params with `genesis` and makes sure it is added on initialization.
+    /// The block is validated to be a well-formed genesis when building, see [`GenesisBlock::try_from`]
+    pub fn add_custom_genesis(mut self, genesis: Block) -> Self {
+        self.config.process_genesis = true;
+        self.custom_genesis = Some(genesis);
+        self
+    }
+
+    pub fn set_cache_size_noise(mut self, cache_size_noise: CacheSizeNoise) -> Self {
+        self.config.cache_size_noise = cache_size_noise;
+        self
+    }
+
+    /// Validates the consensus params and builds the config
+    pub fn try_build(mut self) -> ConfigResult<Config> {
+        if let Some(genesis) = self.custom_genesis.take() {
+            self.config.params.genesis = GenesisBlock::try_from(&genesis)?;
+        }
+        let params = &self.config.params;
+        if params.max_block_level > MAX_BLOCK_LEVEL_UPPER_BOUND {
+            return Err(ConfigError::MaxBlockLevelTooHigh(params.max_block_level, MAX_BLOCK_LEVEL_UPPER_BOUND));
+        }
+        if params.pruning_proof_m == 0 {
+            return Err(ConfigError::ZeroPruningProofM);
+        }
+        let proof_headers = (params.max_block_level as u64 + 1).saturating_mul(params.pruning_proof_m.saturating_mul(2));
+        if proof_headers > MAX_PRUNING_PROOF_HEADERS {
+            return Err(ConfigError::PruningProofTooLarge(params.max_block_level, params.pruning_proof_m, MAX_PRUNING_PROOF_HEADERS));
+        }
+        Ok(self.config)
+    }
+
+    /// Builds the config from params which are known to be valid, such as the predefined network params. Configs built
+    /// from user input should use [`Self::try_build`] and handle the error
     pub fn build(self) -> Config {
-        self.config
+        self.try_build().expect("the consensus params are expected to be valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{
+            genesis::DEVNET_GENESIS,
+            params::{DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET_PARAMS},
+        },
+        header::Header,
+        merkle::calc_hash_merkle_root,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::Transaction,
+    };
+    use kaspa_hashes::Hash;
+
+    #[test]
+    fn test_max_block_level_validation() {
+        for params in [MAINNET_PARAMS, TESTNET_PARAMS, DEVNET_PARAMS, SIMNET_PARAMS] {
+            assert!(ConfigBuilder::new(params).try_build().is_ok());
+        }
+
+        assert_eq!(MAX_BLOCK_LEVEL_UPPER_BOUND, TESTNET_PARAMS.max_block_level);
+        for level in [MAX_BLOCK_LEVEL_UPPER_BOUND + 1, BlockLevel::MAX] {
+            let res = ConfigBuilder::new(MAINNET_PARAMS).edit_consensus_params(|p| p.max_block_level = level).try_build();
+            assert!(matches!(res, Err(ConfigError::MaxBlockLevelTooHigh(l, MAX_BLOCK_LEVEL_UPPER_BOUND)) if l == level));
+        }
+
+        let res = ConfigBuilder::new(MAINNET_PARAMS).edit_consensus_params(|p| p.pruning_proof_m = 0).try_build();
+        assert!(matches!(res, Err(ConfigError::ZeroPruningProofM)));
+
+        let res = ConfigBuilder::new(MAINNET_PARAMS)
+            .edit_consensus_params(|p| {
+                p.max_block_level = MAX_BLOCK_LEVEL_UPPER_BOUND;
+                p.pruning_proof_m = 100_000;
+            })
+            .try_build();
+        assert!(matches!(
+            res,
+            Err(ConfigError::PruningProofTooLarge(MAX_BLOCK_LEVEL_UPPER_BOUND, 100_000, MAX_PRUNING_PROOF_HEADERS))
+        ));
+
+        // A small proof m allows the highest level
+        let res = ConfigBuilder::new(MAINNET_PARAMS)
+            .edit_consensus_params(|p| {
+                p.max_block_level = MAX_BLOCK_LEVEL_UPPER_BOUND;
+                p.pruning_proof_m = 16;
+            })
+            .try_build();
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_custom_genesis() {
+        // A valid custom genesis replaces the params genesis and is processed on initialization
+        let mut custom = DEVNET_GENESIS;
+        custom.timestamp += 1;
+        custom.coinbase_payload = b"custom genesis";
+        let mut block: Block = (&custom).into();
+        let mut header = block.header.as_ref().clone();
+        header.hash_merkle_root = calc_hash_merkle_root(block.transactions.iter(), false);
+        header.finalize();
+        block = Block::new(header, block.transactions.as_ref().clone());
+
+        let config = ConfigBuilder::new(DEVNET_PARAMS).skip_adding_genesis().add_custom_genesis(block.clone()).try_build().unwrap();
+        assert!(config.process_genesis);
+        assert_eq!(config.genesis.hash, block.hash());
+        assert_eq!(config.genesis.coinbase_payload, b"custom genesis");
+        assert_eq!(Block::from(&config.genesis).hash(), block.hash());
+
+        // A genesis with parents is rejected
+        let mut header = block.header.as_ref().clone();
+        header.parents_by_level = vec![vec![Hash::from_u64_word(1)]];
+        header.finalize();
+        let with_parents = Block::new(header, block.transactions.as_ref().clone());
+        let res = ConfigBuilder::new(DEVNET_PARAMS).add_custom_genesis(with_parents.clone()).try_build();
+        assert!(matches!(res, Err(ConfigError::GenesisHasParents(hash)) if hash == with_parents.hash()));
+
+        // A genesis with non-coinbase transactions is rejected
+        let mut txs = block.transactions.as_ref().clone();
+        txs.push(Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]));
+        let res = ConfigBuilder::new(DEVNET_PARAMS).add_custom_genesis(Block::new(block.header.as_ref().clone(), txs)).try_build();
+        assert!(matches!(res, Err(ConfigError::GenesisNotCoinbaseOnly(_))));
+
+        // A genesis whose merkle root does not commit to its transactions is rejected
+        let res = ConfigBuilder::new(DEVNET_PARAMS)
+            .add_custom_genesis(Block::new(Header::from(&custom), block.transactions.as_ref().clone()))
+            .try_build();
+        assert!(matches!(res, Err(ConfigError::GenesisMerkleRootMismatch(_))));
+
+        // A genesis with header fields which are implied for genesis is rejected
+        let mut header = block.header.as_ref().clone();
+        header.blue_score = 1;
+        header.finalize();
+        let res =
+            ConfigBuilder::new(DEVNET_PARAMS).add_custom_genesis(Block::new(header, block.transactions.as_ref().clone())).try_build();
+        assert!(matches!(res, Err(ConfigError::GenesisHeaderMismatch(..))));
     }
 }
@@ -13,6 +13,18 @@ pub enum CoinbaseError {
 
     #[error("coinbase payload length is {0} bytes but it needs to be at least {1} bytes long in order to accommodate the script public key")]
     PayloadCantContainScriptPublicKey(usize, usize),
+
+    #[error("coinbase payload subsidy is {0} while the expected subsidy for DAA score {2} is {1}")]
+    PayloadSubsidyMismatch(u64, u64, u64),
+
+    #[error("miner data encoding length is {0} bytes but it needs to be at least {1} bytes long")]
+    MinerDataEncodingTruncated(usize, usize),
+
+    #[error("miner data encoding length is {0} bytes while the encoded content is {1} bytes long")]
+    MinerDataEncodingTrailingBytes(usize, usize),
+
+    #[error("miner data encoding declares content lengths which overflow the addressable size")]
+    MinerDataEncodingLengthOverflow,
 }
 
 pub type CoinbaseResult<T> = std::result::Result<T, CoinbaseError>;
@@ -1,3 +1,5 @@
+use crate::BlockLevel;
+use kaspa_hashes::Hash;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -17,6 +19,27 @@ pub enum ConfigError {
     #[error("Configuration: --max-tracked-addresses cannot be set above {0}")]
     MaxTrackedAddressesTooHigh(usize),
 
+    #[error("Consensus params: max block level {0} cannot be set above {1}")]
+    MaxBlockLevelTooHigh(BlockLevel, BlockLevel),
+
+    #[error("Consensus params: pruning proof m cannot be zero")]
+    ZeroPruningProofM,
+
+    #[error("Consensus params: max block level {0} with pruning proof m {1} exceeds the pruning proof size bound of {2} headers")]
+    PruningProofTooLarge(BlockLevel, u64, u64),
+
+    #[error("Custom genesis: block {0} has parents")]
+    GenesisHasParents(Hash),
+
+    #[error("Custom genesis: block {0} is expected to contain a single coinbase transaction with no inputs nor outputs")]
+    GenesisNotCoinbaseOnly(Hash),
+
+    #[error("Custom genesis: block {0} has a hash merkle root which does not match its transactions")]
+    GenesisMerkleRootMismatch(Hash),
+
+    #[error("Custom genesis: block {0} has header fields which cannot be part of a genesis header (expected hash {1})")]
+    GenesisHeaderMismatch(Hash, Hash),
+
     #[cfg(feature = "devnet-prealloc")]
     #[error("Cannot preallocate UTXOs on any network except devnet")]
     PreallocUtxosOnNonDevnet,
@@ -17,6 +17,9 @@ pub enum ConsensusError {
     #[error("some data is missing for block {0}")]
     MissingData(Hash),
 
+    #[error("block {0} is not a chain ancestor of block {1}")]
+    NotChainAncestor(Hash, Hash),
+
     #[error("got unexpected pruning point")]
     UnexpectedPruningPoint,
 
@@ -10,6 +10,9 @@ pub enum DifficultyError {
 
     #[error("min window timestamp is equal to the max window timestamp")]
     EmptyTimestampRange,
+
+    #[error("cannot calculate the average target of an empty window")]
+    EmptyWindow,
 }
 
 pub type DifficultyResult<T> = std::result::Result<T, DifficultyError>;
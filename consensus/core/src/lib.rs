@@ -95,6 +95,18 @@ pub struct ChainPath {
     pub removed: Vec<Hash>,
 }
 
+/// Describes the most recent change of the virtual selected chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgInfo {
+    /// The most recent chain block common to both the previous and the new selected chains
+    pub common_ancestor: Hash,
+    /// The number of chain blocks rolled back from the previous selected chain (i.e., the reorg depth).
+    /// Zero if the new chain is a simple extension of the previous one
+    pub removed_chain_len: usize,
+    /// The number of chain blocks added on top of the common ancestor
+    pub added_chain_len: usize,
+}
+
 /// `hashes::Hash` writes 4 u64s so we just use the last one as the hash here
 #[derive(Default, Clone, Copy)]
 pub struct BlockHasher(u64);
@@ -2,7 +2,7 @@ use crate::{
     config::params::Params,
     constants::TRANSIENT_BYTE_TO_MASS_FACTOR,
     subnets::SUBNETWORK_ID_SIZE,
-    tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutput, UtxoEntry, VerifiableTransaction},
+    tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionInput, TransactionOutput, UtxoEntry, VerifiableTransaction},
 };
 use kaspa_hashes::HASH_SIZE;
 
@@ -283,6 +283,31 @@ impl MassCalculator {
         )
         .map(ContextualMasses::new)
     }
+
+    /// Calculates the overall mass of this transaction, i.e., the maximum over all masses, which is the mass
+    /// a transaction fee must be computed against (see [`ContextualMasses::max`]).
+    ///
+    /// If not all UTXO entries are populated, the storage mass cannot be calculated, in which case only the
+    /// non-contextual masses are accounted for and the returned value should be considered a lower bound.
+    ///
+    /// Returns `None` if the storage mass is incomputable, either since the transaction does not meet the
+    /// assumptions of [`calc_storage_mass`] (a non-coinbase transaction with no inputs or with zero-value inputs
+    /// or outputs) or since the mass is too high
+    pub fn calc_overall_mass<T: AsRef<Transaction>>(&self, tx: &MutableTransaction<T>) -> Option<u64> {
+        let non_contextual_masses = self.calc_non_contextual_masses(tx.tx.as_ref());
+        if !tx.is_verifiable() {
+            return Some(non_contextual_masses.max());
+        }
+        let inner = tx.tx.as_ref();
+        if !inner.is_coinbase()
+            && (inner.inputs.is_empty()
+                || inner.outputs.iter().any(|output| output.value == 0)
+                || tx.entries.iter().flatten().any(|entry| entry.amount == 0))
+        {
+            return None;
+        }
+        self.calc_contextual_masses(&tx.as_verifiable()).map(|contextual_masses| contextual_masses.max(non_contextual_masses))
+    }
 }
 
 /// Calculates the storage mass (KIP-0009) for a given set of inputs and outputs.
@@ -629,6 +654,40 @@ mod tests {
         ScriptPublicKey::from_vec(0, vec![1; required_script_len])
     }
 
+    #[test]
+    fn test_overall_mass() {
+        let mass_calculator = MassCalculator::new(1, 10, 1000, 10u64.pow(12));
+        let mut tx = generate_tx_from_amounts(&[100, 200], &[50, 250]);
+
+        // The storage mass of this 2:2 transaction is known (see test_storage_mass) and dominates the non-contextual masses
+        let non_contextual_masses = mass_calculator.calc_non_contextual_masses(&tx.tx);
+        assert!(non_contextual_masses.max() < 9000000000);
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), Some(9000000000));
+
+        // Without storage mass, the overall mass is the max over the non-contextual masses
+        let size = transaction_estimated_serialized_size(&tx.tx);
+        let expected_compute_mass = size + 2 * 2 * 10; // size + 2 outputs with empty script public keys, no sig ops
+        let expected_transient_mass = size * TRANSIENT_BYTE_TO_MASS_FACTOR;
+        assert_eq!(non_contextual_masses, NonContextualMasses::new(expected_compute_mass, expected_transient_mass));
+        tx.tx.outputs[0].value = 100;
+        tx.tx.outputs[1].value = 200;
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), Some(expected_compute_mass.max(expected_transient_mass)));
+
+        // An unpopulated transaction only accounts for the non-contextual masses
+        tx.tx.outputs[0].value = 50;
+        tx.tx.outputs[1].value = 250;
+        tx.entries[1] = None;
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), Some(non_contextual_masses.max()));
+
+        // Populated transactions which violate the storage mass assumptions are incomputable rather than panicking
+        let tx = generate_tx_from_amounts(&[100, 200], &[0, 300]);
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), None);
+        let tx = generate_tx_from_amounts(&[0, 300], &[100, 200]);
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), None);
+        let tx = generate_tx_from_amounts(&[], &[100, 200]);
+        assert_eq!(mass_calculator.calc_overall_mass(&tx), None);
+    }
+
     #[test]
     fn test_storage_mass() {
         // Tx with less outs than ins
@@ -61,7 +61,13 @@ impl UtxoEntry {
     }
 }
 
-impl MemSizeEstimator for UtxoEntry {}
+impl MemSizeEstimator for UtxoEntry {
+    fn estimate_mem_bytes(&self) -> usize {
+        // Scripts exceeding the inline capacity of the script vector are spilled to the heap
+        let script = &self.script_public_key.script;
+        size_of::<Self>() + if script.spilled() { script.capacity() } else { 0 }
+    }
+}
 
 pub type TransactionIndexType = u32;
 
@@ -86,6 +92,17 @@ impl Display for TransactionOutpoint {
     }
 }
 
+impl MemSizeEstimator for TransactionOutpoint {
+    fn estimate_mem_bytes(&self) -> usize {
+        size_of::<Self>()
+    }
+}
+
+/// Estimates the memory size of a UTXO record, i.e., of an entry along with its outpoint key
+pub fn utxo_record_mem_bytes(entry: &UtxoEntry) -> usize {
+    size_of::<TransactionOutpoint>() + entry.estimate_mem_bytes()
+}
+
 /// Represents a Kaspa transaction input
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -713,6 +730,22 @@ mod tests {
         assert_eq!(spk, spk2);
     }
 
+    #[test]
+    fn test_utxo_entry_mem_bytes() {
+        let entry_size = size_of::<UtxoEntry>();
+        // Scripts fitting the inline capacity add no heap bytes
+        for script_len in [0, 34, SCRIPT_VECTOR_SIZE] {
+            let entry = UtxoEntry::new(1, ScriptPublicKey::from_vec(0, vec![1; script_len]), 0, false);
+            assert_eq!(entry.estimate_mem_bytes(), entry_size);
+        }
+        // Longer scripts are spilled to the heap
+        let entry = UtxoEntry::new(1, ScriptPublicKey::from_vec(0, vec![1; 67]), 0, false);
+        assert_eq!(entry.estimate_mem_bytes(), entry_size + 67);
+
+        assert_eq!(TransactionOutpoint::default().estimate_mem_bytes(), 36);
+        assert_eq!(utxo_record_mem_bytes(&entry), 36 + entry_size + 67);
+    }
+
     // use wasm_bindgen_test::wasm_bindgen_test;
     // #[wasm_bindgen_test]
     // pub fn test_wasm_serde_spk_constructor() {
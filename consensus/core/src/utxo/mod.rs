@@ -1,3 +1,4 @@
+pub mod prealloc;
 pub mod utxo_collection;
 pub mod utxo_diff;
 pub mod utxo_error;
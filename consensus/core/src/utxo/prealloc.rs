@@ -0,0 +1,34 @@
+use super::utxo_collection::UtxoCollection;
+use crate::tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry};
+
+/// Deterministically generates a collection of `count` non-coinbase UTXOs of `amount` each, all locked by `script_public_key`.
+/// Outpoints use the transaction ids `1..=count` (as encoded by `TransactionId::from(u64)`) with output index 0, so the same
+/// arguments always result in the same collection. Useful for preallocating a devnet genesis UTXO set or for test setups
+pub fn generate_prealloc_utxos(count: u64, amount: u64, script_public_key: &ScriptPublicKey) -> UtxoCollection {
+    (1..=count)
+        .map(|i| {
+            (
+                TransactionOutpoint { transaction_id: i.into(), index: 0 },
+                UtxoEntry { amount, script_public_key: script_public_key.clone(), block_daa_score: 0, is_coinbase: false },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_prealloc_utxos() {
+        let spk = ScriptPublicKey::from_vec(0, vec![0x51]);
+        let utxos = generate_prealloc_utxos(100, 1_000, &spk);
+        assert_eq!(utxos.len(), 100);
+        assert_eq!(utxos.values().map(|entry| entry.amount).sum::<u64>(), 100_000);
+        assert!(utxos.values().all(|entry| entry.script_public_key == spk && !entry.is_coinbase));
+
+        // Generation is deterministic
+        assert_eq!(generate_prealloc_utxos(100, 1_000, &spk), utxos);
+        assert!(generate_prealloc_utxos(0, 1_000, &spk).is_empty());
+    }
+}
@@ -7,6 +7,25 @@ use kaspa_utils::mem_size::MemSizeEstimator;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry::Vacant;
 
+/// Counts of UTXO entries processed while applying one diff onto another
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct UtxoDiffApplyCounts {
+    /// The number of entries added by the applied diff
+    pub adds: u64,
+    /// The number of entries removed by the applied diff
+    pub removes: u64,
+    /// The number of applied entries which canceled out an opposite entry already in the diff
+    pub conflicts: u64,
+}
+
+impl core::ops::AddAssign for UtxoDiffApplyCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.adds += rhs.adds;
+        self.removes += rhs.removes;
+        self.conflicts += rhs.conflicts;
+    }
+}
+
 pub trait ImmutableUtxoDiff {
     fn added(&self) -> &UtxoCollection;
     fn removed(&self) -> &UtxoCollection;
@@ -85,6 +104,11 @@ impl UtxoDiff {
     /// Applies the provided diff to this diff in-place. This is equal to if the
     /// first diff, and then the second diff were applied to the same base UTXO set
     pub fn with_diff_in_place(&mut self, other: &impl ImmutableUtxoDiff) -> UtxoResult<()> {
+        self.with_diff_in_place_counted(other).map(|_| ())
+    }
+
+    /// Same as [`Self::with_diff_in_place`], but additionally returns counts of the processed entries
+    pub fn with_diff_in_place_counted(&mut self, other: &impl ImmutableUtxoDiff) -> UtxoResult<UtxoDiffApplyCounts> {
         // TODO: should we apply the sanity checks below only in Debug mode?
         if let Some(offending_outpoint) =
             other.removed().intersects_with_rule(&self.remove, |outpoint, entry_to_add, _existing_entry| {
@@ -100,6 +124,7 @@ impl UtxoDiff {
             return Err(UtxoAlgebraError::DuplicateAddPoint(offending_outpoint));
         }
 
+        let mut counts = UtxoDiffApplyCounts { adds: other.added().len() as u64, removes: other.removed().len() as u64, conflicts: 0 };
         let mut intersection = UtxoCollection::new();
 
         // If does not exist neither in `add` nor in `remove` - add to `remove`
@@ -107,6 +132,7 @@ impl UtxoDiff {
         // If already exists in `add` with the same DAA score - remove from `add`
         self.add.remove_collection(&intersection);
 
+        counts.conflicts += intersection.len() as u64;
         intersection.clear();
 
         // If does not exist neither in `add` nor in `remove`, or exists in `remove' with different DAA score - add to 'add'
@@ -114,7 +140,8 @@ impl UtxoDiff {
         // If already exists in `remove` with the same DAA score - remove from `remove`
         self.remove.remove_collection(&intersection);
 
-        Ok(())
+        counts.conflicts += intersection.len() as u64;
+        Ok(counts)
     }
 
     /// Returns a new UTXO diff with the difference between this diff and another
@@ -567,4 +594,37 @@ mod tests {
         drop(utxo_entry1);
         drop(utxo_entry2);
     }
+
+    #[test]
+    fn test_with_diff_counts() {
+        use crate::api::counters::ProcessingCounters;
+
+        let tx_id = TransactionId::from_str("0".repeat(64).as_str()).unwrap();
+        let [outpoint0, outpoint1, outpoint2] = [0, 1, 2].map(|index| TransactionOutpoint::new(tx_id, index));
+        let entry = UtxoEntry::new(10, ScriptPublicKey::default(), 0, true);
+
+        let mut diff =
+            UtxoDiff::new(UtxoCollection::from([(outpoint0, entry.clone())]), UtxoCollection::from([(outpoint1, entry.clone())]));
+        // Removing outpoint0 and re-adding outpoint1 cancel out existing entries, while outpoint2 is a plain add
+        let other = UtxoDiff::new(
+            UtxoCollection::from([(outpoint1, entry.clone()), (outpoint2, entry.clone())]),
+            UtxoCollection::from([(outpoint0, entry.clone())]),
+        );
+
+        let counts = diff.with_diff_in_place_counted(&other).unwrap();
+        assert_eq!(counts, UtxoDiffApplyCounts { adds: 2, removes: 1, conflicts: 2 });
+        assert_eq!(diff, UtxoDiff::new(UtxoCollection::from([(outpoint2, entry.clone())]), UtxoCollection::new()));
+
+        // Applying the reversed diff restores the original and is counted as well
+        let reversed_counts = diff.with_diff_in_place_counted(&other.as_reversed()).unwrap();
+        assert_eq!(reversed_counts, UtxoDiffApplyCounts { adds: 1, removes: 2, conflicts: 1 });
+
+        let counters = ProcessingCounters::default();
+        counters.add_utxo_diff_counts(counts);
+        counters.add_utxo_diff_counts(reversed_counts);
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.utxo_diff_add_counts, 3);
+        assert_eq!(snapshot.utxo_diff_remove_counts, 3);
+        assert_eq!(snapshot.utxo_diff_conflict_counts, 3);
+    }
 }
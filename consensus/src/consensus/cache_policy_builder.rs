@@ -1,23 +1,34 @@
+use kaspa_consensus_core::config::CacheSizeNoise;
 use kaspa_database::prelude::CachePolicy;
 use kaspa_utils::mem_size::MemMode;
-use rand::Rng;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 /// Adds stochastic noise to cache sizes to avoid predictable and equal sizes across all network nodes
-fn noise(size: usize, magnitude: usize) -> usize {
+fn noise(mode: CacheSizeNoise, size: usize, magnitude: usize) -> usize {
     if size == 0 {
         // no noise if original size is zero
-        size
-    } else {
-        size + rand::thread_rng().gen_range(0..16) * magnitude
+        return size;
+    }
+    match mode {
+        CacheSizeNoise::Random => size.saturating_add(rand::thread_rng().gen_range(0..16) * magnitude),
+        // Mix the size into the seed so that different caches still get different noise
+        CacheSizeNoise::Seeded(seed) => size.saturating_add(SmallRng::seed_from_u64(seed ^ size as u64).gen_range(0..16) * magnitude),
+        CacheSizeNoise::Disabled => size,
     }
 }
 
-/// Bounds the size according to the "memory budget" (represented in bytes) and the approximate size of each unit in bytes
-fn bounded_size(desired_units: usize, memory_budget_bytes: usize, approx_unit_bytes: usize) -> usize {
-    let max_size = memory_budget_bytes / approx_unit_bytes;
-    usize::min(desired_units, max_size)
+/// Bounds the size according to the "memory budget" (represented in bytes) and the approximate size of each unit in bytes.
+///
+/// The result never exceeds `memory_budget_bytes / approx_unit_bytes` (a zero unit size is treated as a single byte),
+/// unless `min_units` is larger, in which case `min_units` is returned. The floor takes precedence over the budget so that
+/// small budgets (e.g. due to RAM scaling or level downscaling) never bound a cache down to zero when items are required
+fn bounded_size(desired_units: usize, memory_budget_bytes: usize, approx_unit_bytes: usize, min_units: usize) -> usize {
+    // Division cannot overflow, and comparing against the desired units avoids ever computing `desired_units * approx_unit_bytes`
+    let max_size = memory_budget_bytes / approx_unit_bytes.max(1);
+    usize::min(desired_units, max_size).max(min_units)
 }
 
+#[derive(Clone, Copy)]
 pub struct CachePolicyBuilder {
     bytes_budget: usize,
     max_items: usize,
@@ -25,6 +36,7 @@ pub struct CachePolicyBuilder {
     unit_bytes: Option<usize>,
     tracked: bool,
     mem_mode: MemMode,
+    noise: CacheSizeNoise,
 }
 
 impl Default for CachePolicyBuilder {
@@ -42,9 +54,16 @@ impl CachePolicyBuilder {
             unit_bytes: None,
             tracked: false,
             mem_mode: MemMode::Undefined,
+            noise: CacheSizeNoise::Random,
         }
     }
 
+    /// Sets the noise mode applied to the built cache sizes
+    pub fn noise(mut self, noise: CacheSizeNoise) -> Self {
+        self.noise = noise;
+        self
+    }
+
     pub fn bytes_budget(mut self, bytes_budget: usize) -> Self {
         self.bytes_budget = bytes_budget;
         self
@@ -88,15 +107,33 @@ impl CachePolicyBuilder {
 
     /// Downscale the upper-bound constants by a factor of `2^level`
     pub fn downscale(&self, level: u8) -> Self {
+        self.downscale_by(level, 1)
+    }
+
+    /// Downscale the upper-bound constants by a factor of `2^(level * shift)`. The `min_items` floor is unaffected
+    pub fn downscale_by(&self, level: u8, shift: u8) -> Self {
         // Downscale both upper-bound limits unless they have the initial MAX value.
-        // The calc is equal to downscaled = budget / 2^level
+        // The calc is equal to downscaled = budget / 2^(level * shift), and is zero once the shift exceeds the bit width
+        let shift = level as u32 * shift as u32;
         let bytes_budget =
-            if self.bytes_budget == usize::MAX { self.bytes_budget } else { self.bytes_budget.checked_shr(level as u32).unwrap_or(0) };
-        let max_items =
-            if self.max_items == usize::MAX { self.max_items } else { self.max_items.checked_shr(level as u32).unwrap_or(0) };
+            if self.bytes_budget == usize::MAX { self.bytes_budget } else { self.bytes_budget.checked_shr(shift).unwrap_or(0) };
+        let max_items = if self.max_items == usize::MAX { self.max_items } else { self.max_items.checked_shr(shift).unwrap_or(0) };
         Self { bytes_budget, max_items, ..*self }
     }
 
+    /// The bytes the built policy may hold (noise aside): its byte budget, or the bytes of the `min_items` floor if larger.
+    /// Caches with no byte budget are bounded by item counts only and are accounted as zero
+    pub fn budget_bytes(&self) -> usize {
+        if self.bytes_budget == usize::MAX {
+            return 0;
+        }
+        let unit_bytes = self.unit_bytes.unwrap_or(0);
+        if matches!(self.mem_mode, MemMode::Bytes) {
+            return self.bytes_budget.max(self.min_items.saturating_mul(unit_bytes));
+        }
+        bounded_size(self.max_items, self.bytes_budget, unit_bytes, self.min_items).saturating_mul(unit_bytes)
+    }
+
     pub fn build(&self) -> CachePolicy {
         assert!(self.max_items < usize::MAX || self.bytes_budget < usize::MAX, "max_items or bytes_budget are expected");
 
@@ -105,8 +142,8 @@ impl CachePolicyBuilder {
                 MemMode::Bytes => {
                     assert!(self.max_items == usize::MAX, "max_items is not supported in tracked bytes mode");
                     CachePolicy::Tracked {
-                        max_size: noise(self.bytes_budget, 512), // 0.5KB noise magnitude
-                        min_items: noise(self.min_items, 1),
+                        max_size: noise(self.noise, self.bytes_budget, 512), // 0.5KB noise magnitude
+                        min_items: noise(self.noise, self.min_items, 1),
                         mem_mode: MemMode::Bytes,
                     }
                 }
@@ -118,11 +155,12 @@ impl CachePolicyBuilder {
                             self.max_items,
                             self.bytes_budget,
                             self.unit_bytes.expect("unit_bytes are expected with bytes_budget in units mem mode"),
+                            0, // Tracked caches already respect `min_items` on eviction
                         )
                     };
                     CachePolicy::Tracked {
-                        max_size: noise(max_items, 1),
-                        min_items: noise(self.min_items, 1),
+                        max_size: noise(self.noise, max_items, 1),
+                        min_items: noise(self.noise, self.min_items, 1),
                         mem_mode: MemMode::Units,
                     }
                 }
@@ -130,15 +168,115 @@ impl CachePolicyBuilder {
             }
         } else {
             let max_items = if self.bytes_budget == usize::MAX {
-                self.max_items
+                self.max_items.max(self.min_items)
             } else {
                 bounded_size(
                     self.max_items,
                     self.bytes_budget,
                     self.unit_bytes.expect("unit_bytes are expected with bytes_budget in non-tracked mode"),
+                    self.min_items,
                 )
             };
-            CachePolicy::Count(noise(max_items.max(self.min_items), 1))
+            CachePolicy::Count(noise(self.noise, max_items, 1))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(policy: CachePolicy) -> (usize, usize) {
+        match policy {
+            CachePolicy::Count(max_items) => (max_items, 0),
+            CachePolicy::Tracked { max_size, min_items, .. } => (max_size, min_items),
+            CachePolicy::Empty => (0, 0),
+        }
+    }
+
+    #[test]
+    fn test_cache_size_noise() {
+        let builders = [
+            CachePolicyBuilder::new().max_items(1000).untracked(),
+            CachePolicyBuilder::new().bytes_budget(1_000_000).unit_bytes(100).min_items(20).tracked_units(),
+            CachePolicyBuilder::new().bytes_budget(1_000_000).min_items(20).tracked_bytes(),
+        ];
+        let expected = [(1000, 0), (10_000, 20), (1_000_000, 20)];
+
+        for (builder, expected) in builders.iter().zip(expected) {
+            // Disabled noise yields the exact computed sizes
+            let disabled = builder.noise(CacheSizeNoise::Disabled);
+            assert_eq!(sizes(disabled.build()), expected);
+            assert_eq!(sizes(disabled.downscale(1).build()), sizes(disabled.downscale(1).build()));
+
+            // Seeded noise is reproducible and bounded by the noise magnitude
+            let seeded = builder.noise(CacheSizeNoise::Seeded(42));
+            let (max_size, min_items) = sizes(seeded.build());
+            assert_eq!((max_size, min_items), sizes(seeded.build()));
+            assert!(max_size >= expected.0 && max_size < expected.0 + 16 * 512);
+            assert!(min_items >= expected.1 && min_items < expected.1 + 16);
+        }
+    }
+
+    #[test]
+    fn test_downscale_by() {
+        const MIN_ITEMS: usize = 100;
+        let untracked = CachePolicyBuilder::new()
+            .noise(CacheSizeNoise::Disabled)
+            .bytes_budget(1 << 20)
+            .unit_bytes(32)
+            .min_items(MIN_ITEMS)
+            .untracked();
+        let tracked = CachePolicyBuilder::new()
+            .noise(CacheSizeNoise::Disabled)
+            .bytes_budget(1 << 20)
+            .unit_bytes(32)
+            .min_items(MIN_ITEMS)
+            .tracked_units();
+
+        // A unit shift is equivalent to the default downscale
+        for level in 0..=16 {
+            assert_eq!(sizes(untracked.downscale_by(level, 1).build()), sizes(untracked.downscale(level).build()));
+            assert_eq!(sizes(tracked.downscale_by(level, 1).build()), sizes(tracked.downscale(level).build()));
+        }
+
+        for shift in 0..=4u8 {
+            for level in 0..=255u8 {
+                let expected = ((1usize << 15).checked_shr(level as u32 * shift as u32).unwrap_or(0)).max(MIN_ITEMS);
+                assert_eq!(sizes(untracked.downscale_by(level, shift).build()), (expected, 0));
+
+                // Tracked caches keep the floor as their eviction lower bound
+                let (max_size, min_items) = sizes(tracked.downscale_by(level, shift).build());
+                assert_eq!(max_size.max(min_items), expected);
+                assert_eq!(min_items, MIN_ITEMS);
+            }
+        }
+
+        // A zero shift keeps the full budget for all levels
+        assert_eq!(sizes(untracked.downscale_by(255, 0).build()), (1 << 15, 0));
+    }
+
+    #[test]
+    fn test_bounded_size() {
+        // Overflow-prone inputs: `desired * unit` would overflow but the bound is computed by division only
+        assert_eq!(bounded_size(usize::MAX, usize::MAX, 1, 0), usize::MAX);
+        assert_eq!(bounded_size(usize::MAX, usize::MAX, 128, 0), usize::MAX / 128);
+        assert_eq!(bounded_size(usize::MAX / 2, 1 << 30, usize::MAX, 0), 0);
+        assert_eq!(bounded_size(usize::MAX / 3, 200_000_000, 1 << 20, 0), 190);
+
+        // Zero unit size is treated as a single byte
+        assert_eq!(bounded_size(1000, 100, 0, 0), 100);
+
+        // The result never exceeds the budget bound nor the desired units when no floor is requested
+        for (desired, budget, unit) in [(10, 1000, 7), (1_000_000, 1000, 3), (usize::MAX, 1 << 20, 33), (5, 0, 8)] {
+            let bounded = bounded_size(desired, budget, unit, 0);
+            assert!(bounded <= budget / unit);
+            assert!(bounded <= desired);
+        }
+
+        // The floor prevents bounding down to zero
+        assert_eq!(bounded_size(usize::MAX, 10, 100, 0), 0);
+        assert_eq!(bounded_size(usize::MAX, 10, 100, 4), 4);
+        assert_eq!(bounded_size(1000, 100_000, 10, 4), 1000);
+    }
+}
@@ -354,10 +354,12 @@ impl ConsensusFactory for Factory {
             .build()
             .unwrap();
 
+        let mut config = self.config.clone();
+        config.process_genesis = false;
         let session_lock = SessionLock::new();
         let consensus = Arc::new(Consensus::new(
             db.clone(),
-            Arc::new(self.config.to_builder().skip_adding_genesis().build()),
+            Arc::new(config),
             session_lock.clone(),
             self.notification_root.clone(),
             self.counters.clone(),
@@ -1,6 +1,7 @@
 pub mod cache_policy_builder;
 pub mod ctl;
 pub mod factory;
+mod self_check;
 pub mod services;
 pub mod storage;
 pub mod test_consensus;
@@ -39,16 +40,18 @@ use crate::{
         ProcessingCounters,
     },
     processes::{
+        difficulty::calc_work,
         ghostdag::ordering::SortableBlock,
         window::{WindowManager, WindowType},
     },
 };
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
+    acceptance_data::{AcceptanceData, AcceptedTransaction, AcceptedTransactionsData, MergesetBlockAcceptedTransactions},
     api::{
         args::{TransactionValidationArgs, TransactionValidationBatchArgs},
         stats::BlockCount,
-        BlockValidationFutures, ConsensusApi, ConsensusStats,
+        BlockValidationFutures, ConsensusApi, ConsensusStats, DagIntegrityError, DagStats, DifficultyInfo, SelfCheckReport, TipsInfo,
+        UtxoSetSummary, ValidationResult,
     },
     block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     blockhash::BlockHashExtensions,
@@ -70,9 +73,9 @@ use kaspa_consensus_core::{
     network::NetworkType,
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList, PruningProofMetadata},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, SignableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
     utxo::utxo_inquirer::UtxoInquirerError,
-    BlockHashSet, BlueWorkType, ChainPath, HashMapCustomHasher,
+    BlockHashSet, BlueWorkType, ChainPath, HashMapCustomHasher, ReorgInfo,
 };
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 
@@ -84,13 +87,14 @@ use kaspa_consensusmanager::{SessionLock, SessionReadGuard};
 
 use kaspa_database::prelude::{StoreResultEmptyTuple, StoreResultExtensions};
 use kaspa_hashes::Hash;
+use kaspa_math::Uint256;
 use kaspa_muhash::MuHash;
 use kaspa_txscript::caches::TxScriptCacheCounters;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use std::{
     cmp::Reverse,
-    collections::{BinaryHeap, VecDeque},
+    collections::{BinaryHeap, HashSet, VecDeque},
     future::Future,
     iter::once,
     ops::Deref,
@@ -372,7 +376,7 @@ impl Consensus {
         let mut processed = 0;
         kaspa_core::info!("Upgrading database to include and populate the pruning samples store");
         while let Some(current) = queue.pop_front() {
-            if !self.get_block_status(current).is_some_and(|s| s == BlockStatus::StatusUTXOValid) {
+            if !self.get_block_status(current).is_some_and(|s| s.is_utxo_valid()) {
                 // Skip branches of the tree which are not chain qualified.
                 // This is sufficient since we will only assume this field exists
                 // for such chain qualified blocks
@@ -423,6 +427,23 @@ impl Consensus {
         (async { brx.await.unwrap() }, async { vrx.await.unwrap() })
     }
 
+    fn validate_and_insert_blocks_impl(&self, tasks: impl ExactSizeIterator<Item = BlockTask>) -> Vec<BlockValidationFutures> {
+        let len = tasks.len();
+        let futures = tasks
+            .map(|task| {
+                let (btx, brx): (BlockResultSender, _) = oneshot::channel();
+                let (vtx, vrx): (BlockResultSender, _) = oneshot::channel();
+                self.block_sender.send(BlockProcessingMessage::Process(task, btx, vtx)).unwrap();
+                BlockValidationFutures {
+                    block_task: Box::pin(async { brx.await.unwrap() }),
+                    virtual_state_task: Box::pin(async { vrx.await.unwrap() }),
+                }
+            })
+            .collect();
+        self.counters.blocks_submitted.fetch_add(len as u64, Ordering::Relaxed);
+        futures
+    }
+
     pub fn body_tips(&self) -> BlockHashSet {
         self.body_tips_store.read().get().unwrap().read().clone()
     }
@@ -468,6 +489,39 @@ impl Consensus {
         }
     }
 
+    /// Returns the chain block merging `hash` (i.e., the chain block whose mergeset contains `hash`) along with its selected
+    /// chain index and the index of the selected chain tip. Returns `None` if the block is only merged by virtual, or if it is
+    /// unknown or out of the retention period. The caller is expected to hold the pruning lock
+    fn merging_chain_block(&self, hash: Hash) -> Option<(Hash, u64, u64)> {
+        self.validate_block_exists(hash).ok()?;
+
+        let retention_period_root = self.get_retention_period_root();
+        self.services.reachability_service.is_dag_ancestor_of(retention_period_root, hash).then_some(())?;
+
+        let sc_read = self.selected_chain_store.read();
+        let (tip_index, sink) = sc_read.get_tip().unwrap();
+
+        // Blocks outside of past(sink) (or the sink itself) are only merged by virtual
+        (hash != sink && self.services.reachability_service.is_dag_ancestor_of(hash, sink)).then_some(())?;
+
+        // Binary search for the lowest chain block which has `hash` in its past. Since the selected chain
+        // is ordered topologically, the predicate is monotonic along chain indices
+        let in_strict_past = |index: u64| {
+            let chain_block = sc_read.get_by_index(index).unwrap();
+            chain_block != hash && self.services.reachability_service.is_dag_ancestor_of(hash, chain_block)
+        };
+        let (mut lo, mut hi) = (sc_read.get_by_hash(retention_period_root).unwrap_option().unwrap_or(tip_index), tip_index);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if in_strict_past(mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some((sc_read.get_by_index(lo).unwrap(), lo, tip_index))
+    }
+
     fn estimate_network_hashes_per_second_impl(&self, ghostdag_data: &GhostdagData, window_size: usize) -> ConsensusResult<u64> {
         let window = match self.services.window_manager.block_window(ghostdag_data, WindowType::VaryingWindow(window_size)) {
             Ok(w) => w,
@@ -503,6 +557,10 @@ impl ConsensusApi for Consensus {
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
     }
 
+    fn validate_and_insert_blocks(&self, blocks: Vec<Block>) -> Vec<BlockValidationFutures> {
+        self.validate_and_insert_blocks_impl(blocks.into_iter().map(|block| BlockTask::Ordinary { block }))
+    }
+
     fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
         let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Trusted { block: tb.block });
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
@@ -538,6 +596,10 @@ impl ConsensusApi for Consensus {
         self.services.mass_calculator.calc_contextual_masses(&transaction.as_verifiable())
     }
 
+    fn calculate_transaction_mass(&self, transaction: &MutableTransaction) -> Option<u64> {
+        self.services.mass_calculator.calc_overall_mass(transaction)
+    }
+
     fn get_stats(&self) -> ConsensusStats {
         // This method is designed to return stats asap and not depend on locks which
         // might take time to acquire
@@ -550,6 +612,15 @@ impl ConsensusApi for Consensus {
         }
     }
 
+    fn dag_stats(&self, hashes: &[Hash]) -> DagStats {
+        DagStats::from_block_counts(hashes.iter().filter_map(|&hash| {
+            let ghostdag_data = self.ghostdag_store.get_data(hash).unwrap_option()?;
+            let num_parents = self.headers_store.get_header(hash).unwrap_option()?.direct_parents().len();
+            let num_txs = self.block_transactions_store.get(hash).unwrap_option().map(|txs| txs.len()).unwrap_or_default();
+            Some((ghostdag_data.mergeset_blues.len(), ghostdag_data.mergeset_reds.len(), num_parents, num_txs))
+        }))
+    }
+
     fn get_virtual_daa_score(&self) -> u64 {
         self.lkg_virtual_state.load().daa_score
     }
@@ -584,6 +655,10 @@ impl ConsensusApi for Consensus {
         self.lkg_virtual_state.load().ghostdag_data.selected_parent
     }
 
+    fn last_reorg_info(&self) -> Option<ReorgInfo> {
+        self.virtual_processor.last_reorg_info()
+    }
+
     fn get_sink_timestamp(&self) -> u64 {
         self.headers_store.get_timestamp(self.get_sink()).unwrap()
     }
@@ -652,6 +727,70 @@ impl ConsensusApi for Consensus {
         None
     }
 
+    fn acceptance_depth(&self, hash: Hash) -> Option<u64> {
+        // We need consistency between the retention root, selected chain and reachability reads
+        let _guard = self.pruning_lock.blocking_read();
+        let (_, merging_index, tip_index) = self.merging_chain_block(hash)?;
+        Some(tip_index - merging_index)
+    }
+
+    fn get_block_accepted_transaction_ids(&self, hash: Hash) -> Option<Vec<TransactionId>> {
+        let _guard = self.pruning_lock.blocking_read();
+        let transactions = self.block_transactions_store.get(hash).unwrap_option()?;
+
+        // Blocks merged by virtual have their acceptance data held by the virtual state
+        let virtual_state = self.lkg_virtual_state.load();
+        if virtual_state.ghostdag_data.unordered_mergeset().any(|merged| merged == hash) {
+            let accepted: HashSet<TransactionId> = virtual_state.accepted_tx_ids.iter().copied().collect();
+            return Some(transactions.iter().map(|tx| tx.id()).filter(|id| accepted.contains(id)).collect());
+        }
+
+        let (merging_block, _, _) = self.merging_chain_block(hash)?;
+        let acceptance_data = self.acceptance_data_store.get(merging_block).unwrap_option()?;
+        let block_acceptance_data = acceptance_data.iter().find(|data| data.block_hash == hash)?;
+        Some(block_acceptance_data.accepted_transactions.iter().map(|entry| entry.transaction_id).collect())
+    }
+
+    fn accepting_block_of(&self, tx_id: TransactionId, depth: u64) -> Option<Hash> {
+        // We need consistency between the selected chain and acceptance data reads
+        let _guard = self.pruning_lock.blocking_read();
+        let sc_read = self.selected_chain_store.read();
+        let (tip_index, _) = sc_read.get_tip().unwrap();
+
+        // Bound the time the locks are held, see the chain batch size of the RPC `get_virtual_chain_from_block` call
+        let depth = depth.min(self.config.mergeset_size_limit().upper_bound() * 10);
+
+        // Walk down the selected chain from the sink, stopping early once the chain or its acceptance data are pruned
+        (0..depth)
+            .map_while(|offset| tip_index.checked_sub(offset))
+            .map_while(|index| sc_read.get_by_index(index).unwrap_option())
+            .map_while(|chain_block| self.acceptance_data_store.get(chain_block).unwrap_option().map(|data| (chain_block, data)))
+            .find_map(|(chain_block, acceptance_data)| {
+                acceptance_data
+                    .iter()
+                    .flat_map(|mergeset_data| mergeset_data.accepted_transactions.iter())
+                    .any(|entry| entry.transaction_id == tx_id)
+                    .then_some(chain_block)
+            })
+    }
+
+    fn past_median_time(&self, hash: Hash) -> Option<u64> {
+        // Genesis has no past, so by convention its past median time is its own timestamp
+        if hash == self.config.genesis.hash {
+            return Some(self.config.genesis.timestamp);
+        }
+
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash).ok()?;
+
+        // Verify that the block is in future(retention root), where Ghostdag data is complete
+        self.services.reachability_service.is_dag_ancestor_of(self.get_retention_period_root(), hash).then_some(())?;
+
+        // Note that windows which are not yet filled (close to genesis or to a window-related fork activation)
+        // are handled by the window manager itself
+        self.services.window_manager.calc_past_median_time_for_known_hash(hash).ok()
+    }
+
     fn get_virtual_state_approx_id(&self) -> VirtualStateApproxId {
         self.lkg_virtual_state.load().to_virtual_state_approx_id()
     }
@@ -793,6 +932,25 @@ impl ConsensusApi for Consensus {
         iter.map(|item| item.unwrap()).collect()
     }
 
+    fn get_utxo_entries(&self, outpoints: &[TransactionOutpoint]) -> Vec<Option<UtxoEntry>> {
+        // Hold the read lock for the whole batch so all entries are read from the same virtual UTXO set
+        let virtual_stores = self.virtual_stores.read();
+        outpoints
+            .iter()
+            .map(|outpoint| virtual_stores.utxo_set.get(outpoint).unwrap_option().map(|entry| entry.as_ref().clone()))
+            .collect()
+    }
+
+    fn coinbase_maturity_daa(&self) -> u64 {
+        self.config.params.coinbase_maturity().get(self.get_virtual_daa_score())
+    }
+
+    fn is_coinbase_spendable(&self, outpoint: TransactionOutpoint, at_daa_score: u64) -> Option<bool> {
+        let entry = self.virtual_stores.read().utxo_set.get(&outpoint).unwrap_option()?;
+        // Mirrors the maturity rule applied by the transaction validator, where the maturity is taken at the spending DAA score
+        Some(!entry.is_coinbase || entry.block_daa_score + self.config.params.coinbase_maturity().get(at_daa_score) <= at_daa_score)
+    }
+
     fn get_tips(&self) -> Vec<Hash> {
         self.body_tips_store.read().get().unwrap().read().iter().copied().collect_vec()
     }
@@ -801,6 +959,20 @@ impl ConsensusApi for Consensus {
         self.body_tips_store.read().get().unwrap().read().len()
     }
 
+    fn dag_tips_info(&self) -> TipsInfo {
+        // Hold the tips and headers selected tip read locks together so that the returned hashes reflect a single DAG state.
+        // The sink and virtual parents are read from the same virtual state snapshot
+        let body_tips_store = self.body_tips_store.read();
+        let headers_selected_tip_store = self.headers_selected_tip_store.read();
+        let virtual_state = self.lkg_virtual_state.load();
+        TipsInfo {
+            sink: virtual_state.ghostdag_data.selected_parent,
+            virtual_parents: virtual_state.parents.clone(),
+            body_tips: body_tips_store.get().unwrap().read().iter().copied().collect_vec(),
+            headers_selected_tip: headers_selected_tip_store.get().unwrap().hash,
+        }
+    }
+
     fn get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
@@ -825,10 +997,65 @@ impl ConsensusApi for Consensus {
         Ok(utxos)
     }
 
+    fn pruning_point_utxo_set_summary(&self) -> ConsensusResult<UtxoSetSummary> {
+        // The chunk size bounds the time the pruning utxoset read lock is held per iteration
+        const CHUNK_SIZE: usize = 1024;
+        'scan: loop {
+            // The pruning point is set before the pruning utxoset is advanced to it chain block by chain block, hence
+            // the set is identified by its own position, which must match the pruning point for the set to represent it
+            let position = {
+                let _guard = self.pruning_lock.blocking_read();
+                let position = self.pruning_utxoset_stores.read().utxoset_position().unwrap();
+                if self.pruning_point_store.read().pruning_point().unwrap() != position {
+                    return Err(ConsensusError::UnexpectedPruningPoint);
+                }
+                position
+            };
+
+            let mut summary = UtxoSetSummary::default();
+            let mut from_outpoint = None;
+            loop {
+                // The pruning lock is held only for the pruning point check so that pruning is not blocked for the
+                // whole scan. If the pruning point moved, the set is about to be advanced and we restart the scan
+                {
+                    let _guard = self.pruning_lock.blocking_read();
+                    if self.pruning_point_store.read().pruning_point().unwrap() != position {
+                        continue 'scan;
+                    }
+                }
+                let pruning_utxoset_read = self.pruning_utxoset_stores.read();
+                // The pruning utxoset might have been advanced between chunks, in which case the
+                // partial summary mixes two sets and we restart the scan over the new one
+                if pruning_utxoset_read.utxoset_position().unwrap() != position {
+                    continue 'scan;
+                }
+                let mut chunk_len = 0;
+                for (outpoint, entry) in pruning_utxoset_read
+                    .utxo_set
+                    .seek_iterator(from_outpoint, CHUNK_SIZE, from_outpoint.is_some())
+                    .map(|item| item.unwrap())
+                {
+                    summary.add(&entry);
+                    from_outpoint = Some(outpoint);
+                    chunk_len += 1;
+                }
+                drop(pruning_utxoset_read);
+
+                if chunk_len < CHUNK_SIZE {
+                    return Ok(summary);
+                }
+            }
+        }
+    }
+
     fn modify_coinbase_payload(&self, payload: Vec<u8>, miner_data: &MinerData) -> CoinbaseResult<Vec<u8>> {
         self.services.coinbase_manager.modify_coinbase_payload(payload, miner_data)
     }
 
+    fn max_coinbase_payload_len(&self) -> usize {
+        self.config.params.max_coinbase_payload_len
+    }
+
     fn calc_transaction_hash_merkle_root(&self, txs: &[Transaction], pov_daa_score: u64) -> Hash {
         let storage_mass_activated = self.config.crescendo_activation.is_active(pov_daa_score);
         calc_hash_merkle_root(txs.iter(), storage_mass_activated)
@@ -901,10 +1128,78 @@ impl ConsensusApi for Consensus {
         Ok(self.services.sync_manager.antipast_hashes_between(low, high, Some(max_blocks)))
     }
 
+    fn backward_chain_iterator_to(&self, from: Hash, stop: Hash) -> ConsensusResult<Vec<Hash>> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(from)?;
+        self.validate_block_exists(stop)?;
+        if !self.services.reachability_service.is_chain_ancestor_of(stop, from) {
+            return Err(ConsensusError::NotChainAncestor(stop, from));
+        }
+        Ok(self.services.reachability_service.backward_chain_iterator(from, stop, false).collect())
+    }
+
     fn get_header(&self, hash: Hash) -> ConsensusResult<Arc<Header>> {
         self.headers_store.get_header(hash).unwrap_option().ok_or(ConsensusError::HeaderNotFound(hash))
     }
 
+    fn get_headers(&self, hashes: &[Hash]) -> Vec<Option<Arc<Header>>> {
+        self.headers_store.get_headers_many(hashes)
+    }
+
+    fn self_check(&self, depth: usize) -> SelfCheckReport {
+        self.run_self_check(depth, false)
+    }
+
+    fn validate_dag_integrity(&self, depth: usize) -> ValidationResult {
+        let report = self.run_self_check(depth, true);
+        if report.is_consistent() {
+            Ok(report.checked_blocks)
+        } else {
+            Err(DagIntegrityError(report))
+        }
+    }
+
+    fn difficulty_info(&self, hash: Hash) -> ConsensusResult<DifficultyInfo> {
+        let compact = self.headers_store.get_compact_header_data(hash).unwrap_option().ok_or(ConsensusError::HeaderNotFound(hash))?;
+        let work = calc_work(compact.bits);
+        // Target time per block is expressed in milliseconds
+        let target_time_per_block = self.config.target_time_per_block().get(compact.daa_score);
+        Ok(DifficultyInfo {
+            bits: compact.bits,
+            target: Uint256::from_compact_target_bits(compact.bits),
+            work,
+            estimated_network_hps: work.as_f64() * 1000.0 / target_time_per_block as f64,
+        })
+    }
+
+    fn headers_by_blue_work_range(&self, low: BlueWorkType, high: BlueWorkType, limit: usize) -> Vec<Arc<Header>> {
+        if low > high || limit == 0 {
+            return vec![];
+        }
+
+        // We need consistency between the pruning point, selected chain and headers store reads
+        let _guard = self.pruning_lock.blocking_read();
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let sc_read = self.selected_chain_store.read();
+        let (tip_index, _) = sc_read.get_tip().unwrap();
+        let low_index = sc_read.get_by_hash(pruning_point).unwrap_option().unwrap_or(tip_index);
+        let header_at = |index: u64| self.headers_store.get_header(sc_read.get_by_index(index).unwrap()).unwrap();
+
+        // Blue work is strictly increasing along the selected chain, so we binary search for the first
+        // chain block with blue work >= low and walk forward from there
+        let (mut lo, mut hi) = (low_index, tip_index + 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if header_at(mid).blue_work < low {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        (lo..=tip_index).map(header_at).take_while(|header| header.blue_work <= high).take(limit).collect()
+    }
+
     fn get_headers_selected_tip(&self) -> Hash {
         self.headers_selected_tip_store.read().get().unwrap().hash
     }
@@ -996,6 +1291,50 @@ impl ConsensusApi for Consensus {
         Ok((&*ghostdag).into())
     }
 
+    fn compute_ghostdag(&self, parents: &[Hash]) -> ConsensusResult<ExternalGhostdagData> {
+        if parents.is_empty() {
+            return Err(ConsensusError::General("cannot compute GHOSTDAG data for an empty set of parents"));
+        }
+        // Prevent the pruning processor from deleting data traversed by the computation
+        let _guard = self.pruning_lock.blocking_read();
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        for &parent in parents {
+            match self.get_block_status(parent) {
+                None => return Err(ConsensusError::HeaderNotFound(parent)),
+                Some(BlockStatus::StatusInvalid) => return Err(ConsensusError::InvalidBlock(parent)),
+                _ => {}
+            }
+            if !self.ghostdag_store.has(parent).unwrap() {
+                return Err(ConsensusError::MissingData(parent));
+            }
+            if !self.services.reachability_service.is_dag_ancestor_of(pruning_point, parent) {
+                return Err(ConsensusError::GeneralOwned(format!("parent {parent} is not in the future of the pruning point")));
+            }
+        }
+        for (i, &a) in parents.iter().enumerate() {
+            for &b in &parents[i + 1..] {
+                if self.services.reachability_service.is_dag_ancestor_of(a, b)
+                    || self.services.reachability_service.is_dag_ancestor_of(b, a)
+                {
+                    return Err(ConsensusError::GeneralOwned(format!("parents {a} and {b} are not in each other's anticone")));
+                }
+            }
+        }
+        Ok((&self.services.ghostdag_manager.ghostdag(parents)).into())
+    }
+
+    fn estimate_past_size(&self, hash: Hash) -> ConsensusResult<u64> {
+        let header = self.headers_store.get_header(hash).unwrap_option().ok_or(ConsensusError::HeaderNotFound(hash))?;
+        if hash == self.config.genesis.hash {
+            return Ok(1);
+        }
+        // The DAA score adds the non DAA-excluded mergeset blocks (selected parent included) to the selected parent score,
+        // so relative to the genesis score it counts the exclusive past, genesis included. The blue score counts the blue
+        // past, which is a subset of the past
+        let past_size = (header.daa_score.saturating_sub(self.config.genesis.daa_score)).max(header.blue_score);
+        Ok(past_size + 1)
+    }
+
     fn get_block_children(&self, hash: Hash) -> Option<Vec<Hash>> {
         self.services
             .relations_service
@@ -1016,6 +1355,27 @@ impl ConsensusApi for Consensus {
         self.acceptance_data_store.get(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))
     }
 
+    fn get_block_accepted_transactions_data(&self, hash: Hash) -> Option<AcceptedTransactionsData> {
+        // We need consistency between the acceptance data and block transactions reads
+        let _guard = self.pruning_lock.blocking_read();
+        let acceptance_data = self.acceptance_data_store.get(hash).unwrap_option()?;
+        acceptance_data
+            .iter()
+            .map(|mergeset_data| {
+                let transactions = self.block_transactions_store.get(mergeset_data.block_hash).unwrap_option()?;
+                let accepted_transactions = mergeset_data
+                    .accepted_transactions
+                    .iter()
+                    .map(|entry| AcceptedTransaction {
+                        index_within_block: entry.index_within_block,
+                        transaction: transactions[entry.index_within_block as usize].clone(),
+                    })
+                    .collect();
+                Some(MergesetBlockAcceptedTransactions { block_hash: mergeset_data.block_hash, accepted_transactions })
+            })
+            .collect()
+    }
+
     fn get_blocks_acceptance_data(
         &self,
         hashes: &[Hash],
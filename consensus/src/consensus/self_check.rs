@@ -0,0 +1,193 @@
+use super::Consensus;
+use crate::{
+    model::stores::{
+        acceptance_data::AcceptanceDataStoreReader, ghostdag::GhostdagStoreReader, headers::HeaderStoreReader,
+        headers_selected_tip::HeadersSelectedTipStoreReader, pruning::PruningStoreReader, reachability::ReachabilityStoreReader,
+        relations::RelationsStoreReader, statuses::StatusesStoreReader, tips::TipsStoreReader,
+        utxo_multisets::UtxoMultisetsStoreReader,
+    },
+    processes::reachability::inquirer,
+};
+use itertools::Itertools;
+use kaspa_consensus_core::{
+    api::self_check::{SelfCheckIssue, SelfCheckReport},
+    blockhash::ORIGIN,
+    BlockHashSet,
+};
+use kaspa_database::prelude::StoreResultExtensions;
+use kaspa_hashes::Hash;
+
+impl Consensus {
+    /// Runs read-only consistency checks over the blocks reachable from the DAG tips by up to `depth` parent steps.
+    /// The traversal does not descend below the pruning point, since such blocks might have been partially pruned.
+    /// Store locks are acquired per query in order to not block block processing for the duration of the check.
+    /// If `validate_data` is set, block data is additionally re-verified (see [`Self::validate_block_data`])
+    pub(super) fn run_self_check(&self, depth: usize, validate_data: bool) -> SelfCheckReport {
+        let _guard = self.pruning_lock.blocking_read();
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+
+        let mut current: Vec<Hash> = self.body_tips_store.read().get().unwrap().read().iter().copied().collect();
+        current.push(self.headers_selected_tip_store.read().get().unwrap().hash);
+
+        let mut report = SelfCheckReport::default();
+        let mut visited = BlockHashSet::new();
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for hash in current {
+                if !visited.insert(hash) {
+                    continue;
+                }
+                report.checked_blocks += 1;
+                self.self_check_block(hash, pruning_point, &mut report.issues, &mut next);
+                if validate_data {
+                    self.validate_block_data(hash, pruning_point, &mut report.issues);
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            current = next;
+        }
+
+        report
+    }
+
+    /// Checks a single block and pushes its parents which should be checked next to `next`
+    fn self_check_block(&self, hash: Hash, pruning_point: Hash, issues: &mut Vec<SelfCheckIssue>, next: &mut Vec<Hash>) {
+        if self.statuses_store.read().get(hash).unwrap_option().is_none() {
+            issues.push(SelfCheckIssue::MissingStatus(hash));
+        }
+        if !self.headers_store.has(hash).unwrap() {
+            issues.push(SelfCheckIssue::MissingHeader(hash));
+        }
+        if !self.ghostdag_store.has(hash).unwrap() {
+            issues.push(SelfCheckIssue::MissingGhostdagData(hash));
+        }
+
+        let has_reachability = self.reachability_store.read().has(hash).unwrap();
+        if has_reachability {
+            let reachability = self.reachability_store.read();
+            let tree_parent = reachability.get_parent(hash).unwrap();
+            if tree_parent != ORIGIN && !inquirer::is_strict_chain_ancestor_of(&*reachability, tree_parent, hash).unwrap_or(false) {
+                issues.push(SelfCheckIssue::TreeIntervalNotContained { parent: tree_parent, child: hash });
+            }
+        } else {
+            issues.push(SelfCheckIssue::MissingReachabilityData(hash));
+        }
+
+        let Some(parents) = self.reachability_relations_store.read().get_parents(hash).unwrap_option() else {
+            issues.push(SelfCheckIssue::MissingRelations(hash));
+            return;
+        };
+        for parent in parents.iter().copied().filter(|&parent| parent != ORIGIN) {
+            match self.reachability_relations_store.read().get_children(parent).unwrap_option() {
+                Some(children) => {
+                    if !children.read().contains(&hash) {
+                        issues.push(SelfCheckIssue::MissingChildLink { parent, child: hash });
+                    }
+                }
+                None => {
+                    issues.push(SelfCheckIssue::MissingRelations(parent));
+                    continue;
+                }
+            }
+
+            let reachability = self.reachability_store.read();
+            if !has_reachability || !reachability.has(parent).unwrap() {
+                drop(reachability);
+                // Missing reachability data of the parent is reported once it is checked itself. The walk
+                // is stopped at the pruning point on this branch as well, so pruned blocks are not reported
+                if self.is_in_pruning_point_future(parent, pruning_point) {
+                    next.push(parent);
+                }
+                continue;
+            }
+            if !inquirer::is_dag_ancestor_of(&*reachability, parent, hash).unwrap_or(false) {
+                issues.push(SelfCheckIssue::ParentNotInPast { parent, child: hash });
+            }
+            if inquirer::is_dag_ancestor_of(&*reachability, pruning_point, parent).unwrap_or(false) {
+                next.push(parent);
+            }
+        }
+    }
+
+    /// Returns whether `block` is the pruning point or in its future. Reachability is used when available, and otherwise
+    /// the header blue scores are compared, since a block in the past of the pruning point has a lower blue score.
+    /// Blocks whose header is missing as well are assumed to be pruned
+    fn is_in_pruning_point_future(&self, block: Hash, pruning_point: Hash) -> bool {
+        let reachability = self.reachability_store.read();
+        if reachability.has(block).unwrap() {
+            return inquirer::is_dag_ancestor_of(&*reachability, pruning_point, block).unwrap_or(false);
+        }
+        drop(reachability);
+        let pruning_point_blue_score = self.headers_store.get_blue_score(pruning_point).unwrap();
+        self.headers_store.get_blue_score(block).unwrap_option().is_some_and(|blue_score| blue_score > pruning_point_blue_score)
+    }
+
+    /// Re-verifies the data of a single block against the data it was derived from: GHOSTDAG data is recomputed from
+    /// the block parents, and for chain blocks the UTXO multiset and acceptance data are checked against the header
+    /// commitments. Genesis and blocks whose parents are not all in the future of the pruning point are skipped, since
+    /// their data might have been imported as trusted data or partially pruned
+    fn validate_block_data(&self, hash: Hash, pruning_point: Hash, issues: &mut Vec<SelfCheckIssue>) {
+        if hash == self.config.genesis.hash {
+            return;
+        }
+        let (Some(header), Some(ghostdag_data)) =
+            (self.headers_store.get_header(hash).unwrap_option(), self.ghostdag_store.get_data(hash).unwrap_option())
+        else {
+            // Missing data is reported by the structural checks
+            return;
+        };
+        let Some(parents) = self.relations_stores.read()[0].get_parents(hash).unwrap_option() else {
+            return;
+        };
+        let is_in_pruning_future = |block: Hash| {
+            let reachability = self.reachability_store.read();
+            reachability.has(block).unwrap() && inquirer::is_dag_ancestor_of(&*reachability, pruning_point, block).unwrap_or(false)
+        };
+        let has_ghostdag_data = |block: Hash| self.ghostdag_store.has(block).unwrap();
+        if parents.is_empty() || !parents.iter().copied().all(|parent| is_in_pruning_future(parent) && has_ghostdag_data(parent)) {
+            return;
+        }
+        if !ghostdag_data.unordered_mergeset().all(has_ghostdag_data) {
+            return;
+        }
+
+        let recomputed = self.services.ghostdag_manager.ghostdag(&parents);
+        if recomputed.selected_parent != ghostdag_data.selected_parent
+            || recomputed.blue_score != ghostdag_data.blue_score
+            || recomputed.blue_work != ghostdag_data.blue_work
+            || recomputed.mergeset_blues != ghostdag_data.mergeset_blues
+            || recomputed.mergeset_reds != ghostdag_data.mergeset_reds
+            || recomputed.blues_anticone_sizes != ghostdag_data.blues_anticone_sizes
+        {
+            issues.push(SelfCheckIssue::GhostdagMismatch(hash));
+        }
+
+        // Only chain blocks which were resolved by the virtual processor have a UTXO multiset and acceptance data
+        if let Some(multiset) = self.utxo_multisets_store.get(hash).unwrap_option() {
+            if multiset.finalize() != header.utxo_commitment {
+                issues.push(SelfCheckIssue::UtxoCommitmentMismatch(hash));
+            }
+        }
+        if let Some(acceptance_data) = self.acceptance_data_store.get(hash).unwrap_option() {
+            let mut accepted_tx_ids = acceptance_data
+                .iter()
+                .flat_map(|mergeset_block| mergeset_block.accepted_transactions.iter().map(|entry| entry.transaction_id))
+                .collect_vec();
+            // Accepted tx ids are sorted before the crescendo HF (see the virtual processor UTXO state calculation)
+            if !self.config.crescendo_activation.is_active(header.daa_score) {
+                accepted_tx_ids.sort();
+            }
+            // The selected parent is in the pruning point future (checked above), so its header is available
+            let expected = self.virtual_processor.calc_accepted_id_merkle_root(
+                header.daa_score,
+                accepted_tx_ids.into_iter(),
+                ghostdag_data.selected_parent,
+            );
+            if expected != header.accepted_id_merkle_root {
+                issues.push(SelfCheckIssue::AcceptedIdMerkleRootMismatch(hash));
+            }
+        }
+    }
+}
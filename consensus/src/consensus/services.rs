@@ -128,13 +128,7 @@ impl ConsensusServices {
             reachability_service.clone(),
         );
 
-        let coinbase_manager = CoinbaseManager::new(
-            params.coinbase_payload_script_public_key_max_len,
-            params.max_coinbase_payload_len,
-            params.deflationary_phase_daa_score,
-            params.pre_deflationary_phase_base_subsidy,
-            params.bps(),
-        );
+        let coinbase_manager = CoinbaseManager::from_params(params);
 
         let mass_calculator = MassCalculator::new(
             params.mass_per_tx_byte,
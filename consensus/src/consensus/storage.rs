@@ -6,7 +6,7 @@ use crate::{
         block_window_cache::BlockWindowCacheStore,
         daa::DbDaaStore,
         depth::DbDepthStore,
-        ghostdag::{CompactGhostdagData, DbGhostdagStore},
+        ghostdag::{CompactGhostdagData, DbGhostdagStore, GhostdagData},
         headers::{CompactHeaderData, DbHeadersStore},
         headers_selected_tip::DbHeadersSelectedTipStore,
         past_pruning_points::DbPastPruningPointsStore,
@@ -34,70 +34,57 @@ use kaspa_hashes::Hash;
 use parking_lot::RwLock;
 use std::{ops::DerefMut, sync::Arc};
 
-pub struct ConsensusStorage {
-    // DB
-    db: Arc<DB>,
-
-    // Locked stores
-    pub statuses_store: Arc<RwLock<DbStatusesStore>>,
-    pub relations_stores: Arc<RwLock<Vec<DbRelationsStore>>>,
-    pub reachability_store: Arc<RwLock<DbReachabilityStore>>,
-    pub reachability_relations_store: Arc<RwLock<DbRelationsStore>>,
-    pub pruning_point_store: Arc<RwLock<DbPruningStore>>,
-    pub headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
-    pub body_tips_store: Arc<RwLock<DbTipsStore>>,
-    pub pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
-    pub virtual_stores: Arc<RwLock<VirtualStores>>,
-    pub selected_chain_store: Arc<RwLock<DbSelectedChainStore>>,
-
-    // Append-only stores
-    pub ghostdag_store: Arc<DbGhostdagStore>,
-    pub headers_store: Arc<DbHeadersStore>,
-    pub block_transactions_store: Arc<DbBlockTransactionsStore>,
-    pub past_pruning_points_store: Arc<DbPastPruningPointsStore>,
-    pub daa_excluded_store: Arc<DbDaaStore>,
-    pub depth_store: Arc<DbDepthStore>,
-    pub pruning_samples_store: Arc<DbPruningSamplesStore>,
-
-    // Utxo-related stores
-    pub utxo_diffs_store: Arc<DbUtxoDiffsStore>,
-    pub utxo_multisets_store: Arc<DbUtxoMultisetsStore>,
-    pub acceptance_data_store: Arc<DbAcceptanceDataStore>,
-
-    // Block window caches
-    pub block_window_cache_for_difficulty: Arc<BlockWindowCacheStore>,
-    pub block_window_cache_for_past_median_time: Arc<BlockWindowCacheStore>,
-
-    // "Last Known Good" caches
-    /// The "last known good" virtual state. To be used by any logic which does not want to wait
-    /// for a possible virtual state write to complete but can rather settle with the last known state
-    pub lkg_virtual_state: LkgVirtualState,
+/// The cache policy builders of all consensus stores, as derived from the config. Builders of per-level stores are
+/// downscaled per level when building the store policies
+#[derive(Clone, Copy)]
+struct CachePolicyBuilders {
+    daa_excluded: PolicyBuilder,
+    statuses: PolicyBuilder,
+    reachability_data: PolicyBuilder,
+    ghostdag_compact: PolicyBuilder,
+    headers_compact: PolicyBuilder,
+    parents: PolicyBuilder,
+    children: PolicyBuilder,
+    reachability_sets: PolicyBuilder,
+    difficulty_window: PolicyBuilder,
+    median_window: PolicyBuilder,
+    ghostdag: PolicyBuilder,
+    headers: PolicyBuilder,
+    utxo_diffs: PolicyBuilder,
+    block_data: PolicyBuilder,
+    header_data: PolicyBuilder,
+    utxo_set: PolicyBuilder,
+    transactions: PolicyBuilder,
+    acceptance_data: PolicyBuilder,
+    past_pruning_points: PolicyBuilder,
 }
 
-impl ConsensusStorage {
-    pub fn new(db: Arc<DB>, config: Arc<Config>) -> Arc<Self> {
-        let scale_factor = config.ram_scale;
-        let scaled = |s| (s as f64 * scale_factor) as usize;
-
+impl CachePolicyBuilders {
+    fn new(config: &Config) -> Self {
         let params = &config.params;
         let perf_params = &config.perf;
+        let cache_size_noise = config.cache_size_noise;
+
+        let scale_factor = config.ram_scale * perf_params.cache_budget_scale;
+        let scaled = |s| (s as f64 * scale_factor) as usize;
 
         // Lower and upper bounds
         // [Crescendo]: all usages of pruning upper bounds also bound by actual memory bytes, so we can safely use the larger values
         let pruning_depth = params.pruning_depth().upper_bound() as usize;
-        let pruning_size_for_caches = pruning_depth + params.finality_depth().upper_bound() as usize; // Upper bound for any block/header related data
+        let pruning_size_for_caches = pruning_depth.saturating_add(params.finality_depth().upper_bound() as usize); // Upper bound for any block/header related data
         let level_lower_bound = 2 * params.pruning_proof_m as usize; // Number of items lower bound for level-related caches
 
-        // Budgets in bytes. All byte budgets overall sum up to ~1GB of memory (which obviously takes more low level alloc space)
+        // Budgets in bytes. All byte budgets overall sum up to ~1GB of memory (which obviously takes more low level alloc space).
+        // The sum including their multiplicity is derived by `CachePolicyBuilders::bytes_budget`
         let daa_excluded_budget = scaled(30_000_000);
         let statuses_budget = scaled(30_000_000);
         let reachability_data_budget = scaled(100_000_000);
         let reachability_sets_budget = scaled(100_000_000); // x 2 for tree children and future covering set
         let ghostdag_compact_budget = scaled(15_000_000);
         let headers_compact_budget = scaled(5_000_000);
-        let parents_budget = scaled(80_000_000); // x 3 for reachability and levels
-        let children_budget = scaled(20_000_000); // x 3 for reachability and levels
-        let ghostdag_budget = scaled(80_000_000); // x 2 for levels
+        let parents_budget = scaled(80_000_000); // For reachability, and shrunk per level for levels
+        let children_budget = scaled(20_000_000); // For reachability, and shrunk per level for levels
+        let ghostdag_budget = scaled(80_000_000);
         let headers_budget = scaled(80_000_000);
         let transactions_budget = scaled(40_000_000);
         let utxo_diffs_budget = scaled(40_000_000);
@@ -110,128 +97,254 @@ impl ConsensusStorage {
         let reachability_data_bytes = size_of::<Hash>() + size_of::<ReachabilityData>();
         let ghostdag_compact_bytes = size_of::<Hash>() + size_of::<CompactGhostdagData>();
         let headers_compact_bytes = size_of::<Hash>() + size_of::<CompactHeaderData>();
+        let ghostdag_min_bytes = size_of::<Hash>() + size_of::<GhostdagData>(); // Excluding the mergeset heap allocations
 
         // If the fork is already scheduled, prefer the long-term, permanent values
         let difficulty_window_bytes = params.difficulty_window_size().after() * size_of::<SortableBlock>();
         let median_window_bytes = params.past_median_time_window_size().after() * size_of::<SortableBlock>();
 
         // Cache policy builders
-        let daa_excluded_builder =
-            PolicyBuilder::new().max_items(pruning_depth).bytes_budget(daa_excluded_budget).unit_bytes(daa_excluded_bytes).untracked(); // Required only above the pruning point
-        let statuses_builder =
-            PolicyBuilder::new().max_items(pruning_size_for_caches).bytes_budget(statuses_budget).unit_bytes(status_bytes).untracked();
+        let daa_excluded_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
+            .max_items(pruning_depth)
+            .bytes_budget(daa_excluded_budget)
+            .unit_bytes(daa_excluded_bytes)
+            .untracked(); // Required only above the pruning point
+        let statuses_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
+            .max_items(pruning_size_for_caches)
+            .bytes_budget(statuses_budget)
+            .unit_bytes(status_bytes)
+            .untracked();
         let reachability_data_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .max_items(pruning_size_for_caches)
             .bytes_budget(reachability_data_budget)
             .unit_bytes(reachability_data_bytes)
             .untracked();
         let ghostdag_compact_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .max_items(pruning_size_for_caches)
             .bytes_budget(ghostdag_compact_budget)
             .unit_bytes(ghostdag_compact_bytes)
             .min_items(level_lower_bound)
             .untracked();
         let headers_compact_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .max_items(pruning_size_for_caches)
             .bytes_budget(headers_compact_budget)
             .unit_bytes(headers_compact_bytes)
             .untracked();
         let parents_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .bytes_budget(parents_budget)
             .unit_bytes(size_of::<Hash>())
             .min_items(level_lower_bound)
             .tracked_units();
         let children_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .bytes_budget(children_budget)
             .unit_bytes(size_of::<Hash>())
             .min_items(level_lower_bound)
             .tracked_units();
-        let reachability_sets_builder =
-            PolicyBuilder::new().bytes_budget(reachability_sets_budget).unit_bytes(size_of::<Hash>()).tracked_units();
+        let reachability_sets_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
+            .bytes_budget(reachability_sets_budget)
+            .unit_bytes(size_of::<Hash>())
+            .tracked_units();
         let difficulty_window_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .max_items(perf_params.block_window_cache_size)
             .bytes_budget(block_window_budget)
             .unit_bytes(difficulty_window_bytes)
             .untracked();
         let median_window_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
             .max_items(perf_params.block_window_cache_size)
             .bytes_budget(block_window_budget)
             .unit_bytes(median_window_bytes)
             .untracked();
-        let ghostdag_builder = PolicyBuilder::new().bytes_budget(ghostdag_budget).min_items(level_lower_bound).tracked_bytes();
-        let headers_builder = PolicyBuilder::new().bytes_budget(headers_budget).tracked_bytes();
-        let utxo_diffs_builder = PolicyBuilder::new().bytes_budget(utxo_diffs_budget).tracked_bytes();
-        let block_data_builder = PolicyBuilder::new().max_items(perf_params.block_data_cache_size).untracked();
-        let header_data_builder = PolicyBuilder::new().max_items(perf_params.header_data_cache_size).untracked();
-        let utxo_set_builder = PolicyBuilder::new().max_items(perf_params.utxo_set_cache_size).untracked();
-        let transactions_builder = PolicyBuilder::new().bytes_budget(transactions_budget).tracked_bytes();
-        let acceptance_data_builder = PolicyBuilder::new().bytes_budget(acceptance_data_budget).tracked_bytes();
-        let past_pruning_points_builder = PolicyBuilder::new().max_items(1024).untracked();
+        let ghostdag_builder = PolicyBuilder::new()
+            .noise(cache_size_noise)
+            .bytes_budget(ghostdag_budget)
+            .unit_bytes(ghostdag_min_bytes) // Used for accounting the min items floor only
+            .min_items(level_lower_bound)
+            .tracked_bytes();
+        let headers_builder = PolicyBuilder::new().noise(cache_size_noise).bytes_budget(headers_budget).tracked_bytes();
+        let utxo_diffs_builder = PolicyBuilder::new().noise(cache_size_noise).bytes_budget(utxo_diffs_budget).tracked_bytes();
+        let block_data_builder = PolicyBuilder::new().noise(cache_size_noise).max_items(perf_params.block_data_cache_size).untracked();
+        let header_data_builder =
+            PolicyBuilder::new().noise(cache_size_noise).max_items(perf_params.header_data_cache_size).untracked();
+        let utxo_set_builder = PolicyBuilder::new().noise(cache_size_noise).max_items(perf_params.utxo_set_cache_size).untracked();
+        let transactions_builder = PolicyBuilder::new().noise(cache_size_noise).bytes_budget(transactions_budget).tracked_bytes();
+        let acceptance_data_builder =
+            PolicyBuilder::new().noise(cache_size_noise).bytes_budget(acceptance_data_budget).tracked_bytes();
+        let past_pruning_points_builder = PolicyBuilder::new().noise(cache_size_noise).max_items(1024).untracked();
+
+        Self {
+            daa_excluded: daa_excluded_builder,
+            statuses: statuses_builder,
+            reachability_data: reachability_data_builder,
+            ghostdag_compact: ghostdag_compact_builder,
+            headers_compact: headers_compact_builder,
+            parents: parents_builder,
+            children: children_builder,
+            reachability_sets: reachability_sets_builder,
+            difficulty_window: difficulty_window_builder,
+            median_window: median_window_builder,
+            ghostdag: ghostdag_builder,
+            headers: headers_builder,
+            utxo_diffs: utxo_diffs_builder,
+            block_data: block_data_builder,
+            header_data: header_data_builder,
+            utxo_set: utxo_set_builder,
+            transactions: transactions_builder,
+            acceptance_data: acceptance_data_builder,
+            past_pruning_points: past_pruning_points_builder,
+        }
+    }
+
+    /// The sum of the byte budgets of all byte-bounded store caches, including their multiplicity across stores
+    /// and levels, the per-level shrink and the minimum items floors. Caches bounded by item counts only are excluded
+    fn bytes_budget(&self, config: &Config) -> usize {
+        let shift = config.perf.level_cache_shrink_shift;
+        let levels = (0..=config.params.max_block_level)
+            .map(|level| {
+                self.parents.downscale_by(level, shift).budget_bytes() + self.children.downscale_by(level, shift).budget_bytes()
+            })
+            .sum::<usize>();
+        let reachability = self.reachability_data.budget_bytes()
+            + 2 * self.reachability_sets.budget_bytes() // Tree children and future covering set
+            + self.parents.budget_bytes()
+            + self.children.budget_bytes();
+        let ghostdag = self.ghostdag.downscale(0).budget_bytes() + self.ghostdag_compact.downscale(0).budget_bytes();
+        let headers = self.headers.budget_bytes() + self.headers_compact.budget_bytes();
+        let others = [
+            self.daa_excluded,
+            self.statuses,
+            self.transactions,
+            self.utxo_diffs,
+            self.acceptance_data,
+            self.difficulty_window,
+            self.median_window,
+        ]
+        .iter()
+        .map(|builder| builder.budget_bytes())
+        .sum::<usize>();
+        levels + reachability + ghostdag + headers + others
+    }
+}
+
+pub struct ConsensusStorage {
+    // DB
+    db: Arc<DB>,
+
+    // Locked stores
+    pub statuses_store: Arc<RwLock<DbStatusesStore>>,
+    pub relations_stores: Arc<RwLock<Vec<DbRelationsStore>>>,
+    pub reachability_store: Arc<RwLock<DbReachabilityStore>>,
+    pub reachability_relations_store: Arc<RwLock<DbRelationsStore>>,
+    pub pruning_point_store: Arc<RwLock<DbPruningStore>>,
+    pub headers_selected_tip_store: Arc<RwLock<DbHeadersSelectedTipStore>>,
+    pub body_tips_store: Arc<RwLock<DbTipsStore>>,
+    pub pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
+    pub virtual_stores: Arc<RwLock<VirtualStores>>,
+    pub selected_chain_store: Arc<RwLock<DbSelectedChainStore>>,
+
+    // Append-only stores
+    pub ghostdag_store: Arc<DbGhostdagStore>,
+    pub headers_store: Arc<DbHeadersStore>,
+    pub block_transactions_store: Arc<DbBlockTransactionsStore>,
+    pub past_pruning_points_store: Arc<DbPastPruningPointsStore>,
+    pub daa_excluded_store: Arc<DbDaaStore>,
+    pub depth_store: Arc<DbDepthStore>,
+    pub pruning_samples_store: Arc<DbPruningSamplesStore>,
+
+    // Utxo-related stores
+    pub utxo_diffs_store: Arc<DbUtxoDiffsStore>,
+    pub utxo_multisets_store: Arc<DbUtxoMultisetsStore>,
+    pub acceptance_data_store: Arc<DbAcceptanceDataStore>,
+
+    // Block window caches
+    pub block_window_cache_for_difficulty: Arc<BlockWindowCacheStore>,
+    pub block_window_cache_for_past_median_time: Arc<BlockWindowCacheStore>,
+
+    // "Last Known Good" caches
+    /// The "last known good" virtual state. To be used by any logic which does not want to wait
+    /// for a possible virtual state write to complete but can rather settle with the last known state
+    pub lkg_virtual_state: LkgVirtualState,
+}
+
+impl ConsensusStorage {
+    pub fn new(db: Arc<DB>, config: Arc<Config>) -> Arc<Self> {
+        let params = &config.params;
+        let perf_params = &config.perf;
+        let builders = CachePolicyBuilders::new(&config);
 
         // TODO: consider tracking UtxoDiff byte sizes more accurately including the exact size of ScriptPublicKey
 
         // Headers
-        let statuses_store = Arc::new(RwLock::new(DbStatusesStore::new(db.clone(), statuses_builder.build())));
+        let statuses_store = Arc::new(RwLock::new(DbStatusesStore::new(db.clone(), builders.statuses.build())));
         let relations_stores = Arc::new(RwLock::new(
             (0..=params.max_block_level)
                 .map(|level| {
                     DbRelationsStore::new(
                         db.clone(),
                         level,
-                        parents_builder.downscale(level).build(),
-                        children_builder.downscale(level).build(),
+                        builders.parents.downscale_by(level, perf_params.level_cache_shrink_shift).build(),
+                        builders.children.downscale_by(level, perf_params.level_cache_shrink_shift).build(),
                     )
                 })
                 .collect_vec(),
         ));
         let reachability_store = Arc::new(RwLock::new(DbReachabilityStore::new(
             db.clone(),
-            reachability_data_builder.build(),
-            reachability_sets_builder.build(),
+            builders.reachability_data.build(),
+            builders.reachability_sets.build(),
         )));
 
         let reachability_relations_store = Arc::new(RwLock::new(DbRelationsStore::with_prefix(
             db.clone(),
             DatabaseStorePrefixes::ReachabilityRelations.as_ref(),
-            parents_builder.build(),
-            children_builder.build(),
+            builders.parents.build(),
+            builders.children.build(),
         )));
 
         let ghostdag_store = Arc::new(DbGhostdagStore::new(
             db.clone(),
             0,
-            ghostdag_builder.downscale(0).build(),
-            ghostdag_compact_builder.downscale(0).build(),
+            builders.ghostdag.downscale(0).build(),
+            builders.ghostdag_compact.downscale(0).build(),
         ));
-        let daa_excluded_store = Arc::new(DbDaaStore::new(db.clone(), daa_excluded_builder.build()));
-        let headers_store = Arc::new(DbHeadersStore::new(db.clone(), headers_builder.build(), headers_compact_builder.build()));
-        let depth_store = Arc::new(DbDepthStore::new(db.clone(), header_data_builder.build()));
-        let selected_chain_store = Arc::new(RwLock::new(DbSelectedChainStore::new(db.clone(), header_data_builder.build())));
+        let daa_excluded_store = Arc::new(DbDaaStore::new(db.clone(), builders.daa_excluded.build()));
+        let headers_store = Arc::new(DbHeadersStore::new(db.clone(), builders.headers.build(), builders.headers_compact.build()));
+        let depth_store = Arc::new(DbDepthStore::new(db.clone(), builders.header_data.build()));
+        let selected_chain_store = Arc::new(RwLock::new(DbSelectedChainStore::new(db.clone(), builders.header_data.build())));
 
         // Pruning
         let pruning_point_store = Arc::new(RwLock::new(DbPruningStore::new(db.clone())));
-        let past_pruning_points_store = Arc::new(DbPastPruningPointsStore::new(db.clone(), past_pruning_points_builder.build()));
-        let pruning_utxoset_stores = Arc::new(RwLock::new(PruningUtxosetStores::new(db.clone(), utxo_set_builder.build())));
-        let pruning_samples_store = Arc::new(DbPruningSamplesStore::new(db.clone(), header_data_builder.build()));
+        let past_pruning_points_store = Arc::new(DbPastPruningPointsStore::new(db.clone(), builders.past_pruning_points.build()));
+        let pruning_utxoset_stores = Arc::new(RwLock::new(PruningUtxosetStores::new(db.clone(), builders.utxo_set.build())));
+        let pruning_samples_store = Arc::new(DbPruningSamplesStore::new(db.clone(), builders.header_data.build()));
 
         // Txs
-        let block_transactions_store = Arc::new(DbBlockTransactionsStore::new(db.clone(), transactions_builder.build()));
-        let utxo_diffs_store = Arc::new(DbUtxoDiffsStore::new(db.clone(), utxo_diffs_builder.build()));
-        let utxo_multisets_store = Arc::new(DbUtxoMultisetsStore::new(db.clone(), block_data_builder.build()));
-        let acceptance_data_store = Arc::new(DbAcceptanceDataStore::new(db.clone(), acceptance_data_builder.build()));
+        let block_transactions_store = Arc::new(DbBlockTransactionsStore::new(db.clone(), builders.transactions.build()));
+        let utxo_diffs_store = Arc::new(DbUtxoDiffsStore::new(db.clone(), builders.utxo_diffs.build()));
+        let utxo_multisets_store = Arc::new(DbUtxoMultisetsStore::new(db.clone(), builders.block_data.build()));
+        let acceptance_data_store = Arc::new(DbAcceptanceDataStore::new(db.clone(), builders.acceptance_data.build()));
 
         // Tips
         let headers_selected_tip_store = Arc::new(RwLock::new(DbHeadersSelectedTipStore::new(db.clone())));
         let body_tips_store = Arc::new(RwLock::new(DbTipsStore::new(db.clone())));
 
         // Block windows
-        let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(difficulty_window_builder.build()));
-        let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(median_window_builder.build()));
+        let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(builders.difficulty_window.build()));
+        let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(builders.median_window.build()));
 
         // Virtual stores
         let lkg_virtual_state = LkgVirtualState::default();
         let virtual_stores =
-            Arc::new(RwLock::new(VirtualStores::new(db.clone(), lkg_virtual_state.clone(), utxo_set_builder.build())));
+            Arc::new(RwLock::new(VirtualStores::new(db.clone(), lkg_virtual_state.clone(), builders.utxo_set.build())));
 
         // Ensure that reachability stores are initialized
         reachability::init(reachability_store.write().deref_mut()).unwrap();
@@ -264,4 +377,121 @@ impl ConsensusStorage {
             lkg_virtual_state,
         })
     }
+
+    /// The sum of the cache byte budgets of all consensus stores under `config`, derived from the actual store cache policies
+    pub fn cache_bytes_budget(config: &Config) -> usize {
+        CachePolicyBuilders::new(config).bytes_budget(config)
+    }
+
+    /// Summarizes the live memory use of each byte-tracked store cache as `(store name, tracked bytes, entry count)`
+    pub fn memory_report(&self) -> Vec<(&'static str, usize, usize)> {
+        [
+            ("headers", self.headers_store.cache_footprint()),
+            ("ghostdag", self.ghostdag_store.cache_footprint()),
+            ("block_transactions", self.block_transactions_store.cache_footprint()),
+            ("utxo_diffs", self.utxo_diffs_store.cache_footprint()),
+            ("acceptance_data", self.acceptance_data_store.cache_footprint()),
+        ]
+        .into_iter()
+        .map(|(name, (tracked_bytes, entries))| (name, tracked_bytes, entries))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::stores::{block_transactions::BlockTransactionsStore, headers::HeaderStore};
+    use kaspa_consensus_core::{
+        config::{params::MAINNET_PARAMS, CacheSizeNoise, ConfigBuilder},
+        header::Header,
+        tx::Transaction,
+    };
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder};
+
+    #[test]
+    fn test_memory_report() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let storage = ConsensusStorage::new(db, Arc::new(Config::new(MAINNET_PARAMS)));
+
+        let report = storage.memory_report();
+        assert!(report.iter().all(|&(_, tracked_bytes, entries)| tracked_bytes == 0 && entries == 0));
+
+        for i in 1..=3u64 {
+            let hash = Hash::from_u64_word(i);
+            storage.headers_store.insert(hash, Arc::new(Header::from_precomputed_hash(hash, vec![])), 0).unwrap();
+            storage.block_transactions_store.insert(hash, Arc::new(vec![Transaction::default()])).unwrap();
+        }
+
+        let report = storage.memory_report();
+        let footprint = |name| report.iter().find(|(n, _, _)| *n == name).map(|&(_, bytes, entries)| (bytes, entries)).unwrap();
+        for name in ["headers", "block_transactions"] {
+            let (tracked_bytes, entries) = footprint(name);
+            assert_eq!(entries, 3, "{name}");
+            assert!(tracked_bytes > 0, "{name}");
+        }
+        assert_eq!(footprint("utxo_diffs"), (0, 0));
+    }
+
+    #[test]
+    fn test_adjust_perf_params_to_available_memory() {
+        use kaspa_consensus_core::config::constants::perf::CACHE_MEMORY_FRACTION;
+
+        let baseline = ConsensusStorage::cache_bytes_budget(&Config::new(MAINNET_PARAMS));
+        // All levels are accounted for, each shrunk but no lower than the min items floor
+        let mut single_level = MAINNET_PARAMS;
+        single_level.max_block_level = 0;
+        assert!(baseline > ConsensusStorage::cache_bytes_budget(&Config::new(single_level)));
+
+        // A small memory budget bounds the total budget of the actual cache policies by the target fraction
+        for ram_scale in [0.5, 1.0, 3.0] {
+            for total_bytes in [256_000_000u64, 1_000_000_000, 2_000_000_001] {
+                let config = ConfigBuilder::new(MAINNET_PARAMS)
+                    .apply_args(|config| config.ram_scale = ram_scale)
+                    .adjust_perf_params_to_available_memory(total_bytes, ConsensusStorage::cache_bytes_budget)
+                    .build();
+                let budget = ConsensusStorage::cache_bytes_budget(&config);
+                assert!(budget as f64 <= total_bytes as f64 * CACHE_MEMORY_FRACTION, "{budget} exceeds the target of {total_bytes}");
+                assert!(config.perf.cache_budget_scale <= 1.0);
+            }
+        }
+
+        // Ample memory leaves the budgets intact
+        let config = ConfigBuilder::new(MAINNET_PARAMS)
+            .adjust_perf_params_to_available_memory(64_000_000_000, ConsensusStorage::cache_bytes_budget)
+            .build();
+        assert_eq!(config.perf.cache_budget_scale, 1.0);
+        assert_eq!(ConsensusStorage::cache_bytes_budget(&config), baseline);
+    }
+
+    #[test]
+    fn test_reproducible_cache_sizes() {
+        // A power of two scale keeps the scaled budgets exact. The headers budget is scaled down to 78125 bytes
+        const RAM_SCALE: f64 = 1.0 / 1024.0;
+        const HEADERS_BUDGET: usize = 80_000_000 / 1024;
+        let config = Arc::new(
+            ConfigBuilder::new(MAINNET_PARAMS)
+                .set_cache_size_noise(CacheSizeNoise::Disabled)
+                .apply_args(|config| config.ram_scale = RAM_SCALE)
+                .build(),
+        );
+
+        // Fill the headers cache of two separately constructed storages well beyond its budget
+        let headers_footprint = || {
+            let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let storage = ConsensusStorage::new(db, config.clone());
+            for i in 1..=2000u64 {
+                let hash = Hash::from_u64_word(i);
+                storage.headers_store.insert(hash, Arc::new(Header::from_precomputed_hash(hash, vec![])), 0).unwrap();
+            }
+            storage.headers_store.cache_footprint()
+        };
+        let (tracked_bytes, entries) = headers_footprint();
+        assert_eq!((tracked_bytes, entries), headers_footprint());
+
+        // Without noise the cache is bounded by the exact budget, i.e., there is no room left for another header
+        assert!(entries < 2000);
+        assert!(tracked_bytes <= HEADERS_BUDGET);
+        assert!(HEADERS_BUDGET - tracked_bytes < tracked_bytes / entries);
+    }
 }
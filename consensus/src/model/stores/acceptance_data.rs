@@ -48,6 +48,11 @@ impl DbAcceptanceDataStore {
         Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::AcceptanceData.into()) }
     }
 
+    /// Returns the tracked bytes and the number of entries held by the store cache
+    pub fn cache_footprint(&self) -> (usize, usize) {
+        self.access.cache_footprint()
+    }
+
     pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
         Self::new(Arc::clone(&self.db), cache_policy)
     }
@@ -53,6 +53,11 @@ impl DbBlockTransactionsStore {
         Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::BlockTransactions.into()) }
     }
 
+    /// Returns the tracked bytes and the number of entries held by the store cache
+    pub fn cache_footprint(&self) -> (usize, usize) {
+        self.access.cache_footprint()
+    }
+
     pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
         Self::new(Arc::clone(&self.db), cache_policy)
     }
@@ -72,6 +77,19 @@ impl DbBlockTransactionsStore {
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Returns the number of block bodies in the store.
+    ///
+    /// Note: this performs a full scan over the store entries (though without deserializing them). Prefer
+    /// [`Self::count_at_least`] when only a lower bound is required.
+    pub fn count(&self) -> Result<usize, StoreError> {
+        self.access.count_up_to(usize::MAX)
+    }
+
+    /// Returns whether the store holds at least `n` block bodies. The scan stops after `n` entries.
+    pub fn count_at_least(&self, n: usize) -> Result<bool, StoreError> {
+        Ok(self.access.count_up_to(n)? == n)
+    }
 }
 
 impl BlockTransactionsStoreReader for DbBlockTransactionsStore {
@@ -93,3 +111,25 @@ impl BlockTransactionsStore for DbBlockTransactionsStore {
         self.access.delete(DirectDbWriter::new(&self.db), hash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder};
+
+    #[test]
+    fn test_count_at_least() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let store = DbBlockTransactionsStore::new(db, CachePolicy::Count(2));
+        assert!(store.count_at_least(0).unwrap());
+        assert!(!store.count_at_least(1).unwrap());
+
+        for i in 1..=5u64 {
+            store.insert(i.into(), Arc::new(vec![Transaction::default()])).unwrap();
+        }
+        assert_eq!(5, store.count().unwrap());
+        assert!(store.count_at_least(2).unwrap());
+        assert!(store.count_at_least(5).unwrap());
+        assert!(!store.count_at_least(6).unwrap());
+    }
+}
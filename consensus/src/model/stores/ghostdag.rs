@@ -4,7 +4,7 @@ use kaspa_consensus_core::{blockhash::BlockHashes, BlueWorkType};
 use kaspa_consensus_core::{BlockHashMap, BlockHasher, BlockLevel, HashMapCustomHasher};
 use kaspa_database::prelude::DB;
 use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess, DbKey};
-use kaspa_database::prelude::{CachePolicy, StoreError};
+use kaspa_database::prelude::{CachePolicy, StoreError, StoreResultExtensions};
 use kaspa_database::registry::{DatabaseStorePrefixes, SEPARATOR};
 use kaspa_hashes::Hash;
 
@@ -117,6 +117,15 @@ impl GhostdagData {
         &'a self,
         store: &'a (impl GhostdagStoreReader + ?Sized),
     ) -> impl Iterator<Item = SortableBlock> + 'a {
+        self.ascending_flagged_mergeset_without_selected_parent(store).map(|(b, _)| b)
+    }
+
+    /// Returns an iterator to the mergeset in ascending blue work order (tie-breaking by hash), where each
+    /// block is paired with a flag indicating whether it is blue (`true`) or red (`false`)
+    fn ascending_flagged_mergeset_without_selected_parent<'a>(
+        &'a self,
+        store: &'a (impl GhostdagStoreReader + ?Sized),
+    ) -> impl Iterator<Item = (SortableBlock, bool)> + 'a {
         self.mergeset_blues
             .iter()
             .skip(1) // Skip the selected parent
@@ -130,7 +139,8 @@ impl GhostdagData {
                 |a, b| a.cmp(b),
             )
             .map(|r| match r {
-                Left(b) | Right(b) => b,
+                Left(b) => (b, true),
+                Right(b) => (b, false),
                 Both(_, _) => panic!("distinct blocks are never equal"),
             })
     }
@@ -192,6 +202,29 @@ impl GhostdagData {
         self.mergeset_blues.iter().cloned().chain(self.mergeset_reds.iter().cloned())
     }
 
+    /// Returns an iterator to the mergeset in topological consensus order (see [`Self::consensus_ordered_mergeset`]),
+    /// where each block is paired with a flag indicating whether it is blue (`true`) or red (`false`). This allows
+    /// callers to test blue membership without building a separate set out of `mergeset_blues`
+    pub fn consensus_ordered_flagged_mergeset<'a>(
+        &'a self,
+        store: &'a (impl GhostdagStoreReader + ?Sized),
+    ) -> impl Iterator<Item = (Hash, bool)> + 'a {
+        once((self.selected_parent, true))
+            .chain(self.ascending_flagged_mergeset_without_selected_parent(store).map(|(b, is_blue)| (b.hash, is_blue)))
+    }
+
+    /// Returns an iterator to the mergeset blues in topological consensus order (including the selected parent)
+    pub fn consensus_ordered_mergeset_blues(&self) -> impl Iterator<Item = Hash> + '_ {
+        // Blues are stored by consensus order: the selected parent followed by the rest in ascending blue work order
+        self.mergeset_blues.iter().cloned()
+    }
+
+    /// Returns an iterator to the mergeset reds in topological consensus order
+    pub fn consensus_ordered_mergeset_reds(&self) -> impl Iterator<Item = Hash> + '_ {
+        // Reds are stored in ascending blue work order
+        self.mergeset_reds.iter().cloned()
+    }
+
     pub fn to_compact(&self) -> CompactGhostdagData {
         self.into()
     }
@@ -292,6 +325,11 @@ impl DbGhostdagStore {
         }
     }
 
+    /// Returns the tracked bytes and the number of entries held by the store cache
+    pub fn cache_footprint(&self) -> (usize, usize) {
+        self.access.cache_footprint()
+    }
+
     pub fn clone_with_new_cache(&self, cache_policy: CachePolicy, compact_cache_policy: CachePolicy) -> Self {
         Self::new(Arc::clone(&self.db), self.level, cache_policy, compact_cache_policy)
     }
@@ -315,6 +353,19 @@ impl DbGhostdagStore {
         self.compact_access.delete(BatchDbWriter::new(batch), hash)?;
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Reads the compact ghostdag data of all `hashes` in a single pass, preferring the full data cache
+    /// and falling back to the compact store. The result order matches the input order, with `None` for
+    /// hashes which are missing from the store.
+    pub fn get_compact_data_many(&self, hashes: &[Hash]) -> Vec<Option<CompactGhostdagData>> {
+        hashes
+            .iter()
+            .map(|&hash| match self.access.read_from_cache(hash) {
+                Some(ghostdag_data) => Some(ghostdag_data.to_compact()),
+                None => self.compact_access.read(hash).unwrap_option(),
+            })
+            .collect()
+    }
 }
 
 impl GhostdagStoreReader for DbGhostdagStore {
@@ -518,6 +569,7 @@ impl GhostdagStoreReader for MemoryGhostdagStore {
 mod tests {
     use super::*;
     use kaspa_consensus_core::BlockHashSet;
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder};
 
     #[test]
     fn test_mergeset_iterators() {
@@ -566,5 +618,52 @@ mod tests {
 
         let expected = BlockHashSet::from_iter([1.into(), 4.into(), 2.into(), 5.into(), 3.into(), 6.into()]);
         assert_eq!(expected, data.unordered_mergeset().collect::<BlockHashSet>());
+
+        // The flagged iteration must follow consensus order and agree with the separate blues/reds sets
+        let blues = BlockHashSet::from_iter(data.mergeset_blues.iter().copied());
+        let reds = BlockHashSet::from_iter(data.mergeset_reds.iter().copied());
+        let flagged = data.consensus_ordered_flagged_mergeset(&store).collect_vec();
+        itertools::assert_equal(data.consensus_ordered_mergeset(&store), flagged.iter().map(|&(h, _)| h));
+        for &(hash, is_blue) in flagged.iter() {
+            assert_eq!(is_blue, blues.contains(&hash));
+            assert_eq!(!is_blue, reds.contains(&hash));
+        }
+        itertools::assert_equal(
+            data.consensus_ordered_mergeset_blues(),
+            flagged.iter().filter_map(|&(h, is_blue)| is_blue.then_some(h)),
+        );
+        itertools::assert_equal(
+            data.consensus_ordered_mergeset_reds(),
+            flagged.iter().filter_map(|&(h, is_blue)| (!is_blue).then_some(h)),
+        );
+    }
+
+    #[test]
+    fn test_get_compact_data_many() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        // Use a tiny full-data cache so that some reads are served from the cache and others from the compact store
+        let store = DbGhostdagStore::new(db, 0, CachePolicy::Count(2), CachePolicy::Count(4));
+
+        for i in 1..=6u64 {
+            let mut data = GhostdagData::new_with_selected_parent((i - 1).into(), 5);
+            data.blue_score = i * 10;
+            data.blue_work = (i * 100).into();
+            store.insert(i.into(), Arc::new(data)).unwrap();
+        }
+
+        let hashes: Vec<Hash> = vec![4.into(), 1.into(), 42.into(), 6.into(), 3.into(), 1.into()];
+        let batch = store.get_compact_data_many(&hashes);
+        assert_eq!(batch.len(), hashes.len());
+        for (hash, compact) in hashes.iter().copied().zip(batch) {
+            match store.get_compact_data(hash).unwrap_option() {
+                Some(expected) => {
+                    let compact = compact.unwrap();
+                    assert_eq!(compact.blue_score, expected.blue_score);
+                    assert_eq!(compact.blue_work, expected.blue_work);
+                    assert_eq!(compact.selected_parent, expected.selected_parent);
+                }
+                None => assert!(compact.is_none(), "missing hash {hash} should map to None"),
+            }
+        }
     }
 }
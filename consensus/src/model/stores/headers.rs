@@ -3,7 +3,7 @@ use std::sync::Arc;
 use kaspa_consensus_core::{header::Header, BlockHasher, BlockLevel};
 use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess};
 use kaspa_database::prelude::{CachePolicy, DB};
-use kaspa_database::prelude::{StoreError, StoreResult};
+use kaspa_database::prelude::{StoreError, StoreResult, StoreResultExtensions};
 use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_hashes::Hash;
 use kaspa_utils::mem_size::MemSizeEstimator;
@@ -75,6 +75,11 @@ impl DbHeadersStore {
         }
     }
 
+    /// Returns the tracked bytes and the number of entries held by the store cache
+    pub fn cache_footprint(&self) -> (usize, usize) {
+        self.headers_access.cache_footprint()
+    }
+
     pub fn clone_with_new_cache(&self, cache_policy: CachePolicy, compact_cache_policy: CachePolicy) -> Self {
         Self::new(Arc::clone(&self.db), cache_policy, compact_cache_policy)
     }
@@ -102,6 +107,13 @@ impl DbHeadersStore {
         self.compact_headers_access.delete(BatchDbWriter::new(batch), hash)?;
         self.headers_access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Reads the headers of all `hashes`, one store read per hash, such that cached headers are served
+    /// without a DB access. The result order matches the input order, with `None` for hashes which are
+    /// missing from the store.
+    pub fn get_headers_many(&self, hashes: &[Hash]) -> Vec<Option<Arc<Header>>> {
+        hashes.iter().map(|&hash| self.headers_access.read(hash).unwrap_option().map(|data| data.header)).collect()
+    }
 }
 
 impl HeaderStoreReader for DbHeadersStore {
@@ -400,7 +400,8 @@ impl HeaderProcessor {
         let mut staging = StagingReachabilityStore::new(self.reachability_store.upgradable_read());
         let selected_parent = ghostdag_data.selected_parent;
         let mut reachability_mergeset = ghostdag_data.unordered_mergeset_without_selected_parent();
-        reachability::add_block(&mut staging, ctx.hash, selected_parent, &mut reachability_mergeset).unwrap();
+        reachability::add_block_with_counters(&mut staging, ctx.hash, selected_parent, &mut reachability_mergeset, &self.counters)
+            .unwrap();
 
         // Non-append only stores need to use write locks.
         // Note we need to keep the lock write guards until the batch is written.
@@ -410,7 +411,7 @@ impl HeaderProcessor {
             && reachability::is_chain_ancestor_of(&staging, pp, ctx.hash).unwrap()
         {
             // Hint reachability about the new tip.
-            reachability::hint_virtual_selected_parent(&mut staging, ctx.hash).unwrap();
+            reachability::hint_virtual_selected_parent_with_counters(&mut staging, ctx.hash, &self.counters).unwrap();
             hst_write.set_batch(&mut batch, SortableBlock::new(ctx.hash, header.blue_work)).unwrap();
         }
 
@@ -1,8 +1,8 @@
 use super::ProcessingCounters;
 use kaspa_core::{
-    info,
+    debug, info,
     task::{
-        service::{AsyncService, AsyncServiceFuture},
+        service::{AsyncService, AsyncServiceFuture, ServiceShutdown},
         tick::{TickReason, TickService},
     },
     trace, warn,
@@ -20,11 +20,14 @@ pub struct ConsensusMonitor {
 
     // Tick service
     tick_service: Arc<TickService>,
+
+    // Exit signal
+    shutdown: ServiceShutdown,
 }
 
 impl ConsensusMonitor {
     pub fn new(counters: Arc<ProcessingCounters>, tick_service: Arc<TickService>) -> ConsensusMonitor {
-        ConsensusMonitor { counters, tick_service }
+        ConsensusMonitor { counters, tick_service, shutdown: ServiceShutdown::new() }
     }
 
     pub async fn worker(self: &Arc<ConsensusMonitor>) {
@@ -32,7 +35,7 @@ impl ConsensusMonitor {
         let mut last_log_time = Instant::now();
         let snapshot_interval = 10;
         loop {
-            if let TickReason::Shutdown = self.tick_service.tick(Duration::from_secs(snapshot_interval)).await {
+            if let TickReason::Shutdown = self.shutdown.tick(&self.tick_service, Duration::from_secs(snapshot_interval)).await {
                 // Let the system print final logs before exiting
                 tokio::time::sleep(Duration::from_millis(500)).await;
                 break;
@@ -62,6 +65,24 @@ impl ConsensusMonitor {
                 if delta.body_counts != 0 { delta.mass_counts as f64 / delta.body_counts as f64 } else{ 0f64 },
             );
 
+            debug!(
+                "Applied UTXO diffs in the last {:.2}s: {} added, {} removed and {} conflicting entries",
+                (now - last_log_time).as_secs_f64(),
+                delta.utxo_diff_add_counts,
+                delta.utxo_diff_remove_counts,
+                delta.utxo_diff_conflict_counts
+            );
+
+            if delta.reindex_counts > 0 || delta.reindex_root_advance_counts > 0 {
+                debug!(
+                    "Reachability in the last {:.2}s: {} reindex operations and {} reindex root advances, updating {} block intervals",
+                    (now - last_log_time).as_secs_f64(),
+                    delta.reindex_counts,
+                    delta.reindex_root_advance_counts,
+                    delta.reindexed_block_counts
+                );
+            }
+
             if delta.chain_disqualified_counts > 0 {
                 warn!(
                     "Consensus detected UTXO-invalid blocks which are disqualified from the virtual selected chain (possibly due to inheritance): {} disqualified vs. {} valid chain blocks",
@@ -92,6 +113,7 @@ impl AsyncService for ConsensusMonitor {
 
     fn signal_exit(self: Arc<Self>) {
         trace!("sending an exit signal to {}", MONITOR);
+        self.shutdown.signal();
     }
 
     fn stop(self: Arc<Self>) -> AsyncServiceFuture {
@@ -64,10 +64,10 @@ use kaspa_consensus_core::{
     pruning::PruningPointsList,
     tx::{MutableTransaction, Transaction},
     utxo::{
-        utxo_diff::UtxoDiff,
+        utxo_diff::{UtxoDiff, UtxoDiffApplyCounts},
         utxo_view::{UtxoView, UtxoViewComposition},
     },
-    BlockHashSet, ChainPath,
+    BlockHashSet, ChainPath, ReorgInfo,
 };
 use kaspa_consensus_notify::{
     notification::{
@@ -95,7 +95,7 @@ use kaspa_utils::binary_heap::BinaryHeapExtensions;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use rand::{seq::SliceRandom, Rng};
 use rayon::{
-    prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator},
+    prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator, ParallelSliceMut},
     ThreadPool,
 };
 use rocksdb::WriteBatch;
@@ -172,6 +172,9 @@ pub struct VirtualStateProcessor {
     // Counters
     counters: Arc<ProcessingCounters>,
 
+    // The most recent virtual selected chain change
+    last_reorg_info: RwLock<Option<ReorgInfo>>,
+
     pub(super) crescendo_logger: CrescendoLogger,
 
     // Crescendo hardfork activation score (used here for activating KIPs 9,10)
@@ -243,6 +246,7 @@ impl VirtualStateProcessor {
             pruning_lock,
             notification_root,
             counters,
+            last_reorg_info: Default::default(),
             crescendo_logger: CrescendoLogger::new(),
             crescendo_activation: params.crescendo_activation,
             mining_rules,
@@ -331,6 +335,10 @@ impl VirtualStateProcessor {
             )
             .expect("all possible rule errors are unexpected here");
 
+        if new_sink != prev_sink {
+            self.update_last_reorg_info(prev_sink, &chain_path);
+        }
+
         let compact_sink_ghostdag_data = if let Some(sink_ghostdag_data) = Lazy::get(&sink_ghostdag_data) {
             // If we had to retrieve the full data, we convert it to compact
             sink_ghostdag_data.to_compact()
@@ -374,6 +382,28 @@ impl VirtualStateProcessor {
         }
     }
 
+    /// Records the reorg info for the given chain path. `removed` is ordered from the previous sink downwards,
+    /// so the common ancestor is the selected parent of its last block (or the previous sink itself if empty)
+    fn update_last_reorg_info(&self, prev_sink: Hash, chain_path: &ChainPath) {
+        let common_ancestor = match chain_path.removed.last() {
+            Some(&lowest_removed) => self.ghostdag_store.get_selected_parent(lowest_removed).unwrap(),
+            None => prev_sink,
+        };
+        let reorg_info =
+            ReorgInfo { common_ancestor, removed_chain_len: chain_path.removed.len(), added_chain_len: chain_path.added.len() };
+        if reorg_info.removed_chain_len > 0 {
+            debug!(
+                "Virtual selected chain reorg of depth {} (common ancestor {}, {} added chain blocks)",
+                reorg_info.removed_chain_len, reorg_info.common_ancestor, reorg_info.added_chain_len
+            );
+        }
+        *self.last_reorg_info.write() = Some(reorg_info);
+    }
+
+    pub fn last_reorg_info(&self) -> Option<ReorgInfo> {
+        *self.last_reorg_info.read()
+    }
+
     pub(crate) fn virtual_finality_point(&self, virtual_ghostdag_data: &GhostdagData, pruning_point: Hash) -> Hash {
         let finality_point = self.depth_manager.calc_finality_point(virtual_ghostdag_data, pruning_point);
         if self.reachability_service.is_chain_ancestor_of(pruning_point, finality_point) {
@@ -392,11 +422,12 @@ impl VirtualStateProcessor {
     /// When returning it is guaranteed that `diff` holds the diff of the returned block from virtual
     fn calculate_utxo_state_relatively(&self, stores: &VirtualStores, diff: &mut UtxoDiff, from: Hash, to: Hash) -> Hash {
         // Avoid reorging if disqualified status is already known
-        if self.statuses_store.read().get(to).unwrap() == StatusDisqualifiedFromChain {
+        if self.statuses_store.read().get(to).unwrap().is_disqualified_from_chain() {
             return from;
         }
 
         let mut split_point: Option<Hash> = None;
+        let mut diff_counts = UtxoDiffApplyCounts::default();
 
         // Walk down to the reorg split point
         for current in self.reachability_service.default_backward_chain_iterator(from) {
@@ -407,7 +438,7 @@ impl VirtualStateProcessor {
 
             let mergeset_diff = self.utxo_diffs_store.get(current).unwrap();
             // Apply the diff in reverse
-            diff.with_diff_in_place(&mergeset_diff.as_reversed()).unwrap();
+            diff_counts += diff.with_diff_in_place_counted(&mergeset_diff.as_reversed()).unwrap();
         }
 
         let split_point = split_point.expect("chain iterator was expected to reach the reorg split point");
@@ -424,7 +455,7 @@ impl VirtualStateProcessor {
             if selected_parent != diff_point {
                 // This indicates that the selected parent is disqualified, propagate up and continue
                 let statuses_guard = self.statuses_store.upgradable_read();
-                if statuses_guard.get(current).unwrap() != StatusDisqualifiedFromChain {
+                if !statuses_guard.get(current).unwrap().is_disqualified_from_chain() {
                     RwLockUpgradableReadGuard::upgrade(statuses_guard).set(current, StatusDisqualifiedFromChain).unwrap();
                     chain_disqualified_counter += 1;
                 }
@@ -433,11 +464,11 @@ impl VirtualStateProcessor {
 
             match self.utxo_diffs_store.get(current) {
                 Ok(mergeset_diff) => {
-                    diff.with_diff_in_place(mergeset_diff.deref()).unwrap();
+                    diff_counts += diff.with_diff_in_place_counted(mergeset_diff.deref()).unwrap();
                     diff_point = current;
                 }
                 Err(StoreError::KeyNotFound(_)) => {
-                    if self.statuses_store.read().get(current).unwrap() == StatusDisqualifiedFromChain {
+                    if self.statuses_store.read().get(current).unwrap().is_disqualified_from_chain() {
                         // Current block is already known to be disqualified
                         continue;
                     }
@@ -462,7 +493,7 @@ impl VirtualStateProcessor {
                         debug!("VIRTUAL PROCESSOR, UTXO validated for {current}");
 
                         // Accumulate the diff
-                        diff.with_diff_in_place(&ctx.mergeset_diff).unwrap();
+                        diff_counts += diff.with_diff_in_place_counted(&ctx.mergeset_diff).unwrap();
                         // Update the diff point
                         diff_point = current;
                         // Commit UTXO data for current chain block
@@ -485,6 +516,7 @@ impl VirtualStateProcessor {
         if chain_disqualified_counter > 0 {
             self.counters.chain_disqualified_counts.fetch_add(chain_disqualified_counter, Ordering::Relaxed);
         }
+        self.counters.add_utxo_diff_counts(diff_counts);
 
         diff_point
     }
@@ -549,7 +581,8 @@ impl VirtualStateProcessor {
         self.calculate_utxo_state(&mut ctx, &selected_parent_utxo_view, virtual_daa_window.daa_score);
 
         // Update the accumulated diff
-        accumulated_diff.with_diff_in_place(&ctx.mergeset_diff).unwrap();
+        let diff_counts = accumulated_diff.with_diff_in_place_counted(&ctx.mergeset_diff).unwrap();
+        self.counters.add_utxo_diff_counts(diff_counts);
 
         // Build the new virtual state
         Ok(Arc::new(VirtualState::new(
@@ -875,19 +908,23 @@ impl VirtualStateProcessor {
         let virtual_daa_score = virtual_state.daa_score;
         let virtual_past_median_time = virtual_state.past_median_time;
 
-        self.thread_pool.install(|| {
-            mutable_txs
-                .par_iter_mut()
-                .map(|mtx| {
-                    self.validate_mempool_transaction_impl(
-                        mtx,
-                        &virtual_utxo_view,
-                        virtual_daa_score,
-                        virtual_past_median_time,
-                        args.get(&mtx.id()),
-                    )
-                })
-                .collect::<Vec<TxResult<()>>>()
+        let validate = |mtx: &mut MutableTransaction| {
+            self.validate_mempool_transaction_impl(
+                mtx,
+                &virtual_utxo_view,
+                virtual_daa_score,
+                virtual_past_median_time,
+                args.get(&mtx.id()),
+            )
+        };
+        self.thread_pool.install(|| match args.max_parallelism() {
+            // Split the batch into at most `max_parallelism` chunks, each validated sequentially,
+            // so that the batch never occupies more than this number of pool threads
+            Some(max_parallelism) => {
+                let chunk_len = mutable_txs.len().div_ceil(max_parallelism).max(1);
+                mutable_txs.par_chunks_mut(chunk_len).flat_map_iter(|chunk| chunk.iter_mut().map(validate)).collect()
+            }
+            None => mutable_txs.par_iter_mut().map(validate).collect::<Vec<TxResult<()>>>(),
         })
     }
 
@@ -1066,7 +1103,7 @@ impl VirtualStateProcessor {
                 &virtual_state.mergeset_rewards,
                 &virtual_state.mergeset_non_daa,
             )
-            .unwrap();
+            .map_err(RuleError::BadCoinbasePayload)?;
         txs.insert(0, coinbase.tx);
         let version = BLOCK_VERSION;
         let parents_by_level = self.parents_manager.calc_block_parents(pruning_info.pruning_point, &virtual_state.parents);
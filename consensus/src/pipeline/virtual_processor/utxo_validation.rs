@@ -170,10 +170,8 @@ impl VirtualStateProcessor {
             });
 
             let coinbase_data = self.coinbase_manager.deserialize_coinbase_payload(&txs[0].payload).unwrap();
-            ctx.mergeset_rewards.insert(
-                merged_block,
-                BlockRewardData::new(coinbase_data.subsidy, block_fee, coinbase_data.miner_data.script_public_key),
-            );
+            ctx.mergeset_rewards
+                .insert(merged_block, BlockRewardData::new(coinbase_data.subsidy, block_fee, coinbase_data.miner_data.into_parts().0));
         }
 
         // Before crescendo HF:
@@ -452,7 +450,7 @@ impl VirtualStateProcessor {
 
     /// Calculates the accepted_id_merkle_root based on the current DAA score and the accepted tx ids
     /// refer KIP-15 for more details
-    pub(super) fn calc_accepted_id_merkle_root(
+    pub(crate) fn calc_accepted_id_merkle_root(
         &self,
         daa_score: u64,
         accepted_tx_ids: impl ExactSizeIterator<Item = Hash>,
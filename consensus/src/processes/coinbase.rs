@@ -1,6 +1,6 @@
 use kaspa_consensus_core::{
     coinbase::*,
-    config::params::ForkedParam,
+    config::params::{ForkedParam, Params},
     errors::coinbase::{CoinbaseError, CoinbaseResult},
     subnets,
     tx::{ScriptPublicKey, ScriptVec, Transaction, TransactionOutput},
@@ -15,8 +15,7 @@ const LENGTH_OF_SUBSIDY: usize = size_of::<u64>();
 const LENGTH_OF_SCRIPT_PUB_KEY_VERSION: usize = size_of::<u16>();
 const LENGTH_OF_SCRIPT_PUB_KEY_LENGTH: usize = size_of::<u8>();
 
-const MIN_PAYLOAD_LENGTH: usize =
-    LENGTH_OF_BLUE_SCORE + LENGTH_OF_SUBSIDY + LENGTH_OF_SCRIPT_PUB_KEY_VERSION + LENGTH_OF_SCRIPT_PUB_KEY_LENGTH;
+const MIN_PAYLOAD_LENGTH: usize = COINBASE_PAYLOAD_FIXED_LEN;
 
 // We define a year as 365.25 days and a month as 365.25 / 12 = 30.4375
 // SECONDS_PER_MONTH = 30.4375 * 24 * 60 * 60
@@ -88,6 +87,16 @@ impl CoinbaseManager {
         }
     }
 
+    pub fn from_params(params: &Params) -> Self {
+        Self::new(
+            params.coinbase_payload_script_public_key_max_len,
+            params.max_coinbase_payload_len,
+            params.deflationary_phase_daa_score,
+            params.pre_deflationary_phase_base_subsidy,
+            params.bps(),
+        )
+    }
+
     #[cfg(test)]
     #[inline]
     pub fn bps(&self) -> ForkedParam<u64> {
@@ -108,9 +117,9 @@ impl CoinbaseManager {
         // Note that combinatorically it is nearly impossible for a blue block to be non-DAA
         for blue in ghostdag_data.mergeset_blues.iter().filter(|h| !mergeset_non_daa.contains(h)) {
             let reward_data = mergeset_rewards.get(blue).unwrap();
-            if reward_data.subsidy + reward_data.total_fees > 0 {
-                outputs
-                    .push(TransactionOutput::new(reward_data.subsidy + reward_data.total_fees, reward_data.script_public_key.clone()));
+            let split = reward_data.split_for_template(false, true);
+            if split.miner_amount > 0 {
+                outputs.push(TransactionOutput::new(split.miner_amount, reward_data.script_public_key.clone()));
             }
         }
 
@@ -122,21 +131,18 @@ impl CoinbaseManager {
         if self.bps.activation().is_active(daa_score) {
             for red in ghostdag_data.mergeset_reds.iter() {
                 let reward_data = mergeset_rewards.get(red).unwrap();
-                if mergeset_non_daa.contains(red) {
-                    red_reward += reward_data.total_fees;
-                } else {
-                    red_reward += reward_data.subsidy + reward_data.total_fees;
-                }
+                // Non-DAA reds are not rewarded with subsidy
+                red_reward += reward_data.split_for_template(true, !mergeset_non_daa.contains(red)).red_amount;
             }
         } else {
             for red in ghostdag_data.mergeset_reds.iter().filter(|h| !mergeset_non_daa.contains(h)) {
                 let reward_data = mergeset_rewards.get(red).unwrap();
-                red_reward += reward_data.subsidy + reward_data.total_fees;
+                red_reward += reward_data.split_for_template(true, true).red_amount;
             }
         }
 
         if red_reward > 0 {
-            outputs.push(TransactionOutput::new(red_reward, miner_data.script_public_key.clone()));
+            outputs.push(TransactionOutput::new(red_reward, miner_data.script_public_key().clone()));
         }
 
         // Build the current block's payload
@@ -149,42 +155,48 @@ impl CoinbaseManager {
         })
     }
 
-    pub fn serialize_coinbase_payload<T: AsRef<[u8]>>(&self, data: &CoinbaseData<T>) -> CoinbaseResult<Vec<u8>> {
-        let script_pub_key_len = data.miner_data.script_public_key.script().len();
+    /// Verifies the miner data fits within the script public key and overall payload length limits
+    fn check_miner_data<T: AsRef<[u8]>>(&self, miner_data: &MinerData<T>) -> CoinbaseResult<()> {
+        let script_pub_key_len = miner_data.script_public_key().script().len();
         if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
             return Err(CoinbaseError::PayloadScriptPublicKeyLenAboveMax(
                 script_pub_key_len,
                 self.coinbase_payload_script_public_key_max_len,
             ));
         }
+        let payload_len = miner_data.coinbase_payload_len();
+        if payload_len > self.max_coinbase_payload_len {
+            return Err(CoinbaseError::PayloadLenAboveMax(payload_len, self.max_coinbase_payload_len));
+        }
+        Ok(())
+    }
+
+    pub fn serialize_coinbase_payload<T: AsRef<[u8]>>(&self, data: &CoinbaseData<T>) -> CoinbaseResult<Vec<u8>> {
+        self.check_miner_data(&data.miner_data)?;
+        let script_pub_key_len = data.miner_data.script_public_key().script().len();
         let payload: Vec<u8> = data.blue_score.to_le_bytes().iter().copied()                    // Blue score                   (u64)
             .chain(data.subsidy.to_le_bytes().iter().copied())                                  // Subsidy                      (u64)
-            .chain(data.miner_data.script_public_key.version().to_le_bytes().iter().copied())   // Script public key version    (u16)
+            .chain(data.miner_data.script_public_key().version().to_le_bytes().iter().copied()) // Script public key version    (u16)
             .chain((script_pub_key_len as u8).to_le_bytes().iter().copied())                    // Script public key length     (u8)
-            .chain(data.miner_data.script_public_key.script().iter().copied())                  // Script public key            
-            .chain(data.miner_data.extra_data.as_ref().iter().copied())                         // Extra data
+            .chain(data.miner_data.script_public_key().script().iter().copied())                // Script public key            
+            .chain(data.miner_data.extra_data().iter().copied())                                // Extra data
             .collect();
 
         Ok(payload)
     }
 
     pub fn modify_coinbase_payload<T: AsRef<[u8]>>(&self, mut payload: Vec<u8>, miner_data: &MinerData<T>) -> CoinbaseResult<Vec<u8>> {
-        let script_pub_key_len = miner_data.script_public_key.script().len();
-        if script_pub_key_len > self.coinbase_payload_script_public_key_max_len as usize {
-            return Err(CoinbaseError::PayloadScriptPublicKeyLenAboveMax(
-                script_pub_key_len,
-                self.coinbase_payload_script_public_key_max_len,
-            ));
-        }
+        self.check_miner_data(miner_data)?;
+        let script_pub_key_len = miner_data.script_public_key().script().len();
 
         // Keep only blue score and subsidy. Note that truncate does not modify capacity, so
         // the usual case where the payloads are the same size will not trigger a reallocation
         payload.truncate(LENGTH_OF_BLUE_SCORE + LENGTH_OF_SUBSIDY);
         payload.extend(
-            miner_data.script_public_key.version().to_le_bytes().iter().copied() // Script public key version (u16)
+            miner_data.script_public_key().version().to_le_bytes().iter().copied() // Script public key version (u16)
                 .chain((script_pub_key_len as u8).to_le_bytes().iter().copied()) // Script public key length  (u8)
-                .chain(miner_data.script_public_key.script().iter().copied())    // Script public key
-                .chain(miner_data.extra_data.as_ref().iter().copied()), // Extra data
+                .chain(miner_data.script_public_key().script().iter().copied())  // Script public key
+                .chain(miner_data.extra_data().iter().copied()), // Extra data
         );
 
         Ok(payload)
@@ -224,7 +236,19 @@ impl CoinbaseManager {
             ScriptPublicKey::new(script_pub_key_version, ScriptVec::from_slice(parser.take(script_pub_key_len as usize)));
         let extra_data = parser.remaining;
 
-        Ok(CoinbaseData { blue_score, subsidy, miner_data: MinerData { script_public_key, extra_data } })
+        Ok(CoinbaseData { blue_score, subsidy, miner_data: MinerData::new(script_public_key, extra_data) })
+    }
+
+    /// Deserializes the coinbase payload of a block with DAA score `daa_score` and validates its subsidy against
+    /// the subsidy schedule. Note that the schedule is a function of the DAA score and not of the blue score
+    /// committed to by the payload, hence the DAA score of the block must be provided
+    pub fn try_decode_coinbase_payload<'a>(&self, payload: &'a [u8], daa_score: u64) -> CoinbaseResult<CoinbaseData<&'a [u8]>> {
+        let data = self.deserialize_coinbase_payload(payload)?;
+        let expected_subsidy = self.calc_block_subsidy(daa_score);
+        if data.subsidy != expected_subsidy {
+            return Err(CoinbaseError::PayloadSubsidyMismatch(data.subsidy, expected_subsidy, daa_score));
+        }
+        Ok(data)
     }
 
     pub fn calc_block_subsidy(&self, daa_score: u64) -> u64 {
@@ -519,10 +543,7 @@ mod tests {
         let data = CoinbaseData {
             blue_score: 56,
             subsidy: 44000000000,
-            miner_data: MinerData {
-                script_public_key: ScriptPublicKey::new(0, ScriptVec::from_slice(&script_data)),
-                extra_data: &extra_data as &[u8],
-            },
+            miner_data: MinerData::new(ScriptPublicKey::new(0, ScriptVec::from_slice(&script_data)), &extra_data as &[u8]),
         };
 
         let payload = cbm.serialize_coinbase_payload(&data).unwrap();
@@ -540,20 +561,64 @@ mod tests {
         let expected_data = CoinbaseData {
             blue_score: 29954742,
             subsidy: 31112698372,
-            miner_data: MinerData {
-                script_public_key: ScriptPublicKey::new(
+            miner_data: MinerData::new(
+                ScriptPublicKey::new(
                     0,
                     scriptvec![
                         32, 43, 50, 68, 63, 247, 64, 1, 33, 87, 113, 109, 129, 33, 109, 9, 174, 188, 57, 229, 73, 60, 147, 167, 24,
                         29, 146, 203, 117, 108, 2, 197, 96, 172,
                     ],
                 ),
-                extra_data: &[48u8, 46, 49, 50, 46, 56, 47] as &[u8],
-            },
+                &[48u8, 46, 49, 50, 46, 56, 47] as &[u8],
+            ),
         };
         assert_eq!(expected_data, deserialized_data);
     }
 
+    #[test]
+    fn try_decode_payload_test() {
+        let cbm = create_manager(&MAINNET_PARAMS);
+        let daa_score = MAINNET_PARAMS.deflationary_phase_daa_score + 1000;
+        let subsidy = cbm.calc_block_subsidy(daa_score);
+        let data = CoinbaseData {
+            blue_score: 56,
+            subsidy,
+            miner_data: MinerData::new(ScriptPublicKey::new(0, ScriptVec::from_slice(&[33u8; 34])), &[2u8, 3] as &[u8]),
+        };
+        let payload = cbm.serialize_coinbase_payload(&data).unwrap();
+
+        // A valid payload
+        assert_eq!(cbm.try_decode_coinbase_payload(&payload, daa_score).unwrap(), data);
+
+        // Truncated payloads
+        assert!(matches!(
+            cbm.try_decode_coinbase_payload(&payload[..MIN_PAYLOAD_LENGTH - 1], daa_score),
+            Err(CoinbaseError::PayloadLenBelowMin(..))
+        ));
+        assert!(matches!(
+            cbm.try_decode_coinbase_payload(&payload[..MIN_PAYLOAD_LENGTH + 10], daa_score),
+            Err(CoinbaseError::PayloadCantContainScriptPublicKey(..))
+        ));
+
+        // An oversized payload
+        let mut oversized = payload.clone();
+        oversized.resize(MAINNET_PARAMS.max_coinbase_payload_len + 1, 0);
+        assert!(matches!(cbm.try_decode_coinbase_payload(&oversized, daa_score), Err(CoinbaseError::PayloadLenAboveMax(..))));
+
+        // A corrupted subsidy
+        let mut corrupted = payload.clone();
+        corrupted[LENGTH_OF_BLUE_SCORE..LENGTH_OF_BLUE_SCORE + LENGTH_OF_SUBSIDY].copy_from_slice(&(subsidy + 1).to_le_bytes());
+        assert!(matches!(
+            cbm.try_decode_coinbase_payload(&corrupted, daa_score),
+            Err(CoinbaseError::PayloadSubsidyMismatch(actual, expected, score)) if actual == subsidy + 1 && expected == subsidy && score == daa_score
+        ));
+
+        // A subsidy which is valid for another point in the schedule
+        let later_daa_score = daa_score + 12 * SECONDS_PER_MONTH * MAINNET_PARAMS.bps().after();
+        assert_ne!(cbm.calc_block_subsidy(later_daa_score), subsidy);
+        assert!(matches!(cbm.try_decode_coinbase_payload(&payload, later_daa_score), Err(CoinbaseError::PayloadSubsidyMismatch(..))));
+    }
+
     #[test]
     fn modify_payload_test() {
         let cbm = create_manager(&MAINNET_PARAMS);
@@ -563,20 +628,17 @@ mod tests {
         let data = CoinbaseData {
             blue_score: 56345,
             subsidy: 44000000000,
-            miner_data: MinerData {
-                script_public_key: ScriptPublicKey::new(0, ScriptVec::from_slice(&script_data)),
-                extra_data: &extra_data,
-            },
+            miner_data: MinerData::new(ScriptPublicKey::new(0, ScriptVec::from_slice(&script_data)), &extra_data),
         };
 
         let data2 = CoinbaseData {
             blue_score: data.blue_score,
             subsidy: data.subsidy,
-            miner_data: MinerData {
-                // Modify only miner data
-                script_public_key: ScriptPublicKey::new(0, ScriptVec::from_slice(&[33u8, 255, 33])),
-                extra_data: &[2u8, 3, 23, 98, 34, 34] as &[u8],
-            },
+            // Modify only miner data
+            miner_data: MinerData::new(
+                ScriptPublicKey::new(0, ScriptVec::from_slice(&[33u8, 255, 33])),
+                &[2u8, 3, 23, 98, 34, 34] as &[u8],
+            ),
         };
 
         let mut payload = cbm.serialize_coinbase_payload(&data).unwrap();
@@ -587,13 +649,7 @@ mod tests {
     }
 
     fn create_manager(params: &Params) -> CoinbaseManager {
-        CoinbaseManager::new(
-            params.coinbase_payload_script_public_key_max_len,
-            params.max_coinbase_payload_len,
-            params.deflationary_phase_daa_score,
-            params.pre_deflationary_phase_base_subsidy,
-            params.bps(),
-        )
+        CoinbaseManager::from_params(params)
     }
 
     /// Return a CoinbaseManager with legacy golang 1 BPS properties
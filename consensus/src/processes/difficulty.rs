@@ -150,10 +150,13 @@ impl<T: HeaderStoreReader> FullDifficultyManager<T> {
 
         // We need Uint320 to avoid overflow when summing and multiplying by the window size.
         let difficulty_blocks_len = difficulty_blocks.len() as u64;
-        let targets_sum: Uint320 =
-            difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
-        let average_target = targets_sum / (difficulty_blocks_len);
-        let new_target = average_target * max(max_ts - min_ts, 1) / (self.target_time_per_block * difficulty_blocks_len);
+        let Ok(average_target) = calc_average_target(difficulty_blocks.iter().map(|diff_block| diff_block.bits)) else {
+            // A single-block window is left empty after removing the minimal block
+            return self.genesis_bits;
+        };
+        let measured_duration = max(max_ts - min_ts, 1);
+        debug_assert!(!average_target.overflowing_mul_u64(measured_duration).1, "target scaling should not overflow Uint320");
+        let new_target = average_target * measured_duration / (self.target_time_per_block * difficulty_blocks_len);
         Uint256::try_from(new_target.min(self.max_difficulty_target)).expect("max target < Uint256::MAX").compact_target_bits()
     }
 
@@ -367,10 +370,12 @@ impl<T: HeaderStoreReader, U: GhostdagStoreReader> SampledDifficultyManager<T, U
 
         // We need Uint320 to avoid overflow when summing and multiplying by the window size.
         let difficulty_blocks_len = difficulty_blocks.len() as u64;
-        let targets_sum: Uint320 =
-            difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
-        let average_target = targets_sum / difficulty_blocks_len;
+        let Ok(average_target) = calc_average_target(difficulty_blocks.iter().map(|diff_block| diff_block.bits)) else {
+            // A single-block window is left empty after removing the minimal block
+            return self.genesis_bits;
+        };
         let measured_duration = max(max_ts - min_ts, 1);
+        debug_assert!(!average_target.overflowing_mul_u64(measured_duration).1, "target scaling should not overflow Uint320");
         let expected_duration = self.target_time_per_block * self.difficulty_sample_rate * difficulty_blocks_len; // This does differ from FullDifficultyManager version
         let new_target = average_target * measured_duration / expected_duration;
 
@@ -430,6 +435,74 @@ pub fn level_work(level: u8, max_block_level: u8) -> BlueWorkType {
     BlueWorkType::from_u64(1) << exp.min(MAX_WORK_LEVEL as u32)
 }
 
+/// Calculates the average target of the given set of compact target bits, or returns
+/// [`DifficultyError::EmptyWindow`] if the set is empty. Summation is done over Uint320 in order to avoid overflow.
+pub fn calc_average_target(bits: impl IntoIterator<Item = u32>) -> DifficultyResult<Uint320> {
+    let accumulator: AverageTargetAccumulator = bits.into_iter().collect();
+    accumulator.average_target().ok_or(DifficultyError::EmptyWindow)
+}
+
+/// A streaming counterpart of [`calc_average_target`]. Blocks can be added and removed one at a time,
+/// allowing a window to slide without recomputing the full sum at each step. For the same set of blocks,
+/// the running average is identical to the batch result.
+#[derive(Clone, Debug, Default)]
+pub struct AverageTargetAccumulator {
+    targets_sum: Uint320,
+    count: u64,
+}
+
+impl AverageTargetAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a block with the given compact target `bits` to the accumulated set
+    pub fn add(&mut self, bits: u32) {
+        let (targets_sum, overflow) = self.targets_sum.overflowing_add(Uint320::from(Uint256::from_compact_target_bits(bits)));
+        // Each target is bounded by Uint256::MAX, so the sum can only overflow after 2^64 additions
+        debug_assert!(!overflow, "targets sum should not overflow Uint320");
+        self.targets_sum = targets_sum;
+        self.count += 1;
+    }
+
+    /// Removes a block with the given compact target `bits` from the accumulated set. The caller
+    /// is responsible for only removing blocks which were previously added.
+    pub fn remove(&mut self, bits: u32) {
+        assert!(self.count > 0, "cannot remove a block from an empty accumulator");
+        let (targets_sum, underflow) = self.targets_sum.overflowing_sub(Uint320::from(Uint256::from_compact_target_bits(bits)));
+        debug_assert!(!underflow, "removed a block which was not previously added");
+        self.targets_sum = targets_sum;
+        self.count -= 1;
+    }
+
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the average target of the accumulated set, or `None` if the set is empty
+    pub fn average_target(&self) -> Option<Uint320> {
+        (self.count > 0).then(|| self.targets_sum / self.count)
+    }
+}
+
+impl Extend<u32> for AverageTargetAccumulator {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|bits| self.add(bits));
+    }
+}
+
+impl FromIterator<u32> for AverageTargetAccumulator {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut accumulator = Self::new();
+        accumulator.extend(iter);
+        accumulator
+    }
+}
+
 #[derive(Eq)]
 struct DifficultyBlock {
     timestamp: u64,
@@ -458,12 +531,13 @@ impl Ord for DifficultyBlock {
 
 #[cfg(test)]
 mod tests {
-    use kaspa_consensus_core::{BlockLevel, BlueWorkType, MAX_WORK_LEVEL};
+    use kaspa_consensus_core::{errors::difficulty::DifficultyError, BlockLevel, BlueWorkType, MAX_WORK_LEVEL};
     use kaspa_math::{Uint256, Uint320};
     use kaspa_pow::calc_level_from_pow;
 
-    use crate::processes::difficulty::{calc_work, level_work};
+    use crate::processes::difficulty::{calc_average_target, calc_work, level_work, AverageTargetAccumulator};
     use kaspa_utils::hex::ToHex;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
 
     #[test]
     fn test_target_levels() {
@@ -506,4 +580,43 @@ mod tests {
         // Expect that at level 0, the level work is always 0
         assert_eq!(BlueWorkType::from(0), level_work(0, 255));
     }
+
+    #[test]
+    fn test_streaming_average_target_matches_batch() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let base_target = Uint256::from_compact_target_bits(0x1d00ffff);
+        for _ in 0..20 {
+            // Generate close targets (within ~1% of each other), mimicking a realistic difficulty window
+            let bits: Vec<u32> =
+                (0..300).map(|_| (base_target - base_target / 100 * rng.gen_range(0..100u64) / 100).compact_target_bits()).collect();
+            let window_size = rng.gen_range(1..=50usize);
+
+            let mut accumulator: AverageTargetAccumulator = bits[..window_size].iter().copied().collect();
+            assert_eq!(accumulator.average_target().unwrap(), calc_average_target(bits[..window_size].iter().copied()).unwrap());
+
+            // Slide the window one block at a time and compare with the batch calculation
+            for end in window_size..bits.len() {
+                accumulator.remove(bits[end - window_size]);
+                accumulator.add(bits[end]);
+                let window = &bits[end + 1 - window_size..=end];
+                assert_eq!(accumulator.len(), window_size as u64);
+                assert_eq!(accumulator.average_target().unwrap(), calc_average_target(window.iter().copied()).unwrap());
+            }
+        }
+
+        let mut accumulator = AverageTargetAccumulator::new();
+        assert!(accumulator.average_target().is_none());
+        accumulator.add(0x1d00ffff);
+        accumulator.remove(0x1d00ffff);
+        assert!(accumulator.is_empty());
+        assert!(accumulator.average_target().is_none());
+    }
+
+    #[test]
+    fn test_average_target_of_empty_window() {
+        assert!(matches!(calc_average_target(Vec::<u32>::new()), Err(DifficultyError::EmptyWindow)));
+        assert!(
+            matches!(calc_average_target([0x1d00ffff]), Ok(target) if target == Uint320::from(Uint256::from_compact_target_bits(0x1d00ffff)))
+        );
+    }
 }
@@ -1,7 +1,7 @@
 use super::interval::Interval;
 use super::{tree::*, *};
 use crate::model::stores::reachability::{ReachabilityStore, ReachabilityStoreReader};
-use kaspa_consensus_core::blockhash;
+use kaspa_consensus_core::{api::counters::ProcessingCounters, blockhash};
 use kaspa_hashes::Hash;
 
 /// Init the reachability store to match the state required by the algorithmic layer.
@@ -27,7 +27,18 @@ pub fn add_block(
     selected_parent: Hash,
     mergeset_iterator: HashIterator,
 ) -> Result<()> {
-    add_block_with_params(store, new_block, selected_parent, mergeset_iterator, None, None)
+    add_block_with_params(store, new_block, selected_parent, mergeset_iterator, None, None, None)
+}
+
+/// Same as [`add_block`], but also reports reindex operations to the provided `counters`
+pub fn add_block_with_counters(
+    store: &mut (impl ReachabilityStore + ?Sized),
+    new_block: Hash,
+    selected_parent: Hash,
+    mergeset_iterator: HashIterator,
+    counters: &ProcessingCounters,
+) -> Result<()> {
+    add_block_with_params(store, new_block, selected_parent, mergeset_iterator, None, None, Some(counters))
 }
 
 fn add_block_with_params(
@@ -37,6 +48,7 @@ fn add_block_with_params(
     mergeset_iterator: HashIterator,
     reindex_depth: Option<u64>,
     reindex_slack: Option<u64>,
+    counters: Option<&ProcessingCounters>,
 ) -> Result<()> {
     add_tree_block(
         store,
@@ -44,6 +56,7 @@ fn add_block_with_params(
         selected_parent,
         reindex_depth.unwrap_or(crate::constants::perf::DEFAULT_REINDEX_DEPTH),
         reindex_slack.unwrap_or(crate::constants::perf::DEFAULT_REINDEX_SLACK),
+        counters,
     )?;
     add_dag_block(store, new_block, mergeset_iterator)?;
     Ok(())
@@ -153,6 +166,22 @@ pub fn hint_virtual_selected_parent(store: &mut (impl ReachabilityStore + ?Sized
         hint,
         crate::constants::perf::DEFAULT_REINDEX_DEPTH,
         crate::constants::perf::DEFAULT_REINDEX_SLACK,
+        None,
+    )
+}
+
+/// Same as [`hint_virtual_selected_parent`], but also reports reindex root advances to the provided `counters`
+pub fn hint_virtual_selected_parent_with_counters(
+    store: &mut (impl ReachabilityStore + ?Sized),
+    hint: Hash,
+    counters: &ProcessingCounters,
+) -> Result<()> {
+    try_advancing_reindex_root(
+        store,
+        hint,
+        crate::constants::perf::DEFAULT_REINDEX_DEPTH,
+        crate::constants::perf::DEFAULT_REINDEX_SLACK,
+        Some(counters),
     )
 }
 
@@ -316,6 +345,37 @@ mod tests {
         store.validate_intervals(root).unwrap();
     }
 
+    #[test]
+    fn test_reindex_counters() {
+        // Arrange
+        let mut store = MemoryReachabilityStore::new();
+        let counters = ProcessingCounters::default();
+
+        // Act: a chain deep enough to advance the reindex root, and enough blocks to exhaust the small root capacity
+        let root: Hash = 1.into();
+        let mut builder = TreeBuilder::new_with_params(&mut store, 4, 8).with_counters(&counters);
+        builder.init_with_params(root, Interval::new(1, 1 << 12));
+        for i in 2u64..200 {
+            builder.add_block(i.into(), (i - 1).into());
+        }
+        let snapshot = counters.snapshot();
+
+        // Assert
+        assert!(snapshot.reindex_counts > 0);
+        assert!(snapshot.reindex_root_advance_counts > 0);
+        assert!(snapshot.reindexed_block_counts >= snapshot.reindex_counts);
+        store.validate_intervals(root).unwrap();
+
+        // Counters are only reported when provided
+        let mut store = MemoryReachabilityStore::new();
+        let mut builder = TreeBuilder::new_with_params(&mut store, 4, 8);
+        builder.init_with_params(root, Interval::new(1, 1 << 12));
+        for i in 2u64..200 {
+            builder.add_block(i.into(), (i - 1).into());
+        }
+        assert_eq!(counters.snapshot(), snapshot);
+    }
+
     #[derive(Clone)]
     pub struct DagTestCase {
         genesis: u64,
@@ -530,4 +590,54 @@ mod tests {
             run_dag_test_case_with_staging(&test);
         }
     }
+
+    #[test]
+    fn test_dag_batch_removal() {
+        let genesis = 1u64;
+        let blocks: Vec<(u64, Vec<u64>)> = vec![
+            (2, vec![1]),
+            (3, vec![1]),
+            (4, vec![2, 3]),
+            (5, vec![4]),
+            (6, vec![1]),
+            (7, vec![5, 6]),
+            (8, vec![1]),
+            (9, vec![1]),
+            (10, vec![7, 8, 9]),
+            (11, vec![1]),
+            (12, vec![11, 10]),
+        ];
+
+        let mut reachability = MemoryReachabilityStore::new();
+        let mut relations = MemoryRelationsStore::new();
+        let mut builder = DagBuilder::new(&mut reachability, &mut relations);
+        builder.init();
+        builder.add_block(DagBlock::new(genesis.into(), vec![ORIGIN]));
+        for (block, parents) in blocks.iter() {
+            builder.add_block(DagBlock::new((*block).into(), parents.iter().map(|&i| i.into()).collect()));
+        }
+
+        let mut hashes_ref = subtree(&reachability, ORIGIN);
+        let hashes = hashes_ref.iter().copied().collect_vec();
+        let chain_closure_ref = build_chain_closure(&reachability, &hashes);
+        let dag_closure_ref = build_transitive_closure(&relations, &reachability, &hashes);
+
+        // Delete a connected subset given in a non-topological order (including a duplicate)
+        let deleted: Vec<Hash> = [7u64, 2, 5, 4, 3, 5].map(Hash::from).to_vec();
+        DagBuilder::new(&mut reachability, &mut relations).delete_blocks(&deleted);
+        for hash in deleted.iter() {
+            hashes_ref.remove(hash);
+        }
+
+        reachability.validate_intervals(ORIGIN).unwrap();
+        validate_relations(&relations).unwrap();
+        validate_closures(&relations, &reachability, &chain_closure_ref, &dag_closure_ref, &hashes_ref);
+
+        // Delete the rest of the DAG in a single batch
+        let remaining = hashes_ref.iter().copied().collect_vec();
+        DagBuilder::new(&mut reachability, &mut relations).delete_blocks(&remaining);
+        reachability.validate_intervals(ORIGIN).unwrap();
+        validate_relations(&relations).unwrap();
+        assert!(subtree(&reachability, ORIGIN).is_empty());
+    }
 }
@@ -11,11 +11,23 @@ pub(super) struct ReindexOperationContext<'a, T: ReachabilityStore + ?Sized> {
     subtree_sizes: BlockHashMap<u64>, // Cache for subtree sizes computed during this operation
     depth: u64,
     slack: u64,
+    reindexed_blocks: u64, // The number of interval updates performed during this operation
 }
 
 impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
     pub(super) fn new(store: &'a mut T, depth: u64, slack: u64) -> Self {
-        Self { store, subtree_sizes: BlockHashMap::new(), depth, slack }
+        Self { store, subtree_sizes: BlockHashMap::new(), depth, slack, reindexed_blocks: 0 }
+    }
+
+    /// Returns the number of block intervals updated by this context so far
+    pub(super) fn reindexed_blocks(&self) -> u64 {
+        self.reindexed_blocks
+    }
+
+    fn set_interval(&mut self, block: Hash, interval: Interval) -> Result<()> {
+        self.store.set_interval(block, interval)?;
+        self.reindexed_blocks += 1;
+        Ok(())
     }
 
     /// Traverses the reachability subtree that's defined by the new child
@@ -166,7 +178,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
                 let interval = self.store.interval_children_capacity(current)?;
                 let intervals = interval.split_exponential(&sizes);
                 for (c, ci) in children.iter().copied().zip(intervals) {
-                    self.store.set_interval(c, ci)?;
+                    self.set_interval(c, ci)?;
                 }
                 queue.extend(children.iter());
             }
@@ -361,12 +373,12 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
     }
 
     fn apply_interval_op(&mut self, block: Hash, offset: u64, op: fn(&Interval, u64) -> Interval) -> Result<()> {
-        self.store.set_interval(block, op(&self.store.get_interval(block)?, offset))?;
+        self.set_interval(block, op(&self.store.get_interval(block)?, offset))?;
         Ok(())
     }
 
     fn apply_interval_op_and_propagate(&mut self, block: Hash, offset: u64, op: fn(&Interval, u64) -> Interval) -> Result<()> {
-        self.store.set_interval(block, op(&self.store.get_interval(block)?, offset))?;
+        self.set_interval(block, op(&self.store.get_interval(block)?, offset))?;
         self.propagate_interval(block)?;
         Ok(())
     }
@@ -407,7 +419,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
         let interval_before = Interval::new(interval.start + self.slack, interval.start + self.slack + sum - 1);
 
         for (c, ci) in children_before.iter().cloned().zip(interval_before.split_exact(sizes.as_slice())) {
-            self.store.set_interval(c, ci)?;
+            self.set_interval(c, ci)?;
             self.propagate_interval(c)?;
         }
 
@@ -429,7 +441,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
         let interval_after = Interval::new(interval.end - self.slack - sum, interval.end - self.slack - 1);
 
         for (c, ci) in children_after.iter().cloned().zip(interval_after.split_exact(sizes.as_slice())) {
-            self.store.set_interval(c, ci)?;
+            self.set_interval(c, ci)?;
             self.propagate_interval(c)?;
         }
 
@@ -462,11 +474,11 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
             Note that below following the propagation we reassign the full `allocation` to `child`.
             */
             let narrowed = Interval::new(allocation.start + self.slack, allocation.end - self.slack);
-            self.store.set_interval(child, narrowed)?;
+            self.set_interval(child, narrowed)?;
             self.propagate_interval(child)?;
         }
 
-        self.store.set_interval(child, allocation)?;
+        self.set_interval(child, allocation)?;
         Ok(())
     }
 }
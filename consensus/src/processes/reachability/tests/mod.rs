@@ -16,6 +16,7 @@ use crate::{
 };
 use itertools::Itertools;
 use kaspa_consensus_core::{
+    api::counters::ProcessingCounters,
     blockhash::{BlockHashExtensions, BlockHashes, ORIGIN},
     BlockHashMap, BlockHashSet,
 };
@@ -57,6 +58,7 @@ pub struct TreeBuilder<'a, T: ReachabilityStore + ?Sized> {
     store: &'a mut T,
     reindex_depth: u64,
     reindex_slack: u64,
+    counters: Option<&'a ProcessingCounters>,
 }
 
 impl<'a, T: ReachabilityStore + ?Sized> TreeBuilder<'a, T> {
@@ -65,11 +67,18 @@ impl<'a, T: ReachabilityStore + ?Sized> TreeBuilder<'a, T> {
             store,
             reindex_depth: crate::constants::perf::DEFAULT_REINDEX_DEPTH,
             reindex_slack: crate::constants::perf::DEFAULT_REINDEX_SLACK,
+            counters: None,
         }
     }
 
     pub fn new_with_params(store: &'a mut T, reindex_depth: u64, reindex_slack: u64) -> Self {
-        Self { store, reindex_depth, reindex_slack }
+        Self { store, reindex_depth, reindex_slack, counters: None }
+    }
+
+    /// Reports reindex operations of all following block additions to `counters`
+    pub fn with_counters(mut self, counters: &'a ProcessingCounters) -> Self {
+        self.counters = Some(counters);
+        self
     }
 
     pub fn init(&mut self) -> &mut Self {
@@ -83,8 +92,8 @@ impl<'a, T: ReachabilityStore + ?Sized> TreeBuilder<'a, T> {
     }
 
     pub fn add_block(&mut self, hash: Hash, parent: Hash) -> &mut Self {
-        add_tree_block(self.store, hash, parent, self.reindex_depth, self.reindex_slack).unwrap();
-        try_advancing_reindex_root(self.store, hash, self.reindex_depth, self.reindex_slack).unwrap();
+        add_tree_block(self.store, hash, parent, self.reindex_depth, self.reindex_slack, self.counters).unwrap();
+        try_advancing_reindex_root(self.store, hash, self.reindex_depth, self.reindex_slack, self.counters).unwrap();
         self
     }
 
@@ -138,6 +147,28 @@ impl<'a, T: ReachabilityStore + ?Sized, S: RelationsStore + ChildrenStore + ?Siz
         self
     }
 
+    pub fn delete_blocks(&mut self, hashes: &[Hash]) -> &mut Self {
+        self.delete_blocks_with_writer(self.relations.default_writer(), hashes)
+    }
+
+    /// Deletes a batch of blocks using a single writer. Blocks are deleted bottom-up (by ascending tree height),
+    /// which is the order used by pruning, so that the parents of each deleted block are already relinked
+    pub fn delete_blocks_with_writer(&mut self, mut writer: impl DirectWriter, hashes: &[Hash]) -> &mut Self {
+        let ordered = hashes
+            .iter()
+            .copied()
+            .unique()
+            .map(|hash| (self.reachability.get_height(hash).unwrap(), hash))
+            .sorted()
+            .map(|(_, hash)| hash)
+            .collect_vec();
+        for hash in ordered {
+            let mergeset = delete_reachability_relations(&mut writer, self.relations, self.reachability, hash);
+            delete_block(self.reachability, hash, &mut mergeset.iter().cloned()).unwrap();
+        }
+        self
+    }
+
     pub fn add_block(&mut self, block: DagBlock) -> &mut Self {
         // Select by height (longest chain) just for the sake of internal isolated tests
         let selected_parent = block.parents.iter().cloned().max_by_key(|p| self.reachability.get_height(*p).unwrap()).unwrap();
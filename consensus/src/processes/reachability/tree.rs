@@ -3,17 +3,21 @@
 //!
 use super::{extensions::ReachabilityStoreIntervalExtensions, inquirer::*, reindex::ReindexOperationContext, *};
 use crate::model::stores::reachability::ReachabilityStore;
+use kaspa_consensus_core::api::counters::ProcessingCounters;
 use kaspa_hashes::Hash;
+use std::sync::atomic::Ordering;
 
 /// Adds `new_block` as a child of `parent` in the tree structure. If this block
 /// has no remaining interval to allocate, a reindexing is triggered. When a reindexing
-/// is triggered, the reindex root point is used within the reindex algorithm's logic
+/// is triggered, the reindex root point is used within the reindex algorithm's logic.
+/// Reindex operations are reported to `counters` if provided
 pub fn add_tree_block(
     store: &mut (impl ReachabilityStore + ?Sized),
     new_block: Hash,
     parent: Hash,
     reindex_depth: u64,
     reindex_slack: u64,
+    counters: Option<&ProcessingCounters>,
 ) -> Result<()> {
     // Get the remaining interval capacity
     let remaining = store.interval_remaining_after(parent)?;
@@ -30,6 +34,10 @@ pub fn add_tree_block(
         let reindex_root = store.get_reindex_root()?;
         let mut ctx = ReindexOperationContext::new(store, reindex_depth, reindex_slack);
         ctx.reindex_intervals(new_block, reindex_root)?;
+        if let Some(counters) = counters {
+            counters.reindex_counts.fetch_add(1, Ordering::Relaxed);
+            counters.reindexed_block_counts.fetch_add(ctx.reindexed_blocks(), Ordering::Relaxed);
+        }
     } else {
         let allocated = remaining.split_half().0;
         store.insert(new_block, parent, allocated, parent_height + 1)?;
@@ -108,12 +116,14 @@ pub fn find_next_reindex_root(
 /// since this way it can benefit from chain-robustness which is implied by the security
 /// of the ordering protocol. That is, it enjoys from the fact that all future blocks are
 /// expected to elect the root subtree (by converging to the agreement to have it on the
-/// selected chain). See also the reachability algorithms overview (TODO)
+/// selected chain). See also the reachability algorithms overview (TODO).
+/// Root advances and the blocks reindexed on the way are reported to `counters` if provided
 pub fn try_advancing_reindex_root(
     store: &mut (impl ReachabilityStore + ?Sized),
     hint: Hash,
     reindex_depth: u64,
     reindex_slack: u64,
+    counters: Option<&ProcessingCounters>,
 ) -> Result<()> {
     // Get current root from the store
     let current = store.get_reindex_root()?;
@@ -129,14 +139,20 @@ pub fn try_advancing_reindex_root(
     // if ancestor == next {
     //     trace!("next reindex root is an ancestor of current one, skipping concentration.")
     // }
+    let mut reindexed_blocks = 0;
     while ancestor != next {
         let child = get_next_chain_ancestor_unchecked(store, next, ancestor)?;
         let mut ctx = ReindexOperationContext::new(store, reindex_depth, reindex_slack);
         ctx.concentrate_interval(ancestor, child, child == next)?;
+        reindexed_blocks += ctx.reindexed_blocks();
         ancestor = child;
     }
 
     // Update reindex root in the data store
     store.set_reindex_root(next)?;
+    if let Some(counters) = counters {
+        counters.reindex_root_advance_counts.fetch_add(1, Ordering::Relaxed);
+        counters.reindexed_block_counts.fetch_add(reindexed_blocks, Ordering::Relaxed);
+    }
     Ok(())
 }
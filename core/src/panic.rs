@@ -1,31 +1,136 @@
 use kaspa_core::error;
-use std::{panic, process, thread};
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    fmt::Display,
+    panic::{self, Location, PanicHookInfo},
+    process,
+    sync::{Arc, RwLock},
+    thread,
+};
 
-/// Configures the panic hook to exit the program on every panic
-pub fn configure_panic() {
-    let default_hook = panic::take_hook();
-    panic::set_hook(Box::new(move |panic_info| {
-        // Get the panic location details
-        let (file, line, column) = match panic_info.location() {
-            Some(location) => (location.file(), location.line(), location.column()),
-            None => ("unknown", 0, 0),
-        };
+/// Structured details of a panic, as reported to the callback registered via [`set_panic_callback`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicEvent {
+    /// The subsystem the panic originated from, i.e., the name of the panicking thread
+    pub subsystem: String,
+    pub message: String,
+    pub location: Option<PanicLocation>,
+    pub backtrace: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
 
-        let message = match panic_info.payload().downcast_ref::<&str>() {
+impl Display for PanicLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl PanicEvent {
+    fn from_hook_info(panic_info: &PanicHookInfo<'_>) -> Self {
+        let subsystem = thread::current().name().unwrap_or("<unnamed>").to_owned();
+        Self::new(subsystem, panic_info.payload(), panic_info.location())
+    }
+
+    fn new(subsystem: String, payload: &(dyn Any + Send), location: Option<&Location<'_>>) -> Self {
+        let location = location.map(|location| PanicLocation {
+            file: location.file().to_owned(),
+            line: location.line(),
+            column: location.column(),
+        });
+        let message = match payload.downcast_ref::<&str>() {
             Some(s) => *s,
-            None => match panic_info.payload().downcast_ref::<String>() {
+            None => match payload.downcast_ref::<String>() {
                 Some(s) => &s[..],
                 None => "Box<dyn Any>",
             },
         };
-        // Get the thread name
-        let current_thread = thread::current();
-        let thread_name = current_thread.name().unwrap_or("<unnamed>");
-        // Log the panic
-        error!("thread '{}' panicked at {}:{}:{}: {}", thread_name, file, line, column, message);
+        Self { subsystem, message: message.to_owned(), location, backtrace: Backtrace::force_capture().to_string() }
+    }
+}
+
+pub type PanicCallback = Arc<dyn Fn(&PanicEvent) + Send + Sync>;
+
+static PANIC_CALLBACK: RwLock<Option<PanicCallback>> = RwLock::new(None);
+
+/// Registers a callback receiving the details of every panic caught by the hook installed with [`configure_panic`]
+/// before the program exits. Replaces any previously registered callback
+pub fn set_panic_callback(callback: PanicCallback) {
+    *PANIC_CALLBACK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(callback);
+}
+
+/// Unregisters the panic callback, if any
+pub fn clear_panic_callback() {
+    *PANIC_CALLBACK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Logs the panic and forwards its details to the registered callback, if any
+fn report_panic(panic_info: &PanicHookInfo<'_>) {
+    report_panic_event(&PanicEvent::from_hook_info(panic_info));
+}
+
+fn report_panic_event(event: &PanicEvent) {
+    match event.location {
+        Some(ref location) => error!("thread '{}' panicked at {}: {}", event.subsystem, location, event.message),
+        None => error!("thread '{}' panicked at unknown:0:0: {}", event.subsystem, event.message),
+    }
+    // Clone the callback so that the lock is not held while it runs
+    let callback = PANIC_CALLBACK.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    if let Some(callback) = callback {
+        callback(event);
+    }
+}
+
+/// Configures the panic hook to exit the program on every panic
+pub fn configure_panic() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        // Log the panic and notify the registered callback
+        report_panic(panic_info);
         // Invoke the default hook as well, since it might include additional info such as the full backtrace
         default_hook(panic_info);
         println!("Exiting...");
         process::exit(1);
     }));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_panic_event() {
+        let event = PanicEvent::new("test".to_owned(), &"static message", Some(Location::caller()));
+        assert_eq!(event.subsystem, "test");
+        assert_eq!(event.message, "static message");
+        let location = event.location.as_ref().unwrap();
+        assert!(location.file.ends_with("panic.rs"), "unexpected panic location {location}");
+        assert!(location.line > 0);
+        assert!(!event.backtrace.is_empty());
+
+        assert_eq!(PanicEvent::new("test".to_owned(), &String::from("owned message"), None).message, "owned message");
+        assert_eq!(PanicEvent::new("test".to_owned(), &42, None).message, "Box<dyn Any>");
+    }
+
+    #[test]
+    fn test_panic_callback() {
+        // Panics are reported directly, so the global panic hook shared by all tests is left untouched
+        const MESSAGE: &str = "panic callback test";
+        let events = Arc::new(Mutex::new(Vec::<PanicEvent>::new()));
+        let events_clone = events.clone();
+        set_panic_callback(Arc::new(move |event| events_clone.lock().unwrap().push(event.clone())));
+        let event = PanicEvent::new("panic-test".to_owned(), &MESSAGE, Some(Location::caller()));
+        report_panic_event(&event);
+        clear_panic_callback();
+        report_panic_event(&event);
+
+        assert_eq!(*events.lock().unwrap(), vec![event]);
+    }
+}
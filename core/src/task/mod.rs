@@ -1,5 +1,6 @@
 pub mod runtime;
 pub mod service;
+pub mod spawn;
 pub mod tick;
 
 // TODO: Determine the most appropriate location for task
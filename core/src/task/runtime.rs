@@ -2,7 +2,7 @@ use crate::{signals::Shutdown, task::service::AsyncServiceResult};
 use futures_util::future::{select_all, try_join_all};
 use kaspa_core::core::Core;
 use kaspa_core::service::Service;
-use kaspa_core::task::service::AsyncService;
+use kaspa_core::task::{service::AsyncService, spawn::spawn_named};
 use kaspa_core::trace;
 use std::{
     sync::{Arc, Mutex},
@@ -78,7 +78,7 @@ impl AsyncRuntime {
             .lock()
             .unwrap()
             .iter()
-            .map(|x| tokio::spawn(x.clone().start()))
+            .map(|x| spawn_named(Self::IDENT, &format!("{}-start", x.clone().ident()), x.clone().start()))
             .collect::<Vec<TaskJoinHandle<AsyncServiceResult<()>>>>();
 
         // wait for at least one service to return
@@ -105,7 +105,7 @@ impl AsyncRuntime {
             .lock()
             .unwrap()
             .iter()
-            .map(|x| tokio::spawn(x.clone().stop()))
+            .map(|x| spawn_named(Self::IDENT, &format!("{}-stop", x.clone().ident()), x.clone().stop()))
             .collect::<Vec<TaskJoinHandle<AsyncServiceResult<()>>>>();
         try_join_all(futures).await.unwrap();
 
@@ -1,7 +1,15 @@
+use super::tick::{TickReason, TickService};
 use futures_util::future::BoxFuture;
 use intertrait::CastFromSync;
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use thiserror::Error;
+use triggered::{trigger, Listener, Trigger};
 
 #[derive(Error, Debug)]
 pub enum AsyncServiceError {
@@ -19,3 +27,48 @@ pub trait AsyncService: CastFromSync {
     fn signal_exit(self: Arc<Self>);
     fn stop(self: Arc<Self>) -> AsyncServiceFuture;
 }
+
+/// An exit signal owned by a service and observed by its worker loop.
+///
+/// Services should call [`ServiceShutdown::signal`] from [`AsyncService::signal_exit`] and have their worker
+/// wait through [`ServiceShutdown::tick`], so that exit is observed immediately rather than on the next
+/// tick service wakeup
+pub struct ServiceShutdown {
+    signaled: AtomicBool,
+    trigger: Trigger,
+    listener: Listener,
+}
+
+impl ServiceShutdown {
+    pub fn new() -> Self {
+        let (trigger, listener) = trigger();
+        Self { signaled: AtomicBool::new(false), trigger, listener }
+    }
+
+    pub fn signal(&self) {
+        self.signaled.store(true, Ordering::SeqCst);
+        self.trigger.trigger();
+    }
+
+    pub fn is_signaled(&self) -> bool {
+        self.signaled.load(Ordering::SeqCst)
+    }
+
+    /// Waits until `duration` has elapsed, returning early with [`TickReason::Shutdown`] if either
+    /// this signal or the tick service shutdown is triggered
+    pub async fn tick(&self, tick_service: &TickService, duration: Duration) -> TickReason {
+        if self.is_signaled() {
+            return TickReason::Shutdown;
+        }
+        tokio::select! {
+            reason = tick_service.tick(duration) => reason,
+            _ = self.listener.clone() => TickReason::Shutdown,
+        }
+    }
+}
+
+impl Default for ServiceShutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
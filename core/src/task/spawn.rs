@@ -0,0 +1,144 @@
+//! Helpers for spawning named tokio tasks.
+//!
+//! Task names are composed as `<subsystem>::<name>` so that tasks belonging to the same component are easily
+//! grouped in `tokio-console` and in panic reports. Naming relies on `tokio::task::Builder` which is only available
+//! when building with `--cfg tokio_unstable` and the `task-names` feature, otherwise tasks are spawned unnamed.
+
+use std::future::Future;
+use tokio::task::JoinHandle;
+
+/// Separates the subsystem prefix from the task name
+pub const TASK_NAME_SEPARATOR: &str = "::";
+
+/// Returns the full name given to a task spawned by [`spawn_named`]
+pub fn task_name(subsystem: &str, name: &str) -> String {
+    format!("{subsystem}{TASK_NAME_SEPARATOR}{name}")
+}
+
+/// Spawns `future` as a tokio task named `<subsystem>::<name>`.
+///
+/// Must be called from within a tokio runtime, similarly to `tokio::spawn`
+#[track_caller]
+pub fn spawn_named<F>(subsystem: &str, name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let task_name = task_name(subsystem, name);
+    #[cfg(all(tokio_unstable, feature = "task-names"))]
+    {
+        tokio::task::Builder::new()
+            .name(&task_name)
+            .spawn(future)
+            .unwrap_or_else(|err| panic!("failed spawning task {task_name}: {err}"))
+    }
+    #[cfg(not(all(tokio_unstable, feature = "task-names")))]
+    {
+        let _ = task_name;
+        tokio::spawn(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_name() {
+        assert_eq!(task_name("p2p-hub", "event-loop"), "p2p-hub::event-loop");
+        assert_eq!(task_name("async-runtime", ""), "async-runtime::");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_named() {
+        let handle = spawn_named("test", "answer", async { 42 });
+        assert_eq!(handle.await.unwrap(), 42);
+    }
+
+    #[cfg(not(all(tokio_unstable, feature = "task-names")))]
+    #[tokio::test]
+    async fn test_spawn_named_fallback() {
+        // Without naming support the task is spawned unnamed through `tokio::spawn`, so the handle must behave the
+        // same, including reporting panics and cancellation
+        let handle = spawn_named("test", "yielding", async {
+            tokio::task::yield_now().await;
+            7
+        });
+        assert_eq!(handle.await.unwrap(), 7);
+
+        let handle = spawn_named("test", "panicking", async { panic!("expected panic") });
+        assert!(handle.await.unwrap_err().is_panic());
+
+        let handle = spawn_named("test", "pending", std::future::pending::<()>());
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+    }
+
+    #[cfg(all(tokio_unstable, feature = "task-names"))]
+    #[tokio::test]
+    async fn test_spawn_named_carries_name() {
+        use std::{
+            fmt::Debug,
+            sync::{
+                atomic::{AtomicU64, Ordering},
+                Arc, Mutex,
+            },
+        };
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        // Tokio exposes the task name only through the span it creates when spawning the task,
+        // so we observe it by recording the `task.name` field of all created spans
+        struct TaskNameVisitor<'a>(&'a mut Vec<String>);
+
+        impl Visit for TaskNameVisitor<'_> {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "task.name" {
+                    self.0.push(value.to_owned());
+                }
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                if field.name() == "task.name" {
+                    self.0.push(format!("{value:?}"));
+                }
+            }
+        }
+
+        #[derive(Default)]
+        struct TaskNameRecorder {
+            names: Arc<Mutex<Vec<String>>>,
+            next_id: AtomicU64,
+        }
+
+        impl Subscriber for TaskNameRecorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+                attrs.record(&mut TaskNameVisitor(&mut self.names.lock().unwrap()));
+                span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+            fn event(&self, _: &Event<'_>) {}
+
+            fn enter(&self, _: &span::Id) {}
+
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let recorder = TaskNameRecorder::default();
+        let names = recorder.names.clone();
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        spawn_named("test", "named", async {}).await.unwrap();
+        assert!(names.lock().unwrap().contains(&task_name("test", "named")), "recorded task names: {:?}", names.lock().unwrap());
+    }
+}
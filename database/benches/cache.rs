@@ -0,0 +1,71 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use indexmap::IndexMap;
+use kaspa_database::prelude::{Cache, CachePolicy};
+use kaspa_utils::mem_size::MemMode;
+use rand::Rng;
+
+/// The number of unit-sized items filling the cache before the oversized insert
+const CACHE_ITEMS: usize = 100_000;
+
+fn filled_cache(seed: Option<u64>) -> Cache<u64, Vec<u8>> {
+    let cache = Cache::new(CachePolicy::Tracked { max_size: CACHE_ITEMS, min_items: 0, mem_mode: MemMode::Units });
+    let cache = match seed {
+        Some(seed) => cache.with_eviction_seed(seed),
+        None => cache,
+    };
+    for i in 0..CACHE_ITEMS as u64 {
+        cache.insert(i, vec![0]);
+    }
+    cache
+}
+
+/// The eviction loop as implemented prior to hoisting the RNG, i.e., acquiring the thread RNG per evicted item
+fn evict_with_rng_per_item(map: &mut IndexMap<u64, Vec<u8>>, tracked_size: &mut usize, max_size: usize) {
+    while *tracked_size > max_size && !map.is_empty() {
+        if let Some((_, v)) = map.swap_remove_index(rand::thread_rng().gen_range(0..map.len())) {
+            *tracked_size -= v.len();
+        }
+    }
+}
+
+pub fn bench_tracked_eviction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tracked eviction");
+    for evictions in [100usize, 1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::new("rng per item", evictions), &evictions, |b, &evictions| {
+            b.iter_batched(
+                || ((0..CACHE_ITEMS as u64).map(|i| (i, vec![0u8])).collect::<IndexMap<_, _>>(), CACHE_ITEMS),
+                |(mut map, mut tracked_size)| {
+                    map.insert(u64::MAX, vec![0; evictions]);
+                    tracked_size += evictions;
+                    evict_with_rng_per_item(&mut map, &mut tracked_size, CACHE_ITEMS);
+                    black_box(map)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hoisted thread rng", evictions), &evictions, |b, &evictions| {
+            b.iter_batched(
+                || filled_cache(None),
+                |cache| {
+                    cache.insert(u64::MAX, vec![0; evictions]);
+                    black_box(cache)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hoisted seeded rng", evictions), &evictions, |b, &evictions| {
+            b.iter_batched(
+                || filled_cache(Some(42)),
+                |cache| {
+                    cache.insert(u64::MAX, vec![0; evictions]);
+                    black_box(cache)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_tracked_eviction);
+criterion_main!(benches);
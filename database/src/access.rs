@@ -34,6 +34,11 @@ where
         Self { db, cache: Cache::new(cache_policy), prefix }
     }
 
+    /// Returns the tracked size and the number of entries of the underlying cache
+    pub fn cache_footprint(&self) -> (usize, usize) {
+        (self.cache.tracked_size(), self.cache.len())
+    }
+
     pub fn read_from_cache(&self, key: TKey) -> Option<TData>
     where
         TKey: Copy + AsRef<[u8]>,
@@ -167,6 +172,23 @@ where
         Ok(())
     }
 
+    /// Counts the entries in the store, stopping as soon as `limit` entries were counted. Entry values are never
+    /// deserialized, however all entries up to `limit` are read from the DB, so a `usize::MAX` limit means a full scan
+    pub fn count_up_to(&self, limit: usize) -> Result<usize, StoreError>
+    where
+        TKey: Clone + AsRef<[u8]>,
+    {
+        let prefix_key = DbKey::prefix_only(&self.prefix);
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_iterate_range(rocksdb::PrefixRange(prefix_key.as_ref()));
+        let mut count = 0;
+        for item in self.db.iterator_opt(IteratorMode::From(prefix_key.as_ref(), Direction::Forward), read_opts).take(limit) {
+            item?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     /// A dynamic iterator that can iterate through a specific prefix / bucket, or from a certain start point.
     //TODO: loop and chain iterators for multi-prefix / bucket iterator.
     pub fn seek_iterator(
@@ -245,4 +267,20 @@ mod tests {
         db.write(batch).unwrap();
         assert_eq!(0, access.iterator().count());
     }
+
+    #[test]
+    fn test_count_up_to() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let access = CachedDbAccess::<Hash, u64>::new(db.clone(), CachePolicy::Count(2), vec![1, 2]);
+        // Entries of a neighbouring prefix must not be counted
+        let other = CachedDbAccess::<Hash, u64>::new(db.clone(), CachePolicy::Count(2), vec![1, 3]);
+        other.write_many(DirectDbWriter::new(&db), &mut (0..8).map(|i| (i.into(), 2))).unwrap();
+
+        assert_eq!(0, access.count_up_to(usize::MAX).unwrap());
+        access.write_many(DirectDbWriter::new(&db), &mut (0..16).map(|i| (i.into(), 2))).unwrap();
+        assert_eq!(16, access.count_up_to(usize::MAX).unwrap());
+        assert_eq!(16, access.count_up_to(16).unwrap());
+        assert_eq!(5, access.count_up_to(5).unwrap());
+        assert_eq!(0, access.count_up_to(0).unwrap());
+    }
 }
@@ -1,8 +1,15 @@
 use indexmap::IndexMap;
 use kaspa_utils::mem_size::{MemMode, MemSizeEstimator};
 use parking_lot::RwLock;
-use rand::Rng;
-use std::{collections::hash_map::RandomState, hash::BuildHasher, sync::Arc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum CachePolicy {
@@ -16,7 +23,7 @@ pub enum CachePolicy {
     Tracked { max_size: usize, min_items: usize, mem_mode: MemMode },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 struct CachePolicyInner {
     /// Indicates if this cache was set to be tracked.
     tracked: bool,
@@ -49,6 +56,10 @@ where
     // We use IndexMap and not HashMap because it makes it cheaper to remove a random element when the cache is full.
     map: IndexMap<TKey, TData, S>,
     tracked_size: usize,
+    /// The cache policy. Kept behind the lock so that it can be modified at runtime
+    policy: CachePolicyInner,
+    /// An optional seeded RNG used for selecting eviction indices. When `None`, `thread_rng` is used
+    rng: Option<StdRng>,
 }
 
 impl<TKey, TData, S> Inner<TKey, TData, S>
@@ -57,53 +68,99 @@ where
     TData: Clone + Send + Sync + MemSizeEstimator,
     S: BuildHasher + Default,
 {
+    /// Draws a random eviction index in `0..len` using the seeded RNG if set
+    fn eviction_index(rng: &mut Option<StdRng>, len: usize) -> usize {
+        match rng {
+            Some(rng) => rng.gen_range(0..len),
+            None => rand::thread_rng().gen_range(0..len),
+        }
+    }
+
     /// Evicts items until meeting cache policy requirements (in tracked mode)
-    fn tracked_evict(&mut self, policy: &CachePolicyInner) {
+    fn tracked_evict(&mut self) {
+        if !Self::should_evict(self.tracked_size, self.map.len(), &self.policy) {
+            return;
+        }
+        // Acquire the RNG once for the whole eviction loop rather than per evicted item
+        match &mut self.rng {
+            Some(rng) => Self::tracked_evict_with(&mut self.map, &mut self.tracked_size, &self.policy, rng),
+            None => Self::tracked_evict_with(&mut self.map, &mut self.tracked_size, &self.policy, &mut rand::thread_rng()),
+        }
+    }
+
+    #[inline]
+    fn should_evict(tracked_size: usize, len: usize, policy: &CachePolicyInner) -> bool {
         // We allow passing tracked size limit as long as there are no more than min_items items
-        while self.tracked_size > policy.max_size && self.map.len() > policy.min_items {
-            if let Some((_, v)) = self.map.swap_remove_index(rand::thread_rng().gen_range(0..self.map.len())) {
-                self.tracked_size -= v.estimate_size(policy.mem_mode)
+        tracked_size > policy.max_size && len > policy.min_items
+    }
+
+    fn tracked_evict_with<R: Rng + ?Sized>(
+        map: &mut IndexMap<TKey, TData, S>,
+        tracked_size: &mut usize,
+        policy: &CachePolicyInner,
+        rng: &mut R,
+    ) {
+        while Self::should_evict(*tracked_size, map.len(), policy) {
+            if let Some((_, v)) = map.swap_remove_index(rng.gen_range(0..map.len())) {
+                *tracked_size -= v.estimate_size(policy.mem_mode)
             }
         }
     }
 
-    fn insert(&mut self, policy: &CachePolicyInner, key: TKey, data: TData) {
+    fn insert(&mut self, key: TKey, data: TData) {
+        let policy = self.policy;
         if policy.tracked {
             let new_data_size = data.estimate_size(policy.mem_mode);
             self.tracked_size += new_data_size;
             if let Some(removed) = self.map.insert(key, data) {
                 self.tracked_size -= removed.estimate_size(policy.mem_mode);
             }
-            self.tracked_evict(policy);
+            self.tracked_evict();
         } else {
             if self.map.len() == policy.max_size {
-                self.map.swap_remove_index(rand::thread_rng().gen_range(0..policy.max_size));
+                let index = Self::eviction_index(&mut self.rng, policy.max_size);
+                self.map.swap_remove_index(index);
             }
             self.map.insert(key, data);
         }
     }
 
-    fn update_if_entry_exists<F>(&mut self, policy: &CachePolicyInner, key: TKey, op: F)
+    fn update_if_entry_exists<F>(&mut self, key: TKey, op: F)
     where
         F: Fn(&mut TData),
     {
+        let policy = self.policy;
         if let Some(data) = self.map.get_mut(&key) {
             if policy.tracked {
                 self.tracked_size -= data.estimate_size(policy.mem_mode);
                 op(data);
                 self.tracked_size += data.estimate_size(policy.mem_mode);
-                self.tracked_evict(policy);
+                self.tracked_evict();
             } else {
                 op(data);
             }
         }
     }
 
-    fn remove(&mut self, policy: &CachePolicyInner, key: &TKey) -> Option<TData> {
+    fn set_policy(&mut self, policy: CachePolicyInner) {
+        self.policy = policy;
+        if policy.tracked {
+            self.tracked_size = self.map.values().map(|v| v.estimate_size(policy.mem_mode)).sum();
+            self.tracked_evict();
+        } else {
+            self.tracked_size = 0;
+            while self.map.len() > policy.max_size {
+                let index = Self::eviction_index(&mut self.rng, self.map.len());
+                self.map.swap_remove_index(index);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &TKey) -> Option<TData> {
         match self.map.swap_remove(key) {
             Some(data) => {
-                if policy.tracked {
-                    self.tracked_size -= data.estimate_size(policy.mem_mode);
+                if self.policy.tracked {
+                    self.tracked_size -= data.estimate_size(self.policy.mem_mode);
                 }
                 Some(data)
             }
@@ -118,8 +175,9 @@ where
     TData: Clone + Send + Sync + MemSizeEstimator,
     S: BuildHasher + Default,
 {
-    pub fn new(prealloc_size: usize) -> Self {
-        Self { map: IndexMap::with_capacity_and_hasher(prealloc_size, S::default()), tracked_size: 0 }
+    pub fn new(policy: CachePolicyInner) -> Self {
+        let prealloc_size = if policy.tracked { 0 } else { policy.max_size }; // TODO: estimate prealloc also in tracked mode
+        Self { map: IndexMap::with_capacity_and_hasher(prealloc_size, S::default()), tracked_size: 0, policy, rng: None }
     }
 }
 
@@ -130,7 +188,9 @@ where
     TData: Clone + Send + Sync + MemSizeEstimator,
 {
     inner: Arc<RwLock<Inner<TKey, TData, S>>>,
-    policy: CachePolicyInner,
+    /// Mirrors the policy max size outside of the lock, so that disabled (empty or zero-sized) caches
+    /// can return early w/o acquiring it
+    max_size: Arc<AtomicUsize>,
 }
 
 impl<TKey, TData, S> Cache<TKey, TData, S>
@@ -141,8 +201,18 @@ where
 {
     pub fn new(policy: CachePolicy) -> Self {
         let policy: CachePolicyInner = policy.into();
-        let prealloc_size = if policy.tracked { 0 } else { policy.max_size }; // TODO: estimate prealloc also in tracked mode
-        Self { inner: Arc::new(RwLock::new(Inner::new(prealloc_size))), policy }
+        Self { inner: Arc::new(RwLock::new(Inner::new(policy))), max_size: Arc::new(AtomicUsize::new(policy.max_size)) }
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.max_size.load(Ordering::Relaxed) == 0
+    }
+
+    /// Seeds the RNG used for random eviction, making eviction choices reproducible for
+    /// identical operation sequences. Mostly useful for tests and benchmarks
+    pub fn with_eviction_seed(self, seed: u64) -> Self {
+        self.inner.write().rng = Some(StdRng::seed_from_u64(seed));
+        self
     }
 
     pub fn get(&self, key: &TKey) -> Option<TData> {
@@ -153,21 +223,63 @@ where
         self.inner.read().map.contains_key(key)
     }
 
+    /// Returns the number of entries currently held by the cache
+    pub fn len(&self) -> usize {
+        self.inner.read().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().map.is_empty()
+    }
+
+    /// Returns the overall size currently tracked by the cache, in bytes or units depending on the policy `mem_mode`.
+    /// Always zero for untracked caches
+    pub fn tracked_size(&self) -> usize {
+        self.inner.read().tracked_size
+    }
+
     pub fn insert(&self, key: TKey, data: TData) {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
             return;
         }
+        let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
+            return;
+        }
+        inner.insert(key, data);
+    }
 
-        self.inner.write().insert(&self.policy, key, data);
+    /// Returns the cached value for `key` if it exists, otherwise computes it using `f`, inserts it (subject to
+    /// the cache policy) and returns it. The lookup and insertion are performed under a single write lock.
+    ///
+    /// Note: `f` is called while holding the cache write lock, blocking all other cache readers and writers
+    /// meanwhile. Callers with an expensive `f` should prefer a [`Self::get`] followed by [`Self::insert`] on miss
+    pub fn get_or_insert_with(&self, key: TKey, f: impl FnOnce() -> TData) -> TData {
+        if self.is_disabled() {
+            return f();
+        }
+        let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
+            return f();
+        }
+        if let Some(data) = inner.map.get(&key) {
+            return data.clone();
+        }
+        let data = f();
+        inner.insert(key, data.clone());
+        data
     }
 
     pub fn insert_many(&self, iter: &mut impl Iterator<Item = (TKey, TData)>) {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
             return;
         }
         let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
+            return;
+        }
         for (key, data) in iter {
-            inner.insert(&self.policy, key, data);
+            inner.insert(key, data);
         }
     }
 
@@ -175,37 +287,191 @@ where
     where
         F: Fn(&mut TData),
     {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
+            return;
+        }
+        let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
             return;
         }
-        self.inner.write().update_if_entry_exists(&self.policy, key, op);
+        inner.update_if_entry_exists(key, op);
     }
 
     pub fn remove(&self, key: &TKey) -> Option<TData> {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
+            return None;
+        }
+        let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
             return None;
         }
-        self.inner.write().remove(&self.policy, key)
+        inner.remove(key)
     }
 
     pub fn remove_many(&self, key_iter: &mut impl Iterator<Item = TKey>) {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
             return;
         }
         let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
+            return;
+        }
         for key in key_iter {
-            inner.remove(&self.policy, &key);
+            inner.remove(&key);
         }
     }
 
     pub fn remove_all(&self) {
-        if self.policy.max_size == 0 {
+        if self.is_disabled() {
             return;
         }
         let mut inner = self.inner.write();
+        if inner.policy.max_size == 0 {
+            return;
+        }
         inner.map.clear();
-        if self.policy.tracked {
+        if inner.policy.tracked {
             inner.tracked_size = 0;
         }
     }
+
+    /// Replaces the cache policy at runtime, evicting random items until the cache complies with the new
+    /// limits. When switching into tracked mode (or changing the tracked memory mode), the tracked size is
+    /// recomputed from the current entries. When switching into count mode, the limit applies to the entry count
+    pub fn set_policy(&self, policy: CachePolicy) {
+        let policy: CachePolicyInner = policy.into();
+        let mut inner = self.inner.write();
+        inner.set_policy(policy);
+        self.max_size.store(policy.max_size, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_eviction_is_reproducible() {
+        let run = |seed: u64| {
+            let cache: Cache<u64, u64> = Cache::new(CachePolicy::Count(8)).with_eviction_seed(seed);
+            for i in 0..100 {
+                cache.insert(i, i);
+            }
+            cache.inner.read().map.keys().copied().collect::<Vec<_>>()
+        };
+        let keys = run(42);
+        assert_eq!(keys.len(), 8);
+        assert_eq!(keys, run(42));
+        // Sanity check that the seed actually affects eviction choices
+        assert!((0..8).any(|seed| run(seed) != keys));
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let cache: Cache<u64, u64> = Cache::new(CachePolicy::Count(4));
+        let calls = std::cell::Cell::new(0);
+        let compute = |value: u64| {
+            calls.set(calls.get() + 1);
+            value
+        };
+
+        // Miss: f is called once and the value is inserted
+        assert_eq!(cache.get_or_insert_with(1, || compute(10)), 10);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.get(&1), Some(10));
+
+        // Hit: the existing value is returned and f is not called
+        assert_eq!(cache.get_or_insert_with(1, || compute(20)), 10);
+        assert_eq!(calls.get(), 1);
+
+        // Inserted values respect the policy limit
+        for i in 2..10 {
+            cache.get_or_insert_with(i, || compute(i));
+        }
+        assert_eq!(calls.get(), 9);
+        assert_eq!(cache.len(), 4);
+
+        // An empty cache always computes and never stores
+        let empty: Cache<u64, u64> = Cache::new(CachePolicy::Empty);
+        assert_eq!(empty.get_or_insert_with(1, || compute(10)), 10);
+        assert_eq!(empty.get_or_insert_with(1, || compute(10)), 10);
+        assert_eq!(calls.get(), 11);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_tracked_eviction_converges() {
+        let (max_size, min_items) = (1000, 2);
+        let policy = CachePolicy::Tracked { max_size, min_items, mem_mode: MemMode::Units };
+        let cache: Cache<u64, Vec<u8>> = Cache::new(policy);
+        let assert_consistent = |cache: &Cache<u64, Vec<u8>>| {
+            let inner = cache.inner.read();
+            assert_eq!(inner.tracked_size, inner.map.values().map(|v| v.len()).sum::<usize>());
+            assert!(inner.tracked_size <= max_size || inner.map.len() <= min_items);
+        };
+
+        for i in 0..100 {
+            cache.insert(i, vec![0; 10]);
+        }
+        assert_eq!(cache.tracked_size(), max_size);
+        assert_eq!(cache.len(), 100);
+
+        // A large item forces many evictions within a single insert
+        cache.insert(1000, vec![0; 900]);
+        assert_consistent(&cache);
+        assert!(cache.len() < 100);
+
+        // Growing an existing entry in place triggers eviction as well
+        let key = *cache.inner.read().map.keys().next().unwrap();
+        cache.update_if_entry_exists(key, |v| v.resize(990, 0));
+        assert_consistent(&cache);
+
+        // An item above the max size can only remain alongside at most min items
+        cache.insert(2000, vec![0; 5000]);
+        assert_consistent(&cache);
+        assert!(!cache.contains_key(&2000) || cache.len() <= min_items);
+    }
+
+    #[test]
+    fn test_set_policy_transitions() {
+        let cache: Cache<u64, Vec<u8>> = Cache::new(CachePolicy::Count(100));
+        for i in 0..100 {
+            cache.insert(i, vec![0; 10]);
+        }
+        assert_eq!(cache.len(), 100);
+        assert_eq!(cache.tracked_size(), 0);
+
+        // Count -> smaller count
+        cache.set_policy(CachePolicy::Count(50));
+        assert_eq!(cache.len(), 50);
+
+        // Count -> tracked recomputes the tracked size and evicts down to the max
+        cache.set_policy(CachePolicy::Tracked { max_size: 300, min_items: 0, mem_mode: MemMode::Units });
+        assert_eq!(cache.tracked_size(), 300);
+        assert_eq!(cache.len(), 30);
+
+        // Tracked -> tracked keeps entries when growing and evicts when shrinking
+        cache.set_policy(CachePolicy::Tracked { max_size: 10_000, min_items: 0, mem_mode: MemMode::Units });
+        assert_eq!(cache.tracked_size(), 300);
+        cache.set_policy(CachePolicy::Tracked { max_size: 100, min_items: 0, mem_mode: MemMode::Units });
+        assert!(cache.tracked_size() <= 100);
+        assert_eq!(cache.tracked_size(), cache.len() * 10);
+
+        // Tracked -> count limits by entry count and resets the tracked size
+        cache.set_policy(CachePolicy::Count(4));
+        assert!(cache.len() <= 4);
+        assert_eq!(cache.tracked_size(), 0);
+
+        // Inserts following a policy change respect the new limit
+        for i in 100..110 {
+            cache.insert(i, vec![0; 10]);
+        }
+        assert_eq!(cache.len(), 4);
+
+        // Count -> empty clears the cache and disables inserts
+        cache.set_policy(CachePolicy::Empty);
+        assert!(cache.is_empty());
+        cache.insert(200, vec![0; 10]);
+        assert!(cache.is_empty());
+    }
 }
@@ -15,7 +15,7 @@ use toml::from_str;
 #[cfg(feature = "devnet-prealloc")]
 use kaspa_addresses::Address;
 #[cfg(feature = "devnet-prealloc")]
-use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+use kaspa_consensus_core::utxo::prealloc::generate_prealloc_utxos;
 #[cfg(feature = "devnet-prealloc")]
 use kaspa_txscript::pay_to_address_script;
 #[cfg(feature = "devnet-prealloc")]
@@ -91,6 +91,7 @@ pub struct Args {
     pub disable_grpc: bool,
     pub ram_scale: f64,
     pub retention_period_days: Option<f64>,
+    pub mempool_validation_threads: Option<usize>,
 }
 
 impl Default for Args {
@@ -142,6 +143,7 @@ impl Default for Args {
             disable_grpc: false,
             ram_scale: 1.0,
             retention_period_days: None,
+            mempool_validation_threads: None,
         }
     }
 }
@@ -173,14 +175,7 @@ impl Args {
     pub fn generate_prealloc_utxos(&self, num_prealloc_utxos: u64) -> kaspa_consensus_core::utxo::utxo_collection::UtxoCollection {
         let addr = Address::try_from(&self.prealloc_address.as_ref().unwrap()[..]).unwrap();
         let spk = pay_to_address_script(&addr);
-        (1..=num_prealloc_utxos)
-            .map(|i| {
-                (
-                    TransactionOutpoint { transaction_id: i.into(), index: 0 },
-                    UtxoEntry { amount: self.prealloc_amount, script_public_key: spk.clone(), block_daa_score: 0, is_coinbase: false },
-                )
-            })
-            .collect()
+        generate_prealloc_utxos(num_prealloc_utxos, self.prealloc_amount, &spk)
     }
 
     pub fn network(&self) -> NetworkId {
@@ -379,6 +374,13 @@ a large RAM (~64GB) can set this value to ~3.0-4.0 and gain superior performance
                 .value_parser(clap::value_parser!(f64))
                 .help("The number of total days of data to keep.")
         )
+        .arg(
+            Arg::new("mempool-validation-threads")
+                .long("mempool-validation-threads")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Max number of threads concurrently validating incoming mempool transactions (default: all consensus threads)."),
+        )
         ;
 
     #[cfg(feature = "devnet-prealloc")]
@@ -459,6 +461,10 @@ impl Args {
             disable_grpc: arg_match_unwrap_or::<bool>(&m, "nogrpc", defaults.disable_grpc),
             ram_scale: arg_match_unwrap_or::<f64>(&m, "ram-scale", defaults.ram_scale),
             retention_period_days: m.get_one::<f64>("retention-period-days").cloned().or(defaults.retention_period_days),
+            mempool_validation_threads: m
+                .get_one::<usize>("mempool-validation-threads")
+                .cloned()
+                .or(defaults.mempool_validation_threads),
 
             #[cfg(feature = "devnet-prealloc")]
             num_prealloc_utxos: m.get_one::<u64>("num-prealloc-utxos").cloned(),
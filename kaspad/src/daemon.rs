@@ -16,7 +16,7 @@ use kaspa_database::{
 };
 use kaspa_grpc_server::service::GrpcService;
 use kaspa_notify::{address::tracker::Tracker, subscription::context::SubscriptionContext};
-use kaspa_p2p_lib::Hub;
+use kaspa_p2p_lib::{Hub, KaspadMessagePayloadType, RateLimit, RateLimiterConfig};
 use kaspa_p2p_mining::rule_engine::MiningRuleEngine;
 use kaspa_rpc_service::service::RpcCoreService;
 use kaspa_txscript::caches::TxScriptCacheCounters;
@@ -203,6 +203,15 @@ impl Runtime {
 /// The instance of the [`RpcCoreService`] needs to be released
 /// (dropped) before the `Core` is shut down.
 ///
+/// Per-peer P2P rate limits. Addresses are exchanged only a few times per connection, so a peer exceeding these
+/// limits is flooding us (or being flooded by us) and the excess messages are dropped
+fn p2p_rate_limiter_config() -> RateLimiterConfig {
+    let address_gossip_limit = RateLimit::new(1.0, 10);
+    RateLimiterConfig::new()
+        .with_type_limit(KaspadMessagePayloadType::RequestAddresses, address_gossip_limit)
+        .with_type_limit(KaspadMessagePayloadType::Addresses, address_gossip_limit)
+}
+
 pub fn create_core(args: Args, fd_total_budget: i32) -> (Arc<Core>, Arc<RpcCoreService>) {
     let rt = Runtime::from_args(&args);
     create_core_with_runtime(&rt, &args, fd_total_budget)
@@ -235,12 +244,17 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
         exit(1);
     }
 
-    let config = Arc::new(
-        ConfigBuilder::new(network.into())
-            .adjust_perf_params_to_consensus_params()
-            .apply_args(|config| args.apply_to_config(config))
-            .build(),
-    );
+    let config = match ConfigBuilder::new(network.into())
+        .adjust_perf_params_to_consensus_params()
+        .apply_args(|config| args.apply_to_config(config))
+        .try_build()
+    {
+        Ok(config) => Arc::new(config),
+        Err(err) => {
+            println!("{}", err);
+            exit(1);
+        }
+    };
 
     let app_dir = get_app_dir_from_args(args);
     let db_dir = app_dir.join(network.to_prefixed()).join(DEFAULT_DATA_DIR);
@@ -564,6 +578,7 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         false,
         config.max_block_mass,
         config.ram_scale,
+        args.mempool_validation_threads,
         config.block_template_cache_lifetime,
         mining_counters.clone(),
     )));
@@ -575,7 +590,7 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         tick_service.clone(),
     ));
 
-    let hub = Hub::new();
+    let hub = Hub::with_rate_limiter(p2p_rate_limiter_config());
     let mining_rule_engine = Arc::new(MiningRuleEngine::new(
         consensus_manager.clone(),
         config.clone(),
@@ -12,4 +12,16 @@ pub enum BuilderError {
     CoinbaseError(#[from] CoinbaseError),
 }
 
+impl BuilderError {
+    /// Indicates whether building may succeed if retried with the same miner data. Consensus errors depend on
+    /// the virtual state and on the selected transactions, which both change over time, while coinbase errors
+    /// only depend on the miner data
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BuilderError::ConsensusError(_) => true,
+            BuilderError::CoinbaseError(_) => false,
+        }
+    }
+}
+
 pub type BuilderResult<T> = std::result::Result<T, BuilderError>;
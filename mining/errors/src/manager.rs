@@ -10,6 +10,61 @@ pub enum MiningManagerError {
     /// A mempool rule error
     #[error(transparent)]
     MempoolError(#[from] RuleError),
+
+    /// Building a block template kept failing on invalid transactions until the attempts were exhausted
+    #[error("building a block template failed after {0} attempts")]
+    BlockTemplateAttemptsExhausted(u64),
+}
+
+impl MiningManagerError {
+    /// Indicates whether the failed operation may succeed if retried as is at a later time, i.e., the failure is
+    /// caused by transient node state (virtual, mempool) rather than by the request itself
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            MiningManagerError::BlockTemplateBuilderError(err) => err.is_retryable(),
+            MiningManagerError::MempoolError(err) => err.is_retryable(),
+            // Invalid transactions are removed from the mempool on every failed attempt
+            MiningManagerError::BlockTemplateAttemptsExhausted(_) => true,
+        }
+    }
 }
 
 pub type MiningManagerResult<T> = std::result::Result<T, MiningManagerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        errors::{block::RuleError as BlockRuleError, coinbase::CoinbaseError, tx::TxRuleError},
+        tx::TransactionId,
+    };
+
+    #[test]
+    fn test_is_retryable() {
+        let tx_id = TransactionId::from_u64_word(1);
+        let retryable = [
+            MiningManagerError::BlockTemplateAttemptsExhausted(5),
+            BuilderError::ConsensusError(BlockRuleError::InvalidTransactionsInNewBlock(vec![])).into(),
+            RuleError::RejectMempoolIsFull.into(),
+            RuleError::RejectOrphanPoolIsFull(10, 10).into(),
+            RuleError::RejectMissingOutpoint.into(),
+            RuleError::RejectImmatureSpend(TxRuleError::NoTxInputs).into(),
+            RuleError::RejectDisallowedOrphan(tx_id).into(),
+        ];
+        let permanent = [
+            BuilderError::CoinbaseError(CoinbaseError::PayloadLenAboveMax(200, 150)).into(),
+            RuleError::RejectDuplicate(tx_id).into(),
+            RuleError::RejectAlreadyAccepted(tx_id).into(),
+            RuleError::RejectNonStandard(tx_id, "test".to_string()).into(),
+            RuleError::RejectTxRule(TxRuleError::NoTxInputs).into(),
+            RuleError::RejectSpamTransaction(tx_id).into(),
+            RuleError::RejectOversizedTransaction(tx_id, 200, 100).into(),
+        ];
+        for err in retryable {
+            assert!(err.is_retryable(), "{err} should be retryable");
+        }
+        for err in permanent {
+            assert!(!err.is_retryable(), "{err} should be permanent");
+        }
+    }
+}
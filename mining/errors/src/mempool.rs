@@ -81,6 +81,25 @@ pub enum RuleError {
 
     #[error("Rejected tx {0} from mempool due to incomputable storage mass")]
     RejectStorageMassIncomputable(TransactionId),
+
+    #[error("transaction {0} mass of {1} is larger than the max block mass of {2}")]
+    RejectOversizedTransaction(TransactionId, u64, u64),
+}
+
+impl RuleError {
+    /// Indicates whether the transaction may be accepted if resubmitted as is at a later time, i.e., the rejection
+    /// is caused by the current mempool or UTXO state (pool capacity, missing or immature inputs) rather than by the
+    /// transaction itself
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            RuleError::RejectMempoolIsFull
+                | RuleError::RejectOrphanPoolIsFull(..)
+                | RuleError::RejectMissingOutpoint
+                | RuleError::RejectImmatureSpend(_)
+                | RuleError::RejectDisallowedOrphan(_)
+        )
+    }
 }
 
 impl From<NonStandardError> for RuleError {
@@ -1,8 +1,9 @@
-use super::errors::BuilderResult;
+use super::errors::{BuilderError, BuilderResult};
 use kaspa_consensus_core::{
     api::ConsensusApi,
     block::{BlockTemplate, TemplateBuildMode, TemplateTransactionSelector},
     coinbase::MinerData,
+    errors::coinbase::CoinbaseError,
     tx::COINBASE_TRANSACTION_INDEX,
 };
 use kaspa_core::time::{unix_now, Stopwatch};
@@ -85,15 +86,28 @@ impl BlockTemplateBuilder {
         build_mode: TemplateBuildMode,
     ) -> BuilderResult<BlockTemplate> {
         let _sw = Stopwatch::<20>::with_threshold("build_block_template op");
+        Self::check_miner_data(consensus, miner_data)?;
         Ok(consensus.build_block_template(miner_data.clone(), selector, build_mode)?)
     }
 
+    /// Verifies the coinbase payload resulting from `miner_data` does not exceed the consensus limit,
+    /// so that an invalid template is never produced
+    fn check_miner_data(consensus: &dyn ConsensusApi, miner_data: &MinerData) -> BuilderResult<()> {
+        let max_len = consensus.max_coinbase_payload_len();
+        let payload_len = miner_data.coinbase_payload_len();
+        if payload_len > max_len {
+            return Err(BuilderError::CoinbaseError(CoinbaseError::PayloadLenAboveMax(payload_len, max_len)));
+        }
+        Ok(())
+    }
+
     /// modify_block_template clones an existing block template, modifies it to the requested coinbase data and updates the timestamp
     pub(crate) fn modify_block_template(
         consensus: &dyn ConsensusApi,
         new_miner_data: &MinerData,
         block_template_to_modify: &BlockTemplate,
     ) -> BuilderResult<BlockTemplate> {
+        Self::check_miner_data(consensus, new_miner_data)?;
         let mut block_template = block_template_to_modify.clone();
 
         // The first transaction is always the coinbase transaction
@@ -102,7 +116,7 @@ impl BlockTemplateBuilder {
         coinbase_tx.payload = new_payload;
         if block_template.coinbase_has_red_reward {
             // The last output is always the coinbase red blocks reward
-            coinbase_tx.outputs.last_mut().unwrap().script_public_key = new_miner_data.script_public_key.clone();
+            coinbase_tx.outputs.last_mut().unwrap().script_public_key = new_miner_data.script_public_key().clone();
         }
         // Update the hash merkle root according to the modified transactions
         block_template.block.header.hash_merkle_root =
@@ -1,5 +1,5 @@
 use kaspa_core::{time::Stopwatch, trace};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::collections::HashMap;
 
 use crate::model::candidate_tx::CandidateTransaction;
@@ -50,10 +50,18 @@ pub struct RebalancingWeightedTransactionSelector {
     total_mass: u64,
     total_fees: u64,
     gas_usage_map: HashMap<SubnetworkId, u64>,
+
+    /// The random source used for drawing candidates
+    rng: StdRng,
 }
 
 impl RebalancingWeightedTransactionSelector {
-    pub fn new(policy: Policy, mut transactions: Vec<CandidateTransaction>) -> Self {
+    pub fn new(policy: Policy, transactions: Vec<CandidateTransaction>) -> Self {
+        Self::with_alpha(policy, transactions, ALPHA)
+    }
+
+    /// Builds a selector drawing candidates with probability proportional to `feerate^alpha`
+    pub fn with_alpha(policy: Policy, mut transactions: Vec<CandidateTransaction>, alpha: i32) -> Self {
         let _sw = Stopwatch::<100>::with_threshold("TransactionsSelector::new op");
         // Sort the transactions by subnetwork_id.
         transactions.sort_by(|a, b| a.tx.subnetwork_id.cmp(&b.tx.subnetwork_id));
@@ -72,17 +80,24 @@ impl RebalancingWeightedTransactionSelector {
             total_mass: 0,
             total_fees: 0,
             gas_usage_map: Default::default(),
+            rng: StdRng::from_rng(rand::thread_rng()).expect("thread rng never fails"),
         };
 
         // Create the selectable transactions
         selector.selectable_txs =
-            selector.transactions.iter().map(|x| SelectableTransaction::new(selector.calc_tx_value(x), 0, ALPHA)).collect();
+            selector.transactions.iter().map(|x| SelectableTransaction::new(selector.calc_tx_value(x), 0, alpha)).collect();
         // Prepare the initial candidate list
         selector.candidate_list = CandidateList::new(&selector.selectable_txs);
 
         selector
     }
 
+    /// Replaces the random source used for drawing candidates, allowing reproducible selections
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
     /// select_transactions implements a probabilistic transaction selection algorithm.
     /// The algorithm, roughly, is as follows:
     /// 1. We assign a probability to each transaction equal to:
@@ -105,8 +120,6 @@ impl RebalancingWeightedTransactionSelector {
     /// selected_txs.
     pub fn select_transactions(&mut self) -> Vec<Transaction> {
         let _sw = Stopwatch::<15>::with_threshold("select_transaction op");
-        let mut rng = rand::thread_rng();
-
         self.reset_selection();
 
         while self.candidate_list.candidates.len() - self.used_count > 0 {
@@ -123,7 +136,7 @@ impl RebalancingWeightedTransactionSelector {
             }
 
             // Select a candidate tx at random
-            let r = rng.gen::<f64>() * self.candidate_list.total_p;
+            let r = self.rng.gen::<f64>() * self.candidate_list.total_p;
             let selected_candidate_idx = self.candidate_list.find(r);
             let selected_candidate = self.candidate_list.candidates.get_mut(selected_candidate_idx).unwrap();
 
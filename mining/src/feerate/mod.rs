@@ -3,6 +3,7 @@
 
 use crate::block_template::selector::ALPHA;
 use itertools::Itertools;
+use serde::Serialize;
 use std::fmt::Display;
 
 /// A type representing fee/mass of a transaction in `sompi/gram` units.
@@ -10,7 +11,7 @@ use std::fmt::Display;
 /// taking the transaction mass and multiplying it by feerate: `fee = feerate * mass(tx)`
 pub type Feerate = f64;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct FeerateBucket {
     pub feerate: f64,
     pub estimated_seconds: f64,
@@ -22,7 +23,7 @@ impl Display for FeerateBucket {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct FeerateEstimations {
     /// *Top-priority* feerate bucket. Provides an estimation of the feerate required for sub-second DAG inclusion.
     ///
@@ -50,6 +51,12 @@ impl FeerateEstimations {
             .chain(self.low_buckets.iter().copied())
             .collect()
     }
+
+    /// Returns a single-line JSON representation of the estimations, keyed by bucket kind and field name,
+    /// suitable for logging and for consumption by external tooling
+    pub fn to_compact(&self) -> String {
+        serde_json::to_string(self).expect("estimations consist of plain numeric fields")
+    }
 }
 
 impl Display for FeerateEstimations {
@@ -85,31 +92,39 @@ pub struct FeerateEstimator {
     /// other words, the inverse of the transaction inclusion rate. For instance, if the average transaction mass is 2500 grams,
     /// the block mass limit is 500,000 and the network has 10 BPS, then this number would be 1/2000 seconds.
     inclusion_interval: f64,
+
+    /// The weight exponent used for computing `total_weight`, i.e., `weight = feerate^alpha`
+    alpha: i32,
 }
 
 impl FeerateEstimator {
     pub fn new(total_weight: f64, inclusion_interval: f64) -> Self {
+        Self::with_alpha(total_weight, inclusion_interval, ALPHA)
+    }
+
+    pub fn with_alpha(total_weight: f64, inclusion_interval: f64, alpha: i32) -> Self {
         assert!(total_weight >= 0.0);
         assert!((0f64..1f64).contains(&inclusion_interval));
-        Self { total_weight, inclusion_interval }
+        assert!(alpha > 1, "the estimator math requires alpha > 1");
+        Self { total_weight, inclusion_interval, alpha }
     }
 
     pub(crate) fn feerate_to_time(&self, feerate: f64) -> f64 {
         let (c1, c2) = (self.inclusion_interval, self.total_weight);
-        c1 * c2 / feerate.powi(ALPHA) + c1
+        c1 * c2 / feerate.powi(self.alpha) + c1
     }
 
     fn time_to_feerate(&self, time: f64) -> f64 {
         let (c1, c2) = (self.inclusion_interval, self.total_weight);
         assert!(c1 < time, "{c1}, {time}");
-        ((c1 * c2 / time) / (1f64 - c1 / time)).powf(1f64 / ALPHA as f64)
+        ((c1 * c2 / time) / (1f64 - c1 / time)).powf(1f64 / self.alpha as f64)
     }
 
     /// The antiderivative function of [`feerate_to_time`] excluding the constant shift `+ c1`
     #[inline]
     fn feerate_to_time_antiderivative(&self, feerate: f64) -> f64 {
         let (c1, c2) = (self.inclusion_interval, self.total_weight);
-        c1 * c2 / (-2f64 * feerate.powi(ALPHA - 1))
+        c1 * c2 / (-(self.alpha - 1) as f64 * feerate.powi(self.alpha - 1))
     }
 
     /// Returns the feerate value for which the integral area is `frac` of the total area between `lower` and `upper`.
@@ -127,13 +142,30 @@ impl FeerateEstimator {
         // which can be expressed as z1 + frac * (z2 - z1)
         let z = frac * z2 + (1f64 - frac) * z1;
         // Calc the x value (feerate) corresponding to said area
-        ((c1 * c2) / (-2f64 * z)).powf(1f64 / (ALPHA - 1) as f64)
+        ((c1 * c2) / (-(self.alpha - 1) as f64 * z)).powf(1f64 / (self.alpha - 1) as f64)
+    }
+
+    /// Returns the feerate required for an estimated waiting time of `target_seconds`, bounded below by `minimum_standard_feerate`.
+    /// Targets below one second are treated as one second, since the waiting time cannot go below the inclusion interval
+    fn target_to_feerate(&self, target_seconds: f64, minimum_standard_feerate: f64) -> f64 {
+        self.time_to_feerate(target_seconds.max(1f64)).max(minimum_standard_feerate)
+    }
+
+    /// Returns a feerate bucket for each of the requested target waiting times (in seconds), in the provided order
+    pub fn calc_estimations_for_targets(&self, targets: &[f64], minimum_standard_feerate: f64) -> Vec<FeerateBucket> {
+        targets
+            .iter()
+            .map(|&target| {
+                let feerate = self.target_to_feerate(target, minimum_standard_feerate);
+                FeerateBucket { feerate, estimated_seconds: self.feerate_to_time(feerate) }
+            })
+            .collect()
     }
 
     pub fn calc_estimations(&self, minimum_standard_feerate: f64) -> FeerateEstimations {
         let min = minimum_standard_feerate;
         // Choose `high` such that it provides sub-second waiting time
-        let high = self.time_to_feerate(1f64).max(min);
+        let high = self.target_to_feerate(1f64, min);
         // Choose `low` feerate such that it provides sub-hour waiting time AND it covers (at least) the 0.25 quantile
         let low = self.time_to_feerate(3600f64).max(self.quantile(min, high, 0.25));
         // Choose `normal` feerate such that it provides sub-minute waiting time AND it covers (at least) the 0.66 quantile between low and high.
@@ -156,7 +188,7 @@ impl FeerateEstimator {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct FeeEstimateVerbose {
     pub estimations: FeerateEstimations,
 
@@ -176,7 +208,7 @@ mod tests {
 
     #[test]
     fn test_feerate_estimations() {
-        let estimator = FeerateEstimator { total_weight: 1002283.659, inclusion_interval: 0.004f64 };
+        let estimator = FeerateEstimator { total_weight: 1002283.659, inclusion_interval: 0.004f64, alpha: ALPHA };
         let estimations = estimator.calc_estimations(1.0);
         let buckets = estimations.ordered_buckets();
         for (i, j) in buckets.into_iter().tuple_windows() {
@@ -185,9 +217,37 @@ mod tests {
         dbg!(estimations);
     }
 
+    #[test]
+    fn test_estimations_json_shape() {
+        let bucket = |feerate, estimated_seconds| FeerateBucket { feerate, estimated_seconds };
+        let estimations = FeerateEstimations {
+            priority_bucket: bucket(4.0, 0.5),
+            normal_buckets: vec![bucket(2.0, 30.0), bucket(1.5, 45.0)],
+            low_buckets: vec![bucket(1.0, 3600.0)],
+        };
+        let expected = serde_json::json!({
+            "priority_bucket": { "feerate": 4.0, "estimated_seconds": 0.5 },
+            "normal_buckets": [
+                { "feerate": 2.0, "estimated_seconds": 30.0 },
+                { "feerate": 1.5, "estimated_seconds": 45.0 },
+            ],
+            "low_buckets": [{ "feerate": 1.0, "estimated_seconds": 3600.0 }],
+        });
+        assert_eq!(serde_json::to_value(&estimations).unwrap(), expected);
+
+        // The compact form is single-line and round-trips to the same shape
+        let compact = estimations.to_compact();
+        assert!(!compact.contains('\n'));
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&compact).unwrap(), expected);
+        assert_eq!(
+            estimations.to_string(),
+            "(fee/mass, secs) priority: (4.0000, 0.5000s), normal: (2.0000, 30.0000s), (1.5000, 45.0000s), low: (1.0000, 3600.0000s)"
+        );
+    }
+
     #[test]
     fn test_min_feerate_estimations() {
-        let estimator = FeerateEstimator { total_weight: 0.00659, inclusion_interval: 0.004f64 };
+        let estimator = FeerateEstimator { total_weight: 0.00659, inclusion_interval: 0.004f64, alpha: ALPHA };
         let minimum_feerate = 0.755;
         let estimations = estimator.calc_estimations(minimum_feerate);
         println!("{estimations}");
@@ -199,9 +259,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_estimations_for_targets() {
+        let estimator = FeerateEstimator { total_weight: 1002283.659, inclusion_interval: 0.004f64, alpha: ALPHA };
+        let minimum_feerate = 1.0;
+        let targets = [0.5, 1.0, 5.0, 30.0, 60.0, 600.0, 3600.0, 86400.0];
+        let buckets = estimator.calc_estimations_for_targets(&targets, minimum_feerate);
+        assert_eq!(buckets.len(), targets.len());
+        for (i, j) in buckets.iter().tuple_windows() {
+            assert!(i.feerate >= j.feerate);
+            assert!(i.estimated_seconds <= j.estimated_seconds);
+        }
+        for (target, bucket) in targets.iter().zip(buckets.iter()) {
+            assert!(bucket.feerate >= minimum_feerate);
+            // Unless bounded by the minimum feerate, the estimated time matches the target
+            if bucket.feerate > minimum_feerate && *target >= 1.0 {
+                assert!((bucket.estimated_seconds - target).abs() < 1e-6 * target, "{target}, {bucket}");
+            }
+        }
+
+        // The priority bucket is the special case of a one second target
+        let priority = estimator.calc_estimations(minimum_feerate).priority_bucket;
+        let bucket = estimator.calc_estimations_for_targets(&[1.0], minimum_feerate)[0];
+        assert_eq!((bucket.feerate, bucket.estimated_seconds), (priority.feerate, priority.estimated_seconds));
+    }
+
     #[test]
     fn test_zero_values() {
-        let estimator = FeerateEstimator { total_weight: 0.0, inclusion_interval: 0.0 };
+        let estimator = FeerateEstimator { total_weight: 0.0, inclusion_interval: 0.0, alpha: ALPHA };
         let minimum_feerate = 0.755;
         let estimations = estimator.calc_estimations(minimum_feerate);
         let buckets = estimations.ordered_buckets();
@@ -210,7 +295,7 @@ mod tests {
             assert_eq!(0.0, bucket.estimated_seconds);
         }
 
-        let estimator = FeerateEstimator { total_weight: 0.0, inclusion_interval: 0.1 };
+        let estimator = FeerateEstimator { total_weight: 0.0, inclusion_interval: 0.1, alpha: ALPHA };
         let minimum_feerate = 0.755;
         let estimations = estimator.calc_estimations(minimum_feerate);
         let buckets = estimations.ordered_buckets();
@@ -219,7 +304,7 @@ mod tests {
             assert_eq!(estimator.inclusion_interval, bucket.estimated_seconds);
         }
 
-        let estimator = FeerateEstimator { total_weight: 0.1, inclusion_interval: 0.0 };
+        let estimator = FeerateEstimator { total_weight: 0.1, inclusion_interval: 0.0, alpha: ALPHA };
         let minimum_feerate = 0.755;
         let estimations = estimator.calc_estimations(minimum_feerate);
         let buckets = estimations.ordered_buckets();
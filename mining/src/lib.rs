@@ -34,6 +34,13 @@ pub struct MiningCounters {
     pub input_counts: AtomicU64,
     pub output_counts: AtomicU64,
 
+    // Outcomes of the high priority transactions revalidation
+    pub revalidated_valid_counts: AtomicU64,
+    pub revalidated_accepted_counts: AtomicU64,
+    pub revalidated_missing_outpoint_counts: AtomicU64,
+    pub revalidated_invalid_counts: AtomicU64,
+    pub revalidated_other_counts: AtomicU64,
+
     // Samples
     pub ready_txs_sample: AtomicU64,
     pub txs_sample: AtomicU64,
@@ -52,6 +59,11 @@ impl Default for MiningCounters {
             tx_evicted_counts: Default::default(),
             input_counts: Default::default(),
             output_counts: Default::default(),
+            revalidated_valid_counts: Default::default(),
+            revalidated_accepted_counts: Default::default(),
+            revalidated_missing_outpoint_counts: Default::default(),
+            revalidated_invalid_counts: Default::default(),
+            revalidated_other_counts: Default::default(),
             ready_txs_sample: Default::default(),
             txs_sample: Default::default(),
             orphans_sample: Default::default(),
@@ -71,6 +83,11 @@ impl MiningCounters {
             tx_evicted_counts: self.tx_evicted_counts.load(Ordering::Relaxed),
             input_counts: self.input_counts.load(Ordering::Relaxed),
             output_counts: self.output_counts.load(Ordering::Relaxed),
+            revalidated_valid_counts: self.revalidated_valid_counts.load(Ordering::Relaxed),
+            revalidated_accepted_counts: self.revalidated_accepted_counts.load(Ordering::Relaxed),
+            revalidated_missing_outpoint_counts: self.revalidated_missing_outpoint_counts.load(Ordering::Relaxed),
+            revalidated_invalid_counts: self.revalidated_invalid_counts.load(Ordering::Relaxed),
+            revalidated_other_counts: self.revalidated_other_counts.load(Ordering::Relaxed),
             ready_txs_sample: self.ready_txs_sample.load(Ordering::Relaxed),
             txs_sample: self.txs_sample.load(Ordering::Relaxed),
             orphans_sample: self.orphans_sample.load(Ordering::Relaxed),
@@ -85,6 +102,14 @@ impl MiningCounters {
         }
     }
 
+    pub fn increase_revalidation_counts(&self, valid: u64, accepted: u64, missing_outpoint: u64, invalid: u64, other: u64) {
+        self.revalidated_valid_counts.fetch_add(valid, Ordering::Relaxed);
+        self.revalidated_accepted_counts.fetch_add(accepted, Ordering::Relaxed);
+        self.revalidated_missing_outpoint_counts.fetch_add(missing_outpoint, Ordering::Relaxed);
+        self.revalidated_invalid_counts.fetch_add(invalid, Ordering::Relaxed);
+        self.revalidated_other_counts.fetch_add(other, Ordering::Relaxed);
+    }
+
     pub fn increase_tx_counts(&self, value: u64, priority: Priority) {
         match priority {
             Priority::Low => {
@@ -107,6 +132,11 @@ pub struct MempoolCountersSnapshot {
     pub tx_evicted_counts: u64,
     pub input_counts: u64,
     pub output_counts: u64,
+    pub revalidated_valid_counts: u64,
+    pub revalidated_accepted_counts: u64,
+    pub revalidated_missing_outpoint_counts: u64,
+    pub revalidated_invalid_counts: u64,
+    pub revalidated_other_counts: u64,
     pub ready_txs_sample: u64,
     pub txs_sample: u64,
     pub orphans_sample: u64,
@@ -118,6 +148,17 @@ impl MempoolCountersSnapshot {
         self.high_priority_tx_counts + self.low_priority_tx_counts
     }
 
+    /// Returns the number of high priority transactions removed from the mempool by revalidation
+    pub fn revalidated_removed_counts(&self) -> u64 {
+        self.revalidated_accepted_counts + self.revalidated_missing_outpoint_counts + self.revalidated_invalid_counts
+    }
+
+    /// Indicates whether all counters are equal to those of `other`, i.e., no activity occurred in between.
+    /// Unlike `==`, the elapsed time, which always grows, is ignored
+    pub fn counts_eq(&self, other: &Self) -> bool {
+        Self { elapsed_time: other.elapsed_time, ..*self } == *other
+    }
+
     /// Indicates whether this snapshot has any TPS activity which is worth logging
     pub fn has_tps_activity(&self) -> bool {
         self.tx_accepted_counts > 0 || self.block_tx_counts > 0 || self.low_priority_tx_counts > 0 || self.high_priority_tx_counts > 0
@@ -163,6 +204,13 @@ impl core::ops::Sub for &MempoolCountersSnapshot {
             tx_evicted_counts: self.tx_evicted_counts.saturating_sub(rhs.tx_evicted_counts),
             input_counts: self.input_counts.saturating_sub(rhs.input_counts),
             output_counts: self.output_counts.saturating_sub(rhs.output_counts),
+            revalidated_valid_counts: self.revalidated_valid_counts.saturating_sub(rhs.revalidated_valid_counts),
+            revalidated_accepted_counts: self.revalidated_accepted_counts.saturating_sub(rhs.revalidated_accepted_counts),
+            revalidated_missing_outpoint_counts: self
+                .revalidated_missing_outpoint_counts
+                .saturating_sub(rhs.revalidated_missing_outpoint_counts),
+            revalidated_invalid_counts: self.revalidated_invalid_counts.saturating_sub(rhs.revalidated_invalid_counts),
+            revalidated_other_counts: self.revalidated_other_counts.saturating_sub(rhs.revalidated_other_counts),
             ready_txs_sample: (self.ready_txs_sample + rhs.ready_txs_sample) / 2,
             txs_sample: (self.txs_sample + rhs.txs_sample) / 2,
             orphans_sample: (self.orphans_sample + rhs.orphans_sample) / 2,
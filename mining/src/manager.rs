@@ -2,7 +2,7 @@ use crate::{
     block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
     cache::BlockTemplateCache,
     errors::MiningManagerResult,
-    feerate::{FeeEstimateVerbose, FeerateEstimations, FeerateEstimatorArgs},
+    feerate::{FeeEstimateVerbose, FeerateBucket, FeerateEstimations, FeerateEstimatorArgs},
     mempool::{
         config::Config,
         model::tx::{MempoolTransaction, TransactionPostValidation, TransactionPreValidation, TxRemovalReason},
@@ -13,10 +13,14 @@ use crate::{
         Mempool,
     },
     model::{
+        address_subscriptions::AddressSubscriptions,
+        mempool_snapshot::{MempoolSnapshot, MempoolSnapshotImport},
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         topological_sort::IntoIterTopologically,
+        tx_classification::TxClassification,
         tx_insert::TransactionInsertion,
         tx_query::TransactionQuery,
+        TransactionIdSet,
     },
     MempoolCountersSnapshot, MiningCounters, P2pTxCountSample,
 };
@@ -29,21 +33,51 @@ use kaspa_consensus_core::{
     block::{BlockTemplate, TemplateBuildMode, TemplateTransactionSelector},
     coinbase::MinerData,
     config::params::ForkedParam,
+    constants::MAX_SOMPI,
     errors::{block::RuleError as BlockRuleError, tx::TxRuleError},
+    subnets::SubnetworkId,
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutput},
 };
-use kaspa_consensusmanager::{spawn_blocking, ConsensusProxy};
+use kaspa_consensusmanager::{spawn_blocking, ConsensusProxy, SessionPool};
 use kaspa_core::{debug, error, info, time::Stopwatch, warn};
+use kaspa_hashes::Hash;
 use kaspa_mining_errors::{manager::MiningManagerError, mempool::RuleError};
-use parking_lot::RwLock;
-use std::sync::Arc;
-use tokio::sync::mpsc::UnboundedSender;
+use parking_lot::{Mutex, RwLock};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    sync::Arc,
+};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// A chunk of transactions to be validated together, identified by its exclusive upper bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransactionChunk {
+    /// A chunk whose overall mass is bounded by the validation chunk mass
+    Regular(usize),
+    /// A chunk holding a single transaction whose mass exceeds the max block mass, which can thus never be
+    /// included in a block and should be rejected without validation
+    Oversized(usize),
+}
+
+impl TransactionChunk {
+    fn upper_bound(self) -> usize {
+        match self {
+            TransactionChunk::Regular(upper_bound) | TransactionChunk::Oversized(upper_bound) => upper_bound,
+        }
+    }
+}
 
 pub struct MiningManager {
     config: Arc<Config>,
     block_template_cache: BlockTemplateCache,
     mempool: RwLock<Mempool>,
     counters: Arc<MiningCounters>,
+    /// An optional seeded random source for transaction selection, making block templates reproducible
+    selector_rng: Option<Mutex<StdRng>>,
+    /// Subscriptions to accepted transactions touching sets of addresses
+    address_subscriptions: AddressSubscriptions,
 }
 
 impl MiningManager {
@@ -64,11 +98,13 @@ impl MiningManager {
         relay_non_std_transactions: bool,
         max_block_mass: u64,
         ram_scale: f64,
+        validation_max_parallelism: Option<usize>,
         cache_lifetime: Option<u64>,
         counters: Arc<MiningCounters>,
     ) -> Self {
-        let config =
-            Config::build_default(target_time_per_block, relay_non_std_transactions, max_block_mass).apply_ram_scale(ram_scale);
+        let config = Config::build_default(target_time_per_block, relay_non_std_transactions, max_block_mass)
+            .apply_ram_scale(ram_scale)
+            .apply_validation_max_parallelism(validation_max_parallelism);
         Self::with_config(config, cache_lifetime, counters)
     }
 
@@ -76,7 +112,14 @@ impl MiningManager {
         let config = Arc::new(config);
         let mempool = RwLock::new(Mempool::new(config.clone(), counters.clone()));
         let block_template_cache = BlockTemplateCache::new(cache_lifetime);
-        Self { config, block_template_cache, mempool, counters }
+        Self { config, block_template_cache, mempool, counters, selector_rng: None, address_subscriptions: Default::default() }
+    }
+
+    /// Makes the transaction selection of block templates deterministic by drawing all its randomness
+    /// from a random source seeded with `seed`. Meant for reproducible tests and simulations.
+    pub fn with_selector_seed(mut self, seed: u64) -> Self {
+        self.selector_rng = Some(Mutex::new(StdRng::seed_from_u64(seed)));
+        self
     }
 
     pub fn get_block_template(&self, consensus: &dyn ConsensusApi, miner_data: &MinerData) -> MiningManagerResult<BlockTemplate> {
@@ -106,12 +149,16 @@ impl MiningManager {
         debug!("Building a new block template...");
         let _swo = Stopwatch::<22>::with_threshold("build_block_template full loop");
         let mut attempts: u64 = 0;
+        // Total number of txs removed from the mempool across attempts
+        let (mut total_missing_outpoint, mut total_invalid) = (0usize, 0usize);
         loop {
             attempts += 1;
 
             let selector = self.build_selector();
             let block_template_builder = BlockTemplateBuilder::new();
-            let build_mode = if attempts < self.config.maximum_build_block_template_attempts {
+            let build_mode = if attempts < self.config.maximum_build_block_template_attempts
+                && attempts < self.config.block_template_attempts_limit
+            {
                 TemplateBuildMode::Standard
             } else {
                 TemplateBuildMode::Infallible
@@ -129,17 +176,21 @@ impl MiningManager {
                         }
                         2 => {
                             debug!(
-                                "Built a new block template with {} transactions at second attempt in {:#?}",
+                                "Built a new block template with {} transactions at second attempt in {:#?} ({} txs missing outpoint and {} invalid txs removed)",
                                 block_template.block.transactions.len(),
-                                _swo.elapsed()
+                                _swo.elapsed(),
+                                total_missing_outpoint,
+                                total_invalid
                             );
                         }
                         n => {
                             debug!(
-                                "Built a new block template with {} transactions in {} attempts totaling {:#?}",
+                                "Built a new block template with {} transactions in {} attempts totaling {:#?} ({} txs missing outpoint and {} invalid txs removed)",
                                 block_template.block.transactions.len(),
                                 n,
-                                _swo.elapsed()
+                                _swo.elapsed(),
+                                total_missing_outpoint,
+                                total_invalid
                             );
                         }
                     }
@@ -190,6 +241,19 @@ impl MiningManager {
                         "Building a new block template failed for {} txs missing outpoint and {} invalid txs",
                         missing_outpoint, invalid
                     );
+
+                    total_missing_outpoint += missing_outpoint;
+                    total_invalid += invalid;
+
+                    // The last allowed attempt is infallible so consensus is not expected to reach here past the
+                    // limit. Still bound the loop so that a single call can never monopolize a consensus thread
+                    if attempts >= self.config.block_template_attempts_limit {
+                        warn!(
+                            "Building a new block template failed after {} attempts, removing {} txs missing outpoint and {} invalid txs",
+                            attempts, total_missing_outpoint, total_invalid
+                        );
+                        return Err(MiningManagerError::BlockTemplateAttemptsExhausted(attempts));
+                    }
                 }
                 Err(err) => {
                     warn!("Building a new block template failed: {}", err);
@@ -201,7 +265,10 @@ impl MiningManager {
 
     /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
     pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
-        self.mempool.read().build_selector()
+        match self.selector_rng {
+            Some(ref rng) => self.mempool.read().build_selector(&mut *rng.lock()),
+            None => self.mempool.read().build_selector(&mut rand::thread_rng()),
+        }
     }
 
     /// Returns realtime feerate estimations based on internal mempool state
@@ -214,6 +281,38 @@ impl MiningManager {
         estimator.calc_estimations(self.config.minimum_feerate())
     }
 
+    /// Returns realtime feerate estimations for each of the requested target waiting times (in seconds)
+    pub(crate) fn feerate_estimations_for_targets(&self, virtual_daa_score: u64, targets: &[f64]) -> Vec<FeerateBucket> {
+        let args = FeerateEstimatorArgs::new(
+            self.config.network_blocks_per_second.get(virtual_daa_score),
+            self.config.maximum_mass_per_block,
+        );
+        let estimator = self.mempool.read().build_feerate_estimator(args);
+        estimator.calc_estimations_for_targets(targets, self.config.minimum_feerate())
+    }
+
+    /// Returns the fee (in sompi) estimated for `transaction` to be included in the DAG within `target_seconds`,
+    /// computed as the consensus mass of the transaction multiplied by the feerate required for the target.
+    ///
+    /// The result is never below the minimum relay fee of the transaction, which is also the estimate
+    /// when the mempool holds no competing transactions. Transactions whose mass is incomputable or exceeds
+    /// the max block mass can never be included, hence are rejected
+    pub fn estimate_fee_for_transaction(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transaction: &MutableTransaction,
+        target_seconds: f64,
+    ) -> MiningManagerResult<u64> {
+        let mass =
+            consensus.calculate_transaction_mass(transaction).ok_or(RuleError::RejectStorageMassIncomputable(transaction.id()))?;
+        if mass > self.config.maximum_mass_per_block {
+            return Err(RuleError::RejectOversizedTransaction(transaction.id(), mass, self.config.maximum_mass_per_block).into());
+        }
+        let feerate = self.feerate_estimations_for_targets(consensus.get_virtual_daa_score(), &[target_seconds])[0].feerate;
+        let minimum_fee = self.mempool.read().minimum_required_transaction_relay_fee(mass);
+        Ok(((mass as f64 * feerate).ceil() as u64).clamp(minimum_fee, MAX_SOMPI))
+    }
+
     /// Returns realtime feerate estimations based on internal mempool state with additional verbose data
     pub(crate) fn get_realtime_feerate_estimations_verbose(
         &self,
@@ -342,7 +441,7 @@ impl MiningManager {
         let mut accepted_transactions = Vec::with_capacity(incoming_transactions.len());
         // The validation args map is immutably empty since unorphaned transactions do not require pre processing so there
         // are no feerate thresholds to use. Instead, we rely on this being checked during post processing.
-        let args = TransactionValidationBatchArgs::new();
+        let args = TransactionValidationBatchArgs::new().with_max_parallelism(self.config.validation_max_parallelism);
         // We loop as long as incoming unorphaned transactions do unorphan other transactions when they
         // get validated and inserted into the mempool.
         while !incoming_transactions.is_empty() {
@@ -355,13 +454,19 @@ impl MiningManager {
             // We process the transactions by chunks of max block mass to prevent locking the virtual processor for too long.
             let mut lower_bound: usize = 0;
             let mut validation_results = Vec::with_capacity(transactions.len());
-            while let Some(upper_bound) = self.next_transaction_chunk_upper_bound(&transactions, lower_bound) {
+            while let Some(chunk) = self.next_transaction_chunk(&transactions, lower_bound) {
+                let upper_bound = chunk.upper_bound();
                 assert!(lower_bound < upper_bound, "the chunk is never empty");
-                validation_results.extend(validate_mempool_transactions_in_parallel(
-                    consensus,
-                    &mut transactions[lower_bound..upper_bound],
-                    &args,
-                ));
+                match chunk {
+                    TransactionChunk::Regular(_) => validation_results.extend(validate_mempool_transactions_in_parallel(
+                        consensus,
+                        &mut transactions[lower_bound..upper_bound],
+                        &args,
+                    )),
+                    TransactionChunk::Oversized(_) => {
+                        validation_results.push(Err(self.oversized_transaction_error(&transactions[lower_bound])))
+                    }
+                }
                 lower_bound = upper_bound;
             }
             assert_eq!(transactions.len(), validation_results.len(), "every transaction should have a matching validation result");
@@ -423,14 +528,14 @@ impl MiningManager {
         let mut insert_results: Vec<MiningManagerResult<Arc<Transaction>>> = Vec::with_capacity(transactions.len());
         let mut unorphaned_transactions = vec![];
         let _swo = Stopwatch::<80>::with_threshold("validate_and_insert_transaction_batch topological_sort op");
-        let sorted_transactions = transactions.into_iter().map(MutableTransaction::from_tx).topological_into_iter();
+        let mut sorted_transactions = transactions.into_iter().map(MutableTransaction::from_tx).topological_into_iter();
         drop(_swo);
 
         // read lock on mempool
         // Here, we simply log and drop all erroneous transactions since the caller doesn't care about those anyway
         let mut transactions = Vec::with_capacity(sorted_transactions.len());
-        let mut args = TransactionValidationBatchArgs::new();
-        for chunk in &sorted_transactions.chunks(TRANSACTION_CHUNK_SIZE) {
+        let mut args = TransactionValidationBatchArgs::new().with_max_parallelism(self.config.validation_max_parallelism);
+        for chunk in &sorted_transactions.by_ref().chunks(TRANSACTION_CHUNK_SIZE) {
             let mempool = self.mempool.read();
             let txs = chunk.filter_map(|tx| {
                 let transaction_id = tx.id();
@@ -463,17 +568,29 @@ impl MiningManager {
             transactions.extend(txs);
         }
 
+        // Transactions never yielded by the topological iterator are part of, or depend on, a dependency cycle
+        for transaction in sorted_transactions.into_remainder() {
+            debug!("Rejecting transaction {} belonging to a dependency cycle", transaction.id());
+            insert_results.push(Err(MiningManagerError::MempoolError(RuleError::RejectCycleInMempoolTransactions)));
+        }
+
         // no lock on mempool
         // We process the transactions by chunks of max block mass to prevent locking the virtual processor for too long.
         let mut lower_bound: usize = 0;
         let mut validation_results = Vec::with_capacity(transactions.len());
-        while let Some(upper_bound) = self.next_transaction_chunk_upper_bound(&transactions, lower_bound) {
+        while let Some(chunk) = self.next_transaction_chunk(&transactions, lower_bound) {
+            let upper_bound = chunk.upper_bound();
             assert!(lower_bound < upper_bound, "the chunk is never empty");
-            validation_results.extend(validate_mempool_transactions_in_parallel(
-                consensus,
-                &mut transactions[lower_bound..upper_bound],
-                &args,
-            ));
+            match chunk {
+                TransactionChunk::Regular(_) => validation_results.extend(validate_mempool_transactions_in_parallel(
+                    consensus,
+                    &mut transactions[lower_bound..upper_bound],
+                    &args,
+                )),
+                TransactionChunk::Oversized(_) => {
+                    validation_results.push(Err(self.oversized_transaction_error(&transactions[lower_bound])))
+                }
+            }
             lower_bound = upper_bound;
         }
         assert_eq!(transactions.len(), validation_results.len(), "every transaction should have a matching validation result");
@@ -516,22 +633,40 @@ impl MiningManager {
         insert_results
     }
 
-    fn next_transaction_chunk_upper_bound(&self, transactions: &[MutableTransaction], lower_bound: usize) -> Option<usize> {
+    fn next_transaction_chunk(&self, transactions: &[MutableTransaction], lower_bound: usize) -> Option<TransactionChunk> {
         if lower_bound >= transactions.len() {
             return None;
         }
+        let max_tx_mass = self.config.maximum_mass_per_block;
+        let tx_mass = |tx: &MutableTransaction| tx.calculated_non_contextual_masses.unwrap().max();
+
+        // A transaction which can never fit a block forms a chunk of its own so that the caller can reject it
+        if tx_mass(&transactions[lower_bound]) > max_tx_mass {
+            return Some(TransactionChunk::Oversized(lower_bound + 1));
+        }
+
+        let chunk_mass = self.config.validation_chunk_mass();
         let mut mass = 0;
-        transactions[lower_bound..]
+        let upper_bound = transactions[lower_bound..]
             .iter()
             .position(|tx| {
-                mass += tx.calculated_non_contextual_masses.unwrap().max();
-                mass >= self.config.maximum_mass_per_block
+                let tx_mass = tx_mass(tx);
+                mass += tx_mass;
+                // Also end the chunk right before an oversized transaction
+                tx_mass > max_tx_mass || mass >= chunk_mass
             })
-            // Make sure the upper bound is greater than the lower bound, allowing to handle a very unlikely,
-            // (if not impossible) case where the mass of a single transaction is greater than the maximum
-            // chunk mass.
+            // Make sure the upper bound is greater than the lower bound, allowing to handle the edge case where
+            // the mass of a single transaction equals the maximum chunk mass.
             .map(|relative_index| relative_index.max(1) + lower_bound)
-            .or(Some(transactions.len()))
+            .unwrap_or(transactions.len());
+        Some(TransactionChunk::Regular(upper_bound))
+    }
+
+    /// Builds the rejection of an oversized transaction, see [`TransactionChunk::Oversized`]
+    fn oversized_transaction_error(&self, transaction: &MutableTransaction) -> RuleError {
+        let mass = transaction.calculated_non_contextual_masses.unwrap().max();
+        warn!("Rejecting transaction {} with mass {} exceeding the max block mass", transaction.id(), mass);
+        RuleError::RejectOversizedTransaction(transaction.id(), mass, self.config.maximum_mass_per_block)
     }
 
     /// Try to return a mempool transaction by its id.
@@ -569,6 +704,57 @@ impl MiningManager {
         (transactions, orphans)
     }
 
+    /// Returns, for each mempool transaction, the ids of the in-mempool transactions its inputs spend from.
+    ///
+    /// Orphans and their edges are included according to `query`. The output is meant for visualizing
+    /// the mempool structure with graph tools.
+    pub fn dependency_graph(&self, query: TransactionQuery) -> Vec<(TransactionId, Vec<TransactionId>)> {
+        // read lock on mempool
+        self.mempool.read().dependency_graph(query)
+    }
+
+    /// Writes a JSON snapshot of all the transaction pool and orphan pool entries, including
+    /// their priority and fee metadata, for offline analysis.
+    pub fn export_mempool_snapshot(&self, writer: impl Write) -> std::io::Result<()> {
+        // read lock on mempool
+        let snapshot = self.mempool.read().build_snapshot();
+        serde_json::to_writer(writer, &snapshot)?;
+        Ok(())
+    }
+
+    /// Reads a snapshot written by [`Self::export_mempool_snapshot`] and re-inserts its entries into
+    /// the mempool through the regular validation path, keeping their original priority.
+    ///
+    /// Entries failing validation, for instance because the outpoints they spend no longer exist,
+    /// are logged and skipped.
+    pub fn import_mempool_snapshot(&self, consensus: &dyn ConsensusApi, reader: impl Read) -> std::io::Result<MempoolSnapshotImport> {
+        let MempoolSnapshot { transactions, orphans } = serde_json::from_reader(reader)?;
+        let mut result = MempoolSnapshotImport::default();
+        // Transactions are sorted topologically so that chained transactions find their parents in the mempool
+        for (entries, orphan) in [(transactions, Orphan::Forbidden), (orphans, Orphan::Allowed)] {
+            let mut sorted_entries = entries.topological_into_iter();
+            for entry in sorted_entries.by_ref() {
+                let mut transaction = entry.transaction;
+                transaction.finalize();
+                let transaction_id = transaction.id();
+                match self.validate_and_insert_transaction(consensus, transaction, entry.priority, orphan, RbfPolicy::Forbidden) {
+                    Ok(_) => result.imported += 1,
+                    Err(err) => {
+                        warn!("Skipping mempool snapshot transaction {0}: {1}", transaction_id, err);
+                        result.skipped += 1;
+                    }
+                }
+            }
+            // Entries never yielded by the topological iterator are part of, or depend on, a dependency cycle
+            for entry in sorted_entries.into_remainder() {
+                let err = RuleError::RejectCycleInMempoolTransactions;
+                warn!("Skipping mempool snapshot transaction {0}: {1}", entry.transaction.id(), err);
+                result.skipped += 1;
+            }
+        }
+        Ok(result)
+    }
+
     /// get_transactions_by_addresses returns the sending and receiving transactions for
     /// a set of addresses.
     ///
@@ -582,22 +768,102 @@ impl MiningManager {
         self.mempool.read().get_transactions_by_addresses(script_public_keys, query)
     }
 
+    /// Paged version of [`Self::get_transactions_by_addresses()`], returning at most `limit` transactions
+    /// starting at position `offset`. Transactions are ordered by transaction id, hence the ordering is stable
+    /// across pages (up to concurrent mempool changes).
+    ///
+    /// The mempool is scanned by chunks so that the read lock is never held for the full scan.
+    pub fn get_transactions_by_addresses_paged(
+        &self,
+        script_public_keys: &ScriptPublicKeySet,
+        query: TransactionQuery,
+        offset: usize,
+        limit: usize,
+    ) -> GroupedOwnerTransactions {
+        const TRANSACTION_CHUNK_SIZE: usize = 1000;
+        let mut owner_set = GroupedOwnerTransactions::default();
+        script_public_keys.iter().for_each(|script_public_key| {
+            owner_set.owners.entry(script_public_key.clone()).or_default();
+        });
+        if limit == 0 {
+            return owner_set;
+        }
+
+        // read lock on mempool
+        let (transaction_ids, orphan_ids) = self.mempool.read().get_all_transaction_ids(query);
+        let transaction_ids = transaction_ids.into_iter().chain(orphan_ids).sorted().dedup().collect_vec();
+
+        // read lock on mempool by transaction chunks
+        let mut to_skip = offset;
+        for chunk in transaction_ids.chunks(TRANSACTION_CHUNK_SIZE) {
+            let mempool = self.mempool.read();
+            for transaction_id in chunk {
+                if !mempool.is_transaction_owned_by_any(transaction_id, script_public_keys, query) {
+                    continue;
+                }
+                if to_skip > 0 {
+                    to_skip -= 1;
+                    continue;
+                }
+                mempool.fill_owner_set_transaction(transaction_id, script_public_keys, query, &mut owner_set);
+                if owner_set.transactions.len() >= limit {
+                    return owner_set;
+                }
+            }
+        }
+        owner_set
+    }
+
     pub fn transaction_count(&self, query: TransactionQuery) -> usize {
         self.mempool.read().transaction_count(query)
     }
 
+    /// Returns the number of mempool transactions, orphans included, per subnetwork.
+    /// Useful for detecting unexpected traffic outside of the native subnetwork
+    pub fn transaction_count_by_subnetwork(&self) -> HashMap<SubnetworkId, usize> {
+        self.mempool.read().transaction_count_by_subnetwork(TransactionQuery::All)
+    }
+
+    /// Returns whether the transaction is in the mempool and has no unconfirmed mempool ancestors,
+    /// i.e., whether it is eligible for inclusion in the next block template.
+    pub fn is_transaction_ready(&self, transaction_id: &TransactionId) -> bool {
+        self.mempool.read().is_transaction_ready(transaction_id)
+    }
+
+    /// Returns the number of mempool transactions which are ready to enter the next block template
+    pub fn ready_transaction_count(&self) -> usize {
+        self.mempool.read().ready_transaction_count()
+    }
+
+    /// Updates the mempool with the transactions of a block processed by consensus. Only transactions which were
+    /// actually accepted into the virtual state are removed, the others remain available for future block templates.
+    /// If consensus has no acceptance data for the block, all its transactions are considered accepted.
     pub fn handle_new_block_transactions(
         &self,
         consensus: &dyn ConsensusApi,
+        block_hash: Hash,
         block_daa_score: u64,
         block_transactions: &[Transaction],
     ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
-        // TODO: should use tx acceptance data to verify that new block txs are actually accepted into virtual state.
         // TODO: avoid returning a result from this function (and the underlying function). Any possible error is a
         // problem of the internal implementation and unrelated to the caller
 
+        let accepted_transaction_ids: Option<TransactionIdSet> =
+            consensus.get_block_accepted_transaction_ids(block_hash).map(|ids| ids.into_iter().collect());
+
         // write lock on mempool
-        let unorphaned_transactions = self.mempool.write().handle_new_block_transactions(block_daa_score, block_transactions)?;
+        let (unorphaned_transactions, touched_script_public_keys) = {
+            let mut mempool = self.mempool.write();
+            // The spent UTXO entries must be collected before the accepted transactions leave the mempool
+            let touched_script_public_keys = (!self.address_subscriptions.is_empty())
+                .then(|| mempool.touched_script_public_keys(block_transactions, accepted_transaction_ids.as_ref()));
+            let unorphaned_transactions =
+                mempool.handle_new_block_transactions(block_daa_score, block_transactions, accepted_transaction_ids.as_ref())?;
+            (unorphaned_transactions, touched_script_public_keys)
+        };
+        if let Some(touched_script_public_keys) = touched_script_public_keys {
+            self.address_subscriptions.notify(&touched_script_public_keys);
+        }
 
         // alternate no & write lock on mempool
         let accepted_transactions = self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions);
@@ -605,6 +871,29 @@ impl MiningManager {
         Ok(accepted_transactions)
     }
 
+    /// Subscribes to the transactions accepted by blocks handled by the mempool which touch any of `script_public_keys`,
+    /// either by paying to them or by spending from them (when the spent UTXO entries are known to the mempool).
+    ///
+    /// Each handled block with matching transactions yields a notification holding their ids. Dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe_accepted_by_addresses(&self, script_public_keys: ScriptPublicKeySet) -> UnboundedReceiver<Vec<TransactionId>> {
+        self.address_subscriptions.subscribe(script_public_keys)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn address_subscription_count(&self) -> usize {
+        self.address_subscriptions.len()
+    }
+
+    /// Sets the number of DAA score units a low priority transaction may stay in the mempool without being
+    /// accepted before expiring. High priority transactions never expire.
+    ///
+    /// The new interval applies to subsequent expiry passes only, and is evaluated against the DAA score
+    /// at which each transaction was added to the mempool.
+    pub fn set_transaction_expire_interval_daa_score(&self, transaction_expire_interval_daa_score: u64) {
+        self.mempool.write().set_transaction_expire_interval_daa_score(transaction_expire_interval_daa_score)
+    }
+
     pub fn expire_low_priority_transactions(&self, consensus: &dyn ConsensusApi) {
         // very fine-grained write locks on mempool
         debug!("<> Expiring low priority transactions...");
@@ -668,7 +957,7 @@ impl MiningManager {
         // We process the transactions by level of dependency inside the batch.
         // Doing so allows to remove all chained dependencies of rejected transactions.
         let _swo = Stopwatch::<800>::with_threshold("revalidate topological_sort op");
-        let sorted_transactions = transactions.topological_into_iter();
+        let mut sorted_transactions = transactions.topological_into_iter();
         drop(_swo);
 
         // read lock on mempool by transaction chunks
@@ -676,7 +965,7 @@ impl MiningManager {
         // keeping only the ones actually present in the mempool (see comment above).
         let _swo = Stopwatch::<900>::with_threshold("revalidate populate_mempool_entries op");
         let mut transactions = Vec::with_capacity(sorted_transactions.len());
-        for chunk in &sorted_transactions.chunks(TRANSACTION_CHUNK_SIZE) {
+        for chunk in &sorted_transactions.by_ref().chunks(TRANSACTION_CHUNK_SIZE) {
             let mempool = self.mempool.read();
             let txs = chunk.filter_map(|mut x| {
                 let transaction_id = x.id();
@@ -701,18 +990,31 @@ impl MiningManager {
             });
             transactions.extend(txs);
         }
+        // Transactions part of, or depending on, a dependency cycle are never yielded and thus excluded from revalidation
+        for transaction in sorted_transactions.into_remainder() {
+            warn!("Skipping revalidation of transaction {} belonging to a dependency cycle", transaction.id());
+            other += 1;
+        }
         drop(_swo);
 
         // no lock on mempool
         // We process the transactions by chunks of max block mass to prevent locking the virtual processor for too long.
         let mut lower_bound: usize = 0;
         let mut validation_results = Vec::with_capacity(transactions.len());
-        while let Some(upper_bound) = self.next_transaction_chunk_upper_bound(&transactions, lower_bound) {
+        while let Some(chunk) = self.next_transaction_chunk(&transactions, lower_bound) {
+            let upper_bound = chunk.upper_bound();
             assert!(lower_bound < upper_bound, "the chunk is never empty");
-            let _swo = Stopwatch::<60>::with_threshold("revalidate validate_mempool_transactions_in_parallel op");
-            validation_results
-                .extend(populate_mempool_transactions_in_parallel(consensus, &mut transactions[lower_bound..upper_bound]));
-            drop(_swo);
+            match chunk {
+                TransactionChunk::Regular(_) => {
+                    let _swo = Stopwatch::<60>::with_threshold("revalidate validate_mempool_transactions_in_parallel op");
+                    validation_results
+                        .extend(populate_mempool_transactions_in_parallel(consensus, &mut transactions[lower_bound..upper_bound]));
+                    drop(_swo);
+                }
+                TransactionChunk::Oversized(_) => {
+                    validation_results.push(Err(self.oversized_transaction_error(&transactions[lower_bound])))
+                }
+            }
             lower_bound = upper_bound;
         }
         assert_eq!(transactions.len(), validation_results.len(), "every transaction should have a matching validation result");
@@ -794,6 +1096,13 @@ impl MiningManager {
             drop(_swo);
             drop(mempool);
         }
+        self.counters.increase_revalidation_counts(
+            valid as u64,
+            accepted as u64,
+            missing_outpoint as u64,
+            invalid as u64,
+            other as u64,
+        );
         match accepted + missing_outpoint + invalid {
             0 => {
                 info!("Revalidated {} high priority transactions", valid);
@@ -828,6 +1137,18 @@ impl MiningManager {
         self.mempool.read().is_transaction_output_dust(transaction_output)
     }
 
+    /// Returns the minimal value a spendable output with a script public key of `script_len` bytes
+    /// must hold in order not to be considered dust. See [`Self::is_transaction_output_dust()`].
+    pub fn dust_threshold(&self, script_len: usize) -> u64 {
+        self.mempool.read().dust_threshold(script_len)
+    }
+
+    /// Returns, for each of the passed outputs, whether or not it is considered dust.
+    /// This is the batch version of [`Self::is_transaction_output_dust()`], acquiring the mempool lock only once.
+    pub fn outputs_dust_mask(&self, outputs: &[TransactionOutput]) -> Vec<bool> {
+        self.mempool.read().outputs_dust_mask(outputs)
+    }
+
     pub fn has_accepted_transaction(&self, transaction_id: &TransactionId) -> bool {
         self.mempool.read().has_accepted_transaction(transaction_id)
     }
@@ -840,6 +1161,12 @@ impl MiningManager {
         self.mempool.read().unknown_transactions(transactions)
     }
 
+    /// Partitions `transactions` into accepted, transaction pool, orphan pool and unknown ids
+    /// under a single mempool read lock.
+    pub fn classify_transactions(&self, transactions: Vec<TransactionId>) -> TxClassification {
+        self.mempool.read().classify_transactions(transactions)
+    }
+
     #[cfg(test)]
     pub(crate) fn get_estimated_size(&self) -> usize {
         self.mempool.read().get_estimated_size()
@@ -866,13 +1193,32 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.get_realtime_feerate_estimations(virtual_daa_score)).await.unwrap()
     }
 
-    /// Returns realtime feerate estimations based on internal mempool state with additional verbose data
+    /// Returns realtime feerate estimations for each of the requested target waiting times (in seconds)
+    pub async fn feerate_estimations_for_targets(self, virtual_daa_score: u64, targets: Vec<f64>) -> Vec<FeerateBucket> {
+        spawn_blocking(move || self.inner.feerate_estimations_for_targets(virtual_daa_score, &targets)).await.unwrap()
+    }
+
+    /// Returns the fee (in sompi) estimated for `transaction` to be included in the DAG within `target_seconds`.
+    /// The consensus is only read, hence the session is obtained from `session_pool`
+    pub async fn estimate_fee_for_transaction(
+        self,
+        session_pool: &SessionPool,
+        transaction: MutableTransaction,
+        target_seconds: f64,
+    ) -> MiningManagerResult<u64> {
+        let consensus = session_pool.session().await;
+        consensus.spawn_blocking(move |c| self.inner.estimate_fee_for_transaction(c, &transaction, target_seconds)).await
+    }
+
+    /// Returns realtime feerate estimations based on internal mempool state with additional verbose data.
+    /// The consensus is only read, hence the session is obtained from `session_pool`
     pub async fn get_realtime_feerate_estimations_verbose(
         self,
-        consensus: &ConsensusProxy,
+        session_pool: &SessionPool,
         prefix: kaspa_addresses::Prefix,
     ) -> MiningManagerResult<FeeEstimateVerbose> {
-        consensus.clone().spawn_blocking(move |c| self.inner.get_realtime_feerate_estimations_verbose(c, prefix)).await
+        let consensus = session_pool.session().await;
+        consensus.spawn_blocking(move |c| self.inner.get_realtime_feerate_estimations_verbose(c, prefix)).await
     }
 
     /// Validates a transaction and adds it to the set of known transactions that have not yet been
@@ -921,15 +1267,26 @@ impl MiningManagerProxy {
     pub async fn handle_new_block_transactions(
         self,
         consensus: &ConsensusProxy,
+        block_hash: Hash,
         block_daa_score: u64,
         block_transactions: Arc<Vec<Transaction>>,
     ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
         consensus
             .clone()
-            .spawn_blocking(move |c| self.inner.handle_new_block_transactions(c, block_daa_score, &block_transactions))
+            .spawn_blocking(move |c| self.inner.handle_new_block_transactions(c, block_hash, block_daa_score, &block_transactions))
             .await
     }
 
+    /// For more details, see [`MiningManager::subscribe_accepted_by_addresses()`].
+    pub fn subscribe_accepted_by_addresses(&self, script_public_keys: ScriptPublicKeySet) -> UnboundedReceiver<Vec<TransactionId>> {
+        self.inner.subscribe_accepted_by_addresses(script_public_keys)
+    }
+
+    /// For more details, see [`MiningManager::set_transaction_expire_interval_daa_score()`].
+    pub fn set_transaction_expire_interval_daa_score(&self, transaction_expire_interval_daa_score: u64) {
+        self.inner.set_transaction_expire_interval_daa_score(transaction_expire_interval_daa_score)
+    }
+
     pub async fn expire_low_priority_transactions(self, consensus: &ConsensusProxy) {
         consensus.clone().spawn_blocking(move |c| self.inner.expire_low_priority_transactions(c)).await;
     }
@@ -958,6 +1315,22 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.transaction_count(query)).await.unwrap()
     }
 
+    /// Returns the number of mempool transactions per subnetwork.
+    /// For more details, see [`MiningManager::transaction_count_by_subnetwork()`].
+    pub async fn transaction_count_by_subnetwork(self) -> HashMap<SubnetworkId, usize> {
+        spawn_blocking(move || self.inner.transaction_count_by_subnetwork()).await.unwrap()
+    }
+
+    /// Returns whether the transaction is eligible for the next block template.
+    /// For more details, see [`MiningManager::is_transaction_ready()`].
+    pub async fn is_transaction_ready(self, transaction_id: TransactionId) -> bool {
+        spawn_blocking(move || self.inner.is_transaction_ready(&transaction_id)).await.unwrap()
+    }
+
+    pub async fn ready_transaction_count(self) -> usize {
+        spawn_blocking(move || self.inner.ready_transaction_count()).await.unwrap()
+    }
+
     pub async fn get_all_transactions(self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         spawn_blocking(move || self.inner.get_all_transactions(query)).await.unwrap()
     }
@@ -974,6 +1347,20 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.get_transactions_by_addresses(&script_public_keys, query)).await.unwrap()
     }
 
+    /// Paged version of [`Self::get_transactions_by_addresses()`].
+    /// For more details, see [`MiningManager::get_transactions_by_addresses_paged()`].
+    pub async fn get_transactions_by_addresses_paged(
+        self,
+        script_public_keys: ScriptPublicKeySet,
+        query: TransactionQuery,
+        offset: usize,
+        limit: usize,
+    ) -> GroupedOwnerTransactions {
+        spawn_blocking(move || self.inner.get_transactions_by_addresses_paged(&script_public_keys, query, offset, limit))
+            .await
+            .unwrap()
+    }
+
     /// Returns whether a transaction id was registered as accepted in the mempool, meaning
     /// that the consensus accepted a block containing it and said block was handled by the
     /// mempool.
@@ -998,6 +1385,23 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.unknown_transactions(transactions)).await.unwrap()
     }
 
+    /// Partitions the transaction ids according to their status in the mempool.
+    /// For more details, see [`MiningManager::classify_transactions()`].
+    pub async fn classify_transactions(self, transactions: Vec<TransactionId>) -> TxClassification {
+        spawn_blocking(move || self.inner.classify_transactions(transactions)).await.unwrap()
+    }
+
+    /// Returns the minimal non-dust value for an output with a script public key of `script_len` bytes.
+    /// For more details, see [`MiningManager::dust_threshold()`].
+    pub fn dust_threshold(&self, script_len: usize) -> u64 {
+        self.inner.dust_threshold(script_len)
+    }
+
+    /// Returns, for each of the passed outputs, whether or not it is considered dust.
+    pub async fn outputs_dust_mask(self, outputs: Vec<TransactionOutput>) -> Vec<bool> {
+        spawn_blocking(move || self.inner.outputs_dust_mask(&outputs)).await.unwrap()
+    }
+
     pub fn snapshot(&self) -> MempoolCountersSnapshot {
         self.inner.counters.snapshot()
     }
@@ -1081,7 +1485,7 @@ fn feerate_stats(transactions: Vec<Transaction>, calculated_fees: Vec<u64>) -> O
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kaspa_consensus_core::subnets;
+    use kaspa_consensus_core::{mass::NonContextualMasses, subnets};
     use std::iter::repeat;
 
     fn transactions(length: usize) -> Vec<Transaction> {
@@ -1112,6 +1516,55 @@ mod tests {
         assert!(feerate_stats(txs, calculated_fees).is_none());
     }
 
+    #[test]
+    fn transaction_chunk_upper_bound_test() {
+        const MAX_BLOCK_MASS: u64 = 1_000;
+        let mutable_transactions = |masses: &[u64]| {
+            masses
+                .iter()
+                .map(|mass| {
+                    let mut tx = MutableTransaction::from_tx(Transaction::new(0, vec![], vec![], 0, Default::default(), 0, vec![]));
+                    tx.calculated_non_contextual_masses = Some(NonContextualMasses::new(*mass, *mass));
+                    tx
+                })
+                .collect_vec()
+        };
+        let chunk_bounds = |multiplier: u64, transactions: &[MutableTransaction]| {
+            let config = Config::build_default(ForkedParam::new_const(1000), false, MAX_BLOCK_MASS)
+                .apply_validation_chunk_mass_multiplier(multiplier);
+            let manager = MiningManager::with_config(config, None, Arc::new(MiningCounters::default()));
+            let mut chunks = vec![];
+            let mut lower_bound = 0;
+            while let Some(chunk) = manager.next_transaction_chunk(transactions, lower_bound) {
+                chunks.push(chunk);
+                lower_bound = chunk.upper_bound();
+            }
+            chunks
+        };
+        let bounds = |chunks: Vec<TransactionChunk>| chunks.into_iter().map(|c| c.upper_bound()).collect_vec();
+
+        let transactions = mutable_transactions(&[300; 10]);
+        assert_eq!(bounds(chunk_bounds(1, &transactions)), vec![3, 6, 9, 10]);
+        assert_eq!(bounds(chunk_bounds(2, &transactions)), vec![6, 10]);
+        assert_eq!(bounds(chunk_bounds(4, &transactions)), vec![10]);
+        // A zero multiplier is treated as the default one
+        assert_eq!(chunk_bounds(0, &transactions), chunk_bounds(1, &transactions));
+
+        // A single transaction exceeding the max block mass forms a chunk of its own which is flagged as oversized
+        use TransactionChunk::{Oversized, Regular};
+        let transactions = mutable_transactions(&[5_000, 5_000, 100]);
+        assert_eq!(chunk_bounds(1, &transactions), vec![Oversized(1), Oversized(2), Regular(3)]);
+        assert_eq!(chunk_bounds(2, &transactions), vec![Oversized(1), Oversized(2), Regular(3)]);
+
+        // Oversized transactions also end the preceding regular chunk, even if it has room left
+        let transactions = mutable_transactions(&[100, 1_500, 100, 100]);
+        assert_eq!(chunk_bounds(2, &transactions), vec![Regular(1), Oversized(2), Regular(4)]);
+
+        // A transaction of exactly the max block mass is regular
+        let transactions = mutable_transactions(&[MAX_BLOCK_MASS, 100]);
+        assert_eq!(chunk_bounds(1, &transactions), vec![Regular(1), Regular(2)]);
+    }
+
     #[test]
     fn feerate_stats_inconsistent_test() {
         let calculated_fees = vec![100u64, 200, 300, 400];
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        block_template::builder::BlockTemplateBuilder,
+        block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
         errors::{MiningManagerError, MiningManagerResult},
         manager::MiningManager,
         mempool::{
@@ -10,8 +10,13 @@ mod tests {
             model::frontier::selectors::TakeAllSelector,
             tx::{Orphan, Priority, RbfPolicy},
         },
-        model::{tx_insert::TransactionInsertion, tx_query::TransactionQuery},
-        testutils::consensus_mock::ConsensusMock,
+        model::{
+            mempool_snapshot::{MempoolSnapshot, MempoolSnapshotEntry, MempoolSnapshotImport},
+            owner_txs::ScriptPublicKeySet,
+            tx_insert::TransactionInsertion,
+            tx_query::TransactionQuery,
+        },
+        testutils::consensus_mock::{ConsensusMock, MAX_COINBASE_PAYLOAD_LEN},
         MiningCounters,
     };
     use itertools::Itertools;
@@ -22,9 +27,9 @@ mod tests {
         coinbase::MinerData,
         config::params::ForkedParam,
         constants::{MAX_TX_IN_SEQUENCE_NUM, SOMPI_PER_KASPA, TX_VERSION},
-        errors::tx::TxRuleError,
+        errors::{coinbase::CoinbaseError, tx::TxRuleError},
         mass::{transaction_estimated_serialized_size, NonContextualMasses},
-        subnets::SUBNETWORK_ID_NATIVE,
+        subnets::{SubnetworkId, SUBNETWORK_ID_NATIVE},
         tx::{
             scriptvec, MutableTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint,
             TransactionOutput, UtxoEntry,
@@ -37,7 +42,10 @@ mod tests {
         test_helpers::{create_transaction, create_transaction_with_change, op_true_script},
     };
     use kaspa_utils::mem_size::MemSizeEstimator;
-    use std::{iter::once, sync::Arc};
+    use std::{
+        iter::{empty, once},
+        sync::Arc,
+    };
     use tokio::sync::mpsc::{error::TryRecvError, unbounded_channel};
 
     const TARGET_TIME_PER_BLOCK: u64 = 1_000;
@@ -570,7 +578,8 @@ mod tests {
         let block_with_first_part = build_block_transactions(first_part.iter().map(|mtx| mtx.tx.as_ref()));
         let block_with_rest = build_block_transactions(rest.iter().map(|mtx| mtx.tx.as_ref()));
 
-        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_with_first_part);
+        let result =
+            mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_with_first_part);
         assert!(
             result.is_ok(),
             "the handling by the mempool of the transactions of a block accepted by the consensus should succeed but returned {result:?}"
@@ -591,7 +600,7 @@ mod tests {
         }
 
         // Handle all the other transactions.
-        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 3, &block_with_rest);
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(3), 3, &block_with_rest);
         assert!(
             result.is_ok(),
             "the handling by the mempool of the transactions of a block accepted by the consensus should succeed but returned {result:?}"            
@@ -627,7 +636,7 @@ mod tests {
             transaction_in_the_mempool.tx.inputs[0].previous_outpoint;
         let block_transactions = build_block_transactions(std::iter::once(double_spend_transaction_in_the_block.tx.as_ref()));
 
-        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions);
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_transactions);
         assert!(result.is_ok());
 
         assert!(
@@ -637,6 +646,137 @@ mod tests {
         );
     }
 
+    /// test_double_spend_of_chain_with_block verifies that a block double spending a transaction in the mempool removes it along with
+    /// all its in-mempool descendants, keeping the ready transactions consistent with the remaining transactions
+    #[test]
+    fn test_double_spend_of_chain_with_block() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 2);
+        let parent = create_transaction(&funding_txs[0], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let children = create_children_tree(&parent, 2);
+        let unrelated = create_transaction(&funding_txs[1], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&parent).chain(children.iter()).chain(once(&unrelated)),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, children.len() + 2, "all transactions should be in the mempool");
+        assert_eq!(mining_manager.ready_transaction_count(), 2);
+
+        // A block transaction spending the same outpoint with a different fee
+        let double_spend = create_transaction(&funding_txs[0], 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        assert_ne!(double_spend.id(), parent.id());
+        let block_transactions = build_block_transactions(once(&double_spend));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_transactions);
+        assert!(result.is_ok());
+
+        // The double spent chain is fully removed, and descendants which became ready on the way are not left in the frontier
+        assert_transaction_count(&mining_manager, 1, "only the unrelated transaction should remain in the mempool");
+        assert!(mining_manager.get_transaction(&unrelated.id(), TransactionQuery::TransactionsOnly).is_some());
+        assert_eq!(mining_manager.ready_transaction_count(), 1);
+    }
+
+    /// test_handle_partially_accepted_block_transactions verifies that block transactions which were not accepted
+    /// into the virtual state (e.g. because the block is red) remain in the mempool, and do not evict their double spends
+    #[test]
+    fn test_handle_partially_accepted_block_transactions() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 4);
+        let transactions = funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            transactions.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        // The block holds the first 3 mempool transactions and a double spend of the last one, but only
+        // the first 2 transactions are accepted
+        let double_spend = create_transaction(&funding_txs[3], 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let block_transactions = build_block_transactions(transactions[..3].iter().chain(once(&double_spend)));
+        let block_hash = Hash::from_u64_word(2);
+        consensus.set_block_accepted_transaction_ids(block_hash, vec![transactions[0].id(), transactions[1].id()]);
+
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), block_hash, 2, &block_transactions);
+        assert!(result.is_ok());
+
+        for accepted in transactions[..2].iter() {
+            assert!(!mining_manager.has_transaction(&accepted.id(), TransactionQuery::All));
+            assert!(mining_manager.has_accepted_transaction(&accepted.id()));
+        }
+        for unaccepted in transactions[2..].iter() {
+            assert!(
+                mining_manager.has_transaction(&unaccepted.id(), TransactionQuery::TransactionsOnly),
+                "the unaccepted transaction {} should remain in the mempool",
+                unaccepted.id()
+            );
+            assert!(!mining_manager.has_accepted_transaction(&unaccepted.id()));
+        }
+        assert!(!mining_manager.has_accepted_transaction(&double_spend.id()));
+    }
+
+    /// test_subscribe_accepted_by_addresses verifies that subscribers are notified of accepted transactions touching
+    /// their addresses only, and that dropping a receiver removes its subscription
+    #[test]
+    fn test_subscribe_accepted_by_addresses() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let (watched_script_public_key, _) = op_true_script();
+        let unrelated_script_public_key = ScriptPublicKey::new(0, scriptvec![0x51]);
+        let mut watched_receiver = mining_manager.subscribe_accepted_by_addresses([watched_script_public_key].into());
+        let mut unrelated_receiver = mining_manager.subscribe_accepted_by_addresses([unrelated_script_public_key].into());
+        assert_eq!(mining_manager.address_subscription_count(), 2);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 2);
+        let transactions = funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&transactions[0]),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        let block_transactions = build_block_transactions(once(&transactions[0]));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_transactions);
+        assert!(result.is_ok());
+        assert_eq!(watched_receiver.try_recv(), Ok(vec![transactions[0].id()]));
+        assert_eq!(watched_receiver.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(unrelated_receiver.try_recv(), Err(TryRecvError::Empty));
+
+        // Dropping a receiver unsubscribes
+        drop(unrelated_receiver);
+        let block_transactions = build_block_transactions(once(&transactions[1]));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(3), 3, &block_transactions);
+        assert!(result.is_ok());
+        assert_eq!(watched_receiver.try_recv(), Ok(vec![transactions[1].id()]));
+        assert_eq!(mining_manager.address_subscription_count(), 1);
+
+        drop(watched_receiver);
+        let result = mining_manager.handle_new_block_transactions(
+            consensus.as_ref(),
+            Hash::from_u64_word(4),
+            4,
+            &build_block_transactions(empty()),
+        );
+        assert!(result.is_ok());
+        assert_eq!(mining_manager.address_subscription_count(), 0);
+    }
+
     /// test_orphan_transactions verifies that a transaction could be a part of a new block template only if it's not an orphan.
     #[test]
     fn test_orphan_transactions() {
@@ -693,8 +833,12 @@ mod tests {
         mining_manager.clear_block_template();
         let added_parent_txs = parent_txs.iter().skip(SKIPPED_TXS).cloned().collect::<Vec<_>>();
         added_parent_txs.iter().for_each(|x| consensus.add_transaction(x.clone(), 1));
-        let result =
-            mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &build_block_transactions(added_parent_txs.iter()));
+        let result = mining_manager.handle_new_block_transactions(
+            consensus.as_ref(),
+            Hash::from_u64_word(2),
+            2,
+            &build_block_transactions(added_parent_txs.iter()),
+        );
         assert!(result.is_ok(), "mining manager should handle new block transactions successfully but returns {result:?}");
         let unorphaned_txs = result.unwrap();
         let (populated_txs, orphans) = mining_manager.get_all_transactions(TransactionQuery::All);
@@ -778,8 +922,12 @@ mod tests {
         mining_manager.clear_block_template();
         let added_child_txs = child_txs.iter().skip(SKIPPED_TXS).cloned().collect::<Vec<_>>();
         added_child_txs.iter().for_each(|x| consensus.add_transaction(x.clone(), 2));
-        let result =
-            mining_manager.handle_new_block_transactions(consensus.as_ref(), 4, &build_block_transactions(added_child_txs.iter()));
+        let result = mining_manager.handle_new_block_transactions(
+            consensus.as_ref(),
+            Hash::from_u64_word(4),
+            4,
+            &build_block_transactions(added_child_txs.iter()),
+        );
         assert!(result.is_ok(), "mining manager should handle new block transactions successfully but returns {result:?}");
 
         let unorphaned_txs = result.unwrap();
@@ -840,6 +988,51 @@ mod tests {
         assert_eq!(0, orphans.len(), "the orphan pool is expected to be empty: {}, got: {}", 0, orphans.len());
     }
 
+    /// test_transaction_expire_interval verifies that a configured expire interval applies to subsequent expiry passes,
+    /// expiring low priority transactions once the interval has elapsed while high priority transactions survive
+    #[test]
+    fn test_transaction_expire_interval() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mut config = Config::build_default(ForkedParam::new_const(TARGET_TIME_PER_BLOCK), false, MAX_BLOCK_MASS);
+        // Scan on every expiry pass
+        config.transaction_expire_scan_interval_daa_score = ForkedParam::new_const(0);
+        config.transaction_expire_scan_interval_milliseconds = 0;
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 2);
+        let low_priority_tx = create_transaction(&funding_txs[0], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let high_priority_tx = create_transaction(&funding_txs[1], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        consensus.set_virtual_daa_score(100);
+        for (transaction, priority) in [(&low_priority_tx, Priority::Low), (&high_priority_tx, Priority::High)] {
+            validate_and_insert_transactions(
+                &mining_manager,
+                consensus.as_ref(),
+                once(transaction),
+                priority,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+        }
+        let in_mempool = |transaction: &Transaction| mining_manager.has_transaction(&transaction.id(), TransactionQuery::All);
+
+        // The default interval is far longer
+        consensus.set_virtual_daa_score(111);
+        mining_manager.expire_low_priority_transactions(consensus.as_ref());
+        assert!(in_mempool(&low_priority_tx));
+
+        // The new interval applies from the next pass on
+        mining_manager.set_transaction_expire_interval_daa_score(20);
+        consensus.set_virtual_daa_score(120);
+        mining_manager.expire_low_priority_transactions(consensus.as_ref());
+        assert!(in_mempool(&low_priority_tx), "the low priority transaction should expire only after the interval");
+
+        mining_manager.set_transaction_expire_interval_daa_score(10);
+        mining_manager.expire_low_priority_transactions(consensus.as_ref());
+        assert!(!in_mempool(&low_priority_tx), "the low priority transaction should have expired");
+        assert!(in_mempool(&high_priority_tx), "high priority transactions should never expire");
+    }
+
     /// test_high_priority_transactions verifies that inserting a high priority orphan transaction when the orphan pool is full
     /// evicts a low-priority transaction, if available, or fails if the pool is already filled with high priority transactions.
     #[test]
@@ -1066,6 +1259,524 @@ mod tests {
         assert!(orphan_txs.is_empty(), "orphan pool should be empty");
     }
 
+    /// test_revalidation_counters verifies that revalidating a mix of valid and invalid high priority transactions
+    /// updates the revalidation outcome counters accordingly.
+    #[test]
+    fn test_revalidation_counters() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 4);
+        let spending_txs = funding_txs.iter().map(|tx| create_transaction(tx, 1_000)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            spending_txs.iter(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        // Keep the first 2 transactions valid, make the third miss its outpoint and the fourth invalid
+        consensus.set_status(spending_txs[2].id(), Err(TxRuleError::MissingTxOutpoints));
+        consensus.set_status(spending_txs[3].id(), Err(TxRuleError::NoTxInputs));
+
+        let (tx, _rx) = unbounded_channel();
+        mining_manager.revalidate_high_priority_transactions(consensus.as_ref(), tx);
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.revalidated_valid_counts, 2);
+        assert_eq!(snapshot.revalidated_accepted_counts, 0);
+        assert_eq!(snapshot.revalidated_missing_outpoint_counts, 1);
+        assert_eq!(snapshot.revalidated_invalid_counts, 1);
+        assert_eq!(snapshot.revalidated_other_counts, 0);
+        assert_eq!(snapshot.revalidated_removed_counts(), 2);
+        assert_transaction_count(&mining_manager, 2, "after revalidation,");
+
+        // Counters accumulate across revalidations
+        let (tx, _rx) = unbounded_channel();
+        mining_manager.revalidate_high_priority_transactions(consensus.as_ref(), tx);
+        let delta = &counters.snapshot() - &snapshot;
+        assert_eq!(delta.revalidated_valid_counts, 2);
+        assert_eq!(delta.revalidated_removed_counts(), 0);
+    }
+
+    /// test_mempool_snapshot_round_trip verifies that a mempool snapshot can be exported and imported back,
+    /// and that entries whose outpoints no longer exist are skipped on import.
+    #[test]
+    fn test_mempool_snapshot_round_trip() {
+        const PAIR_COUNT: usize = 3;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+
+        let (parent_txs, child_txs) = create_arrays_of_parent_and_children_transactions(&consensus, PAIR_COUNT);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            parent_txs.iter(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            child_txs.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        // The funding transaction is never added to consensus, so this one is an orphan
+        let orphan_tx = create_transaction(&create_transaction_without_input(vec![700 * SOMPI_PER_KASPA]), 1_000);
+        mining_manager
+            .validate_and_insert_transaction(
+                consensus.as_ref(),
+                orphan_tx.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                RbfPolicy::Forbidden,
+            )
+            .unwrap();
+        assert_transaction_count(&mining_manager, 2 * PAIR_COUNT, "before export");
+
+        let mut buffer = vec![];
+        mining_manager.export_mempool_snapshot(&mut buffer).unwrap();
+
+        let entries = |manager: &MiningManager| {
+            let mut buffer = vec![];
+            manager.export_mempool_snapshot(&mut buffer).unwrap();
+            let snapshot: MempoolSnapshot = serde_json::from_slice(&buffer).unwrap();
+            let ids = |entries: Vec<MempoolSnapshotEntry>| {
+                entries.into_iter().map(|entry| (entry.transaction.id(), entry.priority)).sorted_by_key(|(id, _)| *id).collect_vec()
+            };
+            (ids(snapshot.transactions), ids(snapshot.orphans))
+        };
+        let (expected_transactions, expected_orphans) = entries(&mining_manager);
+        assert_eq!(expected_transactions.len(), 2 * PAIR_COUNT);
+        assert_eq!(expected_orphans, vec![(orphan_tx.id(), Priority::Low)]);
+
+        // Import into a fresh mempool backed by the same consensus state
+        let recovered_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+        let result = recovered_manager.import_mempool_snapshot(consensus.as_ref(), buffer.as_slice()).unwrap();
+        assert_eq!(result, MempoolSnapshotImport { imported: 2 * PAIR_COUNT + 1, skipped: 0 });
+        assert_eq!(entries(&recovered_manager), (expected_transactions, expected_orphans.clone()));
+
+        // Import into a fresh mempool backed by a consensus where the funding outpoints do not exist
+        let empty_consensus = Arc::new(ConsensusMock::new());
+        let stale_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let result = stale_manager.import_mempool_snapshot(empty_consensus.as_ref(), buffer.as_slice()).unwrap();
+        assert_eq!(result, MempoolSnapshotImport { imported: 1, skipped: 2 * PAIR_COUNT });
+        assert_eq!(entries(&stale_manager), (vec![], expected_orphans));
+
+        // Malformed input is reported as an error
+        assert!(stale_manager.import_mempool_snapshot(empty_consensus.as_ref(), &b"not a snapshot"[..]).is_err());
+    }
+
+    /// test_dependency_graph verifies the edges reported between chained mempool transactions and orphans.
+    #[test]
+    fn test_dependency_graph() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        // A chain of 3 transactions spending a funding transaction known by consensus
+        let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let parent_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let child_tx = create_transaction(&parent_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let grandchild_tx = create_transaction(&child_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let chain = [parent_tx.clone(), child_tx.clone(), grandchild_tx.clone()];
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            chain.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        // A chain of 2 orphans spending a funding transaction unknown to consensus
+        let orphan_tx = create_transaction(&create_transaction_without_input(vec![700 * SOMPI_PER_KASPA]), 1_000);
+        let orphan_child_tx = create_transaction(&orphan_tx, 1_000);
+        let orphans = [orphan_tx.clone(), orphan_child_tx.clone()];
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            orphans.iter(),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
+
+        let graph = |query| mining_manager.dependency_graph(query).into_iter().sorted().collect_vec();
+        let expected_transactions =
+            vec![(parent_tx.id(), vec![]), (child_tx.id(), vec![parent_tx.id()]), (grandchild_tx.id(), vec![child_tx.id()])];
+        let expected_orphans = vec![(orphan_tx.id(), vec![]), (orphan_child_tx.id(), vec![orphan_tx.id()])];
+
+        assert_eq!(graph(TransactionQuery::TransactionsOnly), expected_transactions.iter().cloned().sorted().collect_vec());
+        assert_eq!(graph(TransactionQuery::OrphansOnly), expected_orphans.iter().cloned().sorted().collect_vec());
+        assert_eq!(graph(TransactionQuery::All), expected_transactions.into_iter().chain(expected_orphans).sorted().collect_vec());
+    }
+
+    /// test_transaction_readiness verifies that only mempool transactions with no mempool ancestors are reported as ready
+    #[test]
+    fn test_transaction_readiness() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let (parent_tx, child_tx) = create_parent_and_children_transactions(&consensus, vec![500 * SOMPI_PER_KASPA]);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            [parent_tx.clone(), child_tx.clone()].iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::TransactionsOnly), 2);
+
+        assert!(mining_manager.is_transaction_ready(&parent_tx.id()));
+        assert!(!mining_manager.is_transaction_ready(&child_tx.id()));
+        assert!(!mining_manager.is_transaction_ready(&TransactionId::from_u64_word(42)));
+        assert_eq!(mining_manager.ready_transaction_count(), 1);
+
+        // Once the parent is included in a block, the child becomes ready
+        let block_transactions = build_block_transactions([&parent_tx].into_iter());
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_transactions);
+        assert!(result.is_ok());
+        assert!(!mining_manager.is_transaction_ready(&parent_tx.id()));
+        assert!(mining_manager.is_transaction_ready(&child_tx.id()));
+        assert_eq!(mining_manager.ready_transaction_count(), 1);
+    }
+
+    /// test_transaction_count_by_subnetwork verifies that the mempool transactions, orphans included, are counted by subnetwork
+    #[test]
+    fn test_transaction_count_by_subnetwork() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        assert!(mining_manager.transaction_count_by_subnetwork().is_empty());
+
+        let other_subnetwork = SubnetworkId::from_byte(5);
+        let with_subnetwork = |i: u32, subnetwork_id: &SubnetworkId| {
+            let mut mutable_tx = create_transaction_with_utxo_entry(i, 0);
+            let mut tx = mutable_tx.tx.as_ref().clone();
+            tx.subnetwork_id = subnetwork_id.clone();
+            tx.finalize();
+            mutable_tx.tx = Arc::new(tx);
+            mutable_tx
+        };
+        for i in 0..3 {
+            validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), with_subnetwork(i, &SUBNETWORK_ID_NATIVE))
+                .unwrap();
+        }
+        for i in 3..5 {
+            validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), with_subnetwork(i, &other_subnetwork))
+                .unwrap();
+        }
+
+        // An orphan is counted as well
+        let mut orphan = with_subnetwork(5, &other_subnetwork);
+        orphan.entries[0] = None;
+        validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), orphan).unwrap();
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::OrphansOnly), 1);
+
+        let counts = mining_manager.transaction_count_by_subnetwork();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&SUBNETWORK_ID_NATIVE], 3);
+        assert_eq!(counts[&other_subnetwork], 3);
+        assert_eq!(counts.values().sum::<usize>(), mining_manager.transaction_count(TransactionQuery::All));
+    }
+
+    /// test_block_template_attempts_limit verifies that building a block template which keeps failing on invalid
+    /// transactions switches to an infallible build once the attempts limit is reached
+    #[test]
+    fn test_block_template_attempts_limit() {
+        const ATTEMPTS_LIMIT: u64 = 3;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let config = Config::build_default(ForkedParam::new_const(TARGET_TIME_PER_BLOCK), false, MAX_BLOCK_MASS);
+
+        // The limit is capped by the number of attempts after which builds are infallible
+        let uncapped_config = config.clone().apply_block_template_attempts_limit(u64::MAX);
+        assert_eq!(uncapped_config.block_template_attempts_limit, config.maximum_build_block_template_attempts);
+
+        assert!(ATTEMPTS_LIMIT < config.maximum_build_block_template_attempts);
+        let config = config.apply_block_template_attempts_limit(ATTEMPTS_LIMIT);
+        let mining_manager = MiningManager::with_config(config, None, counters);
+        let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+        for i in 0..3 {
+            validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), create_transaction_with_utxo_entry(i, 0))
+                .unwrap();
+        }
+
+        // Standard builds failing indefinitely are bounded by the limit, the selected txs being removed on the first failure
+        consensus.fail_template_builds(u64::MAX);
+        let template = mining_manager.get_block_template(consensus.as_ref(), &miner_data).unwrap();
+        assert_eq!(template.block.transactions.len(), 1);
+        assert_eq!(consensus.template_build_count(), ATTEMPTS_LIMIT);
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::All), 0);
+
+        // Builds failing fewer times than the limit succeed in standard mode
+        mining_manager.clear_block_template();
+        validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), create_transaction_with_utxo_entry(3, 0))
+            .unwrap();
+        consensus.fail_template_builds(1);
+        let template = mining_manager.get_block_template(consensus.as_ref(), &miner_data).unwrap();
+        assert_eq!(template.block.transactions.len(), 1);
+        assert_eq!(consensus.template_build_count(), ATTEMPTS_LIMIT + 2);
+    }
+
+    /// test_validation_max_parallelism verifies that the configured bound on the validation parallelism
+    /// is requested from consensus for every validated batch
+    #[test]
+    fn test_validation_max_parallelism() {
+        for max_parallelism in [None, Some(2)] {
+            let consensus = Arc::new(ConsensusMock::new());
+            let counters = Arc::new(MiningCounters::default());
+            let config = Config::build_default(ForkedParam::new_const(TARGET_TIME_PER_BLOCK), false, MAX_BLOCK_MASS)
+                .apply_validation_max_parallelism(max_parallelism);
+            let mining_manager = MiningManager::with_config(config, None, counters);
+
+            let funding_txs = create_and_add_funding_transactions(&consensus, 4);
+            let transactions =
+                funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+            let results = mining_manager.validate_and_insert_transaction_batch(
+                consensus.as_ref(),
+                transactions,
+                Priority::Low,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+            assert!(results.iter().all(|result| result.is_ok()));
+            assert_eq!(consensus.validation_max_parallelism(), vec![max_parallelism]);
+        }
+    }
+
+    /// test_get_transactions_by_addresses_paged verifies that paging through the owner transactions yields
+    /// every transaction exactly once and in a stable order
+    #[test]
+    fn test_get_transactions_by_addresses_paged() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        const TX_COUNT: usize = 25;
+        let funding_txs = create_and_add_funding_transactions(&consensus, TX_COUNT);
+        let transactions = funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            transactions.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        let (script_public_key, _) = op_true_script();
+        let unrelated_script_public_key = ScriptPublicKey::new(0, scriptvec![0x51]);
+        let script_public_keys: ScriptPublicKeySet = [script_public_key.clone(), unrelated_script_public_key.clone()].into();
+        let full = mining_manager.get_transactions_by_addresses(&script_public_keys, TransactionQuery::All);
+        assert_eq!(full.transactions.len(), TX_COUNT);
+
+        const PAGE_SIZE: usize = 7;
+        let mut paged_ids = vec![];
+        let mut offset = 0;
+        loop {
+            let page =
+                mining_manager.get_transactions_by_addresses_paged(&script_public_keys, TransactionQuery::All, offset, PAGE_SIZE);
+            assert!(page.transactions.len() <= PAGE_SIZE);
+            assert!(page.owners[&unrelated_script_public_key].is_empty());
+            let owner = &page.owners[&script_public_key];
+            assert_eq!(owner.sending_txs.len(), page.transactions.len());
+            assert_eq!(owner.receiving_txs.len(), page.transactions.len());
+            if page.transactions.is_empty() {
+                break;
+            }
+            paged_ids.extend(page.transactions.keys().copied().sorted());
+            offset += page.transactions.len();
+        }
+
+        // No duplicates or gaps, and pages follow the transaction id order
+        assert_eq!(paged_ids, full.transactions.keys().copied().sorted().collect_vec());
+        assert!(mining_manager
+            .get_transactions_by_addresses_paged(&script_public_keys, TransactionQuery::OrphansOnly, 0, PAGE_SIZE)
+            .transactions
+            .is_empty());
+    }
+
+    /// test_classify_transactions verifies that transaction ids are partitioned exhaustively and disjointly
+    /// into accepted, transaction pool, orphan pool and unknown ids
+    #[test]
+    fn test_classify_transactions() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 4);
+        let transactions = funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            transactions.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        let orphan_tx = create_transaction(&create_transaction_without_input(vec![700 * SOMPI_PER_KASPA]), 1_000);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&orphan_tx),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
+
+        // Accept the first 2 transactions in a block
+        let block_transactions = build_block_transactions(transactions[..2].iter());
+        assert!(mining_manager
+            .handle_new_block_transactions(consensus.as_ref(), Hash::from_u64_word(2), 2, &block_transactions)
+            .is_ok());
+
+        let unknown_ids = vec![TransactionId::from_u64_word(1), TransactionId::from_u64_word(2)];
+        let queried =
+            transactions.iter().map(|tx| tx.id()).chain(once(orphan_tx.id())).chain(unknown_ids.iter().copied()).collect_vec();
+        let classification = mining_manager.classify_transactions(queried.clone());
+
+        assert_eq!(classification.accepted, vec![transactions[0].id(), transactions[1].id()]);
+        assert_eq!(classification.in_pool, vec![transactions[2].id(), transactions[3].id()]);
+        assert_eq!(classification.orphan, vec![orphan_tx.id()]);
+        assert_eq!(classification.unknown, unknown_ids);
+
+        // The partition is exhaustive and disjoint
+        assert_eq!(classification.len(), queried.len());
+        let all = classification
+            .accepted
+            .iter()
+            .chain(classification.in_pool.iter())
+            .chain(classification.orphan.iter())
+            .chain(classification.unknown.iter())
+            .copied()
+            .sorted()
+            .collect_vec();
+        assert_eq!(all, queried.into_iter().sorted().collect_vec());
+
+        // The classification agrees with the single purpose lookups
+        assert_eq!(
+            mining_manager.unknown_transactions(all.clone()).into_iter().sorted().collect_vec(),
+            unknown_ids.into_iter().sorted().collect_vec()
+        );
+        assert_eq!(mining_manager.unaccepted_transactions(all.clone()).len(), all.len() - classification.accepted.len());
+    }
+
+    /// test_cyclic_transaction_batch verifies that a batch containing transactions with cyclic dependencies
+    /// gets the cyclic ones rejected while the rest of the batch is still processed.
+    #[test]
+    fn test_cyclic_transaction_batch() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let (parent_txs, child_txs) = create_arrays_of_parent_and_children_transactions(&consensus, 1);
+
+        // A non-finalized transaction keeps the default id, so spending an output of the default id makes it depend on itself
+        let (script_public_key, _) = op_true_script();
+        let input = TransactionInput::new(TransactionOutpoint::new(Hash::default(), 0), vec![], MAX_TX_IN_SEQUENCE_NUM, 1);
+        let output = TransactionOutput::new(SOMPI_PER_KASPA, script_public_key);
+        let cyclic_tx = Transaction::new_non_finalized(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+
+        let batch = vec![child_txs[0].clone(), cyclic_tx, parent_txs[0].clone()];
+        let results = mining_manager.validate_and_insert_transaction_batch(
+            consensus.as_ref(),
+            batch,
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        assert_eq!(results.len(), 3, "every transaction of the batch should have a matching result");
+        let errors = results.into_iter().filter_map(|result| into_mempool_result(result).err()).collect_vec();
+        assert_eq!(errors, vec![RuleError::RejectCycleInMempoolTransactions], "only the cyclic transaction should be rejected");
+        assert_transaction_count(&mining_manager, 2, "after inserting a batch with a cyclic transaction,");
+    }
+
+    /// test_seeded_block_template_selection verifies that mining managers sharing a selector seed
+    /// select the very same transactions for their block templates.
+    #[test]
+    fn test_seeded_block_template_selection() {
+        const SEED: u64 = 7;
+        // A low block mass limit makes the selection require sampling with few transactions
+        const LOW_MAX_BLOCK_MASS: u64 = 20_000;
+        let consensus = Arc::new(ConsensusMock::new());
+        let managers = (0..2)
+            .map(|_| {
+                let counters = Arc::new(MiningCounters::default());
+                MiningManager::new(TARGET_TIME_PER_BLOCK, false, LOW_MAX_BLOCK_MASS, None, counters).with_selector_seed(SEED)
+            })
+            .collect_vec();
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 1_000);
+        let transactions = funding_txs.iter().enumerate().map(|(i, tx)| create_transaction(tx, 1_000 + i as u64 * 10)).collect_vec();
+        for manager in managers.iter() {
+            validate_and_insert_transactions(
+                manager,
+                consensus.as_ref(),
+                transactions.iter(),
+                Priority::Low,
+                Orphan::Forbidden,
+                RbfPolicy::Forbidden,
+            );
+        }
+
+        // Successive selections keep matching since both random sources advance in lockstep
+        for _ in 0..3 {
+            let selections = managers
+                .iter()
+                .map(|manager| manager.build_selector().select_transactions().into_iter().map(|tx| tx.id()).collect_vec())
+                .collect_vec();
+            assert!(selections[0].len() < transactions.len(), "the selection should be partial");
+            assert_eq!(selections[0], selections[1]);
+        }
+    }
+
+    // test_oversized_miner_data verifies that miner data exceeding the max coinbase payload length is rejected
+    // by the template builder, both when building a new template and when modifying a cached one.
+    #[test]
+    fn test_oversized_miner_data() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let valid_miner_data = get_miner_data(Prefix::Testnet);
+        let extra_len = MAX_COINBASE_PAYLOAD_LEN - valid_miner_data.coinbase_payload_len() + 1;
+        let oversized_miner_data = MinerData::new(valid_miner_data.script_public_key().clone(), vec![0u8; extra_len]);
+        let expected_len = MAX_COINBASE_PAYLOAD_LEN + 1;
+        assert_eq!(oversized_miner_data.coinbase_payload_len(), expected_len);
+
+        let assert_oversized = |result: MiningManagerResult<_>| match result {
+            Err(MiningManagerError::BlockTemplateBuilderError(BuilderError::CoinbaseError(CoinbaseError::PayloadLenAboveMax(
+                len,
+                max,
+            )))) => {
+                assert_eq!(len, expected_len);
+                assert_eq!(max, MAX_COINBASE_PAYLOAD_LEN);
+            }
+            Err(err) => panic!("expected a payload length error, got {err}"),
+            Ok(_) => panic!("expected a payload length error, got a block template"),
+        };
+
+        // Building a new template
+        assert_oversized(mining_manager.get_block_template(consensus.as_ref(), &oversized_miner_data));
+
+        // Modifying a cached template
+        mining_manager.get_block_template(consensus.as_ref(), &valid_miner_data).unwrap();
+        assert_oversized(mining_manager.get_block_template(consensus.as_ref(), &oversized_miner_data));
+    }
+
     /// test_modify_block_template verifies that modifying a block template changes coinbase data correctly.
     #[test]
     fn test_modify_block_template() {
@@ -1175,6 +1886,57 @@ mod tests {
         assert!(validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), too_big_tx.clone()).is_err());
     }
 
+    #[test]
+    fn test_estimate_fee_for_transaction() {
+        // Use a small block mass so that a few dozen transactions overflow the block capacity and compete over inclusion
+        const SMALL_BLOCK_MASS: u64 = 2_000;
+        const TX_COUNT: usize = 50;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let config = Config::build_default(ForkedParam::new_const(TARGET_TIME_PER_BLOCK), false, SMALL_BLOCK_MASS);
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        let tx = create_transaction_with_utxo_entry(TX_COUNT as u32, 0);
+        let mass = consensus.calculate_transaction_mass(&tx).unwrap();
+        let targets = [1.0, 10.0, 60.0, 3600.0];
+
+        // With an empty mempool, every target is estimated with the minimum relay fee
+        for target in targets {
+            assert_eq!(mining_manager.estimate_fee_for_transaction(consensus.as_ref(), &tx, target).unwrap(), mass);
+        }
+
+        for i in 0..TX_COUNT {
+            validate_and_insert_mutable_transaction(
+                &mining_manager,
+                consensus.as_ref(),
+                create_transaction_with_utxo_entry(i as u32, 0),
+            )
+            .unwrap();
+        }
+
+        // The estimated fee rises as the target time shrinks, and is never below the minimum relay fee
+        let fees = targets.map(|target| mining_manager.estimate_fee_for_transaction(consensus.as_ref(), &tx, target).unwrap());
+        assert!(fees.iter().all(|&fee| fee >= mass), "{fees:?}");
+        for (shorter, longer) in fees.iter().tuple_windows() {
+            assert!(shorter > longer, "{fees:?}");
+        }
+
+        // A transaction which can never fit into a block is rejected rather than estimated
+        let oversized_tx = {
+            let mut oversized_tx = create_transaction_with_utxo_entry(TX_COUNT as u32 + 1, 0);
+            let mut inner_tx = (*(oversized_tx.tx)).clone();
+            inner_tx.payload = vec![0u8; SMALL_BLOCK_MASS as usize];
+            oversized_tx.tx = inner_tx.into();
+            oversized_tx
+        };
+        let result = mining_manager.estimate_fee_for_transaction(consensus.as_ref(), &oversized_tx, targets[0]);
+        let Err(MiningManagerError::MempoolError(RuleError::RejectOversizedTransaction(id, _, max_mass))) = result else {
+            panic!("an oversized transaction should be rejected, got {result:?}");
+        };
+        assert_eq!(id, oversized_tx.id());
+        assert_eq!(max_mass, SMALL_BLOCK_MASS);
+    }
+
     fn validate_and_insert_mutable_transaction(
         mining_manager: &MiningManager,
         consensus: &dyn ConsensusApi,
@@ -162,6 +162,23 @@ impl Mempool {
         }
     }
 
+    /// dust_threshold returns the minimal value a spendable output with a script public key
+    /// of `script_len` bytes must hold in order not to be considered dust.
+    ///
+    /// This is the inverse of the check performed by [`Self::is_transaction_output_dust`]:
+    /// value * 1000 / (3 * total_serialized_size) >= minimum_relay_transaction_fee
+    pub(crate) fn dust_threshold(&self, script_len: usize) -> u64 {
+        // See is_transaction_output_dust for the breakdown of the 148 bytes p2pk input size
+        let total_serialized_size = 8 + 2 + 8 + script_len as u128 + 148;
+        let min_cost = 3 * total_serialized_size * self.config.minimum_relay_transaction_fee as u128;
+        min_cost.div_ceil(1000).try_into().unwrap_or(u64::MAX)
+    }
+
+    /// outputs_dust_mask returns, for each of the passed outputs, whether or not it is considered dust
+    pub(crate) fn outputs_dust_mask(&self, outputs: &[TransactionOutput]) -> Vec<bool> {
+        outputs.iter().map(|output| self.is_transaction_output_dust(output)).collect()
+    }
+
     /// check_transaction_standard_in_context performs a series of checks on a transaction's
     /// inputs to ensure they are "standard". A standard transaction input within the
     /// context of this function is one whose referenced public key script is of a
@@ -212,7 +229,7 @@ impl Mempool {
 
     /// minimum_required_transaction_relay_fee returns the minimum transaction fee required
     /// for a transaction with the passed mass to be accepted into the mempool and relayed.
-    fn minimum_required_transaction_relay_fee(&self, mass: u64) -> u64 {
+    pub(crate) fn minimum_required_transaction_relay_fee(&self, mass: u64) -> u64 {
         // Calculate the minimum fee for a transaction to be allowed into the
         // mempool and relayed by scaling the base fee. MinimumRelayTransactionFee is in
         // sompi/kg so multiply by mass (which is in grams) and divide by 1000 to get
@@ -394,6 +411,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dust_threshold() {
+        // A pay-to-pubkey script: OP_DATA_32 <32 bytes pubkey> OP_CHECKSIG
+        let script_public_key = ScriptPublicKey::new(0, [vec![0x20], vec![0x01; 32], vec![0xac]].concat().into());
+        let unspendable_script_public_key = ScriptPublicKey::new(0, smallvec![0x01]);
+        for net in NetworkType::iter() {
+            let params: Params = net.into();
+            let config = Config::build_default(params.target_time_per_block(), false, params.max_block_mass);
+            let mempool = Mempool::new(Arc::new(config), Arc::new(MiningCounters::default()));
+
+            let threshold = mempool.dust_threshold(script_public_key.script().len());
+            assert_eq!(threshold, 600);
+            let below = TransactionOutput::new(threshold - 1, script_public_key.clone());
+            let at = TransactionOutput::new(threshold, script_public_key.clone());
+            let above = TransactionOutput::new(threshold + 1, script_public_key.clone());
+            assert!(mempool.is_transaction_output_dust(&below));
+            assert!(!mempool.is_transaction_output_dust(&at));
+            assert!(!mempool.is_transaction_output_dust(&above));
+
+            // The threshold must agree with the single output check for any script length
+            for script_len in [0, 1, 34, 35, 100, 10_000] {
+                let threshold = mempool.dust_threshold(script_len);
+                let script_public_key = ScriptPublicKey::new(0, vec![0x51; script_len].into());
+                assert!(
+                    threshold == 0
+                        || mempool.is_transaction_output_dust(&TransactionOutput::new(threshold - 1, script_public_key.clone()))
+                );
+                assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(threshold, script_public_key)));
+            }
+
+            let outputs = vec![below, at, TransactionOutput::new(5000, unspendable_script_public_key), above];
+            assert_eq!(mempool.outputs_dust_mask(&outputs), vec![true, false, true, false]);
+            assert!(mempool.outputs_dust_mask(&[]).is_empty());
+        }
+    }
+
     #[test]
     fn test_check_transaction_standard_in_isolation() {
         // Create some dummy, but otherwise standard, data for transactions.
@@ -1,8 +1,11 @@
+use crate::block_template::selector::ALPHA;
 use kaspa_consensus_core::{config::params::ForkedParam, constants::TX_VERSION};
+use thiserror::Error;
 
 pub(crate) const DEFAULT_MAXIMUM_TRANSACTION_COUNT: usize = 1_000_000;
 pub(crate) const DEFAULT_MEMPOOL_SIZE_LIMIT: usize = 1_000_000_000;
 pub(crate) const DEFAULT_MAXIMUM_BUILD_BLOCK_TEMPLATE_ATTEMPTS: u64 = 5;
+pub(crate) const DEFAULT_BLOCK_TEMPLATE_ATTEMPTS_LIMIT: u64 = DEFAULT_MAXIMUM_BUILD_BLOCK_TEMPLATE_ATTEMPTS;
 
 pub(crate) const DEFAULT_TRANSACTION_EXPIRE_INTERVAL_SECONDS: u64 = 24 * 60 * 60;
 pub(crate) const DEFAULT_TRANSACTION_EXPIRE_SCAN_INTERVAL_SECONDS: u64 = 60;
@@ -14,6 +17,11 @@ pub(crate) const DEFAULT_ORPHAN_EXPIRE_SCAN_INTERVAL_SECONDS: u64 = 10;
 pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_MASS: u64 = 100_000;
 pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT: u64 = 500;
 
+pub(crate) const DEFAULT_VALIDATION_CHUNK_MASS_MULTIPLIER: u64 = 1;
+
+/// The minimum feerate weighting exponent supported by the feerate estimator math, which requires `alpha > 1`
+pub(crate) const MINIMUM_FEERATE_ALPHA: i32 = 2;
+
 /// DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE specifies the minimum transaction fee for a transaction to be accepted to
 /// the mempool and relayed. It is specified in sompi per 1kg (or 1000 grams) of transaction mass.
 pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
@@ -25,11 +33,24 @@ pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
 pub(crate) const DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 pub(crate) const DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("feerate alpha {0} is invalid, the feerate estimator requires alpha > 1")]
+    InvalidFeerateAlpha(i32),
+}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub maximum_transaction_count: usize,
     pub mempool_size_limit: usize,
     pub maximum_build_block_template_attempts: u64,
+    /// The total number of attempts allowed for building a block template. The last allowed attempt is infallible, so
+    /// a value below `maximum_build_block_template_attempts` lowers the number of standard attempts, while greater values
+    /// would have no effect since consensus never fails an infallible build on invalid transactions. Hence the limit is
+    /// capped by `maximum_build_block_template_attempts`
+    pub block_template_attempts_limit: u64,
     pub transaction_expire_interval_daa_score: ForkedParam<u64>,
     pub transaction_expire_scan_interval_daa_score: ForkedParam<u64>,
     pub transaction_expire_scan_interval_milliseconds: u64,
@@ -46,6 +67,16 @@ pub struct Config {
     pub minimum_standard_transaction_version: u16,
     pub maximum_standard_transaction_version: u16,
     pub network_blocks_per_second: ForkedParam<u64>,
+    /// The exponent used for weighting ready transactions by feerate (`weight = feerate^alpha`) when
+    /// sampling block templates and estimating feerates. Must be greater than 1, lower values are rejected by the
+    /// config constructors.
+    pub feerate_alpha: i32,
+    /// The mass of the transaction chunks validated in parallel, expressed as a multiple of `maximum_mass_per_block`.
+    /// Larger chunks amortize the locking overhead at the cost of holding the virtual processor for longer.
+    pub validation_chunk_mass_multiplier: u64,
+    /// Optional max number of consensus threads concurrently validating a chunk of transactions, leaving the remaining
+    /// threads available for block processing under a transaction flood. If `None`, all consensus threads may be used
+    pub validation_max_parallelism: Option<usize>,
 }
 
 impl Config {
@@ -54,6 +85,7 @@ impl Config {
         maximum_transaction_count: usize,
         mempool_size_limit: usize,
         maximum_build_block_template_attempts: u64,
+        block_template_attempts_limit: u64,
         transaction_expire_interval_daa_score: ForkedParam<u64>,
         transaction_expire_scan_interval_daa_score: ForkedParam<u64>,
         transaction_expire_scan_interval_milliseconds: u64,
@@ -70,11 +102,15 @@ impl Config {
         minimum_standard_transaction_version: u16,
         maximum_standard_transaction_version: u16,
         network_blocks_per_second: ForkedParam<u64>,
-    ) -> Self {
-        Self {
+        feerate_alpha: i32,
+        validation_chunk_mass_multiplier: u64,
+        validation_max_parallelism: Option<usize>,
+    ) -> ConfigResult<Self> {
+        Ok(Self {
             maximum_transaction_count,
             mempool_size_limit,
             maximum_build_block_template_attempts,
+            block_template_attempts_limit: block_template_attempts_limit.min(maximum_build_block_template_attempts).max(1),
             transaction_expire_interval_daa_score,
             transaction_expire_scan_interval_daa_score,
             transaction_expire_scan_interval_milliseconds,
@@ -91,7 +127,10 @@ impl Config {
             minimum_standard_transaction_version,
             maximum_standard_transaction_version,
             network_blocks_per_second,
-        }
+            feerate_alpha: validate_feerate_alpha(feerate_alpha)?,
+            validation_chunk_mass_multiplier,
+            validation_max_parallelism,
+        })
     }
 
     /// Build a default config.
@@ -105,6 +144,7 @@ impl Config {
             maximum_transaction_count: DEFAULT_MAXIMUM_TRANSACTION_COUNT,
             mempool_size_limit: DEFAULT_MEMPOOL_SIZE_LIMIT,
             maximum_build_block_template_attempts: DEFAULT_MAXIMUM_BUILD_BLOCK_TEMPLATE_ATTEMPTS,
+            block_template_attempts_limit: DEFAULT_BLOCK_TEMPLATE_ATTEMPTS_LIMIT,
             transaction_expire_interval_daa_score: target_milliseconds_per_block
                 .map(|v| DEFAULT_TRANSACTION_EXPIRE_INTERVAL_SECONDS * 1000 / v),
             transaction_expire_scan_interval_daa_score: target_milliseconds_per_block
@@ -126,6 +166,9 @@ impl Config {
             minimum_standard_transaction_version: DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION,
             maximum_standard_transaction_version: DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION,
             network_blocks_per_second: target_milliseconds_per_block.map(|v| 1000 / v),
+            feerate_alpha: ALPHA,
+            validation_chunk_mass_multiplier: DEFAULT_VALIDATION_CHUNK_MASS_MULTIPLIER,
+            validation_max_parallelism: None,
         }
     }
 
@@ -136,9 +179,58 @@ impl Config {
         self
     }
 
+    pub fn apply_block_template_attempts_limit(mut self, limit: u64) -> Self {
+        self.block_template_attempts_limit = limit.min(self.maximum_build_block_template_attempts).max(1);
+        self
+    }
+
+    pub fn apply_feerate_alpha(mut self, alpha: i32) -> ConfigResult<Self> {
+        self.feerate_alpha = validate_feerate_alpha(alpha)?;
+        Ok(self)
+    }
+
+    pub fn apply_validation_chunk_mass_multiplier(mut self, multiplier: u64) -> Self {
+        self.validation_chunk_mass_multiplier = multiplier.max(1);
+        self
+    }
+
+    pub fn apply_validation_max_parallelism(mut self, max_parallelism: Option<usize>) -> Self {
+        self.validation_max_parallelism = max_parallelism.map(|n| n.max(1));
+        self
+    }
+
+    /// Returns the maximum mass of a chunk of transactions validated in parallel
+    pub(crate) fn validation_chunk_mass(&self) -> u64 {
+        self.maximum_mass_per_block.saturating_mul(self.validation_chunk_mass_multiplier.max(1))
+    }
+
     /// Returns the minimum standard fee/mass ratio currently required by the mempool
     pub(crate) fn minimum_feerate(&self) -> f64 {
         // The parameter minimum_relay_transaction_fee is in sompi/kg units so divide by 1000 to get sompi/gram
         self.minimum_relay_transaction_fee as f64 / 1000.0
     }
 }
+
+fn validate_feerate_alpha(alpha: i32) -> ConfigResult<i32> {
+    if alpha < MINIMUM_FEERATE_ALPHA {
+        return Err(ConfigError::InvalidFeerateAlpha(alpha));
+    }
+    Ok(alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feerate_alpha_validation() {
+        let config = Config::build_default(ForkedParam::new_const(1000), false, 500_000);
+        assert_eq!(config.feerate_alpha, ALPHA);
+        for alpha in [-1, 0, 1] {
+            assert_eq!(config.clone().apply_feerate_alpha(alpha).unwrap_err(), ConfigError::InvalidFeerateAlpha(alpha));
+        }
+        for alpha in [2, 4] {
+            assert_eq!(config.clone().apply_feerate_alpha(alpha).unwrap().feerate_alpha, alpha);
+        }
+    }
+}
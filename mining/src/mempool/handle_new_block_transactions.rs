@@ -1,10 +1,13 @@
-use crate::mempool::{
-    errors::RuleResult,
-    model::{
-        pool::Pool,
-        tx::{MempoolTransaction, TxRemovalReason},
+use crate::{
+    mempool::{
+        errors::RuleResult,
+        model::{
+            pool::Pool,
+            tx::{MempoolTransaction, TxRemovalReason},
+        },
+        Mempool,
     },
-    Mempool,
+    model::TransactionIdSet,
 };
 use kaspa_consensus_core::{
     api::ConsensusApi,
@@ -14,10 +17,16 @@ use kaspa_core::time::Stopwatch;
 use std::{collections::HashSet, sync::atomic::Ordering};
 
 impl Mempool {
+    /// Removes from the mempool the block transactions which were accepted into the virtual state, along with their
+    /// double spends. If `accepted_transaction_ids` is `None`, all block transactions are assumed to be accepted.
+    ///
+    /// Block transactions which were not accepted (for instance, because the block is red) are kept in the mempool
+    /// so they remain available for future block templates.
     pub(crate) fn handle_new_block_transactions(
         &mut self,
         block_daa_score: u64,
         block_transactions: &[Transaction],
+        accepted_transaction_ids: Option<&TransactionIdSet>,
     ) -> RuleResult<Vec<MempoolTransaction>> {
         let _sw = Stopwatch::<400>::with_threshold("handle_new_block_transactions op");
         let mut unorphaned_transactions = vec![];
@@ -26,6 +35,9 @@ impl Mempool {
         let mut output_counts = 0;
         for transaction in block_transactions[1..].iter() {
             let transaction_id = transaction.id();
+            if accepted_transaction_ids.is_some_and(|ids| !ids.contains(&transaction_id)) {
+                continue;
+            }
             // Rust rewrite: This behavior does differ from golang implementation.
             // If the transaction got accepted via a peer but is still an orphan here, do not remove
             // its redeemers in the orphan pool. We give those a chance to be unorphaned and included
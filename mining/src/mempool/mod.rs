@@ -1,8 +1,12 @@
 use crate::{
     feerate::{FeerateEstimator, FeerateEstimatorArgs},
     model::{
+        address_subscriptions::TouchedScriptPublicKeys,
+        mempool_snapshot::MempoolSnapshot,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
+        tx_classification::TxClassification,
         tx_query::TransactionQuery,
+        TransactionIdSet,
     },
     MiningCounters,
 };
@@ -14,10 +18,13 @@ use self::{
 };
 use kaspa_consensus_core::{
     block::TemplateTransactionSelector,
-    tx::{MutableTransaction, TransactionId},
+    config::params::ForkedParam,
+    subnets::SubnetworkId,
+    tx::{MutableTransaction, Transaction, TransactionId},
 };
 use kaspa_core::time::Stopwatch;
-use std::sync::Arc;
+use rand::Rng;
+use std::{collections::HashMap, sync::Arc};
 
 pub(crate) mod check_transaction_standard;
 pub mod config;
@@ -61,6 +68,31 @@ impl Mempool {
         Self { config, transaction_pool, orphan_pool, accepted_transactions, counters }
     }
 
+    /// Returns, for each accepted transaction of a block, the script public keys of its outputs and of the
+    /// UTXO entries it spends, when those are known by the transaction pool
+    pub(crate) fn touched_script_public_keys(
+        &self,
+        block_transactions: &[Transaction],
+        accepted_transaction_ids: Option<&TransactionIdSet>,
+    ) -> Vec<TouchedScriptPublicKeys> {
+        block_transactions[1..]
+            .iter()
+            .map(|transaction| (transaction.id(), transaction))
+            .filter(|(transaction_id, _)| accepted_transaction_ids.is_none_or(|ids| ids.contains(transaction_id)))
+            .map(|(transaction_id, transaction)| {
+                let spent = self.transaction_pool.get(&transaction_id).into_iter().flat_map(|tx| tx.mtx.entries.iter().flatten());
+                let script_public_keys = transaction
+                    .outputs
+                    .iter()
+                    .map(|output| &output.script_public_key)
+                    .chain(spent.map(|entry| &entry.script_public_key))
+                    .cloned()
+                    .collect();
+                (transaction_id, script_public_keys)
+            })
+            .collect()
+    }
+
     pub(crate) fn get_transaction(&self, transaction_id: &TransactionId, query: TransactionQuery) -> Option<MutableTransaction> {
         let mut transaction = None;
         if query.include_transaction_pool() {
@@ -72,6 +104,21 @@ impl Mempool {
         transaction.map(|x| x.mtx.clone())
     }
 
+    /// Replaces the config of the mempool and of all its pools. The new config applies to subsequent operations only.
+    fn set_config(&mut self, config: Config) {
+        let config = Arc::new(config);
+        self.transaction_pool.set_config(config.clone());
+        self.orphan_pool.set_config(config.clone());
+        self.accepted_transactions.set_config(config.clone());
+        self.config = config;
+    }
+
+    pub(crate) fn set_transaction_expire_interval_daa_score(&mut self, transaction_expire_interval_daa_score: u64) {
+        let mut config = self.config.as_ref().clone();
+        config.transaction_expire_interval_daa_score = ForkedParam::new_const(transaction_expire_interval_daa_score);
+        self.set_config(config);
+    }
+
     pub(crate) fn has_transaction(&self, transaction_id: &TransactionId, query: TransactionQuery) -> bool {
         (query.include_transaction_pool() && self.transaction_pool.has(transaction_id))
             || (query.include_orphan_pool() && self.orphan_pool.has(transaction_id))
@@ -83,6 +130,28 @@ impl Mempool {
         (transactions, orphans)
     }
 
+    pub(crate) fn build_snapshot(&self) -> MempoolSnapshot {
+        MempoolSnapshot {
+            transactions: self.transaction_pool.all().values().map(Into::into).collect(),
+            orphans: self.orphan_pool.all().values().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns, for each transaction of the queried pools, the ids of its parents found in the queried pools
+    pub(crate) fn dependency_graph(&self, query: TransactionQuery) -> Vec<(TransactionId, Vec<TransactionId>)> {
+        let pools = self.queried_pools(query).collect::<Vec<_>>();
+        pools
+            .iter()
+            .flat_map(|pool| pool.all().values())
+            .map(|transaction| {
+                let mut parents =
+                    pools.iter().flat_map(|pool| pool.get_parent_transaction_ids_in_pool(&transaction.mtx)).collect::<Vec<_>>();
+                parents.sort();
+                (transaction.id(), parents)
+            })
+            .collect()
+    }
+
     pub(crate) fn get_all_transaction_ids(&self, query: TransactionQuery) -> (Vec<TransactionId>, Vec<TransactionId>) {
         let transactions = if query.include_transaction_pool() { self.transaction_pool.get_all_transaction_ids() } else { vec![] };
         let orphans = if query.include_orphan_pool() { self.orphan_pool.get_all_transaction_ids() } else { vec![] };
@@ -104,6 +173,31 @@ impl Mempool {
         owner_set
     }
 
+    fn queried_pools(&self, query: TransactionQuery) -> impl Iterator<Item = &dyn Pool> {
+        let transactions = query.include_transaction_pool().then_some(&self.transaction_pool as &dyn Pool).into_iter();
+        let orphans = query.include_orphan_pool().then_some(&self.orphan_pool as &dyn Pool).into_iter();
+        transactions.chain(orphans)
+    }
+
+    pub(crate) fn is_transaction_owned_by_any(
+        &self,
+        transaction_id: &TransactionId,
+        script_public_keys: &ScriptPublicKeySet,
+        query: TransactionQuery,
+    ) -> bool {
+        self.queried_pools(query).any(|pool| pool.is_owned_by_any(transaction_id, script_public_keys))
+    }
+
+    pub(crate) fn fill_owner_set_transaction(
+        &self,
+        transaction_id: &TransactionId,
+        script_public_keys: &ScriptPublicKeySet,
+        query: TransactionQuery,
+        owner_set: &mut GroupedOwnerTransactions,
+    ) {
+        self.queried_pools(query).for_each(|pool| pool.fill_owner_set_transaction(transaction_id, script_public_keys, owner_set));
+    }
+
     pub(crate) fn transaction_count(&self, query: TransactionQuery) -> usize {
         let mut count = 0;
         if query.include_transaction_pool() {
@@ -115,6 +209,19 @@ impl Mempool {
         count
     }
 
+    /// Returns the number of transactions of the queried pools per subnetwork
+    pub(crate) fn transaction_count_by_subnetwork(&self, query: TransactionQuery) -> HashMap<SubnetworkId, usize> {
+        let mut counts = HashMap::new();
+        for transaction in self.queried_pools(query).flat_map(|pool| pool.all().values()) {
+            *counts.entry(transaction.mtx.tx.subnetwork_id.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    pub(crate) fn is_transaction_ready(&self, transaction_id: &TransactionId) -> bool {
+        self.transaction_pool.is_transaction_ready(transaction_id)
+    }
+
     pub(crate) fn ready_transaction_count(&self) -> usize {
         self.transaction_pool.ready_transaction_count()
     }
@@ -124,9 +231,9 @@ impl Mempool {
     }
 
     /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
-    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
+    pub(crate) fn build_selector<R: Rng + ?Sized>(&self, rng: &mut R) -> Box<dyn TemplateTransactionSelector> {
         let _sw = Stopwatch::<10>::with_threshold("build_selector op");
-        self.transaction_pool.build_selector()
+        self.transaction_pool.build_selector(rng)
     }
 
     /// Builds a feerate estimator based on internal state of the ready transactions frontier
@@ -158,6 +265,23 @@ impl Mempool {
         self.accepted_transactions.unaccepted(&mut not_in_pools_txs)
     }
 
+    pub(crate) fn classify_transactions(&self, transactions: Vec<TransactionId>) -> TxClassification {
+        let mut classification = TxClassification::default();
+        for transaction_id in transactions {
+            let category = if self.transaction_pool.has(&transaction_id) {
+                &mut classification.in_pool
+            } else if self.orphan_pool.has(&transaction_id) {
+                &mut classification.orphan
+            } else if self.accepted_transactions.has(&transaction_id) {
+                &mut classification.accepted
+            } else {
+                &mut classification.unknown
+            };
+            category.push(transaction_id);
+        }
+        classification
+    }
+
     #[cfg(test)]
     pub(crate) fn get_estimated_size(&self) -> usize {
         self.transaction_pool.get_estimated_size()
@@ -165,7 +289,9 @@ impl Mempool {
 }
 
 pub mod tx {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
     pub enum Priority {
         Low,
         High,
@@ -1,5 +1,7 @@
 use crate::{
+    block_template::selector::ALPHA,
     feerate::{FeerateEstimator, FeerateEstimatorArgs},
+    mempool::model::tx::MempoolTransaction,
     model::candidate_tx::CandidateTransaction,
     Policy, RebalancingWeightedTransactionSelector,
 };
@@ -7,7 +9,7 @@ use crate::{
 use feerate_key::FeerateTransactionKey;
 use kaspa_consensus_core::{block::TemplateTransactionSelector, tx::Transaction};
 use kaspa_core::trace;
-use rand::{distributions::Uniform, prelude::Distribution, Rng};
+use rand::{distributions::Uniform, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
 use search_tree::SearchTree;
 use selectors::{SequenceSelector, SequenceSelectorInput, TakeAllSelector};
 use std::{collections::HashSet, iter::FusedIterator, sync::Arc};
@@ -31,6 +33,11 @@ const INITIAL_AVG_MASS: f64 = 2036.0;
 /// Decay factor of average mass weighting.
 const AVG_MASS_DECAY_FACTOR: f64 = 0.99999;
 
+/// Relative tolerance when comparing the tree-maintained total weight with a sequential recomputation,
+/// since the two sum the same weights in different orders
+#[cfg(test)]
+const WEIGHT_CONSISTENCY_TOLERANCE: f64 = 1e-9;
+
 /// Management of the transaction pool frontier, that is, the set of transactions in
 /// the transaction pool which have no mempool ancestors and are essentially ready
 /// to enter the next block template.
@@ -43,15 +50,31 @@ pub struct Frontier {
 
     /// Tracks the average transaction mass throughout the mempool's lifespan using a decayed weighting mechanism
     average_transaction_mass: f64,
+
+    /// The sampling exponent used for computing key weights, i.e., `weight = feerate^alpha`
+    alpha: i32,
 }
 
 impl Default for Frontier {
     fn default() -> Self {
-        Self { search_tree: Default::default(), total_mass: Default::default(), average_transaction_mass: INITIAL_AVG_MASS }
+        Self::new(ALPHA)
     }
 }
 
 impl Frontier {
+    pub fn new(alpha: i32) -> Self {
+        Self { search_tree: Default::default(), total_mass: Default::default(), average_transaction_mass: INITIAL_AVG_MASS, alpha }
+    }
+
+    pub fn alpha(&self) -> i32 {
+        self.alpha
+    }
+
+    /// Builds a frontier key for `tx` weighted according to the frontier sampling exponent
+    pub(crate) fn build_key(&self, tx: &MempoolTransaction) -> FeerateTransactionKey {
+        FeerateTransactionKey::from_mempool_transaction(tx, self.alpha)
+    }
+
     pub fn total_weight(&self) -> f64 {
         self.search_tree.total_weight()
     }
@@ -68,6 +91,10 @@ impl Frontier {
         self.len() == 0
     }
 
+    pub fn contains(&self, key: &FeerateTransactionKey) -> bool {
+        self.search_tree.contains(key)
+    }
+
     pub fn insert(&mut self, key: FeerateTransactionKey) -> bool {
         let mass = key.mass;
         if self.search_tree.insert(key) {
@@ -93,6 +120,58 @@ impl Frontier {
         }
     }
 
+    /// Inserts a batch of keys, accumulating the total mass delta once for the whole batch.
+    /// Keys already in the frontier are skipped. Returns the number of keys actually inserted.
+    pub fn insert_many(&mut self, keys: impl IntoIterator<Item = FeerateTransactionKey>) -> usize {
+        let mut inserted = 0;
+        let mut mass_delta = 0;
+        for key in keys {
+            let mass = key.mass;
+            if self.search_tree.insert(key) {
+                inserted += 1;
+                mass_delta += mass;
+                // The decaying average is order dependent, hence it is updated per inserted key (see `insert`)
+                self.average_transaction_mass =
+                    self.average_transaction_mass * AVG_MASS_DECAY_FACTOR + mass as f64 * (1.0 - AVG_MASS_DECAY_FACTOR);
+            }
+        }
+        self.total_mass += mass_delta;
+        #[cfg(test)]
+        self.assert_consistency();
+        inserted
+    }
+
+    /// Removes a batch of keys, accumulating the total mass delta once for the whole batch.
+    /// Keys missing from the frontier are skipped. Returns the number of keys actually removed.
+    pub fn remove_many<'a>(&mut self, keys: impl IntoIterator<Item = &'a FeerateTransactionKey>) -> usize {
+        let mut removed = 0;
+        let mut mass_delta = 0;
+        for key in keys {
+            if self.search_tree.remove(key) {
+                removed += 1;
+                mass_delta += key.mass;
+            }
+        }
+        self.total_mass -= mass_delta;
+        #[cfg(test)]
+        self.assert_consistency();
+        removed
+    }
+
+    /// Recomputes the total mass, total weight and key count from the frontier keys and asserts they
+    /// match the incrementally maintained totals. Linear in the frontier size, hence tests only.
+    #[cfg(test)]
+    pub fn assert_consistency(&self) {
+        let (mass, weight) = self.search_tree.ascending_iter().fold((0u64, 0f64), |(m, w), k| (m + k.mass, w + k.weight()));
+        assert_eq!(self.total_mass, mass, "frontier total mass drifted from the sum of key masses");
+        assert_eq!(self.search_tree.total_count(), self.len(), "frontier subtree counts drifted from the number of keys");
+        let total_weight = self.total_weight();
+        assert!(
+            (total_weight - weight).abs() <= WEIGHT_CONSISTENCY_TOLERANCE * total_weight.abs().max(weight.abs()),
+            "frontier total weight {total_weight} drifted from the sum of key weights {weight}"
+        );
+    }
+
     /// Samples the frontier in-place based on the provided policy and returns a SequenceSelector.
     ///
     /// This sampling algorithm should be used when frontier total mass is high enough compared to
@@ -190,16 +269,28 @@ impl Frontier {
     /// full transaction selection in less than 150 µs even if the frontier has 1M entries (!!). See mining/benches
     /// for more details.  
     pub fn build_selector(&self, policy: &Policy) -> Box<dyn TemplateTransactionSelector> {
+        self.build_selector_with_rng(policy, &mut rand::thread_rng())
+    }
+
+    /// Same as [`Self::build_selector`], drawing all the randomness, including the one used later on by the
+    /// returned selector, from `rng`. A seeded `rng` thus makes the selection fully reproducible.
+    pub fn build_selector_with_rng<R>(&self, policy: &Policy, rng: &mut R) -> Box<dyn TemplateTransactionSelector>
+    where
+        R: Rng + ?Sized,
+    {
         if self.total_mass <= policy.max_block_mass {
             Box::new(TakeAllSelector::new(self.search_tree.ascending_iter().map(|k| k.tx.clone()).collect()))
         } else if self.total_mass > policy.max_block_mass * COLLISION_FACTOR {
-            let mut rng = rand::thread_rng();
-            Box::new(SequenceSelector::new(self.sample_inplace(&mut rng, policy, &mut 0), policy.clone()))
+            Box::new(SequenceSelector::new(self.sample_inplace(rng, policy, &mut 0), policy.clone()))
         } else {
-            Box::new(RebalancingWeightedTransactionSelector::new(
-                policy.clone(),
-                self.search_tree.ascending_iter().cloned().map(CandidateTransaction::from_key).collect(),
-            ))
+            Box::new(
+                RebalancingWeightedTransactionSelector::with_alpha(
+                    policy.clone(),
+                    self.search_tree.ascending_iter().cloned().map(CandidateTransaction::from_key).collect(),
+                    self.alpha,
+                )
+                .with_rng(StdRng::from_rng(rng).expect("rng never fails")),
+            )
         }
     }
 
@@ -217,9 +308,10 @@ impl Frontier {
 
     /// Exposed for benchmarking purposes
     pub fn build_rebalancing_selector(&self) -> Box<dyn TemplateTransactionSelector> {
-        Box::new(RebalancingWeightedTransactionSelector::new(
+        Box::new(RebalancingWeightedTransactionSelector::with_alpha(
             Policy::new(500_000),
             self.search_tree.ascending_iter().cloned().map(CandidateTransaction::from_key).collect(),
+            self.alpha,
         ))
     }
 
@@ -229,7 +321,7 @@ impl Frontier {
         let bps = args.network_blocks_per_second as f64;
         let mut mass_per_block = args.maximum_mass_per_block as f64;
         let mut inclusion_interval = average_transaction_mass / (mass_per_block * bps);
-        let mut estimator = FeerateEstimator::new(self.total_weight(), inclusion_interval);
+        let mut estimator = FeerateEstimator::with_alpha(self.total_weight(), inclusion_interval, self.alpha);
 
         // Search for better estimators by possibly removing extremely high outliers
         let mut down_iter = self.search_tree.descending_iter().peekable();
@@ -250,7 +342,7 @@ impl Frontier {
 
             // Compute the weight up to, and excluding, current key (which translates to zero weight if peek() is none)
             let prefix_weight = down_iter.peek().map(|key| self.search_tree.prefix_weight(key)).unwrap_or_default();
-            let pending_estimator = FeerateEstimator::new(prefix_weight, inclusion_interval);
+            let pending_estimator = FeerateEstimator::with_alpha(prefix_weight, inclusion_interval, self.alpha);
 
             // Test the pending estimator vs. the current one
             if pending_estimator.feerate_to_time(1.0) < estimator.feerate_to_time(1.0) {
@@ -264,6 +356,18 @@ impl Frontier {
         estimator
     }
 
+    /// Returns the frontier key with the `k`-th highest feerate (with `k = 0` being the highest) in log(n) time
+    pub fn nth_by_feerate(&self, k: usize) -> Option<FeerateTransactionKey> {
+        let index = self.len().checked_sub(k + 1)?;
+        self.search_tree.select(index)
+    }
+
+    /// Returns the position of `key` in decreasing feerate order (with `0` being the highest feerate),
+    /// or `None` if the key is not in the frontier. Runs in log(n) time.
+    pub fn rank_of(&self, key: &FeerateTransactionKey) -> Option<usize> {
+        self.search_tree.contains(key).then(|| self.len() - self.search_tree.prefix_count(key))
+    }
+
     /// Returns an iterator to the transactions in the frontier in increasing feerate order
     pub fn ascending_iter(&self) -> impl DoubleEndedIterator<Item = &Arc<Transaction>> + ExactSizeIterator + FusedIterator {
         self.search_tree.ascending_iter().map(|key| &key.tx)
@@ -273,7 +377,7 @@ impl Frontier {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use feerate_key::tests::build_feerate_key;
+    use feerate_key::tests::{build_feerate_key, build_feerate_key_with_alpha};
     use itertools::Itertools;
     use rand::thread_rng;
     use std::collections::HashMap;
@@ -380,6 +484,159 @@ mod tests {
         assert_eq!(frontier.total_mass(), frontier.search_tree.ascending_iter().map(|k| k.mass).sum::<u64>());
     }
 
+    #[test]
+    pub fn test_batch_vs_individual_operations() {
+        let mut rng = thread_rng();
+        let cap = 2000;
+        let keys = (0..cap as u64).map(|i| build_feerate_key(rng.gen_range(1..100000), rng.gen_range(1..100000), i)).collect_vec();
+
+        let mut individual = Frontier::default();
+        let mut batch = Frontier::default();
+
+        // Insert all keys, including some duplicates
+        let to_insert = keys.iter().chain(keys.iter().take(cap / 4)).cloned().collect_vec();
+        let inserted = to_insert.iter().cloned().filter(|k| individual.insert(k.clone())).count();
+        assert_eq!(cap, inserted);
+        assert_eq!(inserted, batch.insert_many(to_insert));
+
+        // Remove half of the keys along with keys which are not in the frontier
+        let missing = (cap as u64..cap as u64 + 100).map(|i| build_feerate_key(1000, 1000, i)).collect_vec();
+        let to_remove = keys.iter().step_by(2).chain(missing.iter()).collect_vec();
+        let removed = to_remove.iter().filter(|k| individual.remove(k)).count();
+        assert_eq!(cap / 2, removed);
+        assert_eq!(removed, batch.remove_many(to_remove));
+
+        assert_eq!(individual.len(), batch.len());
+        assert_eq!(individual.total_mass(), batch.total_mass());
+        assert_eq!(batch.total_mass(), batch.search_tree.ascending_iter().map(|k| k.mass).sum::<u64>());
+        assert_eq!(individual.total_weight(), batch.total_weight());
+        assert_eq!(individual.average_transaction_mass, batch.average_transaction_mass);
+        assert!(individual.ascending_iter().zip(batch.ascending_iter()).all(|(a, b)| a.id() == b.id()));
+    }
+
+    #[test]
+    pub fn test_consistency_under_random_updates() {
+        let mut rng = thread_rng();
+        let cap = 3000;
+        let keys = (0..cap as u64).map(|i| build_feerate_key(rng.gen_range(1..1_000_000), rng.gen_range(1..100000), i)).collect_vec();
+        let mut frontier = Frontier::default();
+        frontier.assert_consistency();
+
+        // Interleave random single and batch inserts/removes, including duplicate insertions and missing removals
+        for round in 0..20 {
+            for _ in 0..200 {
+                let key = &keys[rng.gen_range(0..cap)];
+                if rng.gen_bool(0.6) {
+                    frontier.insert(key.clone());
+                } else {
+                    frontier.remove(key);
+                }
+            }
+            let batch = (0..100).map(|_| keys[rng.gen_range(0..cap)].clone()).collect_vec();
+            if round % 2 == 0 {
+                frontier.insert_many(batch);
+            } else {
+                frontier.remove_many(batch.iter());
+            }
+            frontier.assert_consistency();
+        }
+
+        // Drain the frontier completely and make sure the totals return to zero
+        let remaining = frontier.search_tree.ascending_iter().cloned().collect_vec();
+        for key in remaining.iter() {
+            assert!(frontier.remove(key));
+        }
+        frontier.assert_consistency();
+        assert_eq!(0, frontier.total_mass());
+        assert!(frontier.is_empty());
+    }
+
+    #[test]
+    pub fn test_order_statistics() {
+        let mut rng = thread_rng();
+        let cap = 500;
+        let keys = (0..cap as u64).map(|i| build_feerate_key(rng.gen_range(1..100000), rng.gen_range(1..100000), i)).collect_vec();
+        let mut frontier = Frontier::default();
+        assert!(frontier.nth_by_feerate(0).is_none());
+        assert_eq!(cap, frontier.insert_many(keys.iter().cloned()));
+
+        let check = |frontier: &Frontier| {
+            let descending = frontier.search_tree.descending_iter().cloned().collect_vec();
+            for (k, key) in descending.iter().enumerate() {
+                assert_eq!(Some(key), frontier.nth_by_feerate(k).as_ref());
+                assert_eq!(Some(k), frontier.rank_of(key));
+            }
+            assert!(frontier.nth_by_feerate(descending.len()).is_none());
+        };
+        check(&frontier);
+
+        // Positions are updated as keys are removed
+        frontier.remove_many(keys.iter().step_by(3));
+        check(&frontier);
+        for key in keys.iter().step_by(3) {
+            assert!(frontier.rank_of(key).is_none());
+        }
+
+        // The highest feerate key is at position 0
+        let top = build_feerate_key(1_000_000_000, 1000, cap as u64);
+        frontier.insert(top.clone());
+        assert_eq!(Some(0), frontier.rank_of(&top));
+        assert_eq!(Some(top), frontier.nth_by_feerate(0));
+        check(&frontier);
+    }
+
+    #[test]
+    pub fn test_seeded_selection_is_reproducible() {
+        let mut rng = thread_rng();
+        let cap = 1000;
+        let mass: u64 = 1000;
+        let mut frontier = Frontier::default();
+        frontier.insert_many((0..cap as u64).map(|i| build_feerate_key(rng.gen_range(1..100000), mass, i)));
+
+        // Cover all selector kinds: in-place sampling, rebalancing selection and take-all
+        for max_block_mass in [100_000, 500_000, 2_000_000] {
+            let policy = Policy::new(max_block_mass);
+            let select = |seed: u64| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut selector = frontier.build_selector_with_rng(&policy, &mut rng);
+                selector.select_transactions().into_iter().map(|tx| tx.id()).collect_vec()
+            };
+            let selected = select(42);
+            assert!(!selected.is_empty());
+            assert_eq!(selected, select(42), "selection with max block mass {max_block_mass} should be reproducible");
+        }
+    }
+
+    #[test]
+    pub fn test_sampling_distribution_by_alpha() {
+        const SAMPLES: usize = 10_000;
+        let mut rng = thread_rng();
+        let cap = 1000;
+        let mass: u64 = 1000;
+
+        // Returns the fraction of samples hitting the high feerate half of the frontier
+        let mut sample_high_fraction = |alpha: i32| {
+            let mut frontier = Frontier::new(alpha);
+            for i in 0..cap as u64 {
+                // Half of the txs have feerate 1, the other half have feerate 2
+                let fee = if i % 2 == 0 { mass } else { 2 * mass };
+                frontier.insert(build_feerate_key_with_alpha(fee, mass, alpha, i)).then_some(()).unwrap();
+            }
+            let distr = Uniform::new(0f64, frontier.total_weight());
+            let high = (0..SAMPLES).filter(|_| frontier.search_tree.search(distr.sample(&mut rng)).fee == 2 * mass).count();
+            high as f64 / SAMPLES as f64
+        };
+
+        // Expected fractions are 2^alpha / (1 + 2^alpha), i.e., 0.8, ~0.889 and ~0.941
+        let quadratic = sample_high_fraction(2);
+        let cubic = sample_high_fraction(3);
+        let quartic = sample_high_fraction(4);
+        assert!((quadratic - 4.0 / 5.0).abs() < 0.02, "{quadratic}");
+        assert!((cubic - 8.0 / 9.0).abs() < 0.02, "{cubic}");
+        assert!((quartic - 16.0 / 17.0).abs() < 0.02, "{quartic}");
+        assert!(quadratic < cubic && cubic < quartic);
+    }
+
     /// Epsilon used for various test comparisons
     const EPS: f64 = 0.000001;
 
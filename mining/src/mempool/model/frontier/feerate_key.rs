@@ -20,10 +20,26 @@ impl PartialEq for FeerateTransactionKey {
 
 impl FeerateTransactionKey {
     pub fn new(fee: u64, mass: u64, tx: Arc<Transaction>) -> Self {
+        Self::with_alpha(fee, mass, ALPHA, tx)
+    }
+
+    /// Builds a key whose sampling weight is `feerate^alpha`
+    pub fn with_alpha(fee: u64, mass: u64, alpha: i32, tx: Arc<Transaction>) -> Self {
         // NOTE: any change to the way this weight is calculated (such as scaling by some factor)
         // requires a reversed update to total_weight in `Frontier::build_feerate_estimator`. This
         // is because the math methods in FeeEstimator assume this specific weight function.
-        Self { fee, mass, weight: (fee as f64 / mass as f64).powi(ALPHA), tx }
+        Self { fee, mass, weight: (fee as f64 / mass as f64).powi(alpha), tx }
+    }
+
+    pub(crate) fn from_mempool_transaction(tx: &MempoolTransaction, alpha: i32) -> Self {
+        // NOTE: The code below is a mempool simplification reducing the various block mass units to a
+        //       single one-dimension value (making it easier to select transactions for block templates).
+        // Future mempool improvements are expected to refine this behavior and use the multi-dimension values
+        // in order to optimize and increase block space usage.
+        let mass = ContextualMasses::new(tx.mtx.tx.mass())
+            .max(tx.mtx.calculated_non_contextual_masses.expect("masses are expected to be calculated"));
+        let fee = tx.mtx.calculated_fee.expect("fee is expected to be populated");
+        Self::with_alpha(fee, mass, alpha, tx.mtx.tx.clone())
     }
 
     pub fn feerate(&self) -> f64 {
@@ -75,19 +91,6 @@ impl Ord for FeerateTransactionKey {
     }
 }
 
-impl From<&MempoolTransaction> for FeerateTransactionKey {
-    fn from(tx: &MempoolTransaction) -> Self {
-        // NOTE: The code below is a mempool simplification reducing the various block mass units to a
-        //       single one-dimension value (making it easier to select transactions for block templates).
-        // Future mempool improvements are expected to refine this behavior and use the multi-dimension values
-        // in order to optimize and increase block space usage.
-        let mass = ContextualMasses::new(tx.mtx.tx.mass())
-            .max(tx.mtx.calculated_non_contextual_masses.expect("masses are expected to be calculated"));
-        let fee = tx.mtx.calculated_fee.expect("fee is expected to be populated");
-        Self::new(fee, mass, tx.mtx.tx.clone())
-    }
-}
-
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -109,4 +112,9 @@ pub(crate) mod tests {
     pub(crate) fn build_feerate_key(fee: u64, mass: u64, id: u64) -> FeerateTransactionKey {
         FeerateTransactionKey::new(fee, mass, generate_unique_tx(id))
     }
+
+    /// Test helper for generating a feerate key with a unique tx (per u64 id) and a custom weight exponent
+    pub(crate) fn build_feerate_key_with_alpha(fee: u64, mass: u64, alpha: i32, id: u64) -> FeerateTransactionKey {
+        FeerateTransactionKey::with_alpha(fee, mass, alpha, generate_unique_tx(id))
+    }
 }
@@ -16,24 +16,37 @@ type FeerateKey = FeerateTransactionKey;
 /// For instance if the query point is `123.56` and the top 3 subtrees have weights `120, 10.5 ,100` then we
 /// recursively query the middle subtree with the point `123.56 - 120 = 3.56`.
 ///
+/// Subtree key counts are maintained alongside the weights, which similarly allows order-statistic
+/// queries (select by position and rank of a key) in log time.
+///
 /// See SearchArgument implementation below for more details.
 #[derive(Clone, Copy, Debug, Default)]
-struct FeerateWeight(f64);
+struct FeerateWeight {
+    weight: f64,
+    count: usize,
+}
 
 impl FeerateWeight {
     /// Returns the weight value
     pub fn weight(&self) -> f64 {
-        self.0
+        self.weight
+    }
+
+    /// Returns the number of keys
+    pub fn count(&self) -> usize {
+        self.count
     }
 }
 
 impl Argument<FeerateKey> for FeerateWeight {
     fn from_leaf(keys: &[FeerateKey]) -> Self {
-        Self(keys.iter().map(|k| k.weight()).sum())
+        // Sum the weights cached within the keys (rather than recomputing `feerate^alpha`), which keeps
+        // the argument consistent with the exponent each key was built with
+        Self { weight: keys.iter().map(|k| k.weight()).sum(), count: keys.len() }
     }
 
     fn from_inner(_keys: &[FeerateKey], arguments: &[Self]) -> Self {
-        Self(arguments.iter().map(|a| a.0).sum())
+        Self { weight: arguments.iter().map(|a| a.weight).sum(), count: arguments.iter().map(|a| a.count).sum() }
     }
 }
 
@@ -62,8 +75,8 @@ impl SearchArgument<FeerateKey> for FeerateWeight {
         // Search algorithm: Locate the next subtree to visit by iterating through `arguments`
         // and subtracting the query until the correct range is found
         for (i, a) in arguments.iter().enumerate() {
-            if query >= a.0 {
-                query -= a.0;
+            if query >= a.weight {
+                query -= a.weight;
             } else {
                 return Some((i, query));
             }
@@ -74,25 +87,25 @@ impl SearchArgument<FeerateKey> for FeerateWeight {
         // last leaf (see locate_in_leaf as well)
         match arguments.len() {
             0 => None,
-            n => Some((n - 1, arguments[n - 1].0)),
+            n => Some((n - 1, arguments[n - 1].weight)),
         }
     }
 }
 
-/// Visitor struct which accumulates the prefix weight up to a provided key (inclusive) in log time.
+/// Visitor struct which accumulates the prefix weight and key count up to a provided key (inclusive) in log time.
 ///
-/// The basic idea is to use the subtree weights stored in the tree for walking down from the root
-/// to the leaf (corresponding to the searched key), and accumulating all weights proceeding the walk-down path
-struct PrefixWeightVisitor<'a> {
+/// The basic idea is to use the subtree arguments stored in the tree for walking down from the root
+/// to the leaf (corresponding to the searched key), and accumulating all arguments proceeding the walk-down path
+struct PrefixVisitor<'a> {
     /// The key to search up to
     key: &'a FeerateKey,
-    /// This field accumulates the prefix weight during the visit process
-    accumulated_weight: f64,
+    /// This field accumulates the prefix weight and count during the visit process
+    accumulated: FeerateWeight,
 }
 
-impl<'a> PrefixWeightVisitor<'a> {
+impl<'a> PrefixVisitor<'a> {
     pub fn new(key: &'a FeerateKey) -> Self {
-        Self { key, accumulated_weight: Default::default() }
+        Self { key, accumulated: Default::default() }
     }
 
     /// Returns the index of the first `key ∈ keys` such that `key > self.key`. If no such key
@@ -111,8 +124,8 @@ impl<'a> PrefixWeightVisitor<'a> {
     }
 }
 
-impl DescendVisit<FeerateKey, (), FeerateWeight> for PrefixWeightVisitor<'_> {
-    type Result = f64;
+impl DescendVisit<FeerateKey, (), FeerateWeight> for PrefixVisitor<'_> {
+    type Result = FeerateWeight;
 
     fn visit_inner(&mut self, keys: &[FeerateKey], arguments: &[FeerateWeight]) -> DescendVisitResult<Self::Result> {
         let idx = self.search_in_keys(keys);
@@ -120,9 +133,10 @@ impl DescendVisit<FeerateKey, (), FeerateWeight> for PrefixWeightVisitor<'_> {
         //      a. arguments.len() == keys.len() + 1 (n inner node keys are the separators between n+1 subtrees)
         //      b. idx <= keys.len() (hence idx < arguments.len())
 
-        // Based on the invariants, we first accumulate all the subtree weights up to idx
+        // Based on the invariants, we first accumulate all the subtree arguments up to idx
         for argument in arguments.iter().take(idx) {
-            self.accumulated_weight += argument.weight();
+            self.accumulated.weight += argument.weight();
+            self.accumulated.count += argument.count();
         }
 
         // ..and then go down to the idx'th subtree
@@ -134,10 +148,36 @@ impl DescendVisit<FeerateKey, (), FeerateWeight> for PrefixWeightVisitor<'_> {
         let idx = self.search_in_keys(keys);
         // Accumulate all key weights up to idx (which is inclusive if self.key ∈ tree)
         for key in keys.iter().take(idx) {
-            self.accumulated_weight += key.weight();
+            self.accumulated.weight += key.weight();
         }
+        self.accumulated.count += idx;
         // ..and return the final result
-        Some(self.accumulated_weight)
+        Some(self.accumulated)
+    }
+}
+
+/// Visitor struct which selects the key at a provided position (by ascending key order) in log time,
+/// by walking down the subtrees according to their key counts
+struct SelectVisitor {
+    /// The position of the searched key, relative to the currently visited subtree
+    index: usize,
+}
+
+impl DescendVisit<FeerateKey, (), FeerateWeight> for SelectVisitor {
+    type Result = FeerateKey;
+
+    fn visit_inner(&mut self, _keys: &[FeerateKey], arguments: &[FeerateWeight]) -> DescendVisitResult<Self::Result> {
+        for (i, argument) in arguments.iter().enumerate() {
+            if self.index < argument.count() {
+                return DescendVisitResult::GoDown(i);
+            }
+            self.index -= argument.count();
+        }
+        DescendVisitResult::Cancel
+    }
+
+    fn visit_leaf(&mut self, keys: &[FeerateKey], _values: &[()]) -> Option<Self::Result> {
+        keys.get(self.index).cloned()
     }
 }
 
@@ -155,6 +195,7 @@ type InnerTree = BPlusTree<NodeStoreVec<FeerateKey, (), FeerateWeight>>;
 ///        according to key order, in log(n) time
 ///     5. Access the total weight in O(1) time. The total weight has numerical stability since it
 ///        is recomputed from subtree weights for each item insertion/removal
+///     6. Select a key by its position and compute the prefix count of a key (i.e., its rank) in log(n) time
 ///
 /// Computing the prefix weight is a crucial operation if the tree is used for random sampling and
 /// the tree is highly imbalanced in terms of weight variance.
@@ -203,10 +244,36 @@ impl SearchTree {
         self.tree.root_argument().weight()
     }
 
+    /// Access the key count maintained within subtree arguments in O(1) time
+    #[cfg(debug_assertions)]
+    pub fn total_count(&self) -> usize {
+        self.tree.root_argument().count()
+    }
+
     /// Computes the prefix weight of a key, i.e., the sum of weights up to that key (inclusive)
     /// according to key order, in log(n) time
     pub fn prefix_weight(&self, key: &FeerateKey) -> f64 {
-        self.tree.descend_visit(PrefixWeightVisitor::new(key)).unwrap()
+        self.tree.descend_visit(PrefixVisitor::new(key)).unwrap().weight()
+    }
+
+    /// Computes the prefix count of a key, i.e., the number of keys up to that key (inclusive)
+    /// according to key order, in log(n) time
+    pub fn prefix_count(&self, key: &FeerateKey) -> usize {
+        self.tree.descend_visit(PrefixVisitor::new(key)).unwrap().count()
+    }
+
+    /// Returns whether the key is in the tree, in log(n) time
+    pub fn contains(&self, key: &FeerateKey) -> bool {
+        self.tree.get(key).is_some()
+    }
+
+    /// Selects the key at position `index` according to ascending key order in log(n) time.
+    /// Returns `None` if `index` is out of bounds.
+    pub fn select(&self, index: usize) -> Option<FeerateKey> {
+        if index >= self.len() {
+            return None;
+        }
+        self.tree.descend_visit(SelectVisitor { index })
     }
 
     /// Iterate the tree in descending key order (going down from the
@@ -315,6 +382,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_total_weight_consistency() {
+        let mut tree = SearchTree::new();
+        let mass = 2000;
+        let fees = vec![[123, 113, 10_000, 1000, 2050, 2048]; 64 * (64 + 1)].into_iter().flatten().collect_vec();
+        let keys = fees.iter().copied().enumerate().map(|(i, fee)| build_feerate_key(fee, mass, i as u64)).collect_vec();
+
+        let assert_consistent = |tree: &SearchTree| {
+            let expected: f64 = tree.ascending_iter().map(|k| k.weight()).sum();
+            let eps = expected * 1e-9;
+            assert!((expected - tree.total_weight()).abs() <= eps, "{expected}, {}", tree.total_weight());
+        };
+
+        // Interleave a series of inserts and removes, checking consistency along the way
+        for (i, key) in keys.iter().cloned().enumerate() {
+            tree.insert(key);
+            if i % 3 == 0 {
+                tree.remove(&keys[i / 2]);
+            }
+            if i % 1000 == 0 {
+                assert_consistent(&tree);
+            }
+        }
+        assert_consistent(&tree);
+
+        for key in keys.iter().step_by(2) {
+            tree.remove(key);
+        }
+        assert_consistent(&tree);
+
+        for key in keys.iter() {
+            tree.remove(key);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(0.0, tree.total_weight());
+    }
+
+    #[test]
+    fn test_order_statistic_queries() {
+        let mut tree = SearchTree::new();
+        let mass = 2000;
+        // Make sure the tree has intermediate nodes (see test_feerate_weight_queries)
+        let fees = vec![[123, 113, 10_000, 1000, 2050, 2048]; 64 * (64 + 1)].into_iter().flatten().collect_vec();
+        let keys = fees.iter().copied().enumerate().map(|(i, fee)| build_feerate_key(fee, mass, i as u64)).collect_vec();
+        for key in keys.iter().cloned() {
+            tree.insert(key);
+        }
+        for key in keys.iter().step_by(5) {
+            tree.remove(key);
+        }
+
+        let v = tree.ascending_iter().cloned().collect_vec();
+        for (i, key) in v.iter().enumerate() {
+            assert_eq!(Some(key), tree.select(i).as_ref());
+            assert_eq!(i + 1, tree.prefix_count(key));
+            assert!(tree.contains(key));
+        }
+        assert!(tree.select(v.len()).is_none());
+
+        // Removed keys are not contained but still have a well defined prefix count
+        for key in keys.iter().step_by(5) {
+            assert!(!tree.contains(key));
+            assert_eq!(v.partition_point(|k| k < key), tree.prefix_count(key));
+        }
+    }
+
     #[test]
     fn test_tree_rev_iter() {
         let mut tree = SearchTree::new();
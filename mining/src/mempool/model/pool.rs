@@ -126,6 +126,41 @@ pub(crate) trait Pool {
             });
         });
     }
+
+    /// Returns whether the transaction is in the pool and is either sending from or receiving to any of `script_public_keys`
+    fn is_owned_by_any(&self, transaction_id: &TransactionId, script_public_keys: &ScriptPublicKeySet) -> bool {
+        self.get(transaction_id).is_some_and(|transaction| {
+            transaction.mtx.entries.iter().flatten().any(|entry| script_public_keys.contains(&entry.script_public_key))
+                || transaction.mtx.tx.outputs.iter().any(|output| script_public_keys.contains(&output.script_public_key))
+        })
+    }
+
+    /// Adds the transaction to `owner_set` for each of `script_public_keys` it is sending from or receiving to
+    fn fill_owner_set_transaction(
+        &self,
+        transaction_id: &TransactionId,
+        script_public_keys: &ScriptPublicKeySet,
+        owner_set: &mut GroupedOwnerTransactions,
+    ) {
+        let Some(transaction) = self.get(transaction_id) else {
+            return;
+        };
+        script_public_keys.iter().for_each(|script_public_key| {
+            let owner = owner_set.owners.entry(script_public_key.clone()).or_default();
+
+            // Sending transactions
+            if transaction.mtx.entries.iter().flatten().any(|entry| entry.script_public_key == *script_public_key) {
+                owner_set.transactions.entry(*transaction_id).or_insert_with(|| transaction.mtx.clone());
+                owner.sending_txs.insert(*transaction_id);
+            }
+
+            // Receiving transactions
+            if transaction.mtx.tx.outputs.iter().any(|output| output.script_public_key == *script_public_key) {
+                owner_set.transactions.entry(*transaction_id).or_insert_with(|| transaction.mtx.clone());
+                owner.receiving_txs.insert(*transaction_id);
+            }
+        });
+    }
 }
 
 pub(crate) struct PoolIndex {
@@ -14,11 +14,13 @@ use crate::{
     model::{topological_index::TopologicalIndex, TransactionIdSet},
     Policy,
 };
+use itertools::Itertools;
 use kaspa_consensus_core::{
     block::TemplateTransactionSelector,
     tx::{MutableTransaction, TransactionId, TransactionOutpoint},
 };
 use kaspa_core::{debug, time::unix_now, trace};
+use rand::Rng;
 use std::{
     collections::{hash_map::Keys, hash_set::Iter},
     iter::once,
@@ -82,11 +84,11 @@ pub(crate) struct TransactionsPool {
 impl TransactionsPool {
     pub(crate) fn new(config: Arc<Config>) -> Self {
         Self {
+            ready_transactions: Frontier::new(config.feerate_alpha),
             config,
             all_transactions: MempoolTransactionCollection::default(),
             parent_transactions: TransactionsEdges::default(),
             chained_transactions: TransactionsEdges::default(),
-            ready_transactions: Default::default(),
             last_expire_scan_daa_score: 0,
             last_expire_scan_time: unix_now(),
             utxo_set: MempoolUtxoSet::new(),
@@ -94,6 +96,10 @@ impl TransactionsPool {
         }
     }
 
+    pub(crate) fn set_config(&mut self, config: Arc<Config>) {
+        self.config = config;
+    }
+
     /// Add a mutable transaction to the pool
     pub(crate) fn add_transaction(
         &mut self,
@@ -122,7 +128,7 @@ impl TransactionsPool {
         let parents = self.get_parent_transaction_ids_in_pool(&transaction.mtx);
         self.parent_transactions.insert(id, parents.clone());
         if parents.is_empty() {
-            self.ready_transactions.insert((&transaction).into());
+            self.ready_transactions.insert(self.ready_transactions.build_key(&transaction));
         }
         for parent_id in parents {
             let entry = self.chained_transactions.entry(parent_id).or_default();
@@ -138,6 +144,55 @@ impl TransactionsPool {
 
     /// Fully removes the transaction from all relational sets, as well as from the UTXO set
     pub(crate) fn remove_transaction(&mut self, transaction_id: &TransactionId) -> RuleResult<MempoolTransaction> {
+        let mut newly_ready = vec![];
+        let removed_tx = self.detach_transaction(transaction_id, &mut newly_ready)?;
+        for id in newly_ready {
+            let tx = self.all_transactions.get(&id).unwrap();
+            self.ready_transactions.insert(self.ready_transactions.build_key(tx));
+        }
+        self.ready_transactions.remove(&self.ready_transactions.build_key(&removed_tx));
+        Ok(removed_tx)
+    }
+
+    /// Fully removes a batch of transactions from all relational sets, as well as from the UTXO set, updating the
+    /// ready transactions frontier once for the whole batch. Returns the removed transactions in removal order.
+    ///
+    /// On error, the transactions removed up to that point remain removed and the frontier is kept consistent with them
+    pub(crate) fn remove_transactions(&mut self, transaction_ids: &[TransactionId]) -> RuleResult<Vec<MempoolTransaction>> {
+        let mut removed = Vec::with_capacity(transaction_ids.len());
+        let mut newly_ready = vec![];
+        let mut result = Ok(());
+        for transaction_id in transaction_ids {
+            match self.detach_transaction(transaction_id, &mut newly_ready) {
+                Ok(tx) => removed.push(tx),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        let removed_keys = removed.iter().map(|tx| self.ready_transactions.build_key(tx)).collect_vec();
+        self.ready_transactions.remove_many(removed_keys.iter());
+        // Transactions which became ready and were then removed later in the batch are skipped
+        let ready_keys = newly_ready
+            .iter()
+            .filter_map(|id| self.all_transactions.get(id))
+            .map(|tx| self.ready_transactions.build_key(tx))
+            .collect_vec();
+        self.ready_transactions.insert_many(ready_keys);
+
+        result.map(|_| removed)
+    }
+
+    /// Removes the transaction from all relational sets and from the UTXO set, leaving the ready transactions frontier
+    /// to the caller: the removed transaction should be removed from it, and the transactions collected into
+    /// `newly_ready` (whose last parent in the pool was the removed transaction) should be inserted into it
+    fn detach_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+        newly_ready: &mut Vec<TransactionId>,
+    ) -> RuleResult<MempoolTransaction> {
         // Remove all bijective parent/chained relations
         if let Some(parents) = self.parent_transactions.get(transaction_id) {
             for parent in parents.iter() {
@@ -151,8 +206,7 @@ impl TransactionsPool {
                 if let Some(parents) = self.parent_transactions.get_mut(chain) {
                     parents.remove(transaction_id);
                     if parents.is_empty() {
-                        let tx = self.all_transactions.get(chain).unwrap();
-                        self.ready_transactions.insert(tx.into());
+                        newly_ready.push(*chain);
                     }
                 }
             }
@@ -163,8 +217,6 @@ impl TransactionsPool {
         // Remove the transaction itself
         let removed_tx = self.all_transactions.remove(transaction_id).ok_or(RuleError::RejectMissingTransaction(*transaction_id))?;
 
-        self.ready_transactions.remove(&(&removed_tx).into());
-
         // TODO: consider using `self.parent_transactions.get(transaction_id)`
         // The tradeoff to consider is whether it might be possible that a parent tx exists in the pool
         // however its relation as parent is not registered. This can supposedly happen in rare cases where
@@ -194,6 +246,11 @@ impl TransactionsPool {
         }
     }
 
+    /// Returns whether the transaction is in the pool and has no mempool ancestors, i.e., is part of the ready frontier
+    pub(crate) fn is_transaction_ready(&self, transaction_id: &TransactionId) -> bool {
+        self.get(transaction_id).is_some_and(|tx| self.ready_transactions.contains(&self.ready_transactions.build_key(tx)))
+    }
+
     pub(crate) fn ready_transaction_count(&self) -> usize {
         self.ready_transactions.len()
     }
@@ -203,8 +260,8 @@ impl TransactionsPool {
     }
 
     /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
-    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
-        self.ready_transactions.build_selector(&Policy::new(self.config.maximum_mass_per_block))
+    pub(crate) fn build_selector<R: Rng + ?Sized>(&self, rng: &mut R) -> Box<dyn TemplateTransactionSelector> {
+        self.ready_transactions.build_selector_with_rng(&Policy::new(self.config.maximum_mass_per_block), rng)
     }
 
     /// Builds a feerate estimator based on internal state of the ready transactions frontier
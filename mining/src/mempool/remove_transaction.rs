@@ -30,9 +30,8 @@ impl Mempool {
         }
 
         let mut removed_orphans: Vec<TransactionId> = vec![];
-        for tx_id in removed_transactions.iter() {
-            // Remove the tx from the transaction pool and the UTXO set (handled within the pool)
-            let tx = self.transaction_pool.remove_transaction(tx_id)?;
+        // Remove the txs from the transaction pool and the UTXO set (handled within the pool)
+        for tx in self.transaction_pool.remove_transactions(&removed_transactions)? {
             // Update/remove descendent orphan txs (depending on `remove_redeemers`)
             let txs = self.orphan_pool.update_orphans_after_transaction_removed(&tx, remove_redeemers)?;
             removed_orphans.extend(txs.into_iter().map(|x| x.id()));
@@ -0,0 +1,56 @@
+use super::owner_txs::ScriptPublicKeySet;
+use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionId};
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// The script public keys touched by an accepted transaction, i.e., the keys of its outputs and,
+/// when known, of the UTXO entries it spends
+pub(crate) type TouchedScriptPublicKeys = (TransactionId, Vec<ScriptPublicKey>);
+
+struct AddressSubscription {
+    script_public_keys: ScriptPublicKeySet,
+    sender: UnboundedSender<Vec<TransactionId>>,
+}
+
+/// Subscriptions to accepted transactions touching a set of addresses.
+///
+/// A subscription lives as long as its receiver. Subscriptions with a dropped receiver are
+/// removed on the next notification or subscription.
+#[derive(Default)]
+pub(crate) struct AddressSubscriptions {
+    subscriptions: Mutex<Vec<AddressSubscription>>,
+}
+
+impl AddressSubscriptions {
+    pub(crate) fn subscribe(&self, script_public_keys: ScriptPublicKeySet) -> UnboundedReceiver<Vec<TransactionId>> {
+        let (sender, receiver) = unbounded_channel();
+        let mut subscriptions = self.subscriptions.lock();
+        subscriptions.retain(|subscription| !subscription.sender.is_closed());
+        subscriptions.push(AddressSubscription { script_public_keys, sender });
+        receiver
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.subscriptions.lock().is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.subscriptions.lock().len()
+    }
+
+    /// Sends to each subscriber the ids of the transactions touching any of its addresses, if any
+    pub(crate) fn notify(&self, touched: &[TouchedScriptPublicKeys]) {
+        self.subscriptions.lock().retain(|subscription| {
+            let transaction_ids = touched
+                .iter()
+                .filter(|(_, script_public_keys)| script_public_keys.iter().any(|key| subscription.script_public_keys.contains(key)))
+                .map(|(transaction_id, _)| *transaction_id)
+                .collect::<Vec<_>>();
+            if transaction_ids.is_empty() {
+                !subscription.sender.is_closed()
+            } else {
+                subscription.sender.send(transaction_ids).is_ok()
+            }
+        });
+    }
+}
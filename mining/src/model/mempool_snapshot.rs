@@ -0,0 +1,48 @@
+use crate::mempool::{model::tx::MempoolTransaction, tx::Priority};
+use kaspa_consensus_core::tx::Transaction;
+use serde::{Deserialize, Serialize};
+
+/// A serializable capture of the full mempool content, used for offline analysis of mempool
+/// anomalies and for reproducing a mempool state in a test harness
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MempoolSnapshot {
+    pub transactions: Vec<MempoolSnapshotEntry>,
+    pub orphans: Vec<MempoolSnapshotEntry>,
+}
+
+/// A single mempool entry with its priority and fee metadata
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MempoolSnapshotEntry {
+    pub transaction: Transaction,
+    pub priority: Priority,
+    pub fee: Option<u64>,
+    pub feerate: Option<f64>,
+    pub added_at_daa_score: u64,
+}
+
+impl From<&MempoolTransaction> for MempoolSnapshotEntry {
+    fn from(transaction: &MempoolTransaction) -> Self {
+        Self {
+            transaction: transaction.mtx.tx.as_ref().clone(),
+            priority: transaction.priority,
+            fee: transaction.mtx.calculated_fee,
+            feerate: transaction.mtx.calculated_feerate(),
+            added_at_daa_score: transaction.added_at_daa_score,
+        }
+    }
+}
+
+impl AsRef<Transaction> for MempoolSnapshotEntry {
+    fn as_ref(&self) -> &Transaction {
+        &self.transaction
+    }
+}
+
+/// Outcome of a mempool snapshot import
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MempoolSnapshotImport {
+    /// Number of snapshot entries re-inserted into the mempool
+    pub imported: usize,
+    /// Number of snapshot entries rejected by validation, for instance because their outpoints no longer exist
+    pub skipped: usize,
+}
@@ -1,10 +1,13 @@
 use kaspa_consensus_core::tx::TransactionId;
 use std::collections::HashSet;
 
+pub(crate) mod address_subscriptions;
 pub mod candidate_tx;
+pub mod mempool_snapshot;
 pub mod owner_txs;
 pub mod topological_index;
 pub mod topological_sort;
+pub mod tx_classification;
 pub mod tx_insert;
 pub mod tx_query;
 
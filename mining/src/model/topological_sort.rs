@@ -1,5 +1,6 @@
 use itertools::Itertools;
 use kaspa_consensus_core::tx::Transaction;
+use kaspa_mining_errors::mempool::{RuleError, RuleResult};
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     iter::{FusedIterator, Map},
@@ -8,13 +9,15 @@ use std::{
 type IndexSet = HashSet<usize>;
 
 pub trait TopologicalSort {
-    fn topological_sort(self) -> Self
+    /// Sorts the transactions topologically or returns [`RuleError::RejectCycleInMempoolTransactions`]
+    /// if their dependencies form a cycle
+    fn topological_sort(self) -> RuleResult<Self>
     where
         Self: Sized;
 }
 
 impl<T: AsRef<Transaction> + Clone> TopologicalSort for Vec<T> {
-    fn topological_sort(self) -> Self {
+    fn topological_sort(self) -> RuleResult<Self> {
         let mut sorted = Vec::with_capacity(self.len());
         let mut in_degree: Vec<i32> = vec![0; self.len()];
 
@@ -65,9 +68,13 @@ impl<T: AsRef<Transaction> + Clone> TopologicalSort for Vec<T> {
             }
             sorted.push(self[current].clone());
         }
-        assert_eq!(sorted.len(), self.len(), "by definition, cryptographically no cycle can exist in a DAG of transactions");
+        // By definition, cryptographically no cycle can exist in a DAG of transactions. Still, transactions with
+        // forged or non-finalized ids may come from external input, so we reject them rather than panic.
+        if sorted.len() != self.len() {
+            return Err(RuleError::RejectCycleInMempoolTransactions);
+        }
 
-        sorted
+        Ok(sorted)
     }
 }
 
@@ -197,6 +204,11 @@ where
     }
 }
 
+/// Yields transactions incrementally as soon as all their in-batch dependencies have been yielded.
+///
+/// Ready transactions are queued in FIFO order, so the stream emits the dependency layers of the batch
+/// one after another without ever materializing a full layer, which lets consumers pipeline the
+/// processing of large batches (for instance by chunks of mass).
 pub struct TopologicalIntoIter<T: AsRef<Transaction>> {
     transactions: Vec<Option<T>>,
     in_degree: Vec<i32>,
@@ -246,6 +258,14 @@ impl<T: AsRef<Transaction>> TopologicalIntoIter<T> {
         });
         Self { transactions, in_degree, edges, queue, yields_count: 0 }
     }
+
+    /// Consumes the iterator and returns the transactions it did not yield.
+    ///
+    /// Once the iterator is exhausted, the remainder holds exactly the transactions being part of,
+    /// or depending on, a dependency cycle. It is always empty for a valid DAG of transactions.
+    pub fn into_remainder(self) -> Vec<T> {
+        self.transactions.into_iter().flatten().collect()
+    }
 }
 
 impl<T: AsRef<Transaction>> Iterator for TopologicalIntoIter<T> {
@@ -282,3 +302,127 @@ impl<T: AsRef<Transaction>> ExactSizeIterator for TopologicalIntoIter<T> {
         self.transactions.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{ScriptPublicKey, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput},
+    };
+    use kaspa_hashes::Hash;
+
+    fn spending(outpoints: &[TransactionOutpoint], finalize: bool) -> Transaction {
+        let inputs = outpoints.iter().map(|outpoint| TransactionInput::new(*outpoint, vec![], 0, 0)).collect();
+        let outputs =
+            vec![TransactionOutput::new(1, ScriptPublicKey::default()), TransactionOutput::new(2, ScriptPublicKey::default())];
+        let mut tx = Transaction::new_non_finalized(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        if finalize {
+            tx.finalize();
+        }
+        tx
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let root = spending(&[TransactionOutpoint::new(Hash::from_u64_word(1), 0)], true);
+        let left = spending(&[TransactionOutpoint::new(root.id(), 0)], true);
+        let right = spending(&[TransactionOutpoint::new(root.id(), 1)], true);
+        let leaf = spending(&[TransactionOutpoint::new(left.id(), 0), TransactionOutpoint::new(right.id(), 0)], true);
+        let transactions = vec![leaf.clone(), right.clone(), left.clone(), root.clone()];
+
+        let position = |sorted: &[Transaction], tx: &Transaction| sorted.iter().position(|x| x.id() == tx.id()).unwrap();
+        let check = |sorted: Vec<Transaction>| {
+            assert_eq!(sorted.len(), 4);
+            assert!(position(&sorted, &root) < position(&sorted, &left));
+            assert!(position(&sorted, &root) < position(&sorted, &right));
+            assert!(position(&sorted, &left) < position(&sorted, &leaf));
+            assert!(position(&sorted, &right) < position(&sorted, &leaf));
+        };
+        check(transactions.clone().topological_sort().unwrap());
+        check(transactions.topological_iter().cloned().collect());
+
+        let mut iter = transactions.topological_into_iter();
+        check(iter.by_ref().collect());
+        assert!(iter.into_remainder().is_empty());
+    }
+
+    /// Groups the transactions by dependency layer, a layer holding the transactions whose longest chain
+    /// of in-batch ancestors has the layer index as length
+    fn layers(transactions: &[Transaction]) -> Vec<Vec<TransactionId>> {
+        let mut depths: HashMap<TransactionId, usize> = HashMap::new();
+        let mut layers: Vec<Vec<TransactionId>> = vec![];
+        while depths.len() < transactions.len() {
+            let layer = transactions
+                .iter()
+                .filter(|tx| !depths.contains_key(&tx.id()))
+                .filter(|tx| {
+                    tx.inputs.iter().all(|input| {
+                        depths.contains_key(&input.previous_outpoint.transaction_id)
+                            || !transactions.iter().any(|x| x.id() == input.previous_outpoint.transaction_id)
+                    })
+                })
+                .map(|tx| tx.id())
+                .collect_vec();
+            layer.iter().for_each(|id| {
+                depths.insert(*id, layers.len());
+            });
+            layers.push(layer);
+        }
+        layers
+    }
+
+    #[test]
+    fn test_incremental_stream_follows_layers() {
+        // Build a DAG of 4 layers where every transaction of a layer spends outputs of up to two transactions of the previous layer
+        let mut transactions =
+            (0..8).map(|i| spending(&[TransactionOutpoint::new(Hash::from_u64_word(i + 1), 0)], true)).collect_vec();
+        let mut previous_layer = transactions.clone();
+        for _ in 0..3 {
+            let layer = (0..previous_layer.len())
+                .map(|i| {
+                    let first = TransactionOutpoint::new(previous_layer[i].id(), 0);
+                    let second = TransactionOutpoint::new(previous_layer[(i * 3 + 1) % previous_layer.len()].id(), 1);
+                    match i % 3 {
+                        0 => spending(&[first], true),
+                        _ => spending(&[first, second], true),
+                    }
+                })
+                .collect_vec();
+            transactions.extend(layer.iter().cloned());
+            previous_layer = layer;
+        }
+        // Shuffle the batch deterministically so that the input order differs from the layered order
+        let transactions =
+            transactions.iter().enumerate().sorted_by_key(|(i, _)| (i * 7) % 32).map(|(_, tx)| tx.clone()).collect_vec();
+
+        let expected = layers(&transactions);
+        assert_eq!(expected.len(), 4);
+
+        // The stream must emit the layers one after another, each one fully before the next
+        let stream = transactions.clone().topological_into_iter().map(|tx| tx.id()).collect_vec();
+        assert_eq!(stream.len(), transactions.len());
+        let mut offset = 0;
+        for layer in expected {
+            let streamed = stream[offset..offset + layer.len()].iter().copied().sorted().collect_vec();
+            assert_eq!(streamed, layer.into_iter().sorted().collect_vec());
+            offset += streamed.len();
+        }
+    }
+
+    #[test]
+    fn test_cyclic_dependencies() {
+        // A non-finalized transaction has the default id, so spending an output of the default id forms a self-cycle
+        let cyclic = spending(&[TransactionOutpoint::new(Hash::default(), 0)], false);
+        let dependent = spending(&[TransactionOutpoint::new(cyclic.id(), 1)], true);
+        let independent = spending(&[TransactionOutpoint::new(Hash::from_u64_word(1), 0)], true);
+        let transactions = vec![dependent.clone(), cyclic.clone(), independent.clone()];
+
+        assert_eq!(transactions.clone().topological_sort(), Err(RuleError::RejectCycleInMempoolTransactions));
+
+        let mut iter = transactions.topological_into_iter();
+        assert_eq!(iter.by_ref().map(|tx| tx.id()).collect_vec(), vec![independent.id()]);
+        let remainder = iter.into_remainder().into_iter().map(|tx| tx.id()).collect_vec();
+        assert_eq!(remainder, vec![dependent.id(), cyclic.id()]);
+    }
+}
@@ -0,0 +1,26 @@
+use kaspa_consensus_core::tx::TransactionId;
+
+/// A partition of a set of transaction ids according to their status in the mempool.
+/// Each queried id belongs to exactly one of the categories.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TxClassification {
+    /// Transactions registered as accepted by a block handled by the mempool
+    pub accepted: Vec<TransactionId>,
+    /// Transactions in the ordinary mempool transaction pool
+    pub in_pool: Vec<TransactionId>,
+    /// Transactions in the orphan pool
+    pub orphan: Vec<TransactionId>,
+    /// Transactions neither in the mempool, nor in the orphan pool nor accepted
+    pub unknown: Vec<TransactionId>,
+}
+
+impl TxClassification {
+    /// Returns the total number of classified transactions
+    pub fn len(&self) -> usize {
+        self.accepted.len() + self.in_pool.len() + self.orphan.len() + self.unknown.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -1,23 +1,51 @@
 use super::MiningCounters;
-use crate::manager::MiningManagerProxy;
-use kaspa_consensusmanager::ConsensusManager;
+use crate::{feerate::FeerateEstimations, manager::MiningManagerProxy, MempoolCountersSnapshot};
+use kaspa_consensusmanager::{ConsensusManager, SessionPool, DEFAULT_SESSION_POOL_MAX_AGE};
 use kaspa_core::{
     debug, info,
     task::{
-        service::{AsyncService, AsyncServiceFuture},
+        service::{AsyncService, AsyncServiceFuture, ServiceShutdown},
         tick::{TickReason, TickService},
     },
     trace,
 };
-use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_txscript::caches::{TxScriptCacheCounters, TxScriptCacheCountersSnapshot};
 use std::{sync::Arc, time::Duration};
 
 const MONITOR: &str = "mempool-monitor";
 
+/// Default grace period granted to the system for printing final logs on shutdown
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_millis(500);
+
+/// Default interval between consecutive counter snapshots
+const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default number of snapshots per logged report (i.e., log on every snapshot)
+const DEFAULT_LOG_CADENCE: u64 = 1;
+
+/// Default relative change of the priority bucket feerate (between consecutive estimations) above which an alert is emitted
+const DEFAULT_FEERATE_ALERT_THRESHOLD: f64 = 0.5;
+
+/// Default number of estimations following an alert during which further alerts are suppressed
+const DEFAULT_FEERATE_ALERT_DEBOUNCE: u64 = 6;
+
+/// The final stats emitted by the monitor on shutdown, so that the last interval is not lost
+#[derive(Debug)]
+pub struct MiningMonitorFinalSnapshot {
+    /// Counters delta since the last reported snapshot
+    pub delta: MempoolCountersSnapshot,
+
+    /// The most recent feerate estimations computed by the monitor, if any
+    pub feerate_estimations: Option<FeerateEstimations>,
+}
+
+pub type FinalSnapshotCallback = Box<dyn Fn(MiningMonitorFinalSnapshot) + Send + Sync>;
+
 pub struct MiningMonitor {
     mining_manager: MiningManagerProxy,
 
-    consensus_manager: Arc<ConsensusManager>,
+    /// Read-only consensus sessions used for feerate estimations
+    session_pool: SessionPool,
 
     // Counters
     counters: Arc<MiningCounters>,
@@ -26,6 +54,27 @@ pub struct MiningMonitor {
 
     // Tick service
     tick_service: Arc<TickService>,
+
+    /// Exit signal set by `signal_exit` and checked by the worker on every iteration
+    shutdown: ServiceShutdown,
+
+    /// Grace period granted to the system for printing final logs on shutdown
+    shutdown_grace: Duration,
+
+    /// Interval between consecutive counter snapshots
+    snapshot_interval: Duration,
+
+    /// Number of snapshots per logged report
+    log_cadence: u64,
+
+    /// Relative priority feerate change triggering an alert, or `None` if alerts are disabled
+    feerate_alert_threshold: Option<f64>,
+
+    /// Number of estimations following an alert during which further alerts are suppressed
+    feerate_alert_debounce: u64,
+
+    /// Optional callback receiving the final snapshot on shutdown
+    final_snapshot_callback: Option<FinalSnapshotCallback>,
 }
 
 impl MiningMonitor {
@@ -36,75 +85,246 @@ impl MiningMonitor {
         tx_script_cache_counters: Arc<TxScriptCacheCounters>,
         tick_service: Arc<TickService>,
     ) -> MiningMonitor {
-        MiningMonitor { mining_manager, consensus_manager, counters, tx_script_cache_counters, tick_service }
+        MiningMonitor {
+            mining_manager,
+            session_pool: SessionPool::new(consensus_manager, DEFAULT_SESSION_POOL_MAX_AGE),
+            counters,
+            tx_script_cache_counters,
+            tick_service,
+            shutdown: ServiceShutdown::new(),
+            shutdown_grace: DEFAULT_SHUTDOWN_GRACE,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            log_cadence: DEFAULT_LOG_CADENCE,
+            feerate_alert_threshold: Some(DEFAULT_FEERATE_ALERT_THRESHOLD),
+            feerate_alert_debounce: DEFAULT_FEERATE_ALERT_DEBOUNCE,
+            final_snapshot_callback: None,
+        }
+    }
+
+    pub fn with_snapshot_interval(mut self, snapshot_interval: Duration) -> Self {
+        self.snapshot_interval = snapshot_interval;
+        self
+    }
+
+    /// Sets the number of snapshots per logged report. A zero cadence is treated as 1.
+    pub fn with_log_cadence(mut self, log_cadence: u64) -> Self {
+        self.log_cadence = log_cadence;
+        self
+    }
+
+    pub fn with_shutdown_grace(mut self, shutdown_grace: Duration) -> Self {
+        self.shutdown_grace = shutdown_grace;
+        self
+    }
+
+    /// Sets the relative change of the priority bucket feerate (e.g., `0.5` for 50%) above which an
+    /// info-level alert is emitted. Passing `None` disables the alerts.
+    pub fn with_feerate_alert_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.feerate_alert_threshold = threshold;
+        self
+    }
+
+    /// Sets the number of estimations following an alert during which further alerts are suppressed
+    pub fn with_feerate_alert_debounce(mut self, debounce: u64) -> Self {
+        self.feerate_alert_debounce = debounce;
+        self
+    }
+
+    pub fn with_final_snapshot_callback(mut self, callback: FinalSnapshotCallback) -> Self {
+        self.final_snapshot_callback = Some(callback);
+        self
+    }
+
+    fn worker_state(&self) -> WorkerState {
+        WorkerState {
+            last_snapshot: self.counters.snapshot(),
+            last_tx_script_cache_snapshot: self.tx_script_cache_counters.snapshot(),
+            last_feerate_estimations: None,
+            cadence: ReportCadence::new(self.log_cadence),
+            feerate_alerts: self
+                .feerate_alert_threshold
+                .map(|threshold| FeerateJumpDetector::new(threshold, self.feerate_alert_debounce)),
+            feerate_estimation_counts: 0,
+        }
     }
 
     pub async fn worker(self: &Arc<MiningMonitor>) {
-        let mut last_snapshot = self.counters.snapshot();
-        let mut last_tx_script_cache_snapshot = self.tx_script_cache_counters.snapshot();
-        let snapshot_interval = 10;
+        let mut state = self.worker_state();
         loop {
-            if let TickReason::Shutdown = self.tick_service.tick(Duration::from_secs(snapshot_interval)).await {
+            if let TickReason::Shutdown = self.shutdown.tick(&self.tick_service, self.snapshot_interval).await {
+                // Report the stats accumulated since the last snapshot so they are not lost
+                let delta = &self.counters.snapshot() - &state.last_snapshot;
+                debug!("Final mempool stats: {:?}", delta);
+                if let Some(callback) = self.final_snapshot_callback.as_ref() {
+                    callback(MiningMonitorFinalSnapshot { delta, feerate_estimations: state.last_feerate_estimations.take() });
+                }
                 // Let the system print final logs before exiting
-                tokio::time::sleep(Duration::from_millis(500)).await;
+                tokio::time::sleep(self.shutdown_grace).await;
                 break;
             }
+            self.report(&mut state).await;
+        }
 
-            let snapshot = self.counters.snapshot();
-            let tx_script_cache_snapshot = self.tx_script_cache_counters.snapshot();
-            if snapshot == last_snapshot {
-                // No update, avoid printing useless info
-                continue;
-            }
+        trace!("mempool monitor thread exiting after computing {} feerate estimations", state.feerate_estimation_counts);
+    }
 
-            // Subtract the snapshots
-            let delta = &snapshot - &last_snapshot;
-            let tx_script_cache_delta = &tx_script_cache_snapshot - &last_tx_script_cache_snapshot;
+    /// Reports the stats accumulated since the last reported snapshot.
+    ///
+    /// Feerate estimations require a consensus session, hence they are only computed when the snapshot shows
+    /// tx activity. Otherwise the last estimations are kept as the most recent ones
+    async fn report(&self, state: &mut WorkerState) {
+        if !state.cadence.tick() {
+            return;
+        }
 
-            if delta.has_tps_activity() {
-                info!(
-                    "Tx throughput stats: {:.2} u-tps, {:.2}% e-tps (in: {} via RPC, {} via P2P, out: {} via accepted blocks)",
-                    delta.u_tps(),
-                    delta.e_tps() * 100.0,
-                    delta.high_priority_tx_counts,
-                    delta.low_priority_tx_counts,
-                    delta.tx_accepted_counts,
-                );
-                let feerate_estimations = self
-                    .mining_manager
-                    .clone()
-                    .get_realtime_feerate_estimations(self.consensus_manager.consensus().unguarded_session().get_virtual_daa_score())
-                    .await;
-                debug!("Realtime feerate estimations: {}", feerate_estimations);
-            }
-            if delta.tx_evicted_counts > 0 {
+        let snapshot = self.counters.snapshot();
+        let tx_script_cache_snapshot = self.tx_script_cache_counters.snapshot();
+        if snapshot.counts_eq(&state.last_snapshot) {
+            // No update, avoid printing useless info and querying for estimations
+            return;
+        }
+
+        // Subtract the snapshots
+        let delta = &snapshot - &state.last_snapshot;
+        let tx_script_cache_delta = &tx_script_cache_snapshot - &state.last_tx_script_cache_snapshot;
+
+        if delta.has_tps_activity() {
+            info!(
+                "Tx throughput stats: {:.2} u-tps, {:.2}% e-tps (in: {} via RPC, {} via P2P, out: {} via accepted blocks)",
+                delta.u_tps(),
+                delta.e_tps() * 100.0,
+                delta.high_priority_tx_counts,
+                delta.low_priority_tx_counts,
+                delta.tx_accepted_counts,
+            );
+            let virtual_daa_score = self.session_pool.session().await.get_virtual_daa_score();
+            let feerate_estimations = self.mining_manager.clone().get_realtime_feerate_estimations(virtual_daa_score).await;
+            debug!("Realtime feerate estimations: {}", feerate_estimations.to_compact());
+            if let Some(jump) = state.feerate_alerts.as_mut().and_then(|detector| detector.observe(&feerate_estimations)) {
                 info!(
-                    "Mempool stats: {} transactions were evicted from the mempool in favor of incoming higher feerate transactions",
-                    delta.tx_evicted_counts
-                );
-            }
-            if tx_script_cache_snapshot != last_tx_script_cache_snapshot {
-                debug!(
-                    "UTXO set stats: {} spent, {} created ({} signatures validated, {} cache hits, {:.2} hit ratio)",
-                    delta.input_counts,
-                    delta.output_counts,
-                    tx_script_cache_delta.insert_counts,
-                    tx_script_cache_delta.get_counts,
-                    tx_script_cache_delta.hit_ratio()
-                );
-            }
-            if delta.txs_sample + delta.orphans_sample > 0 {
-                debug!(
-                    "Mempool sample: {} ready out of {} txs, {} orphans, {} cached as accepted",
-                    delta.ready_txs_sample, delta.txs_sample, delta.orphans_sample, delta.accepted_sample
+                    "Feerate alert: priority feerate changed by {:+.1}% ({:.4} -> {:.4} sompi/gram)",
+                    jump.relative_change() * 100.0,
+                    jump.previous,
+                    jump.current
                 );
             }
+            state.feerate_estimation_counts += 1;
+            state.last_feerate_estimations = Some(feerate_estimations);
+        }
+        if delta.tx_evicted_counts > 0 {
+            info!(
+                "Mempool stats: {} transactions were evicted from the mempool in favor of incoming higher feerate transactions",
+                delta.tx_evicted_counts
+            );
+        }
+        if delta.revalidated_valid_counts + delta.revalidated_removed_counts() > 0 {
+            debug!(
+                "Revalidation stats: {} valid, {} removed high priority transactions ({} accepted, {} missing outpoint, {} invalid)",
+                delta.revalidated_valid_counts,
+                delta.revalidated_removed_counts(),
+                delta.revalidated_accepted_counts,
+                delta.revalidated_missing_outpoint_counts,
+                delta.revalidated_invalid_counts
+            );
+        }
+        if tx_script_cache_snapshot != state.last_tx_script_cache_snapshot {
+            debug!(
+                "UTXO set stats: {} spent, {} created ({} signatures validated, {} cache hits, {:.2} hit ratio)",
+                delta.input_counts,
+                delta.output_counts,
+                tx_script_cache_delta.insert_counts,
+                tx_script_cache_delta.get_counts,
+                tx_script_cache_delta.hit_ratio()
+            );
+        }
+        if delta.txs_sample + delta.orphans_sample > 0 {
+            debug!(
+                "Mempool sample: {} ready out of {} txs, {} orphans, {} cached as accepted",
+                delta.ready_txs_sample, delta.txs_sample, delta.orphans_sample, delta.accepted_sample
+            );
+        }
+
+        state.last_snapshot = snapshot;
+        state.last_tx_script_cache_snapshot = tx_script_cache_snapshot;
+    }
+}
+
+/// The state carried by the monitor worker across ticks
+struct WorkerState {
+    last_snapshot: MempoolCountersSnapshot,
+    last_tx_script_cache_snapshot: TxScriptCacheCountersSnapshot,
+
+    /// The most recent feerate estimations, kept as long as no tx activity is observed
+    last_feerate_estimations: Option<FeerateEstimations>,
+
+    cadence: ReportCadence,
+    feerate_alerts: Option<FeerateJumpDetector>,
 
-            last_snapshot = snapshot;
-            last_tx_script_cache_snapshot = tx_script_cache_snapshot;
+    /// Number of feerate estimations computed so far
+    feerate_estimation_counts: u64,
+}
+
+/// A significant change of the priority bucket feerate between consecutive estimations
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FeerateJump {
+    previous: f64,
+    current: f64,
+}
+
+impl FeerateJump {
+    fn relative_change(&self) -> f64 {
+        (self.current - self.previous) / self.previous
+    }
+}
+
+/// Detects significant jumps in the priority bucket feerate between consecutive estimations,
+/// debouncing repeated alerts
+struct FeerateJumpDetector {
+    threshold: f64,
+    debounce: u64,
+    last_feerate: Option<f64>,
+    suppressed: u64,
+}
+
+impl FeerateJumpDetector {
+    fn new(threshold: f64, debounce: u64) -> Self {
+        Self { threshold, debounce, last_feerate: None, suppressed: 0 }
+    }
+
+    /// Registers a new estimation and returns the detected jump if an alert should be emitted
+    fn observe(&mut self, estimations: &FeerateEstimations) -> Option<FeerateJump> {
+        let current = estimations.priority_bucket.feerate;
+        let previous = self.last_feerate.replace(current)?;
+        if self.suppressed > 0 {
+            self.suppressed -= 1;
+            return None;
+        }
+        let jump = FeerateJump { previous, current };
+        if previous > 0.0 && jump.relative_change().abs() > self.threshold {
+            self.suppressed = self.debounce;
+            Some(jump)
+        } else {
+            None
         }
+    }
+}
+
+/// Gates the monitor reports so that only every `cadence`-th snapshot is logged
+struct ReportCadence {
+    cadence: u64,
+    ticks: u64,
+}
+
+impl ReportCadence {
+    fn new(cadence: u64) -> Self {
+        // Guard against a zero cadence (which would otherwise cause a modulo by zero)
+        Self { cadence: cadence.max(1), ticks: 0 }
+    }
 
-        trace!("mempool monitor thread exiting");
+    /// Registers a tick and returns whether a report should be emitted for it
+    fn tick(&mut self) -> bool {
+        self.ticks += 1;
+        self.ticks % self.cadence == 0
     }
 }
 
@@ -123,6 +343,7 @@ impl AsyncService for MiningMonitor {
 
     fn signal_exit(self: Arc<Self>) {
         trace!("sending an exit signal to {}", MONITOR);
+        self.shutdown.signal();
     }
 
     fn stop(self: Arc<Self>) -> AsyncServiceFuture {
@@ -132,3 +353,135 @@ impl AsyncService for MiningMonitor {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{feerate::FeerateBucket, manager::MiningManager, testutils::consensus_mock::ConsensusMock};
+    use kaspa_core::task::spawn::spawn_named;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_final_snapshot_on_shutdown() {
+        let counters = Arc::new(MiningCounters::default());
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(Arc::new(ConsensusMock::new())));
+        let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new(1000, false, 500_000, None, counters.clone())));
+        let tick_service = Arc::new(TickService::new());
+
+        let fired = Arc::new(AtomicUsize::new(0));
+        let fired_clone = fired.clone();
+        let monitor = Arc::new(
+            MiningMonitor::new(
+                mining_manager,
+                consensus_manager,
+                counters,
+                Arc::new(TxScriptCacheCounters::default()),
+                tick_service.clone(),
+            )
+            .with_shutdown_grace(Duration::ZERO)
+            .with_final_snapshot_callback(Box::new(move |snapshot| {
+                assert!(snapshot.feerate_estimations.is_none(), "no activity, hence no estimations are expected");
+                fired_clone.fetch_add(1, Ordering::SeqCst);
+            })),
+        );
+
+        let worker = spawn_named(MONITOR, "worker", async move { monitor.worker().await });
+        tick_service.shutdown();
+        timeout(Duration::from_secs(5), worker).await.unwrap().unwrap();
+        assert_eq!(1, fired.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_signal_exit_interrupts_tick() {
+        let counters = Arc::new(MiningCounters::default());
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(Arc::new(ConsensusMock::new())));
+        let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new(1000, false, 500_000, None, counters.clone())));
+        let tick_service = Arc::new(TickService::new());
+        let snapshot_interval = Duration::from_secs(60);
+        let monitor = Arc::new(
+            MiningMonitor::new(mining_manager, consensus_manager, counters, Arc::new(TxScriptCacheCounters::default()), tick_service)
+                .with_snapshot_interval(snapshot_interval)
+                .with_shutdown_grace(Duration::ZERO),
+        );
+
+        let worker = spawn_named(MONITOR, "worker", {
+            let monitor = monitor.clone();
+            async move { monitor.worker().await }
+        });
+        // Let the worker enter its first tick before signaling
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        monitor.clone().signal_exit();
+
+        // The worker exits well within a single tick, and without the tick service being shut down
+        timeout(snapshot_interval / 10, worker).await.unwrap().unwrap();
+        assert!(monitor.shutdown.is_signaled());
+    }
+
+    #[tokio::test]
+    async fn test_quiescent_mempool_skips_estimations() {
+        let counters = Arc::new(MiningCounters::default());
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(Arc::new(ConsensusMock::new())));
+        let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new(1000, false, 500_000, None, counters.clone())));
+        let monitor = MiningMonitor::new(
+            mining_manager,
+            consensus_manager,
+            counters.clone(),
+            Arc::new(TxScriptCacheCounters::default()),
+            Arc::new(TickService::new()),
+        );
+        let mut state = monitor.worker_state();
+
+        // A quiescent mempool requires no estimation
+        assert!(counters.snapshot().counts_eq(&state.last_snapshot));
+        for _ in 0..5 {
+            monitor.report(&mut state).await;
+        }
+        assert_eq!(0, state.feerate_estimation_counts);
+        assert!(state.last_feerate_estimations.is_none());
+
+        // Tx activity triggers an estimation
+        counters.high_priority_tx_counts.fetch_add(1, Ordering::Relaxed);
+        monitor.report(&mut state).await;
+        assert_eq!(1, state.feerate_estimation_counts);
+
+        // Activity without txs (here, a mempool sample) is not enough, and the last estimations are kept
+        counters.txs_sample.fetch_add(1, Ordering::Relaxed);
+        monitor.report(&mut state).await;
+        for _ in 0..5 {
+            monitor.report(&mut state).await;
+        }
+        assert_eq!(1, state.feerate_estimation_counts);
+        assert!(state.last_feerate_estimations.is_some());
+    }
+
+    #[test]
+    fn test_report_cadence() {
+        const TICKS: usize = 30;
+        for (cadence, expected) in [(0, 30), (1, 30), (3, 10), (4, 7), (10, 3), (31, 0)] {
+            let mut gate = ReportCadence::new(cadence);
+            let emissions = (0..TICKS).filter(|_| gate.tick()).count();
+            assert_eq!(expected, emissions, "cadence {cadence}");
+        }
+    }
+
+    #[test]
+    fn test_feerate_jump_alerts() {
+        let estimations = |feerate: f64| FeerateEstimations {
+            priority_bucket: FeerateBucket { feerate, estimated_seconds: 1.0 },
+            normal_buckets: vec![],
+            low_buckets: vec![],
+        };
+
+        // A spike followed by a fast fall back, which is expected to be debounced
+        let sequence = [1.0, 1.1, 1.0, 10.0, 10.5, 1.0, 1.05, 1.0, 1.1];
+        let mut detector = FeerateJumpDetector::new(0.5, 3);
+        let alerts = sequence.into_iter().filter_map(|feerate| detector.observe(&estimations(feerate))).collect::<Vec<_>>();
+        assert_eq!(vec![FeerateJump { previous: 1.0, current: 10.0 }], alerts);
+
+        // Without debouncing, the fall back triggers an alert as well
+        let mut detector = FeerateJumpDetector::new(0.5, 0);
+        let alerts = sequence.into_iter().filter_map(|feerate| detector.observe(&estimations(feerate))).count();
+        assert_eq!(2, alerts);
+    }
+}
@@ -17,7 +17,7 @@ impl CoinbaseManagerMock {
 
     pub(super) fn expected_coinbase_transaction(&self, miner_data: MinerData) -> CoinbaseTransactionTemplate {
         const SUBSIDY: u64 = 500 * SOMPI_PER_KASPA;
-        let output = TransactionOutput::new(SUBSIDY, miner_data.script_public_key.clone());
+        let output = TransactionOutput::new(SUBSIDY, miner_data.script_public_key().clone());
 
         let payload = self.serialize_coinbase_payload(&CoinbaseData { blue_score: 1, subsidy: SUBSIDY, miner_data });
 
@@ -28,26 +28,26 @@ impl CoinbaseManagerMock {
     }
 
     pub(super) fn serialize_coinbase_payload(&self, data: &CoinbaseData) -> Vec<u8> {
-        let script_pub_key_len = data.miner_data.script_public_key.script().len();
+        let script_pub_key_len = data.miner_data.script_public_key().script().len();
         let payload: Vec<u8> = data.blue_score.to_le_bytes().iter().copied()                    // Blue score                   (u64)
             .chain(data.subsidy.to_le_bytes().iter().copied())                                  // Subsidy                      (u64)
-            .chain(data.miner_data.script_public_key.version().to_le_bytes().iter().copied())   // Script public key version    (u16)
+            .chain(data.miner_data.script_public_key().version().to_le_bytes().iter().copied()) // Script public key version    (u16)
             .chain((script_pub_key_len as u8).to_le_bytes().iter().copied())                    // Script public key length     (u8)
-            .chain(data.miner_data.script_public_key.script().iter().copied())                  // Script public key            
-            .chain(data.miner_data.extra_data.iter().copied())                                  // Extra data
+            .chain(data.miner_data.script_public_key().script().iter().copied())                // Script public key            
+            .chain(data.miner_data.extra_data().iter().copied())                                // Extra data
             .collect();
 
         payload
     }
 
     pub fn modify_coinbase_payload(&self, mut payload: Vec<u8>, miner_data: &MinerData) -> Vec<u8> {
-        let script_pub_key_len = miner_data.script_public_key.script().len();
+        let script_pub_key_len = miner_data.script_public_key().script().len();
         payload.truncate(LENGTH_OF_BLUE_SCORE + LENGTH_OF_SUBSIDY);
         payload.extend(
-            miner_data.script_public_key.version().to_le_bytes().iter().copied() // Script public key version (u16)
+            miner_data.script_public_key().version().to_le_bytes().iter().copied() // Script public key version (u16)
                 .chain((script_pub_key_len as u8).to_le_bytes().iter().copied()) // Script public key length  (u8)
-                .chain(miner_data.script_public_key.script().iter().copied())    // Script public key
-                .chain(miner_data.extra_data.iter().copied()), // Extra data
+                .chain(miner_data.script_public_key().script().iter().copied())  // Script public key
+                .chain(miner_data.extra_data().iter().copied()), // Extra data
         );
 
         payload
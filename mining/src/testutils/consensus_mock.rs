@@ -18,16 +18,35 @@ use kaspa_consensus_core::{
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
     utxo::utxo_collection::UtxoCollection,
 };
+use kaspa_consensusmanager::ConsensusCtl;
 use kaspa_core::time::unix_now;
 use kaspa_hashes::{Hash, ZERO_HASH};
 
 use parking_lot::RwLock;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+/// The max coinbase payload length reported by the mock, matching the value of all network params
+pub(crate) const MAX_COINBASE_PAYLOAD_LEN: usize = 204;
 
 pub(crate) struct ConsensusMock {
     transactions: RwLock<HashMap<TransactionId, Arc<Transaction>>>,
     statuses: RwLock<HashMap<TransactionId, TxResult<()>>>,
     utxos: RwLock<UtxoCollection>,
+    /// Ids of the block transactions accepted into the virtual state, by block hash
+    block_acceptance: RwLock<HashMap<Hash, Vec<TransactionId>>>,
+    virtual_daa_score: AtomicU64,
+    /// Number of upcoming block template builds which fail, reporting all selected transactions as invalid
+    failing_template_builds: AtomicU64,
+    template_build_count: AtomicU64,
+    /// The max parallelism requested by each batch validation call
+    validation_max_parallelism: RwLock<Vec<Option<usize>>>,
 }
 
 impl ConsensusMock {
@@ -36,9 +55,38 @@ impl ConsensusMock {
             transactions: RwLock::new(HashMap::default()),
             statuses: RwLock::new(HashMap::default()),
             utxos: RwLock::new(HashMap::default()),
+            block_acceptance: RwLock::new(HashMap::default()),
+            virtual_daa_score: AtomicU64::new(0),
+            failing_template_builds: AtomicU64::new(0),
+            template_build_count: AtomicU64::new(0),
+            validation_max_parallelism: RwLock::new(vec![]),
         }
     }
 
+    pub(crate) fn set_virtual_daa_score(&self, virtual_daa_score: u64) {
+        self.virtual_daa_score.store(virtual_daa_score, Ordering::SeqCst);
+    }
+
+    /// Registers the block transactions accepted into the virtual state. Blocks with no registered
+    /// acceptance are reported as lacking acceptance data
+    pub(crate) fn set_block_accepted_transaction_ids(&self, block_hash: Hash, accepted_transaction_ids: Vec<TransactionId>) {
+        self.block_acceptance.write().insert(block_hash, accepted_transaction_ids);
+    }
+
+    /// Makes the next `count` standard block template builds fail with all selected transactions reported as invalid.
+    /// Similarly to consensus, infallible builds never fail on invalid transactions
+    pub(crate) fn fail_template_builds(&self, count: u64) {
+        self.failing_template_builds.store(count, Ordering::SeqCst);
+    }
+
+    pub(crate) fn validation_max_parallelism(&self) -> Vec<Option<usize>> {
+        self.validation_max_parallelism.read().clone()
+    }
+
+    pub(crate) fn template_build_count(&self) -> u64 {
+        self.template_build_count.load(Ordering::SeqCst)
+    }
+
     pub(crate) fn set_status(&self, transaction_id: TransactionId, status: TxResult<()>) {
         self.statuses.write().insert(transaction_id, status);
     }
@@ -74,14 +122,30 @@ impl ConsensusMock {
     }
 }
 
+impl ConsensusCtl for ConsensusMock {
+    fn start(&self) -> Vec<JoinHandle<()>> {
+        vec![]
+    }
+
+    fn stop(&self) {}
+
+    fn make_active(&self) {}
+}
+
 impl ConsensusApi for ConsensusMock {
     fn build_block_template(
         &self,
         miner_data: MinerData,
         mut tx_selector: Box<dyn TemplateTransactionSelector>,
-        _build_mode: TemplateBuildMode,
+        build_mode: TemplateBuildMode,
     ) -> Result<BlockTemplate, RuleError> {
+        self.template_build_count.fetch_add(1, Ordering::SeqCst);
         let mut txs = tx_selector.select_transactions();
+        if matches!(build_mode, TemplateBuildMode::Standard)
+            && self.failing_template_builds.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok()
+        {
+            return Err(RuleError::InvalidTransactionsInNewBlock(txs.iter().map(|tx| (tx.id(), TxRuleError::NoTxInputs)).collect()));
+        }
         let coinbase_manager = CoinbaseManagerMock::new();
         let coinbase = coinbase_manager.expected_coinbase_transaction(miner_data.clone());
         txs.insert(0, coinbase.tx);
@@ -145,8 +209,9 @@ impl ConsensusApi for ConsensusMock {
     fn validate_mempool_transactions_in_parallel(
         &self,
         transactions: &mut [MutableTransaction],
-        _: &TransactionValidationBatchArgs,
+        args: &TransactionValidationBatchArgs,
     ) -> Vec<TxResult<()>> {
+        self.validation_max_parallelism.write().push(args.max_parallelism());
         transactions.iter_mut().map(|x| self.validate_mempool_transaction(x, &Default::default())).collect()
     }
 
@@ -163,8 +228,16 @@ impl ConsensusApi for ConsensusMock {
         Some(ContextualMasses::new(0))
     }
 
+    fn calculate_transaction_mass(&self, transaction: &MutableTransaction) -> Option<u64> {
+        Some(self.calculate_transaction_non_contextual_masses(&transaction.tx).max())
+    }
+
+    fn get_block_accepted_transaction_ids(&self, hash: Hash) -> Option<Vec<TransactionId>> {
+        self.block_acceptance.read().get(&hash).cloned()
+    }
+
     fn get_virtual_daa_score(&self) -> u64 {
-        0
+        self.virtual_daa_score.load(Ordering::SeqCst)
     }
 
     fn get_virtual_state_approx_id(&self) -> VirtualStateApproxId {
@@ -176,6 +249,10 @@ impl ConsensusApi for ConsensusMock {
         Ok(coinbase_manager.modify_coinbase_payload(payload, miner_data))
     }
 
+    fn max_coinbase_payload_len(&self) -> usize {
+        MAX_COINBASE_PAYLOAD_LEN
+    }
+
     fn calc_transaction_hash_merkle_root(&self, txs: &[Transaction], _pov_daa_score: u64) -> Hash {
         calc_hash_merkle_root(txs.iter(), false)
     }
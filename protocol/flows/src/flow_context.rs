@@ -52,7 +52,7 @@ use std::{
     iter::once,
     ops::Deref,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
     time::Duration,
@@ -76,6 +76,11 @@ const MAX_ORPHANS_UPPER_BOUND: usize = 1024;
 /// The min time to wait before allowing another parallel request
 const REQUEST_SCOPE_WAIT_TIME: Duration = Duration::from_secs(1);
 
+/// The number of attempts (and the initial backoff between them) for relaying an rpc-submitted block to peers whose
+/// outgoing route was momentarily full
+const SUBMITTED_BLOCK_BROADCAST_ATTEMPTS: usize = 3;
+const SUBMITTED_BLOCK_BROADCAST_BACKOFF: Duration = Duration::from_millis(100);
+
 /// Represents a block event to be logged
 #[derive(Debug, PartialEq)]
 pub enum BlockLogEvent {
@@ -240,7 +245,7 @@ pub struct FlowContextInner {
 
     // Orphan parameters
     orphan_resolution_range: u32,
-    max_orphans: usize,
+    max_orphans: AtomicUsize,
 
     // Mining rule engine
     mining_rule_engine: Arc<MiningRuleEngine>,
@@ -345,7 +350,7 @@ impl FlowContext {
                 block_event_logger: if bps_upper_bound > 1 { Some(BlockEventLogger::new(bps_upper_bound)) } else { None },
                 bps_upper_bound,
                 orphan_resolution_range,
-                max_orphans,
+                max_orphans: AtomicUsize::new(max_orphans),
                 config,
                 mining_rule_engine,
             }),
@@ -361,7 +366,16 @@ impl FlowContext {
     }
 
     pub fn max_orphans(&self) -> usize {
-        self.max_orphans
+        self.max_orphans.load(Ordering::Relaxed)
+    }
+
+    /// Adjusts the max number of orphans kept in the orphans pool (bounded by [`MAX_ORPHANS_UPPER_BOUND`]),
+    /// evicting orphans if the pool currently exceeds the new limit
+    pub async fn set_max_orphans(&self, max_orphans: usize) {
+        let max_orphans = max_orphans.min(MAX_ORPHANS_UPPER_BOUND);
+        let mut orphans_pool = self.orphans_pool.write().await;
+        orphans_pool.set_max_orphans(max_orphans);
+        self.max_orphans.store(max_orphans, Ordering::Relaxed);
     }
 
     pub fn start_async_services(&self) {
@@ -459,6 +473,14 @@ impl FlowContext {
         self.orphans_pool.write().await.add_orphan(consensus, orphan_block).await
     }
 
+    /// Filters out orphan roots which were recently requested from `peer` and marks the remaining ones as requested
+    pub async fn filter_and_mark_orphan_roots(&self, peer: PeerKey, roots: Vec<Hash>) -> Vec<Hash> {
+        let mut orphans_pool = self.orphans_pool.write().await;
+        let roots = orphans_pool.filter_unrequested(peer, roots);
+        orphans_pool.mark_roots_requested(peer, &roots);
+        roots
+    }
+
     pub async fn is_known_orphan(&self, hash: Hash) -> bool {
         self.orphans_pool.read().await.is_known_orphan(hash)
     }
@@ -509,8 +531,16 @@ impl FlowContext {
             warn!("Validation failed for block {}: {}", hash, err);
             return Err(err)?;
         }
-        // Broadcast as soon as the block has been validated and inserted into the DAG
-        self.hub.broadcast(make_message!(Payload::InvRelayBlock, InvRelayBlockMessage { hash: Some(hash.into()) })).await;
+        // Broadcast as soon as the block has been validated and inserted into the DAG. Peers which could not be reached
+        // are retried in the background, so that the submitting miner is not delayed
+        let hub = self.hub.clone();
+        tokio::spawn(async move {
+            let msg = make_message!(Payload::InvRelayBlock, InvRelayBlockMessage { hash: Some(hash.into()) });
+            let failed = hub.broadcast_with_retry(msg, SUBMITTED_BLOCK_BROADCAST_ATTEMPTS, SUBMITTED_BLOCK_BROADCAST_BACKOFF).await;
+            if !failed.is_empty() {
+                debug!("Failed relaying submitted block {} to {} peers", hash, failed.len());
+            }
+        });
 
         let daa_score = block.header.daa_score;
         self.on_new_block(consensus, Default::default(), block, virtual_state_task).await;
@@ -589,7 +619,7 @@ impl FlowContext {
             if let Ok(txs) = self
                 .mining_manager()
                 .clone()
-                .handle_new_block_transactions(consensus, block.header.daa_score, block.transactions.clone())
+                .handle_new_block_transactions(consensus, block.hash(), block.header.daa_score, block.transactions.clone())
                 .await
             {
                 transactions_to_broadcast.enqueue_chunk(txs.into_iter().map(|x| x.id()));
@@ -6,14 +6,19 @@ use kaspa_consensus_core::{
 use kaspa_consensusmanager::{BlockProcessingBatch, ConsensusProxy};
 use kaspa_core::debug;
 use kaspa_hashes::Hash;
+use kaspa_p2p_lib::PeerKey;
 use rand::Rng;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     iter::once,
+    time::{Duration, Instant},
 };
 
 use super::process_queue::ProcessQueue;
 
+/// The time window within which a requested orphan root is not requested again
+const ROOT_REQUEST_TTL: Duration = Duration::from_secs(10);
+
 /// The output of an orphan pool block query
 #[derive(Debug)]
 pub enum OrphanOutput {
@@ -34,6 +39,19 @@ enum FindRootsOutput {
     NoRoots(HashSet<Hash>),
 }
 
+/// A snapshot of orphan pool metrics, indicating orphan pressure and fragmentation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OrphanMetrics {
+    /// The number of orphans currently in the pool
+    pub count: usize,
+    /// The max number of orphans the pool is allowed to hold
+    pub capacity: usize,
+    /// The number of parent-child links between orphans both residing in the pool
+    pub total_children_edges: usize,
+    /// The length (in blocks) of the longest chain of orphans linked via direct parents
+    pub deepest_chain: usize,
+}
+
 struct OrphanBlock {
     /// The actual block
     block: Block,
@@ -59,6 +77,11 @@ pub struct OrphanBlocksPool {
     max_orphans: usize,
     /// The log base 2 of `max_orphans`
     max_orphans_log: usize,
+    /// Recently requested orphan roots keyed by the requesting peer along with their request time, ordered by
+    /// request time. Bounded by `max_orphans` where the oldest requests are dropped first
+    requested_roots: IndexMap<(PeerKey, Hash), Instant>,
+    /// The time window within which a requested root is suppressed
+    root_request_ttl: Duration,
 }
 
 impl OrphanBlocksPool {
@@ -67,7 +90,26 @@ impl OrphanBlocksPool {
             orphans: IndexMap::with_capacity(max_orphans),
             max_orphans,
             max_orphans_log: (max_orphans as f64).log2().ceil() as usize,
+            requested_roots: IndexMap::new(),
+            root_request_ttl: ROOT_REQUEST_TTL,
+        }
+    }
+
+    /// Updates the max number of orphans kept in the pool. When shrinking below the current pool size,
+    /// random orphans are evicted until the pool fits the new limit.
+    pub fn set_max_orphans(&mut self, max_orphans: usize) {
+        while self.orphans.len() > max_orphans {
+            let rand_index = rand::thread_rng().gen_range(0..self.orphans.len());
+            if let Some((evicted, _)) = self.orphans.swap_remove_index(rand_index) {
+                debug!("Evicted {} from the orphan blocks pool while shrinking its max size to {}", evicted, max_orphans);
+            }
+        }
+        // Only grow the allocation when needed, so repeated adjustments never reallocate needlessly
+        if max_orphans > self.orphans.capacity() {
+            self.orphans.reserve(max_orphans - self.orphans.len());
         }
+        self.max_orphans = max_orphans;
+        self.max_orphans_log = (max_orphans as f64).log2().ceil() as usize;
     }
 
     /// Adds the provided block to the orphan pool. Returns None if the block is already
@@ -129,11 +171,86 @@ impl OrphanBlocksPool {
         Some(OrphanOutput::Roots(roots))
     }
 
+    /// Records that the provided orphan roots were requested from `peer`, so that identical requests from the same
+    /// peer within the TTL window are suppressed by [`Self::filter_unrequested`]. Requests are tracked per peer so
+    /// that a root requested from a peer which then failed to deliver it can still be requested from other peers
+    pub fn mark_roots_requested(&mut self, peer: PeerKey, roots: &[Hash]) {
+        let now = Instant::now();
+        for &root in roots {
+            // Re-insert in order to keep the map ordered by request time
+            self.requested_roots.shift_remove(&(peer, root));
+            self.requested_roots.insert((peer, root), now);
+        }
+        // Drop expired requests and keep the cache bounded, oldest first
+        while let Some((_, &requested_at)) = self.requested_roots.first() {
+            if now.duration_since(requested_at) < self.root_request_ttl && self.requested_roots.len() <= self.max_orphans {
+                break;
+            }
+            self.requested_roots.shift_remove_index(0);
+        }
+    }
+
+    /// Returns the roots out of `roots` which were not requested from `peer` within the TTL window
+    pub fn filter_unrequested(&self, peer: PeerKey, roots: Vec<Hash>) -> Vec<Hash> {
+        roots
+            .into_iter()
+            .filter(|&root| {
+                self.requested_roots.get(&(peer, root)).is_none_or(|requested_at| requested_at.elapsed() >= self.root_request_ttl)
+            })
+            .collect()
+    }
+
     /// Returns whether this block is in the orphan pool.
     pub fn is_known_orphan(&self, hash: Hash) -> bool {
         self.orphans.contains_key(&hash)
     }
 
+    /// Returns a metrics snapshot of the pool. Computation is linear in the pool size (and thus bounded
+    /// by `max_orphans`) since each orphan depth is memoized and visited once.
+    pub fn metrics(&self) -> OrphanMetrics {
+        let total_children_edges =
+            self.orphans.values().map(|orphan| orphan.children.iter().filter(|&c| self.orphans.contains_key(c)).count()).sum();
+
+        // Depth of an orphan is 1 + the max depth of its orphan parents. Iterative post-order DFS
+        // avoids recursion and each depth is computed once
+        let mut depths: HashMap<Hash, usize> = HashMap::with_capacity(self.orphans.len());
+        let mut stack = Vec::new();
+        for &start in self.orphans.keys() {
+            if depths.contains_key(&start) {
+                continue;
+            }
+            stack.push(start);
+            while let Some(&current) = stack.last() {
+                if depths.contains_key(&current) {
+                    stack.pop();
+                    continue;
+                }
+                let mut depth = 0;
+                let mut pending = false;
+                for parent in self.orphans[&current].block.header.direct_parents().iter().filter(|&p| self.orphans.contains_key(p)) {
+                    match depths.get(parent) {
+                        Some(&parent_depth) => depth = depth.max(parent_depth),
+                        None => {
+                            stack.push(*parent);
+                            pending = true;
+                        }
+                    }
+                }
+                if !pending {
+                    stack.pop();
+                    depths.insert(current, depth + 1);
+                }
+            }
+        }
+
+        OrphanMetrics {
+            count: self.orphans.len(),
+            capacity: self.max_orphans,
+            total_children_edges,
+            deepest_chain: depths.values().copied().max().unwrap_or_default(),
+        }
+    }
+
     /// Returns the orphan roots of the provided orphan. Orphan roots are ancestors of this orphan which are
     /// not in the orphan pool AND do not exist consensus-wise or are header-only. Given an orphan relayed by
     /// a peer, these blocks should be the next-in-line to be requested from that peer.
@@ -289,8 +406,10 @@ mod tests {
     };
     use kaspa_consensusmanager::{ConsensusInstance, SessionLock};
     use kaspa_core::assert_match;
+    use kaspa_utils::networking::PeerId;
     use parking_lot::RwLock;
-    use std::sync::Arc;
+    use std::{net::Ipv4Addr, sync::Arc};
+    use uuid::Uuid;
 
     #[derive(Default)]
     struct MockProcessor {
@@ -360,4 +479,97 @@ mod tests {
 
         drop((a, b, c, d, e, f, g, h, k));
     }
+
+    #[test]
+    fn test_orphan_root_requests_dedup() {
+        let mut pool = OrphanBlocksPool::new(4);
+        let peer = |id: u128| PeerKey::new(PeerId::new(Uuid::from_u128(id)), Ipv4Addr::LOCALHOST.into());
+        let (p1, p2) = (peer(1), peer(2));
+        let roots: Vec<Hash> = vec![1.into(), 2.into()];
+
+        assert_eq!(pool.filter_unrequested(p1, roots.clone()), roots);
+        pool.mark_roots_requested(p1, &roots);
+
+        // A root requested twice from the same peer within the TTL is filtered the second time
+        assert!(pool.filter_unrequested(p1, roots.clone()).is_empty());
+        assert_eq!(pool.filter_unrequested(p1, vec![2.into(), 3.into()]), vec![3.into()]);
+
+        // Requests from one peer do not suppress requests from other peers
+        assert_eq!(pool.filter_unrequested(p2, roots.clone()), roots);
+
+        // The cache is bounded by the pool capacity, dropping the oldest requests first
+        pool.mark_roots_requested(p1, &[3.into(), 4.into(), 5.into()]);
+        assert_eq!(pool.requested_roots.len(), 4);
+        assert_eq!(pool.filter_unrequested(p1, roots.clone()), vec![1.into()]);
+
+        // Requests expire once the TTL elapses
+        pool.root_request_ttl = Duration::from_millis(10);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(pool.filter_unrequested(p1, roots.clone()), roots);
+        pool.mark_roots_requested(p2, &[6.into()]);
+        assert_eq!(pool.requested_roots.keys().copied().collect::<Vec<_>>(), vec![(p2, 6.into())]);
+    }
+
+    #[tokio::test]
+    async fn test_orphan_pool_set_max_orphans() {
+        let max_orphans = 10;
+        let ci = ConsensusInstance::new(SessionLock::new(), Arc::new(MockProcessor::default()));
+        let consensus = ci.session().await;
+        let mut pool = OrphanBlocksPool::new(max_orphans);
+
+        // Fill the pool with orphans missing a common root
+        for i in 0..max_orphans as u64 {
+            pool.add_orphan(&consensus, Block::from_precomputed_hash((100 + i).into(), vec![1.into()])).await.unwrap();
+        }
+        assert_eq!(pool.orphans.len(), max_orphans);
+
+        // Shrinking trims the pool to the new size
+        pool.set_max_orphans(4);
+        assert_eq!(pool.orphans.len(), 4);
+        assert_eq!(pool.max_orphans, 4);
+        assert_eq!(pool.max_orphans_log, 2);
+
+        // The new cap is enforced by evicting on insertion
+        for i in 0..4 {
+            pool.add_orphan(&consensus, Block::from_precomputed_hash((200 + i).into(), vec![1.into()])).await.unwrap();
+            assert_eq!(pool.orphans.len(), 4);
+        }
+
+        // Growing raises the cap without evicting and keeps the allocation large enough
+        let capacity = pool.orphans.capacity();
+        pool.set_max_orphans(8);
+        assert_eq!(pool.orphans.capacity(), capacity);
+        pool.set_max_orphans(capacity + 8);
+        assert!(pool.orphans.capacity() >= capacity + 8);
+        for i in 0..4 {
+            pool.add_orphan(&consensus, Block::from_precomputed_hash((300 + i).into(), vec![1.into()])).await.unwrap();
+        }
+        assert_eq!(pool.orphans.len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_orphan_pool_metrics() {
+        let ci = ConsensusInstance::new(SessionLock::new(), Arc::new(MockProcessor::default()));
+        let consensus = ci.session().await;
+        let mut pool = OrphanBlocksPool::new(10);
+        assert_eq!(pool.metrics(), OrphanMetrics { count: 0, capacity: 10, total_children_edges: 0, deepest_chain: 0 });
+
+        // A diamond of orphans missing root 1 alongside an isolated orphan missing root 2
+        let blocks = [
+            Block::from_precomputed_hash(10.into(), vec![1.into()]),
+            Block::from_precomputed_hash(11.into(), vec![10.into()]),
+            Block::from_precomputed_hash(12.into(), vec![10.into()]),
+            Block::from_precomputed_hash(13.into(), vec![11.into(), 12.into()]),
+            Block::from_precomputed_hash(20.into(), vec![2.into()]),
+        ];
+        // Insert in reverse order to verify children are tracked regardless of arrival order
+        for block in blocks.iter().rev() {
+            pool.add_orphan(&consensus, block.clone()).await.unwrap();
+        }
+        assert_eq!(pool.metrics(), OrphanMetrics { count: 5, capacity: 10, total_children_edges: 4, deepest_chain: 3 });
+
+        // Removing the diamond tip shortens the deepest chain and drops its incoming edges
+        pool.orphans.swap_remove(&Hash::from(13u64));
+        assert_eq!(pool.metrics(), OrphanMetrics { count: 4, capacity: 10, total_children_edges: 2, deepest_chain: 2 });
+    }
 }
@@ -111,7 +111,7 @@ impl HandleRelayInvsFlow {
                 OrphanOutput::NoRoots(_) => continue, // Existing orphan w/o missing roots
                 OrphanOutput::Roots(roots) => {
                     // Known orphan with roots to enqueue
-                    self.enqueue_orphan_roots(inv.hash, roots, inv.known_within_range);
+                    self.enqueue_orphan_roots(inv.hash, roots, inv.known_within_range).await;
                     continue;
                 }
             }
@@ -216,7 +216,9 @@ impl HandleRelayInvsFlow {
         }
     }
 
-    fn enqueue_orphan_roots(&mut self, _orphan: Hash, roots: Vec<Hash>, known_within_range: bool) {
+    async fn enqueue_orphan_roots(&mut self, _orphan: Hash, roots: Vec<Hash>, known_within_range: bool) {
+        // Orphans often share roots, so roots which were recently requested from this peer are not requested again
+        let roots = self.ctx.filter_and_mark_orphan_roots(self.router.key(), roots).await;
         self.invs_route.enqueue_indirect_invs(roots, known_within_range)
     }
 
@@ -285,7 +287,7 @@ impl HandleRelayInvsFlow {
                 }
                 Some(OrphanOutput::Roots(roots)) => {
                     self.ctx.log_block_event(BlockLogEvent::Orphaned(hash, roots.len()));
-                    self.enqueue_orphan_roots(hash, roots, known_within_range)
+                    self.enqueue_orphan_roots(hash, roots, known_within_range).await
                 }
                 None | Some(OrphanOutput::Unknown) => {}
             }
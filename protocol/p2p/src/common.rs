@@ -58,6 +58,9 @@ pub enum ProtocolError {
     #[error("outgoing route capacity has been reached (peer: {0})")]
     OutgoingRouteCapacityReached(String),
 
+    #[error("outgoing rate limit for message type {0:?} has been reached (peer: {1})")]
+    OutgoingRateLimitReached(KaspadMessagePayloadType, String),
+
     #[error("no flow has been registered for message type {0:?}")]
     NoRouteForMessageType(KaspadMessagePayloadType),
 
@@ -1,15 +1,22 @@
-use crate::{common::ProtocolError, pb::KaspadMessage, ConnectionInitializer, Peer, Router};
-use kaspa_core::{debug, info, warn};
-use parking_lot::RwLock;
+use crate::{common::ProtocolError, pb::KaspadMessage, ConnectionInitializer, KaspadMessagePayloadType, Peer, Router};
+use kaspa_core::{debug, info, task::spawn::spawn_named, trace, warn};
 use std::{
-    collections::{hash_map::Entry::Occupied, HashMap},
+    collections::HashSet,
     sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio::sync::mpsc::Receiver as MpscReceiver;
+use tokio::{sync::mpsc::Receiver as MpscReceiver, time::sleep};
 
-use super::peer::PeerKey;
+use super::{
+    peer::{PeerInfo, PeerKey},
+    peer_map::ShardedPeerMap,
+    rate_limiter::{PeerRateLimiter, RateLimiterConfig},
+};
 use rand::prelude::IteratorRandom;
 
+/// Subsystem prefix of tasks spawned by the hub
+const HUB: &str = "p2p-hub";
+
 #[derive(Debug)]
 pub(crate) enum HubEvent {
     NewPeer(Arc<Router>),
@@ -19,21 +26,31 @@ pub(crate) enum HubEvent {
 /// Hub of active peers (represented as Router objects). Note that all public methods of this type are exposed through the Adaptor
 #[derive(Debug, Clone)]
 pub struct Hub {
-    /// Map of currently active peers
+    /// Map of currently active peers, sharded in order to reduce lock contention between peer
+    /// additions/removals and broadcasts
     ///
     /// Note: the map key holds the node id and IP to prevent node impersonating.
-    pub(crate) peers: Arc<RwLock<HashMap<PeerKey, Arc<Router>>>>,
+    pub(crate) peers: Arc<ShardedPeerMap>,
+
+    /// Optional per-peer message rate limits, applied to both incoming and outgoing messages of every
+    /// registered peer. Messages exceeding the rate are dropped
+    rate_limiter_config: Option<Arc<RateLimiterConfig>>,
 }
 
 impl Hub {
     pub fn new() -> Self {
-        Self { peers: Arc::new(RwLock::new(HashMap::new())) }
+        Self { peers: Default::default(), rate_limiter_config: None }
+    }
+
+    /// Creates a hub which limits the rate of messages sent to and received from each peer according to `config`
+    pub fn with_rate_limiter(config: RateLimiterConfig) -> Self {
+        Self { peers: Default::default(), rate_limiter_config: Some(Arc::new(config)) }
     }
 
     /// Starts a loop for receiving central hub events from all peer routers. This mechanism is used for
     /// managing a collection of active peers and for supporting a broadcast operation.
     pub(crate) fn start_event_loop(self, mut hub_receiver: MpscReceiver<HubEvent>, initializer: Arc<dyn ConnectionInitializer>) {
-        tokio::spawn(async move {
+        spawn_named(HUB, "event-loop", async move {
             while let Some(new_event) = hub_receiver.recv().await {
                 match new_event {
                     HubEvent::NewPeer(new_router) => {
@@ -61,13 +78,10 @@ impl Hub {
                         }
                     }
                     HubEvent::PeerClosing(router) => {
-                        if let Occupied(entry) = self.peers.write().entry(router.key()) {
-                            // We search for the router by identity, but make sure to delete it only if it's actually the same object.
-                            // This is extremely important in cases of duplicate connection rejection etc.
-                            if Arc::ptr_eq(entry.get(), &router) {
-                                entry.remove_entry();
-                                debug!("P2P, Hub event loop, removing peer, router-id: {}", router.identity());
-                            }
+                        // We search for the router by identity, but make sure to delete it only if it's actually the same object.
+                        // This is extremely important in cases of duplicate connection rejection etc.
+                        if self.peers.remove_if_same(&router) {
+                            debug!("P2P, Hub event loop, removing peer, router-id: {}", router.identity());
                         }
                     }
                 }
@@ -77,7 +91,10 @@ impl Hub {
     }
 
     async fn insert_new_router(&self, new_router: Arc<Router>) {
-        let prev = self.peers.write().insert(new_router.key(), new_router);
+        if let Some(config) = self.rate_limiter_config.as_ref() {
+            new_router.set_rate_limiter(PeerRateLimiter::new(config.clone()));
+        }
+        let prev = self.peers.insert(new_router.key(), new_router);
         if let Some(previous_router) = prev {
             // This is not supposed to ever happen but can on rare race-conditions
             previous_router.close().await;
@@ -87,8 +104,8 @@ impl Hub {
 
     /// Selects a random subset of peers, trying to select at least half for outbound when possible
     fn select_some_peers(&self, num_peers: usize) -> impl Iterator<Item = Arc<Router>> {
-        let peers = self.peers.read();
-        let total_outbound = peers.values().filter(|peer| peer.is_outbound()).count();
+        let peers = self.peers.values();
+        let total_outbound = peers.iter().filter(|peer| peer.is_outbound()).count();
         let total_inbound = peers.len() - total_outbound;
 
         let mut outbound_count = ((num_peers + 1) / 2).min(total_outbound);
@@ -103,20 +120,39 @@ impl Hub {
 
         let thread_rng = &mut rand::thread_rng();
 
-        peers
-            .values()
-            .filter(|peer| peer.is_outbound())
-            .cloned()
+        let (outbound, inbound): (Vec<_>, Vec<_>) = peers.into_iter().partition(|peer| peer.is_outbound());
+        outbound
+            .into_iter()
             .choose_multiple(thread_rng, outbound_count) // Randomly select about half from outbound
             .into_iter() // Then select the rest from inbound
-            .chain(peers.values().filter(|peer| !peer.is_outbound()).cloned().choose_multiple(thread_rng, inbound_count))
+            .chain(inbound.into_iter().choose_multiple(thread_rng, inbound_count))
+    }
+
+    /// Checks whether `msg` may be sent to `router` under the configured rate limits (if any)
+    fn check_rate_limit(&self, router: &Router, msg: &KaspadMessage) -> Result<(), ProtocolError> {
+        let (Some(rate_limiter), Some(payload)) = (router.rate_limiter(), msg.payload.as_ref()) else {
+            return Ok(());
+        };
+        let payload_type = KaspadMessagePayloadType::from(payload);
+        if rate_limiter.check_outgoing(payload_type, Instant::now()) {
+            Ok(())
+        } else {
+            trace!("P2P, Hub, dropping {:?} message to peer {} due to rate limit", payload_type, router);
+            Err(ProtocolError::OutgoingRateLimitReached(payload_type, router.to_string()))
+        }
+    }
+
+    /// Enqueues `msg` to `router` if it is within the rate limits
+    async fn enqueue_limited(&self, router: &Router, msg: KaspadMessage) -> Result<(), ProtocolError> {
+        self.check_rate_limit(router, &msg)?;
+        router.enqueue(msg).await
     }
 
     /// Send a message to a specific peer
     pub async fn send(&self, peer_key: PeerKey, msg: KaspadMessage) -> Result<bool, ProtocolError> {
-        let op = self.peers.read().get(&peer_key).cloned();
+        let op = self.peers.get(&peer_key);
         if let Some(router) = op {
-            router.enqueue(msg).await?;
+            self.enqueue_limited(&router, msg).await?;
             Ok(true)
         } else {
             Ok(false)
@@ -125,12 +161,43 @@ impl Hub {
 
     /// Broadcast a message to all peers
     pub async fn broadcast(&self, msg: KaspadMessage) {
-        let peers = self.peers.read().values().cloned().collect::<Vec<_>>();
+        let peers = self.peers.values();
         for router in peers {
-            let _ = router.enqueue(msg.clone()).await;
+            let _ = self.enqueue_limited(&router, msg.clone()).await;
         }
     }
 
+    /// Broadcast a message to all peers, retrying peers for which sending failed up to `attempts` times in total.
+    /// The delay between consecutive rounds starts at `backoff` and is doubled each round. Peers which were removed
+    /// from the hub mid-flight (or whose connection was closed) are not retried. Returns the keys of the peers
+    /// which are still active but to which the message could not be delivered
+    pub async fn broadcast_with_retry(&self, msg: KaspadMessage, attempts: usize, backoff: Duration) -> HashSet<PeerKey> {
+        assert!(attempts > 0);
+        let mut pending = self.peers.values();
+        let mut delay = backoff;
+        for attempt in 1..=attempts {
+            let mut failed = Vec::new();
+            for router in pending {
+                match self.enqueue_limited(&router, msg.clone()).await {
+                    Ok(()) => {}
+                    Err(ProtocolError::ConnectionClosed) => {}
+                    Err(err) => {
+                        debug!("P2P, Hub, broadcast to peer {} failed (attempt {}/{}): {}", router, attempt, attempts, err);
+                        failed.push(router);
+                    }
+                }
+            }
+            if failed.is_empty() || attempt == attempts {
+                return failed.into_iter().map(|r| r.key()).collect();
+            }
+            sleep(delay).await;
+            delay = delay.saturating_mul(2);
+            // Only retry peers which are still registered with the hub (by identity)
+            pending = failed.into_iter().filter(|r| self.peers.is_registered(r)).collect();
+        }
+        unreachable!("the last attempt always returns")
+    }
+
     /// Broadcast a message to only some number of peers
     pub async fn broadcast_to_some_peers(&self, msg: KaspadMessage, num_peers: usize) {
         assert!(num_peers > 0);
@@ -138,7 +205,7 @@ impl Hub {
         let peers = self.select_some_peers(num_peers);
 
         for router in peers {
-            let _ = router.enqueue(msg.clone()).await;
+            let _ = self.enqueue_limited(&router, msg.clone()).await;
         }
     }
 
@@ -147,17 +214,17 @@ impl Hub {
         if msgs.is_empty() {
             return;
         }
-        let peers = self.peers.read().values().cloned().collect::<Vec<_>>();
+        let peers = self.peers.values();
         for router in peers {
             for msg in msgs.iter().cloned() {
-                let _ = router.enqueue(msg).await;
+                let _ = self.enqueue_limited(&router, msg).await;
             }
         }
     }
 
     /// Terminate a specific peer
     pub async fn terminate(&self, peer_key: PeerKey) {
-        let op = self.peers.read().get(&peer_key).cloned();
+        let op = self.peers.get(&peer_key);
         if let Some(router) = op {
             // This will eventually lead to peer removal through the Hub event loop
             router.close().await;
@@ -166,7 +233,7 @@ impl Hub {
 
     /// Terminate all peers
     pub async fn terminate_all_peers(&self) {
-        let peers = self.peers.write().drain().map(|(_, r)| r).collect::<Vec<_>>();
+        let peers = self.peers.drain();
         for router in peers {
             router.close().await;
         }
@@ -174,27 +241,34 @@ impl Hub {
 
     /// Returns a list of all currently active peers
     pub fn active_peers(&self) -> Vec<Peer> {
-        self.peers.read().values().map(|r| r.as_ref().into()).collect()
+        self.peers.values().iter().map(|r| r.as_ref().into()).collect()
+    }
+
+    /// Returns connection metadata of all currently active peers
+    pub fn peer_infos(&self) -> Vec<PeerInfo> {
+        // Clone the routers out so that the lock is held only briefly
+        let peers = self.peers.values();
+        peers.iter().map(|r| r.as_ref().into()).collect()
     }
 
     /// Returns the number of currently active peers
     pub fn active_peers_len(&self) -> usize {
-        self.peers.read().len()
+        self.peers.len()
     }
 
     /// Returns the number of outbound/inbound active peers (depending on the `outbound` argument)
     pub fn peers_query(&self, outbound: bool) -> usize {
-        self.peers.read().values().filter(|r| r.is_outbound() == outbound).count()
+        self.peers.count(|r| r.is_outbound() == outbound)
     }
 
     /// Returns whether there are currently active peers
     pub fn has_peers(&self) -> bool {
-        !self.peers.read().is_empty()
+        !self.peers.is_empty()
     }
 
     /// Returns whether a peer matching `peer_key` is registered
     pub fn has_peer(&self, peer_key: PeerKey) -> bool {
-        self.peers.read().contains_key(&peer_key)
+        self.peers.contains_key(&peer_key)
     }
 }
 
@@ -203,3 +277,197 @@ impl Default for Hub {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        make_message,
+        pb::{kaspad_message::Payload as KaspadMessagePayload, PingMessage, PongMessage},
+        RateLimit,
+    };
+    use futures::future::join_all;
+    use kaspa_utils::networking::PeerId;
+    use std::net::SocketAddr;
+    use tokio::{sync::mpsc::channel as mpsc_channel, time::timeout};
+    use uuid::Uuid;
+
+    fn mock_router(hub: &Hub, port: u16, is_outbound: bool) -> (Arc<Router>, MpscReceiver<KaspadMessage>) {
+        mock_router_with_capacity(hub, port, is_outbound, 16)
+    }
+
+    fn mock_router_with_capacity(
+        hub: &Hub,
+        port: u16,
+        is_outbound: bool,
+        capacity: usize,
+    ) -> (Arc<Router>, MpscReceiver<KaspadMessage>) {
+        let (hub_sender, _) = mpsc_channel(1);
+        let (outgoing_sender, outgoing_receiver) = mpsc_channel(capacity);
+        let address = SocketAddr::from(([127, 0, 0, 1], port));
+        let router = Router::new_mock(address, is_outbound, hub_sender, outgoing_sender);
+        router.set_identity(PeerId::new(Uuid::from_u128(port as u128)));
+        hub.peers.insert(router.key(), router.clone());
+        (router, outgoing_receiver)
+    }
+
+    #[test]
+    fn test_peer_infos() {
+        let hub = Hub::new();
+        let (outbound, _rx1) = mock_router(&hub, 16111, true);
+        let (inbound, _rx2) = mock_router(&hub, 16112, false);
+        let activity = inbound.connection_started() + Duration::from_millis(5);
+        inbound.mock_activity(activity);
+
+        let mut infos = hub.peer_infos();
+        infos.sort_by_key(|info| info.net_address.port());
+        assert_eq!(infos.len(), 2);
+
+        assert_eq!(infos[0].key, outbound.key());
+        assert_eq!(infos[0].net_address, outbound.net_address());
+        assert!(infos[0].is_outbound);
+        assert_eq!(infos[0].connection_started, outbound.connection_started());
+        // No activity was recorded, hence last activity is the connection time
+        assert_eq!(infos[0].last_activity, outbound.connection_started());
+
+        assert_eq!(infos[1].key, inbound.key());
+        assert!(!infos[1].is_outbound);
+        assert_eq!(infos[1].connection_started, inbound.connection_started());
+        assert_eq!(infos[1].last_activity, activity);
+        assert!(infos[1].time_since_last_activity() <= infos[1].time_connected());
+    }
+
+    fn ping(nonce: u64) -> KaspadMessage {
+        make_message!(KaspadMessagePayload::Ping, PingMessage { nonce })
+    }
+
+    // Runs with a paused clock, so the backoff rounds and the drainer below are ordered by virtual time only
+    #[tokio::test(start_paused = true)]
+    async fn test_broadcast_with_retry() {
+        let hub = Hub::new();
+        // Routers with a single-slot outgoing route which is already occupied, hence the first send fails
+        let (flaky, mut flaky_rx) = mock_router_with_capacity(&hub, 16111, true, 1);
+        let (removed, mut removed_rx) = mock_router_with_capacity(&hub, 16112, true, 1);
+        let (stuck, _stuck_rx) = mock_router_with_capacity(&hub, 16113, false, 1);
+        for router in [&flaky, &removed, &stuck] {
+            router.enqueue(ping(0)).await.unwrap();
+        }
+        let (_healthy, mut healthy_rx) = mock_router(&hub, 16114, false);
+
+        // Once the first attempt failed, free the flaky route and remove the other router from the hub
+        let hub_clone = hub.clone();
+        let removed_key = removed.key();
+        let drainer = tokio::spawn(async move {
+            sleep(Duration::from_millis(20)).await;
+            assert_eq!(flaky_rx.recv().await.unwrap().payload, ping(0).payload);
+            hub_clone.peers.remove(&removed_key);
+            flaky_rx
+        });
+
+        let start = tokio::time::Instant::now();
+        let failed = hub.broadcast_with_retry(ping(1), 3, Duration::from_millis(100)).await;
+
+        // Three attempts were made, with the backoff doubled between them
+        assert_eq!(start.elapsed(), Duration::from_millis(300));
+
+        // Only the stuck router, which is still active, is reported as failed
+        assert_eq!(failed, HashSet::from([stuck.key()]));
+
+        // The retry delivered the message to the flaky router
+        let mut flaky_rx = drainer.await.unwrap();
+        assert_eq!(flaky_rx.try_recv().unwrap().payload, ping(1).payload);
+
+        // The healthy router got the message on the first attempt
+        assert_eq!(healthy_rx.try_recv().unwrap().payload, ping(1).payload);
+        assert!(healthy_rx.try_recv().is_err());
+
+        // The removed router was not retried, so its route only holds the original message
+        assert_eq!(removed_rx.try_recv().unwrap().payload, ping(0).payload);
+        assert!(removed_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let hub =
+            Hub::with_rate_limiter(RateLimiterConfig::new().with_type_limit(KaspadMessagePayloadType::Ping, RateLimit::new(1.0, 3)));
+        let (router, mut outgoing_rx) = mock_router(&hub, 16111, true);
+
+        // Routers are limited only once registered through the hub
+        hub.peers.remove(&router.key());
+        assert!(router.rate_limiter().is_none());
+        hub.insert_new_router(router.clone()).await;
+        assert!(router.rate_limiter().is_some());
+
+        // Outgoing messages beyond the burst are dropped
+        let sent = join_all((0..10).map(|nonce| hub.send(router.key(), ping(nonce)))).await;
+        assert_eq!(sent.iter().filter(|res| res.is_ok()).count(), 3);
+        assert!(sent.iter().any(|res| matches!(res, Err(ProtocolError::OutgoingRateLimitReached(KaspadMessagePayloadType::Ping, _)))));
+        assert_eq!((0..10).filter_map(|_| outgoing_rx.try_recv().ok()).count(), 3);
+
+        // As are incoming messages, while unlimited message types are routed as usual
+        let mut pings = router.subscribe(vec![KaspadMessagePayloadType::Ping]);
+        let mut pongs = router.subscribe(vec![KaspadMessagePayloadType::Pong]);
+        for nonce in 0..10 {
+            router.route_to_flow(ping(nonce)).unwrap();
+            router.route_to_flow(make_message!(KaspadMessagePayload::Pong, PongMessage { nonce })).unwrap();
+        }
+        assert_eq!((0..10).filter_map(|_| pings.try_recv().ok()).count(), 3);
+        assert_eq!((0..10).filter_map(|_| pongs.try_recv().ok()).count(), 10);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_peer_updates() {
+        const TASKS: u16 = 8;
+        const ROUTERS_PER_TASK: u16 = 32;
+        const BROADCASTS: u64 = 50;
+
+        // Routers are created with a route capacity fitting all broadcasts, and are registered by the tasks below
+        let hub = Hub::new();
+        let routers = (0..TASKS * ROUTERS_PER_TASK)
+            .map(|i| {
+                let (router, rx) = mock_router_with_capacity(&hub, 20000 + i, i % 2 == 0, BROADCASTS as usize);
+                hub.peers.remove(&router.key());
+                (router, rx)
+            })
+            .collect::<Vec<_>>();
+        assert!(!hub.has_peers());
+
+        let mut tasks = Vec::new();
+        for chunk in routers.chunks(ROUTERS_PER_TASK as usize) {
+            let hub = hub.clone();
+            let chunk = chunk.iter().map(|(router, _)| router.clone()).collect::<Vec<_>>();
+            tasks.push(tokio::spawn(async move {
+                // Add all routers, then remove every other one, querying the hub in between
+                for router in chunk.iter() {
+                    hub.insert_new_router(router.clone()).await;
+                    assert!(hub.has_peer(router.key()));
+                    tokio::task::yield_now().await;
+                }
+                for router in chunk.iter().step_by(2) {
+                    assert!(hub.peers.remove_if_same(router));
+                    let _ = hub.peer_infos();
+                    tokio::task::yield_now().await;
+                }
+            }));
+        }
+        for nonce in 0..BROADCASTS {
+            let hub = hub.clone();
+            tasks.push(tokio::spawn(async move {
+                hub.broadcast(ping(nonce)).await;
+                hub.broadcast_to_some_peers(ping(nonce), 8).await;
+            }));
+        }
+        timeout(Duration::from_secs(10), join_all(tasks))
+            .await
+            .expect("peer updates deadlocked")
+            .into_iter()
+            .for_each(|res| res.unwrap());
+
+        // Exactly the odd routers of every task remain
+        let expected = routers.iter().skip(1).step_by(2).map(|(router, _)| router.key()).collect::<HashSet<_>>();
+        assert_eq!(hub.active_peers_len(), expected.len());
+        assert_eq!(hub.active_peers().iter().map(|peer| peer.key()).collect::<HashSet<_>>(), expected);
+        assert_eq!(hub.peers_query(true) + hub.peers_query(false), expected.len());
+        assert!(routers.iter().all(|(router, _)| hub.has_peer(router.key()) == expected.contains(&router.key())));
+    }
+}
@@ -3,4 +3,6 @@ pub mod connection_handler;
 pub mod hub;
 pub mod payload_type;
 pub mod peer;
+pub(crate) mod peer_map;
+pub mod rate_limiter;
 pub mod router;
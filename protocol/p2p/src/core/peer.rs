@@ -77,6 +77,10 @@ impl PeerKey {
     pub fn new(identity: PeerId, ip: IpAddress) -> Self {
         Self { identity, ip }
     }
+
+    pub fn identity(&self) -> PeerId {
+        self.identity
+    }
 }
 
 impl From<&Peer> for PeerKey {
@@ -90,3 +94,25 @@ impl Display for PeerKey {
         write!(f, "{}+{}", self.identity, self.ip)
     }
 }
+
+/// Connection metadata of an active peer, intended for monitoring
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    pub key: PeerKey,
+    pub net_address: SocketAddr,
+    pub is_outbound: bool,
+    pub connection_started: Instant,
+    pub last_activity: Instant,
+}
+
+impl PeerInfo {
+    /// Time since the connection was established, in milliseconds
+    pub fn time_connected(&self) -> u64 {
+        Instant::now().duration_since(self.connection_started).as_millis() as u64
+    }
+
+    /// Time since the last message was received from this peer, in milliseconds
+    pub fn time_since_last_activity(&self) -> u64 {
+        Instant::now().duration_since(self.last_activity).as_millis() as u64
+    }
+}
@@ -0,0 +1,96 @@
+use super::{peer::PeerKey, router::Router};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+/// Default number of shards of the active peers map
+pub(crate) const DEFAULT_PEER_MAP_SHARDS: usize = 16;
+
+type Shard = RwLock<HashMap<PeerKey, Arc<Router>>>;
+
+/// A map of the active peers sharded by peer identity, so that peer additions and removals (which require a
+/// write lock) only contend with operations on the same shard rather than with every reader.
+///
+/// Operations spanning all peers lock one shard at a time, hence they observe a per-shard consistent view
+/// which is not necessarily an atomic snapshot of the whole map
+#[derive(Debug)]
+pub(crate) struct ShardedPeerMap {
+    shards: Box<[Shard]>,
+}
+
+impl ShardedPeerMap {
+    /// Creates a map with `shards` shards, rounded up to a power of two
+    pub(crate) fn new(shards: usize) -> Self {
+        let shards = shards.max(1).next_power_of_two();
+        Self { shards: (0..shards).map(|_| Default::default()).collect() }
+    }
+
+    fn shard(&self, key: &PeerKey) -> &Shard {
+        // Fold both halves of the identity so that shards are balanced for random as well as sequential identities
+        let (high, low) = key.identity().0.as_u64_pair();
+        &self.shards[((high ^ low) as usize) & (self.shards.len() - 1)]
+    }
+
+    pub(crate) fn get(&self, key: &PeerKey) -> Option<Arc<Router>> {
+        self.shard(key).read().get(key).cloned()
+    }
+
+    pub(crate) fn contains_key(&self, key: &PeerKey) -> bool {
+        self.shard(key).read().contains_key(key)
+    }
+
+    /// Inserts `router` under `key`, returning the previously registered router, if any
+    pub(crate) fn insert(&self, key: PeerKey, router: Arc<Router>) -> Option<Arc<Router>> {
+        self.shard(&key).write().insert(key, router)
+    }
+
+    pub(crate) fn remove(&self, key: &PeerKey) -> Option<Arc<Router>> {
+        self.shard(key).write().remove(key)
+    }
+
+    /// Removes `router` only if it is the very object registered under its key. Returns whether it was removed
+    pub(crate) fn remove_if_same(&self, router: &Arc<Router>) -> bool {
+        let key = router.key();
+        let mut shard = self.shard(&key).write();
+        if shard.get(&key).is_some_and(|current| Arc::ptr_eq(current, router)) {
+            shard.remove(&key);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Indicates whether `router` is the very object registered under its key
+    pub(crate) fn is_registered(&self, router: &Arc<Router>) -> bool {
+        let key = router.key();
+        self.shard(&key).read().get(&key).is_some_and(|current| Arc::ptr_eq(current, router))
+    }
+
+    /// Returns all registered routers
+    pub(crate) fn values(&self) -> Vec<Arc<Router>> {
+        self.shards.iter().flat_map(|shard| shard.read().values().cloned().collect::<Vec<_>>()).collect()
+    }
+
+    /// Removes and returns all registered routers
+    pub(crate) fn drain(&self) -> Vec<Arc<Router>> {
+        self.shards.iter().flat_map(|shard| shard.write().drain().map(|(_, router)| router).collect::<Vec<_>>()).collect()
+    }
+
+    /// Returns the number of registered routers matching `predicate`
+    pub(crate) fn count(&self, predicate: impl Fn(&Router) -> bool) -> usize {
+        self.shards.iter().map(|shard| shard.read().values().filter(|router| predicate(router)).count()).sum()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.read().is_empty())
+    }
+}
+
+impl Default for ShardedPeerMap {
+    fn default() -> Self {
+        Self::new(DEFAULT_PEER_MAP_SHARDS)
+    }
+}
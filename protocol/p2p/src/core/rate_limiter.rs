@@ -0,0 +1,155 @@
+use crate::KaspadMessagePayloadType;
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+/// A token-bucket rate limit: messages are admitted at a sustained `rate` (messages per second)
+/// with bursts of up to `burst` messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub rate: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(rate: f64, burst: u32) -> Self {
+        assert!(rate > 0.0, "rate limit must be positive");
+        assert!(burst > 0, "burst must be positive");
+        Self { rate, burst }
+    }
+}
+
+/// Per-peer rate limiting configuration. Limits can be set per message type, where message types
+/// with no specific limit fall back to the default limit (if any), and otherwise are not limited
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiterConfig {
+    default_limit: Option<RateLimit>,
+    type_limits: HashMap<KaspadMessagePayloadType, RateLimit>,
+}
+
+impl RateLimiterConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    pub fn with_type_limit(mut self, payload_type: KaspadMessagePayloadType, limit: RateLimit) -> Self {
+        self.type_limits.insert(payload_type, limit);
+        self
+    }
+
+    pub fn limit_for(&self, payload_type: KaspadMessagePayloadType) -> Option<RateLimit> {
+        self.type_limits.get(&payload_type).copied().or(self.default_limit)
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, last_refill: now }
+    }
+
+    fn try_consume(&mut self, limit: RateLimit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.rate).min(limit.burst as f64);
+        self.last_refill = self.last_refill.max(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Holds the token buckets of a single peer, keyed by message type, separately for incoming and outgoing messages.
+/// The limiter is owned by the peer router, so its state is released along with the router
+#[derive(Debug)]
+pub(crate) struct PeerRateLimiter {
+    config: Arc<RateLimiterConfig>,
+    incoming: Mutex<HashMap<KaspadMessagePayloadType, TokenBucket>>,
+    outgoing: Mutex<HashMap<KaspadMessagePayloadType, TokenBucket>>,
+}
+
+impl PeerRateLimiter {
+    pub(crate) fn new(config: Arc<RateLimiterConfig>) -> Self {
+        Self { config, incoming: Default::default(), outgoing: Default::default() }
+    }
+
+    /// Returns whether a message of `payload_type` received from the peer at `now` is within the configured rate
+    pub(crate) fn check_incoming(&self, payload_type: KaspadMessagePayloadType, now: Instant) -> bool {
+        self.check(&self.incoming, payload_type, now)
+    }
+
+    /// Returns whether a message of `payload_type` sent to the peer at `now` is within the configured rate
+    pub(crate) fn check_outgoing(&self, payload_type: KaspadMessagePayloadType, now: Instant) -> bool {
+        self.check(&self.outgoing, payload_type, now)
+    }
+
+    fn check(
+        &self,
+        buckets: &Mutex<HashMap<KaspadMessagePayloadType, TokenBucket>>,
+        payload_type: KaspadMessagePayloadType,
+        now: Instant,
+    ) -> bool {
+        let Some(limit) = self.config.limit_for(payload_type) else {
+            return true;
+        };
+        buckets.lock().entry(payload_type).or_insert_with(|| TokenBucket::new(limit, now)).try_consume(limit, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_excess_messages_are_dropped() {
+        let config = RateLimiterConfig::new()
+            .with_default_limit(RateLimit::new(2.0, 3))
+            .with_type_limit(KaspadMessagePayloadType::Block, RateLimit::new(100.0, 50));
+        let limiter = PeerRateLimiter::new(Arc::new(config));
+        let start = Instant::now();
+
+        // Drive 10 address messages at the same instant: only the burst is admitted
+        let admitted = (0..10).filter(|_| limiter.check_outgoing(KaspadMessagePayloadType::Addresses, start)).count();
+        assert_eq!(admitted, 3);
+
+        // Blocks have a separate, higher limit and are unaffected by the exhausted address bucket
+        let admitted = (0..10).filter(|_| limiter.check_outgoing(KaspadMessagePayloadType::Block, start)).count();
+        assert_eq!(admitted, 10);
+
+        // Incoming messages have their own buckets
+        let admitted = (0..10).filter(|_| limiter.check_incoming(KaspadMessagePayloadType::Addresses, start)).count();
+        assert_eq!(admitted, 3);
+
+        // After one second the bucket is refilled according to the rate
+        let later = start + Duration::from_secs(1);
+        let admitted = (0..10).filter(|_| limiter.check_outgoing(KaspadMessagePayloadType::Addresses, later)).count();
+        assert_eq!(admitted, 2);
+
+        // Refill is capped by the burst size
+        let much_later = later + Duration::from_secs(60);
+        let admitted = (0..10).filter(|_| limiter.check_outgoing(KaspadMessagePayloadType::Addresses, much_later)).count();
+        assert_eq!(admitted, 3);
+    }
+
+    #[test]
+    fn test_unlimited_types() {
+        let config = RateLimiterConfig::new().with_type_limit(KaspadMessagePayloadType::Addresses, RateLimit::new(1.0, 1));
+        let limiter = PeerRateLimiter::new(Arc::new(config));
+        let now = Instant::now();
+        assert!((0..100).all(|_| limiter.check_outgoing(KaspadMessagePayloadType::InvRelayBlock, now)));
+        assert!(limiter.check_outgoing(KaspadMessagePayloadType::Addresses, now));
+        assert!(!limiter.check_outgoing(KaspadMessagePayloadType::Addresses, now));
+    }
+}
@@ -11,6 +11,7 @@ use std::fmt::{Debug, Display};
 use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
 use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
 use tokio::select;
@@ -19,7 +20,10 @@ use tokio::sync::mpsc::{channel as mpsc_channel, Receiver as MpscReceiver, Sende
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tonic::Streaming;
 
-use super::peer::{PeerKey, PeerProperties};
+use super::{
+    peer::{PeerInfo, PeerKey, PeerProperties},
+    rate_limiter::PeerRateLimiter,
+};
 
 pub struct IncomingRoute {
     rx: MpscReceiver<KaspadMessage>,
@@ -126,6 +130,9 @@ pub struct Router {
     /// Time of creation of this object and the connection it holds
     connection_started: Instant,
 
+    /// Time of the last message received from this peer
+    last_activity: SeqLock<Instant>,
+
     /// Routing map for mapping messages to subscribed flows
     routing_map_by_type: RwLock<HashMap<KaspadMessagePayloadType, MpscSender<KaspadMessage>>>,
 
@@ -139,6 +146,9 @@ pub struct Router {
 
     /// Used for managing router mutable state
     mutable_state: Mutex<RouterMutableState>,
+
+    /// Optional rate limits of messages from and to this peer. Set by the hub once the peer is registered
+    rate_limiter: OnceLock<PeerRateLimiter>,
 }
 
 impl Display for Router {
@@ -153,6 +163,18 @@ impl From<&Router> for PeerKey {
     }
 }
 
+impl From<&Router> for PeerInfo {
+    fn from(router: &Router) -> Self {
+        Self {
+            key: router.key(),
+            net_address: router.net_address,
+            is_outbound: router.is_outbound,
+            connection_started: router.connection_started,
+            last_activity: router.last_activity(),
+        }
+    }
+}
+
 impl From<&Router> for Peer {
     fn from(router: &Router) -> Self {
         Self::new(
@@ -183,16 +205,19 @@ impl Router {
         let (start_sender, start_receiver) = oneshot_channel();
         let (shutdown_sender, mut shutdown_receiver) = oneshot_channel();
 
+        let connection_started = Instant::now();
         let router = Arc::new(Router {
             identity: Default::default(),
             net_address,
             is_outbound,
-            connection_started: Instant::now(),
+            connection_started,
+            last_activity: SeqLock::new(connection_started),
             routing_map_by_type: RwLock::new(HashMap::new()),
             routing_map_by_id: RwLock::new(HashMap::new()),
             outgoing_route,
             hub_sender,
             mutable_state: Mutex::new(RouterMutableState::new(Some(start_sender), Some(shutdown_sender))),
+            rate_limiter: OnceLock::new(),
         });
 
         let router_clone = router.clone();
@@ -212,6 +237,7 @@ impl Router {
                     res = incoming_stream.message() => match res {
                         Ok(Some(msg)) => {
                             trace!("P2P msg: {:?}, router-id: {}, peer: {}", message_summary(&msg), router.identity(), router);
+                            router.update_last_activity();
                             match router.route_to_flow(msg) {
                                 Ok(()) => {},
                                 Err(e) => {
@@ -277,6 +303,15 @@ impl Router {
         Instant::now().duration_since(self.connection_started).as_millis() as u64
     }
 
+    /// Time of the last message received from this peer (or of connection creation if none were received yet)
+    pub fn last_activity(&self) -> Instant {
+        self.last_activity.read()
+    }
+
+    fn update_last_activity(&self) {
+        *self.last_activity.lock_write() = Instant::now();
+    }
+
     pub fn properties(&self) -> Arc<PeerProperties> {
         self.mutable_state.lock().properties.clone()
     }
@@ -294,6 +329,15 @@ impl Router {
         self.mutable_state.lock().last_ping_duration
     }
 
+    pub(crate) fn rate_limiter(&self) -> Option<&PeerRateLimiter> {
+        self.rate_limiter.get()
+    }
+
+    /// Sets the rate limiter of this peer. Has no effect if a limiter was already set
+    pub(crate) fn set_rate_limiter(&self, rate_limiter: PeerRateLimiter) {
+        let _ = self.rate_limiter.set(rate_limiter);
+    }
+
     pub fn incoming_flow_baseline_channel_size() -> usize {
         256
     }
@@ -374,6 +418,14 @@ impl Router {
             return Err(ProtocolError::from_reject_message(reject.reason));
         }
 
+        // Drop messages exceeding the incoming rate limits (if any), protecting the flows from a flooding peer
+        if let Some(rate_limiter) = self.rate_limiter() {
+            if !rate_limiter.check_incoming(msg_type, Instant::now()) {
+                trace!("P2P, dropping {:?} message from peer {} due to rate limit", msg_type, self);
+                return Ok(());
+            }
+        }
+
         let op = if msg.response_id != BLANK_ROUTE_ID {
             self.routing_map_by_id.read().get(&msg.response_id).cloned()
         } else {
@@ -451,6 +503,37 @@ impl Router {
     }
 }
 
+#[cfg(test)]
+impl Router {
+    /// Creates a router with no underlying network connection for testing purposes. Messages enqueued
+    /// to this router are sent to `outgoing_route`
+    pub(crate) fn new_mock(
+        net_address: SocketAddr,
+        is_outbound: bool,
+        hub_sender: MpscSender<HubEvent>,
+        outgoing_route: MpscSender<KaspadMessage>,
+    ) -> Arc<Self> {
+        let connection_started = Instant::now();
+        Arc::new(Router {
+            identity: Default::default(),
+            net_address,
+            is_outbound,
+            connection_started,
+            last_activity: SeqLock::new(connection_started),
+            routing_map_by_type: RwLock::new(HashMap::new()),
+            routing_map_by_id: RwLock::new(HashMap::new()),
+            outgoing_route,
+            hub_sender,
+            mutable_state: Mutex::new(RouterMutableState::new(None, None)),
+            rate_limiter: OnceLock::new(),
+        })
+    }
+
+    pub(crate) fn mock_activity(&self, instant: Instant) {
+        *self.last_activity.lock_write() = instant;
+    }
+}
+
 fn match_for_io_error(err_status: &tonic::Status) -> Option<&std::io::Error> {
     let mut err: &(dyn std::error::Error + 'static) = err_status;
 
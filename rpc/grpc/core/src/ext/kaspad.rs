@@ -1,20 +1,71 @@
-use kaspa_notify::{scope::Scope, subscription::Command};
+use kaspa_addresses::{Address, AddressError};
+use kaspa_notify::{
+    scope::{
+        BlockAddedScope, FinalityConflictScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope,
+        SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+    },
+    subscription::Command,
+};
+use std::collections::HashSet;
+use thiserror::Error;
 
-use crate::protowire::{
-    kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage,
-    NotifyFinalityConflictRequestMessage, NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
-    NotifySinkBlueScoreChangedRequestMessage, NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage,
-    NotifyVirtualDaaScoreChangedRequestMessage,
+use crate::{
+    ops::KaspadPayloadOps,
+    protowire::{
+        kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage,
+        NotifyFinalityConflictRequestMessage, NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
+        NotifySinkBlueScoreChangedRequestMessage, NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage,
+        NotifyVirtualDaaScoreChangedRequestMessage,
+    },
 };
 
+/// Error returned when mapping a gRPC request back to a notification scope
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum GrpcScopeError {
+    #[error("the request has no payload")]
+    MissingPayload,
+
+    #[error("the request {0:?} is not a subscription")]
+    NotSubscription(KaspadPayloadOps),
+
+    #[error("invalid address in UtxosChanged scope: {0}")]
+    InvalidAddress(#[from] AddressError),
+}
+
 impl KaspadRequest {
     pub fn from_notification_type(scope: &Scope, command: Command) -> Self {
         KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::from_notification_type(scope, command)) }
     }
 
+    /// Builds a single `UtxosChanged` subscription request out of multiple scopes. See [`merge_utxos_changed_scopes`].
+    pub fn from_utxos_changed_scopes<'a>(scopes: impl IntoIterator<Item = &'a UtxosChangedScope>, command: Command) -> Self {
+        KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::from_utxos_changed_scopes(scopes, command)) }
+    }
+
     pub fn is_subscription(&self) -> bool {
         self.payload.as_ref().is_some_and(|x| x.is_subscription())
     }
+
+    /// Returns the scope and command of a subscription request. See [`kaspad_request::Payload::subscription_info`].
+    pub fn subscription_info(&self) -> Option<(Scope, Command)> {
+        self.payload.as_ref().and_then(|x| x.subscription_info())
+    }
+}
+
+/// Merges multiple `UtxosChanged` scopes into a single scope holding the deduplicated union of their
+/// addresses, preserving the order of first occurrence.
+///
+/// Note that an empty address set stands for all addresses, so if any of the scopes is empty, so is the merged scope.
+pub fn merge_utxos_changed_scopes<'a>(scopes: impl IntoIterator<Item = &'a UtxosChangedScope>) -> UtxosChangedScope {
+    let mut seen = HashSet::new();
+    let mut addresses = Vec::new();
+    for scope in scopes {
+        if scope.addresses.is_empty() {
+            return UtxosChangedScope::default();
+        }
+        addresses.extend(scope.addresses.iter().filter(|&address| seen.insert(address)).cloned());
+    }
+    UtxosChangedScope::new(addresses)
 }
 
 impl kaspad_request::Payload {
@@ -45,10 +96,7 @@ impl kaspad_request::Payload {
                     command: command.into(),
                 })
             }
-            Scope::UtxosChanged(ref scope) => kaspad_request::Payload::NotifyUtxosChangedRequest(NotifyUtxosChangedRequestMessage {
-                addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
-                command: command.into(),
-            }),
+            Scope::UtxosChanged(ref scope) => Self::from_utxos_changed_scopes([scope], command),
             Scope::SinkBlueScoreChanged(_) => {
                 kaspad_request::Payload::NotifySinkBlueScoreChangedRequest(NotifySinkBlueScoreChangedRequestMessage {
                     command: command.into(),
@@ -67,6 +115,15 @@ impl kaspad_request::Payload {
         }
     }
 
+    /// Builds a single `UtxosChanged` subscription payload out of multiple scopes. See [`merge_utxos_changed_scopes`].
+    pub fn from_utxos_changed_scopes<'a>(scopes: impl IntoIterator<Item = &'a UtxosChangedScope>, command: Command) -> Self {
+        let scope = merge_utxos_changed_scopes(scopes);
+        kaspad_request::Payload::NotifyUtxosChangedRequest(NotifyUtxosChangedRequestMessage {
+            addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+            command: command.into(),
+        })
+    }
+
     pub fn is_subscription(&self) -> bool {
         use crate::protowire::kaspad_request::Payload;
         matches!(
@@ -83,6 +140,64 @@ impl kaspad_request::Payload {
                 | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_)
         )
     }
+
+    /// Returns the parsed scope and command of a subscription payload in one step, with the `StopNotifying*`
+    /// variants mapping to [`Command::Stop`]. Returns `None` if the payload is not a subscription or if its
+    /// scope cannot be parsed (see [`GrpcScopeError`]).
+    pub fn subscription_info(&self) -> Option<(Scope, Command)> {
+        use crate::protowire::kaspad_request::Payload;
+        let command = match self {
+            Payload::NotifyBlockAddedRequest(message) => message.command,
+            Payload::NotifyVirtualChainChangedRequest(message) => message.command,
+            Payload::NotifyFinalityConflictRequest(message) => message.command,
+            Payload::NotifyUtxosChangedRequest(message) => message.command,
+            Payload::NotifySinkBlueScoreChangedRequest(message) => message.command,
+            Payload::NotifyVirtualDaaScoreChangedRequest(message) => message.command,
+            Payload::NotifyPruningPointUtxoSetOverrideRequest(message) => message.command,
+            Payload::NotifyNewBlockTemplateRequest(message) => message.command,
+            Payload::StopNotifyingUtxosChangedRequest(_) | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_) => {
+                Command::Stop.into()
+            }
+            _ => return None,
+        };
+        Scope::try_from(self).ok().map(|scope| (scope, command.into()))
+    }
+}
+
+impl TryFrom<&KaspadRequest> for Scope {
+    type Error = GrpcScopeError;
+
+    fn try_from(request: &KaspadRequest) -> Result<Self, Self::Error> {
+        request.payload.as_ref().ok_or(GrpcScopeError::MissingPayload)?.try_into()
+    }
+}
+
+impl TryFrom<&kaspad_request::Payload> for Scope {
+    type Error = GrpcScopeError;
+
+    fn try_from(payload: &kaspad_request::Payload) -> Result<Self, Self::Error> {
+        use crate::protowire::kaspad_request::Payload;
+        let parse_addresses = |addresses: &[String]| {
+            addresses.iter().map(|x| Address::try_from(x.as_str())).collect::<Result<Vec<_>, _>>().map(UtxosChangedScope::new)
+        };
+        let scope = match payload {
+            Payload::NotifyBlockAddedRequest(_) => BlockAddedScope::default().into(),
+            Payload::NotifyVirtualChainChangedRequest(message) => {
+                VirtualChainChangedScope::new(message.include_accepted_transaction_ids).into()
+            }
+            Payload::NotifyFinalityConflictRequest(_) => FinalityConflictScope::default().into(),
+            Payload::NotifyUtxosChangedRequest(message) => parse_addresses(&message.addresses)?.into(),
+            Payload::StopNotifyingUtxosChangedRequest(message) => parse_addresses(&message.addresses)?.into(),
+            Payload::NotifySinkBlueScoreChangedRequest(_) => SinkBlueScoreChangedScope::default().into(),
+            Payload::NotifyVirtualDaaScoreChangedRequest(_) => VirtualDaaScoreChangedScope::default().into(),
+            Payload::NotifyPruningPointUtxoSetOverrideRequest(_) | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_) => {
+                PruningPointUtxoSetOverrideScope::default().into()
+            }
+            Payload::NotifyNewBlockTemplateRequest(_) => NewBlockTemplateScope::default().into(),
+            payload => return Err(GrpcScopeError::NotSubscription(payload.into())),
+        };
+        Ok(scope)
+    }
 }
 
 impl KaspadResponse {
@@ -111,4 +226,173 @@ impl kaspad_response::Payload {
             _ => false,
         }
     }
+
+    /// Returns the name of the payload variant, e.g., for logging and metrics purposes
+    pub fn var_name(&self) -> &'static str {
+        use crate::protowire::kaspad_response::Payload;
+        // Note: keep this match exhaustive (no catch-all) so that any new variant requires an explicit name
+        match self {
+            Payload::GetCurrentNetworkResponse(_) => "GetCurrentNetworkResponse",
+            Payload::SubmitBlockResponse(_) => "SubmitBlockResponse",
+            Payload::GetBlockTemplateResponse(_) => "GetBlockTemplateResponse",
+            Payload::NotifyBlockAddedResponse(_) => "NotifyBlockAddedResponse",
+            Payload::BlockAddedNotification(_) => "BlockAddedNotification",
+            Payload::GetPeerAddressesResponse(_) => "GetPeerAddressesResponse",
+            Payload::GetSinkResponse(_) => "GetSinkResponse",
+            Payload::GetMempoolEntryResponse(_) => "GetMempoolEntryResponse",
+            Payload::GetConnectedPeerInfoResponse(_) => "GetConnectedPeerInfoResponse",
+            Payload::AddPeerResponse(_) => "AddPeerResponse",
+            Payload::SubmitTransactionResponse(_) => "SubmitTransactionResponse",
+            Payload::NotifyVirtualChainChangedResponse(_) => "NotifyVirtualChainChangedResponse",
+            Payload::VirtualChainChangedNotification(_) => "VirtualChainChangedNotification",
+            Payload::GetBlockResponse(_) => "GetBlockResponse",
+            Payload::GetSubnetworkResponse(_) => "GetSubnetworkResponse",
+            Payload::GetVirtualChainFromBlockResponse(_) => "GetVirtualChainFromBlockResponse",
+            Payload::GetBlocksResponse(_) => "GetBlocksResponse",
+            Payload::GetBlockCountResponse(_) => "GetBlockCountResponse",
+            Payload::GetBlockDagInfoResponse(_) => "GetBlockDagInfoResponse",
+            Payload::ResolveFinalityConflictResponse(_) => "ResolveFinalityConflictResponse",
+            Payload::NotifyFinalityConflictResponse(_) => "NotifyFinalityConflictResponse",
+            Payload::FinalityConflictNotification(_) => "FinalityConflictNotification",
+            Payload::FinalityConflictResolvedNotification(_) => "FinalityConflictResolvedNotification",
+            Payload::GetMempoolEntriesResponse(_) => "GetMempoolEntriesResponse",
+            Payload::ShutdownResponse(_) => "ShutdownResponse",
+            Payload::GetHeadersResponse(_) => "GetHeadersResponse",
+            Payload::NotifyUtxosChangedResponse(_) => "NotifyUtxosChangedResponse",
+            Payload::UtxosChangedNotification(_) => "UtxosChangedNotification",
+            Payload::GetUtxosByAddressesResponse(_) => "GetUtxosByAddressesResponse",
+            Payload::GetSinkBlueScoreResponse(_) => "GetSinkBlueScoreResponse",
+            Payload::NotifySinkBlueScoreChangedResponse(_) => "NotifySinkBlueScoreChangedResponse",
+            Payload::SinkBlueScoreChangedNotification(_) => "SinkBlueScoreChangedNotification",
+            Payload::BanResponse(_) => "BanResponse",
+            Payload::UnbanResponse(_) => "UnbanResponse",
+            Payload::GetInfoResponse(_) => "GetInfoResponse",
+            Payload::StopNotifyingUtxosChangedResponse(_) => "StopNotifyingUtxosChangedResponse",
+            Payload::NotifyPruningPointUtxoSetOverrideResponse(_) => "NotifyPruningPointUtxoSetOverrideResponse",
+            Payload::PruningPointUtxoSetOverrideNotification(_) => "PruningPointUtxoSetOverrideNotification",
+            Payload::StopNotifyingPruningPointUtxoSetOverrideResponse(_) => "StopNotifyingPruningPointUtxoSetOverrideResponse",
+            Payload::EstimateNetworkHashesPerSecondResponse(_) => "EstimateNetworkHashesPerSecondResponse",
+            Payload::NotifyVirtualDaaScoreChangedResponse(_) => "NotifyVirtualDaaScoreChangedResponse",
+            Payload::VirtualDaaScoreChangedNotification(_) => "VirtualDaaScoreChangedNotification",
+            Payload::GetBalanceByAddressResponse(_) => "GetBalanceByAddressResponse",
+            Payload::GetBalancesByAddressesResponse(_) => "GetBalancesByAddressesResponse",
+            Payload::NotifyNewBlockTemplateResponse(_) => "NotifyNewBlockTemplateResponse",
+            Payload::NewBlockTemplateNotification(_) => "NewBlockTemplateNotification",
+            Payload::GetMempoolEntriesByAddressesResponse(_) => "GetMempoolEntriesByAddressesResponse",
+            Payload::GetCoinSupplyResponse(_) => "GetCoinSupplyResponse",
+            Payload::PingResponse(_) => "PingResponse",
+            Payload::GetMetricsResponse(_) => "GetMetricsResponse",
+            Payload::GetServerInfoResponse(_) => "GetServerInfoResponse",
+            Payload::GetSyncStatusResponse(_) => "GetSyncStatusResponse",
+            Payload::GetDaaScoreTimestampEstimateResponse(_) => "GetDaaScoreTimestampEstimateResponse",
+            Payload::SubmitTransactionReplacementResponse(_) => "SubmitTransactionReplacementResponse",
+            Payload::GetConnectionsResponse(_) => "GetConnectionsResponse",
+            Payload::GetSystemInfoResponse(_) => "GetSystemInfoResponse",
+            Payload::GetFeeEstimateResponse(_) => "GetFeeEstimateResponse",
+            Payload::GetFeeEstimateExperimentalResponse(_) => "GetFeeEstimateExperimentalResponse",
+            Payload::GetCurrentBlockColorResponse(_) => "GetCurrentBlockColorResponse",
+            Payload::GetUtxoReturnAddressResponse(_) => "GetUtxoReturnAddressResponse",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protowire::StopNotifyingUtxosChangedRequestMessage;
+    use kaspa_addresses::{Address, Prefix, Version};
+
+    fn address(i: u8) -> Address {
+        Address::new(Prefix::Mainnet, Version::PubKey, &[i; 32])
+    }
+
+    #[test]
+    fn test_merge_utxos_changed_scopes() {
+        let first = UtxosChangedScope::new(vec![address(1), address(2), address(3), address(2)]);
+        let second = UtxosChangedScope::new(vec![address(3), address(4), address(1)]);
+        let merged = merge_utxos_changed_scopes([&first, &second]);
+        assert_eq!(vec![address(1), address(2), address(3), address(4)], merged.addresses);
+
+        let request = KaspadRequest::from_utxos_changed_scopes([&first, &second], Command::Start);
+        match request.payload {
+            Some(kaspad_request::Payload::NotifyUtxosChangedRequest(message)) => {
+                let expected: Vec<String> = merged.addresses.iter().map(|x| x.into()).collect();
+                assert_eq!(expected, message.addresses);
+                assert_eq!(Command::Start, Command::from(message.command));
+            }
+            _ => panic!("expected a NotifyUtxosChangedRequest payload"),
+        }
+
+        // An empty scope stands for all addresses and thus absorbs all others
+        let all = UtxosChangedScope::default();
+        assert!(merge_utxos_changed_scopes([&first, &all, &second]).addresses.is_empty());
+    }
+
+    #[test]
+    fn test_response_payload_var_names() {
+        use kaspad_response::Payload;
+        let payloads = [
+            Payload::GetCurrentNetworkResponse(Default::default()),
+            Payload::SubmitBlockResponse(Default::default()),
+            Payload::GetBlockTemplateResponse(Default::default()),
+            Payload::NotifyBlockAddedResponse(Default::default()),
+            Payload::BlockAddedNotification(Default::default()),
+            Payload::GetInfoResponse(Default::default()),
+            Payload::StopNotifyingUtxosChangedResponse(Default::default()),
+            Payload::UtxosChangedNotification(Default::default()),
+            Payload::GetFeeEstimateResponse(Default::default()),
+            Payload::GetUtxoReturnAddressResponse(Default::default()),
+        ];
+        let names = payloads.iter().map(|payload| payload.var_name()).collect::<HashSet<_>>();
+        assert_eq!(payloads.len(), names.len(), "var names are expected to be distinct");
+        assert!(names.iter().all(|name| !name.is_empty()));
+        assert_eq!("BlockAddedNotification", payloads[4].var_name());
+    }
+
+    #[test]
+    fn test_scope_from_request() {
+        let scope = UtxosChangedScope::new(vec![address(1), address(2)]);
+        let request = KaspadRequest::from_notification_type(&scope.clone().into(), Command::Start);
+        assert_eq!(Ok(Scope::UtxosChanged(scope)), Scope::try_from(&request));
+
+        let scope = VirtualChainChangedScope::new(true);
+        let request = KaspadRequest::from_notification_type(&scope.clone().into(), Command::Stop);
+        assert_eq!(Ok(Scope::VirtualChainChanged(scope)), Scope::try_from(&request));
+
+        let request = KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::GetInfoRequest(Default::default())) };
+        assert_eq!(Err(GrpcScopeError::NotSubscription(KaspadPayloadOps::GetInfo)), Scope::try_from(&request));
+
+        let request = KaspadRequest { id: 0, payload: None };
+        assert_eq!(Err(GrpcScopeError::MissingPayload), Scope::try_from(&request));
+
+        let request = KaspadRequest {
+            id: 0,
+            payload: Some(kaspad_request::Payload::NotifyUtxosChangedRequest(NotifyUtxosChangedRequestMessage {
+                addresses: vec!["not an address".to_string()],
+                command: Command::Start.into(),
+            })),
+        };
+        assert!(matches!(Scope::try_from(&request), Err(GrpcScopeError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_subscription_info() {
+        let scope = UtxosChangedScope::new(vec![address(1), address(2)]);
+
+        let request = KaspadRequest::from_notification_type(&scope.clone().into(), Command::Start);
+        assert_eq!(Some((Scope::UtxosChanged(scope.clone()), Command::Start)), request.subscription_info());
+
+        let request = KaspadRequest {
+            id: 0,
+            payload: Some(kaspad_request::Payload::StopNotifyingUtxosChangedRequest(StopNotifyingUtxosChangedRequestMessage {
+                addresses: scope.addresses.iter().map(|x| x.into()).collect(),
+            })),
+        };
+        assert!(request.is_subscription());
+        assert_eq!(Some((Scope::UtxosChanged(scope), Command::Stop)), request.subscription_info());
+
+        let request = KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::GetInfoRequest(Default::default())) };
+        assert!(!request.is_subscription());
+        assert_eq!(None, request.subscription_info());
+    }
 }
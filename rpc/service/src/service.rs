@@ -21,7 +21,7 @@ use kaspa_consensus_notify::{
     notifier::ConsensusNotifier,
     {connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification},
 };
-use kaspa_consensusmanager::ConsensusManager;
+use kaspa_consensusmanager::{ConsensusManager, SessionPool, DEFAULT_SESSION_POOL_MAX_AGE};
 use kaspa_core::time::unix_now;
 use kaspa_core::{
     core::Core,
@@ -122,6 +122,8 @@ pub struct RpcCoreService {
     fee_estimate_cache: ExpiringCache<RpcFeeEstimate>,
     fee_estimate_verbose_cache: ExpiringCache<kaspa_mining::errors::MiningManagerResult<GetFeeEstimateExperimentalResponse>>,
     mining_rule_engine: Arc<MiningRuleEngine>,
+    /// Shares read-only consensus sessions between concurrent queries
+    session_pool: Arc<SessionPool>,
 }
 
 const RPC_CORE: &str = "rpc-core";
@@ -204,6 +206,8 @@ impl RpcCoreService {
         let notifier =
             Arc::new(Notifier::new(RPC_CORE, EVENT_TYPE_ARRAY[..].into(), collectors, subscribers, subscription_context, 1, policies));
 
+        let session_pool = Arc::new(SessionPool::new(consensus_manager.clone(), DEFAULT_SESSION_POOL_MAX_AGE));
+
         Self {
             consensus_manager,
             notifier,
@@ -227,6 +231,7 @@ impl RpcCoreService {
             fee_estimate_cache: ExpiringCache::new(Duration::from_millis(500), Duration::from_millis(1000)),
             fee_estimate_verbose_cache: ExpiringCache::new(Duration::from_millis(500), Duration::from_millis(1000)),
             mining_rule_engine,
+            session_pool,
         }
     }
 
@@ -254,6 +259,10 @@ impl RpcCoreService {
         self.core_shutdown_request.listener.clone()
     }
 
+    pub fn mining_manager(&self) -> MiningManagerProxy {
+        self.mining_manager.clone()
+    }
+
     async fn get_utxo_set_by_script_public_key<'a>(
         &self,
         addresses: impl Iterator<Item = &'a RpcAddress>,
@@ -860,14 +869,16 @@ NOTE: This error usually indicates an RPC conversion error between the node and
     ) -> RpcResult<GetFeeEstimateExperimentalResponse> {
         if request.verbose {
             let mining_manager = self.mining_manager.clone();
-            let consensus_manager = self.consensus_manager.clone();
+            let session_pool = self.session_pool.clone();
             let prefix = self.config.prefix();
 
             let response = self
                 .fee_estimate_verbose_cache
                 .get(async move {
-                    let session = consensus_manager.consensus().unguarded_session();
-                    mining_manager.get_realtime_feerate_estimations_verbose(&session, prefix).await.map(FeeEstimateVerbose::into_rpc)
+                    mining_manager
+                        .get_realtime_feerate_estimations_verbose(&session_pool, prefix)
+                        .await
+                        .map(FeeEstimateVerbose::into_rpc)
                 })
                 .await?;
             Ok(response)
@@ -1,5 +1,6 @@
 use async_channel::unbounded;
 use clap::Parser;
+use flate2::{write::GzEncoder, Compression};
 use futures::{future::try_join_all, Future};
 use itertools::Itertools;
 use kaspa_alloc::init_allocator_with_default_settings;
@@ -8,20 +9,22 @@ use kaspa_consensus::{
     consensus::Consensus,
     constants::perf::PerfParams,
     model::stores::{
-        block_transactions::BlockTransactionsStoreReader,
-        ghostdag::{GhostdagStoreReader, KType},
-        headers::HeaderStoreReader,
-        relations::RelationsStoreReader,
+        block_transactions::BlockTransactionsStoreReader, ghostdag::KType, headers::HeaderStoreReader, relations::RelationsStoreReader,
     },
     params::{ForkActivation, Params, TenBps, DEVNET_PARAMS, NETWORK_DELAY_BOUND, SIMNET_PARAMS},
 };
 use kaspa_consensus_core::{
-    api::ConsensusApi, block::Block, blockstatus::BlockStatus, config::bps::calculate_ghostdag_k, errors::block::BlockProcessResult,
-    mining_rules::MiningRules, BlockHashSet, BlockLevel, HashMapCustomHasher,
+    api::{ConsensusApi, DagStats},
+    block::Block,
+    blockstatus::BlockStatus,
+    config::bps::calculate_ghostdag_k,
+    errors::block::BlockProcessResult,
+    mining_rules::MiningRules,
+    BlockHashSet, BlockLevel, HashMapCustomHasher,
 };
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_core::{
-    info,
+    error, info,
     task::{service::AsyncService, tick::TickService},
     time::unix_now,
     trace, warn,
@@ -31,8 +34,17 @@ use kaspa_database::{create_temp_db, load_existing_db};
 use kaspa_hashes::Hash;
 use kaspa_perf_monitor::{builder::Builder, counters::CountersSnapshot};
 use kaspa_utils::fd_budget;
+use serde::{Deserialize, Serialize};
 use simulator::network::KaspaNetworkSimulator;
-use std::{collections::VecDeque, sync::Arc, time::Duration};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    process::exit,
+    sync::Arc,
+    time::Duration,
+};
 
 pub mod simulator;
 
@@ -95,6 +107,11 @@ struct Args {
     #[arg(short, long)]
     input_dir: Option<String>,
 
+    /// Path to dump the simulated DAG to as JSON lines of blocks and their parents. The dump is gzipped if the path
+    /// ends with `.gz`
+    #[arg(long)]
+    dump_dag: Option<String>,
+
     /// Indicates whether to test pruning. Currently this means we shorten the pruning constants and avoid validating
     /// the DAG in a separate consensus following the simulation phase
     #[arg(long, default_value_t = false)]
@@ -209,7 +226,13 @@ fn main_impl(mut args: Args) {
     if !args.test_pruning {
         builder = builder.set_archival();
     }
-    let config = Arc::new(builder.build());
+    let config = match builder.try_build() {
+        Ok(config) => Arc::new(config),
+        Err(err) => {
+            error!("{}", err);
+            exit(1);
+        }
+    };
     let default_fd = fd_budget::limit() / 2;
     let mut conn_builder = ConnBuilder::default().with_parallelism(num_cpus::get()).with_files_limit(default_fd);
     if let Some(rocksdb_files_limit) = args.rocksdb_files_limit {
@@ -261,6 +284,13 @@ fn main_impl(mut args: Args) {
         (consensus, lifetime)
     };
 
+    if let Some(path) = args.dump_dag.as_ref() {
+        let root = if args.test_pruning { consensus.pruning_point() } else { config.genesis.hash };
+        let hashes = topologically_ordered_hashes(&consensus, root);
+        dump_dag_json(&consensus, &hashes, Path::new(path)).unwrap_or_else(|err| panic!("failed dumping the DAG to {path}: {err}"));
+        info!("Dumped {} blocks to {}", hashes.len(), path);
+    }
+
     if args.test_pruning {
         let hashes = topologically_ordered_hashes(&consensus, consensus.pruning_point());
         let num_blocks = hashes.len();
@@ -434,7 +464,7 @@ async fn validate(src_consensus: &Consensus, dst_consensus: &Consensus, params:
     }
 
     // Assert that at least one body tip was resolved with valid UTXO
-    assert!(dst_consensus.body_tips().iter().copied().any(|h| dst_consensus.block_status(h) == BlockStatus::StatusUTXOValid));
+    assert!(dst_consensus.body_tips().iter().copied().any(|h| dst_consensus.block_status(h).is_utxo_valid()));
     let elapsed = start.elapsed();
     info!(
         "Total validation time: {:?}, {} processing rate: {:.2} (b/s), transaction processing rate: {:.2} (t/s)",
@@ -480,18 +510,46 @@ fn topologically_ordered_hashes(src_consensus: &Consensus, genesis_hash: Hash) -
     vec
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct JsonBlock {
+    id: String,
+    parents: Vec<String>,
+}
+
+/// Dumps the blocks in `hashes` along with their parents as JSON lines, gzipped if `path` ends with `.gz`
+fn dump_dag_json(src_consensus: &Consensus, hashes: &[Hash], path: &Path) -> std::io::Result<()> {
+    let relations = src_consensus.relations_stores.read();
+    let blocks = hashes.iter().map(|&hash| JsonBlock {
+        id: hash.to_string(),
+        parents: relations[0].get_parents(hash).unwrap().iter().map(|parent| parent.to_string()).collect(),
+    });
+    write_json_lines(blocks, path)
+}
+
+fn write_json_lines<T: Serialize>(items: impl IntoIterator<Item = T>, path: &Path) -> std::io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        write_lines(items, &mut encoder)?;
+        // Finishing writes the gzip trailer, without which the archive is truncated
+        encoder.finish()?.flush()
+    } else {
+        let mut writer = writer;
+        write_lines(items, &mut writer)?;
+        writer.flush()
+    }
+}
+
+fn write_lines<T: Serialize>(items: impl IntoIterator<Item = T>, writer: &mut impl Write) -> std::io::Result<()> {
+    for item in items {
+        serde_json::to_writer(&mut *writer, &item)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
 fn print_stats(src_consensus: &Consensus, hashes: &[Hash], delay: f64, bps: f64, k: KType) -> usize {
-    let blues_mean = hashes.iter().map(|&h| src_consensus.ghostdag_store.get_data(h).unwrap().mergeset_blues.len()).sum::<usize>()
-        as f64
-        / hashes.len() as f64;
-    let reds_mean = hashes.iter().map(|&h| src_consensus.ghostdag_store.get_data(h).unwrap().mergeset_reds.len()).sum::<usize>()
-        as f64
-        / hashes.len() as f64;
-    let parents_mean = hashes.iter().map(|&h| src_consensus.headers_store.get_header(h).unwrap().direct_parents().len()).sum::<usize>()
-        as f64
-        / hashes.len() as f64;
-    let num_txs = hashes.iter().map(|&h| src_consensus.block_transactions_store.get(h).unwrap().len()).sum::<usize>();
-    let txs_mean = num_txs as f64 / hashes.len() as f64;
+    let DagStats { blues_mean, reds_mean, parents_mean, txs_mean, num_txs } = src_consensus.dag_stats(hashes);
     info!("[DELAY={delay}, BPS={bps}, GHOSTDAG K={k}]");
     info!("[Average stats of generated DAG] blues: {blues_mean}, reds: {reds_mean}, parents: {parents_mean}, txs: {txs_mean}");
     num_txs
@@ -514,4 +572,32 @@ mod tests {
         kaspa_core::panic::configure_panic();
         main_impl(args);
     }
+
+    #[test]
+    fn test_gzipped_dag_dump() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let blocks = || {
+            (0..100u64).map(|i| JsonBlock {
+                id: Hash::from_u64_word(i + 1).to_string(),
+                parents: (i.saturating_sub(3)..i).map(|p| Hash::from_u64_word(p + 1).to_string()).collect(),
+            })
+        };
+        let dir = std::env::temp_dir();
+        let plain_path = dir.join(format!("simpa-dag-dump-{}.json", std::process::id()));
+        let gz_path = dir.join(format!("simpa-dag-dump-{}.json.gz", std::process::id()));
+        write_json_lines(blocks(), &plain_path).unwrap();
+        write_json_lines(blocks(), &gz_path).unwrap();
+
+        let plain = std::fs::read_to_string(&plain_path).unwrap();
+        let mut decompressed = String::new();
+        GzDecoder::new(File::open(&gz_path).unwrap()).read_to_string(&mut decompressed).unwrap();
+        std::fs::remove_file(&plain_path).unwrap();
+        std::fs::remove_file(&gz_path).unwrap();
+
+        assert_eq!(decompressed, plain);
+        let parsed = decompressed.lines().map(|line| serde_json::from_str::<JsonBlock>(line).unwrap()).collect_vec();
+        assert_eq!(parsed, blocks().collect_vec());
+    }
 }
@@ -193,11 +193,11 @@ impl Miner {
             vec![TransactionInput::new(outpoint, vec![], 0, 0)],
             if multiple_outputs && input_amount > 4 {
                 vec![
-                    TransactionOutput::new(input_amount / 2, self.miner_data.script_public_key.clone()),
-                    TransactionOutput::new(input_amount / 2 - 1, self.miner_data.script_public_key.clone()),
+                    TransactionOutput::new(input_amount / 2, self.miner_data.script_public_key().clone()),
+                    TransactionOutput::new(input_amount / 2 - 1, self.miner_data.script_public_key().clone()),
                 ]
             } else {
-                vec![TransactionOutput::new(input_amount - 1, self.miner_data.script_public_key.clone())]
+                vec![TransactionOutput::new(input_amount - 1, self.miner_data.script_public_key().clone())]
             },
             0,
             SUBNETWORK_ID_NATIVE,
@@ -219,7 +219,7 @@ impl Miner {
     fn process_block(&mut self, block: Block, env: &mut Environment<Block>) -> Suspension {
         for tx in block.transactions.iter() {
             for (i, output) in tx.outputs.iter().enumerate() {
-                if output.script_public_key.eq(&self.miner_data.script_public_key) {
+                if output.script_public_key.eq(self.miner_data.script_public_key()) {
                     if self.possible_unspent_outpoints.len() == self.max_cached_outpoints {
                         self.possible_unspent_outpoints.swap_remove_index(self.rng.gen_range(0..self.max_cached_outpoints));
                     }
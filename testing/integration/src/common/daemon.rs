@@ -150,6 +150,12 @@ impl Daemon {
         self.client_manager.clone()
     }
 
+    /// Returns the RPC core service of the node, giving tests direct access to the components it wraps
+    pub fn rpc_core_service(&self) -> Arc<RpcCoreService> {
+        let async_service = Arc::downcast::<AsyncRuntime>(self.core.find(AsyncRuntime::IDENT).unwrap().arc_any()).unwrap();
+        Arc::downcast::<RpcCoreService>(async_service.find(RpcCoreService::IDENT).unwrap().arc_any()).unwrap()
+    }
+
     pub fn grpc_server_started(&self) -> Listener {
         self.grpc_server_started.clone()
     }
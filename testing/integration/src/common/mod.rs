@@ -9,6 +9,9 @@ pub mod client;
 pub mod client_notify;
 pub mod daemon;
 pub mod listener;
+pub mod submitter;
+pub mod tx_dag;
+pub mod tx_peer;
 pub mod utils;
 
 pub fn open_file(file_path: &Path) -> File {
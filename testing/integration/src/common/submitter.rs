@@ -0,0 +1,181 @@
+use async_channel::Sender;
+use async_trait::async_trait;
+use kaspa_core::{info, warn};
+use kaspa_grpc_client::GrpcClient;
+use kaspa_rpc_core::api::rpc::RpcApi;
+use std::{sync::Arc, time::Duration};
+use tokio::time::{interval, sleep, Instant, MissedTickBehavior};
+
+/// A TPS value meaning no pacing is applied
+pub const UNREGULATED_TPS: u64 = u64::MAX;
+
+/// A mempool target value meaning no backpressure is applied
+pub const NO_MEMPOOL_TARGET: u64 = u64::MAX;
+
+/// Provides the current mempool size of the node being submitted to
+#[async_trait]
+pub trait MempoolSizeSource: Send + Sync {
+    async fn mempool_size(&self) -> u64;
+}
+
+#[async_trait]
+impl MempoolSizeSource for GrpcClient {
+    async fn mempool_size(&self) -> u64 {
+        self.get_info().await.unwrap().mempool_size
+    }
+}
+
+/// Paces the submission of items to a channel (typically the distribution channel of a client pool) at a target TPS.
+///
+/// If a mempool target is set, the submission starts unregulated and switches to the target TPS once the mempool exceeds
+/// the target by 5%, in which case it is also paused until the mempool drains back below the target
+pub struct RateLimitedSubmitter {
+    tps: u64,
+    mempool_target: u64,
+    mempool: Option<Arc<dyn MempoolSizeSource>>,
+    check_interval: Duration,
+}
+
+impl RateLimitedSubmitter {
+    pub fn new(tps: u64) -> Self {
+        Self { tps, mempool_target: NO_MEMPOOL_TARGET, mempool: None, check_interval: Duration::from_millis(200) }
+    }
+
+    pub fn with_mempool_target(mut self, mempool_target: u64, mempool: Arc<dyn MempoolSizeSource>) -> Self {
+        self.mempool_target = mempool_target;
+        self.mempool = Some(mempool);
+        self
+    }
+
+    /// Sets the minimal interval between two mempool size queries
+    pub fn with_check_interval(mut self, check_interval: Duration) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    fn has_backpressure(&self) -> bool {
+        self.mempool.is_some() && self.mempool_target != NO_MEMPOOL_TARGET
+    }
+
+    /// Sends `items` to `sender` until either the items are exhausted, the channel is closed or `is_running` returns false.
+    /// Returns the number of items sent
+    pub async fn submit<T>(&self, items: impl IntoIterator<Item = T>, sender: &Sender<T>, is_running: impl Fn() -> bool) -> usize {
+        let mut tps = if self.has_backpressure() { UNREGULATED_TPS } else { self.tps };
+        let mut pacer = Self::pacer(tps);
+        let mut last_check_time = Instant::now() - self.check_interval;
+        let mut log_index = 0;
+        let mut sent = 0;
+
+        for item in items {
+            if let Some(pacer) = pacer.as_mut() {
+                pacer.tick().await;
+            }
+            if let Some(mempool) = self.mempool.as_ref().filter(|_| self.has_backpressure()) {
+                if last_check_time.elapsed() >= self.check_interval {
+                    last_check_time = Instant::now();
+                    let mut mempool_size = mempool.mempool_size().await;
+                    if log_index % 10 == 0 {
+                        info!("Mempool size: {:#?}, txs submitted: {}", mempool_size, sent);
+                    }
+                    log_index += 1;
+
+                    if mempool_size > (self.mempool_target as f32 * 1.05) as u64 {
+                        if tps != self.tps {
+                            warn!("Applying TPS pressure");
+                            tps = self.tps;
+                        }
+                        while mempool_size > self.mempool_target && is_running() {
+                            sleep(self.check_interval).await;
+                            mempool_size = mempool.mempool_size().await;
+                            if log_index % 10 == 0 {
+                                info!(
+                                    "Mempool size: {:#?} (targeting {:#?}), txs submitted: {}",
+                                    mempool_size, self.mempool_target, sent
+                                );
+                            }
+                            log_index += 1;
+                        }
+                        // Restart pacing so that the pause is not compensated by a burst
+                        pacer = Self::pacer(tps);
+                    }
+                }
+            }
+            if !is_running() || sender.send(item).await.is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        sent
+    }
+
+    fn pacer(tps: u64) -> Option<tokio::time::Interval> {
+        (tps != UNREGULATED_TPS).then(|| {
+            // Missed ticks are bursted in order to keep the average rate when the period is below the timer resolution
+            let mut pacer = interval(Duration::from_secs_f64(1.0 / tps as f64));
+            pacer.set_missed_tick_behavior(MissedTickBehavior::Burst);
+            pacer
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    struct MockMempool(AtomicU64);
+
+    #[async_trait]
+    impl MempoolSizeSource for MockMempool {
+        async fn mempool_size(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_rate() {
+        const TPS: u64 = 500;
+        const COUNT: usize = 1000;
+        let (sender, receiver) = async_channel::unbounded();
+        let submitter = RateLimitedSubmitter::new(TPS);
+
+        let start = Instant::now();
+        let sent = submitter.submit(0..COUNT, &sender, || true).await;
+        let elapsed = start.elapsed().as_secs_f64();
+        assert_eq!(sent, COUNT);
+        assert_eq!(receiver.len(), COUNT);
+
+        // The first item is sent immediately, hence the rate is measured over the following intervals
+        let rate = (COUNT - 1) as f64 / elapsed;
+        assert!((rate - TPS as f64).abs() < TPS as f64 * 0.1, "achieved rate {rate:.1} is not within 10% of the target {TPS}");
+    }
+
+    #[tokio::test]
+    async fn test_submit_backpressure() {
+        let (sender, receiver) = async_channel::unbounded();
+        let mempool = Arc::new(MockMempool(AtomicU64::new(1000)));
+        let submitter = Arc::new(
+            RateLimitedSubmitter::new(UNREGULATED_TPS)
+                .with_mempool_target(100, mempool.clone())
+                .with_check_interval(Duration::from_millis(10)),
+        );
+
+        let task = tokio::spawn({
+            let submitter = submitter.clone();
+            async move { submitter.submit(0..50, &sender, || true).await }
+        });
+
+        // The submission is paused while the mempool is above the target
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(receiver.len(), 0);
+
+        mempool.0.store(0, Ordering::Relaxed);
+        assert_eq!(task.await.unwrap(), 50);
+        assert_eq!(receiver.len(), 50);
+
+        // Stopping interrupts the submission
+        let (sender, receiver) = async_channel::unbounded();
+        assert_eq!(RateLimitedSubmitter::new(UNREGULATED_TPS).submit(0..50, &sender, || false).await, 0);
+        assert!(receiver.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use super::utils::{required_fee, CONTRACT_FACTOR, EXPAND_FACTOR};
+use itertools::Itertools;
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::{
+    constants::TX_VERSION,
+    sign::sign,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{ScriptPublicKey, SignableTransaction, Transaction, TransactionId, TransactionInput, TransactionOutput},
+    utxo::{
+        prealloc::generate_prealloc_utxos,
+        utxo_collection::{UtxoCollection, UtxoCollectionExtensions},
+        utxo_diff::UtxoDiff,
+    },
+};
+use kaspa_core::info;
+use kaspa_txscript::{extract_script_pub_key_address, pay_to_address_script};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use secp256k1::Keypair;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{hash_map::Entry::Occupied, HashMap, HashSet},
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+/// Environment variable holding the path of a TX DAG dataset file used by the benchmarks.
+/// If the file exists the dataset is loaded from it, otherwise the generated dataset is saved to it
+pub const TX_DAG_DATASET_ENV_VAR: &str = "KASPA_TX_DAG_DATASET";
+
+/// A DAG of signed transactions over an initial UTXO set, ordered topologically
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TxDag {
+    pub initial_utxoset: UtxoCollection,
+    pub txs: Vec<Arc<Transaction>>,
+}
+
+impl TxDag {
+    /// Sanity test verifying that the TX DAG is valid, topologically ordered and has no double spends
+    pub fn verify(&self) {
+        let mut prev_txs: HashMap<TransactionId, Arc<Transaction>> = HashMap::new();
+        let mut used_outpoints = HashSet::with_capacity(self.txs.len() * 2);
+        for tx in self.txs.iter() {
+            for input in tx.inputs.iter() {
+                assert!(used_outpoints.insert(input.previous_outpoint));
+                if let Occupied(e) = prev_txs.entry(input.previous_outpoint.transaction_id) {
+                    assert!(e.get().outputs.len() > input.previous_outpoint.index as usize);
+                } else {
+                    assert!(self.initial_utxoset.contains_key(&input.previous_outpoint));
+                }
+            }
+            assert!(prev_txs.insert(tx.id(), tx.clone()).is_none());
+        }
+    }
+
+    /// Returns the address owning the initial UTXO set, which is expected to be preallocated to a single address
+    pub fn prealloc_address(&self, prefix: Prefix) -> Address {
+        let spk =
+            self.initial_utxoset.values().map(|entry| &entry.script_public_key).all_equal_value().expect("a single prealloc address");
+        extract_script_pub_key_address(spk, prefix).unwrap()
+    }
+
+    /// Returns the amount of each of the initial UTXOs, which are expected to be preallocated with a uniform amount
+    pub fn prealloc_amount(&self) -> u64 {
+        self.initial_utxoset.values().map(|entry| entry.amount).all_equal_value().expect("a uniform prealloc amount")
+    }
+
+    /// Saves the dataset to `path` so that it can later be replayed with [`TxDag::load`]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, self).map_err(io::Error::other)?;
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(reader).map_err(io::Error::other)
+    }
+
+    /// Loads the dataset from `path` if the file exists, otherwise generates it with `generate` and saves it to `path`
+    pub fn load_or_generate(path: impl AsRef<Path>, generate: impl FnOnce() -> Self) -> io::Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            info!("Loading TX DAG dataset from {}", path.display());
+            return Self::load(path);
+        }
+        let dag = generate();
+        info!("Saving TX DAG dataset to {}", path.display());
+        dag.save(path)?;
+        Ok(dag)
+    }
+
+    /// Same as [`TxDag::load_or_generate`] with the path taken from [`TX_DAG_DATASET_ENV_VAR`].
+    /// If the variable is not set, the dataset is generated without being persisted
+    pub fn load_or_generate_from_env(generate: impl FnOnce() -> Self) -> io::Result<Self> {
+        match std::env::var(TX_DAG_DATASET_ENV_VAR) {
+            Ok(path) => Self::load_or_generate(path, generate),
+            Err(_) => Ok(generate()),
+        }
+    }
+}
+
+/// Builds a TX DAG level by level, where each level holds up to `width` transactions spending
+/// `contract_factor` UTXOs each and creating `expand_factor` outputs each
+pub struct TxDagGenerator {
+    schnorr_key: Keypair,
+    spk: ScriptPublicKey,
+    levels: usize,
+    width: usize,
+    contract_factor: usize,
+    expand_factor: u64,
+    seed: Option<u64>,
+}
+
+impl TxDagGenerator {
+    pub fn new(schnorr_key: Keypair, spk: ScriptPublicKey, levels: usize, width: usize) -> Self {
+        Self { schnorr_key, spk, levels, width, contract_factor: CONTRACT_FACTOR as usize, expand_factor: EXPAND_FACTOR, seed: None }
+    }
+
+    /// Sets the number of inputs of each transaction
+    pub fn with_contract_factor(mut self, contract_factor: usize) -> Self {
+        assert!(contract_factor > 0);
+        self.contract_factor = contract_factor;
+        self
+    }
+
+    /// Sets the number of outputs of each transaction
+    pub fn with_expand_factor(mut self, expand_factor: u64) -> Self {
+        assert!(expand_factor > 0);
+        self.expand_factor = expand_factor;
+        self
+    }
+
+    /// Makes the selection of spent UTXOs, and hence the DAG structure and transaction ids, reproducible.
+    /// Note that signatures might still differ between runs
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the number of UTXOs required for filling the first level
+    pub fn required_utxos(&self) -> usize {
+        self.contract_factor * self.width
+    }
+
+    pub fn generate(&self, initial_utxoset: UtxoCollection) -> TxDag {
+        /*
+        Algo:
+           perform level by level:
+               for target txs per level:
+                   select random utxos (distinctly)
+                   create and sign a tx
+                   append tx to level txs
+                   append tx to utxo diff
+               apply level utxo diff to the utxo collection
+        */
+
+        let num_inputs = self.contract_factor;
+        let num_outputs = self.expand_factor;
+        let mut rng = self.seed.map(StdRng::seed_from_u64);
+        let mut utxoset = initial_utxoset.clone();
+        let mut txs = Vec::with_capacity(self.levels * self.width);
+
+        for i in 0..self.levels {
+            let mut utxo_diff = UtxoDiff::default();
+            let selected = match rng.as_mut() {
+                Some(rng) => {
+                    // Sort before shuffling since the iteration order of the collection is not deterministic
+                    let mut outpoints = utxoset.keys().copied().collect_vec();
+                    outpoints.sort_by_key(|outpoint| (outpoint.transaction_id, outpoint.index));
+                    outpoints.partial_shuffle(rng, self.required_utxos()).0.to_vec()
+                }
+                None => utxoset.keys().take(self.required_utxos()).copied().collect_vec(),
+            };
+            selected
+                .into_iter()
+                .chunks(num_inputs)
+                .into_iter()
+                .map(|c| c.into_iter().map(|o| (TransactionInput::new(o, vec![], 0, 1), utxoset[&o].clone())).unzip())
+                .collect::<Vec<(Vec<_>, Vec<_>)>>()
+                .into_par_iter()
+                .map(|(inputs, entries)| {
+                    let total_in = entries.iter().map(|e| e.amount).sum::<u64>();
+                    let total_out = total_in - required_fee(num_inputs, num_outputs);
+                    let outputs = (0..num_outputs)
+                        .map(|_| TransactionOutput { value: total_out / num_outputs, script_public_key: self.spk.clone() })
+                        .collect_vec();
+                    let unsigned_tx = Transaction::new(TX_VERSION, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+                    sign(SignableTransaction::with_entries(unsigned_tx, entries), self.schnorr_key)
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .for_each(|signed_tx| {
+                    utxo_diff.add_transaction(&signed_tx.as_verifiable(), 0).unwrap();
+                    txs.push(Arc::new(signed_tx.tx));
+                });
+            utxoset.remove_collection(&utxo_diff.remove);
+            utxoset.add_collection(&utxo_diff.add);
+
+            if i % (self.levels / 10).max(1) == 0 {
+                info!("Generated {} txs", txs.len());
+            }
+        }
+
+        TxDag { initial_utxoset, txs }
+    }
+}
+
+/// Generates a TX DAG over a UTXO set preallocated to the address of a new random key, as done by the benchmarks
+pub fn generate_prealloc_tx_dag(levels: usize, width: usize, prealloc_amount: u64) -> TxDag {
+    let (prealloc_sk, prealloc_pk) = secp256k1::generate_keypair(&mut rand::thread_rng());
+    let prealloc_address =
+        Address::new(Prefix::Simnet, kaspa_addresses::Version::PubKey, &prealloc_pk.x_only_public_key().0.serialize());
+    let schnorr_key = Keypair::from_secret_key(secp256k1::SECP256K1, &prealloc_sk);
+    let spk = pay_to_address_script(&prealloc_address);
+    let generator = TxDagGenerator::new(schnorr_key, spk.clone(), levels, width);
+    generator.generate(generate_prealloc_utxos(generator.required_utxos() as u64, prealloc_amount, &spk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+    use kaspa_txscript::pay_to_script_hash_script;
+
+    #[test]
+    fn test_tx_dag_generation() {
+        let schnorr_key = Keypair::from_seckey_slice(secp256k1::SECP256K1, &[7u8; 32]).unwrap();
+        let spk = pay_to_script_hash_script(&[1, 2, 3]);
+        let (levels, width) = (4, 10);
+        let generator =
+            TxDagGenerator::new(schnorr_key, spk.clone(), levels, width).with_contract_factor(2).with_expand_factor(2).with_seed(42);
+        let utxoset = generate_prealloc_utxos(generator.required_utxos() as u64, 10 * SOMPI_PER_KASPA, &spk);
+
+        let dag = generator.generate(utxoset.clone());
+        dag.verify();
+        assert_eq!(dag.txs.len(), levels * width);
+        assert!(dag.txs.iter().all(|tx| tx.inputs.len() == 2 && tx.outputs.len() == 2));
+
+        // A seeded generation is reproducible in terms of DAG structure
+        let other = generator.generate(utxoset);
+        assert_eq!(dag.txs.iter().map(|tx| tx.id()).collect_vec(), other.txs.iter().map(|tx| tx.id()).collect_vec());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_tx_dag_double_spend_verification() {
+        let schnorr_key = Keypair::from_seckey_slice(secp256k1::SECP256K1, &[7u8; 32]).unwrap();
+        let spk = pay_to_script_hash_script(&[1, 2, 3]);
+        let generator = TxDagGenerator::new(schnorr_key, spk.clone(), 2, 5);
+        let mut dag = generator.generate(generate_prealloc_utxos(generator.required_utxos() as u64, SOMPI_PER_KASPA, &spk));
+        let double_spend = dag.txs[0].clone();
+        dag.txs.push(double_spend);
+        dag.verify();
+    }
+
+    #[test]
+    fn test_tx_dag_dataset_round_trip() {
+        let (secret_key, public_key) = secp256k1::generate_keypair(&mut rand::thread_rng());
+        let address = Address::new(Prefix::Simnet, kaspa_addresses::Version::PubKey, &public_key.x_only_public_key().0.serialize());
+        let schnorr_key = Keypair::from_secret_key(secp256k1::SECP256K1, &secret_key);
+        let spk = pay_to_address_script(&address);
+        let generator = TxDagGenerator::new(schnorr_key, spk.clone(), 3, 8).with_expand_factor(2);
+        let dag = generator.generate(generate_prealloc_utxos(generator.required_utxos() as u64, 10 * SOMPI_PER_KASPA, &spk));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tx_dag.bin");
+        let loaded = TxDag::load_or_generate(&path, || dag.clone()).unwrap();
+        assert!(path.exists());
+        assert_eq!(loaded.txs, dag.txs);
+
+        // The second call must load the dataset rather than regenerate it
+        let loaded = TxDag::load_or_generate(&path, || panic!("the dataset should be loaded from file")).unwrap();
+        loaded.verify();
+        assert_eq!(loaded.initial_utxoset, dag.initial_utxoset);
+        assert_eq!(loaded.txs, dag.txs);
+        assert_eq!(loaded.prealloc_address(Prefix::Simnet), address);
+        assert_eq!(loaded.prealloc_amount(), 10 * SOMPI_PER_KASPA);
+    }
+}
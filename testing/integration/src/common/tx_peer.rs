@@ -0,0 +1,246 @@
+use kaspa_consensus_core::{
+    network::NetworkId,
+    tx::{Transaction, TransactionId},
+};
+use kaspa_core::{debug, time::unix_now, warn};
+use kaspa_p2p_lib::{
+    common::ProtocolError,
+    make_message,
+    pb::{kaspad_message::Payload, AddressesMessage, InvTransactionsMessage, PongMessage, TransactionNotFoundMessage, VersionMessage},
+    Adaptor, ConnectionError, ConnectionInitializer, Hub, IncomingRoute, KaspadHandshake, KaspadMessagePayloadType, PeerKey, Router,
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use uuid::Uuid;
+
+const PROTOCOL_VERSION: u32 = 7;
+
+/// Message types the node might send which are irrelevant to a tx-only peer and are silently dropped
+const IGNORED_MESSAGE_TYPES: &[KaspadMessagePayloadType] = &[
+    KaspadMessagePayloadType::Addresses,
+    KaspadMessagePayloadType::Block,
+    KaspadMessagePayloadType::Transaction,
+    KaspadMessagePayloadType::BlockLocator,
+    KaspadMessagePayloadType::RequestRelayBlocks,
+    KaspadMessagePayloadType::IbdBlock,
+    KaspadMessagePayloadType::InvRelayBlock,
+    KaspadMessagePayloadType::InvTransactions,
+    KaspadMessagePayloadType::Pong,
+    KaspadMessagePayloadType::TransactionNotFound,
+    KaspadMessagePayloadType::PruningPointUtxoSetChunk,
+    KaspadMessagePayloadType::RequestIbdBlocks,
+    KaspadMessagePayloadType::UnexpectedPruningPoint,
+    KaspadMessagePayloadType::IbdBlockLocator,
+    KaspadMessagePayloadType::IbdBlockLocatorHighestHash,
+    KaspadMessagePayloadType::RequestNextPruningPointUtxoSetChunk,
+    KaspadMessagePayloadType::DonePruningPointUtxoSetChunks,
+    KaspadMessagePayloadType::IbdBlockLocatorHighestHashNotFound,
+    KaspadMessagePayloadType::BlockWithTrustedData,
+    KaspadMessagePayloadType::DoneBlocksWithTrustedData,
+    KaspadMessagePayloadType::RequestPruningPointAndItsAnticone,
+    KaspadMessagePayloadType::BlockHeaders,
+    KaspadMessagePayloadType::RequestNextHeaders,
+    KaspadMessagePayloadType::DoneHeaders,
+    KaspadMessagePayloadType::RequestPruningPointUtxoSet,
+    KaspadMessagePayloadType::RequestHeaders,
+    KaspadMessagePayloadType::RequestBlockLocator,
+    KaspadMessagePayloadType::PruningPoints,
+    KaspadMessagePayloadType::RequestPruningPointProof,
+    KaspadMessagePayloadType::PruningPointProof,
+    KaspadMessagePayloadType::BlockWithTrustedDataV4,
+    KaspadMessagePayloadType::TrustedData,
+    KaspadMessagePayloadType::RequestIbdChainBlockLocator,
+    KaspadMessagePayloadType::IbdChainBlockLocator,
+    KaspadMessagePayloadType::RequestAntipast,
+    KaspadMessagePayloadType::RequestNextPruningPointAndItsAnticoneBlocks,
+];
+
+type TxSource = Box<dyn Iterator<Item = Arc<Transaction>> + Send>;
+
+/// State shared between the peer handle and its connection flows
+struct BroadcastState {
+    network_name: String,
+    tps: f64,
+
+    /// The transactions left to broadcast. Taken by the broadcasting flow once the connection is initialized
+    source: Mutex<Option<TxSource>>,
+
+    /// Transactions which were announced to the node and can be served on request
+    announced: RwLock<HashMap<TransactionId, Arc<Transaction>>>,
+
+    broadcast_count: AtomicU64,
+    served_count: AtomicU64,
+}
+
+struct TxBroadcastInitializer {
+    state: Arc<BroadcastState>,
+}
+
+#[async_trait::async_trait]
+impl ConnectionInitializer for TxBroadcastInitializer {
+    async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+        let mut handshake = KaspadHandshake::new(&router);
+        router.start();
+
+        let version_message = VersionMessage {
+            protocol_version: PROTOCOL_VERSION,
+            services: 0,
+            timestamp: unix_now() as i64,
+            address: None,
+            id: Vec::from(Uuid::new_v4().as_bytes()),
+            user_agent: "/kaspa-tx-broadcast-peer/".to_string(),
+            disable_relay_tx: true,
+            subnetwork_id: None,
+            network: self.state.network_name.clone(),
+        };
+        let peer_version_message = handshake.handshake(version_message).await?;
+        debug!("TxBroadcastPeer, protocol versions - self: {}, peer: {}", PROTOCOL_VERSION, peer_version_message.protocol_version);
+
+        // Flows must subscribe before the ready exchange since the node starts sending right after it
+        let requests_route = router.subscribe(vec![
+            KaspadMessagePayloadType::RequestAddresses,
+            KaspadMessagePayloadType::Ping,
+            KaspadMessagePayloadType::RequestTransactions,
+        ]);
+        let ignored_route = router.subscribe(IGNORED_MESSAGE_TYPES.to_vec());
+
+        handshake.exchange_ready_messages().await?;
+
+        tokio::spawn(serve_requests(self.state.clone(), router.clone(), requests_route));
+        tokio::spawn(drain_ignored(ignored_route));
+        if let Some(source) = self.state.source.lock().take() {
+            tokio::spawn(broadcast(self.state.clone(), router, source));
+        }
+
+        Ok(())
+    }
+}
+
+/// Answers the node requests which are mandatory for keeping the connection alive, as well as the requests for announced txs
+async fn serve_requests(state: Arc<BroadcastState>, router: Arc<Router>, mut route: IncomingRoute) {
+    while let Some(msg) = route.recv().await {
+        let res = match msg.payload {
+            Some(Payload::RequestAddresses(_)) => {
+                router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list: vec![] })).await
+            }
+            Some(Payload::Ping(ping)) => router.enqueue(make_message!(Payload::Pong, PongMessage { nonce: ping.nonce })).await,
+            Some(Payload::RequestTransactions(request)) => match request.try_into() {
+                Ok(ids) => serve_transactions(&state, &router, ids).await,
+                Err(err) => Err(ProtocolError::ConversionError(err)),
+            },
+            _ => Ok(()),
+        };
+        if let Err(err) = res {
+            debug!("TxBroadcastPeer, exiting the request serving loop: {}", err);
+            break;
+        }
+    }
+}
+
+async fn serve_transactions(state: &BroadcastState, router: &Router, ids: Vec<TransactionId>) -> Result<(), ProtocolError> {
+    for id in ids {
+        let tx = state.announced.read().get(&id).cloned();
+        match tx {
+            Some(tx) => {
+                router.enqueue(make_message!(Payload::Transaction, (&*tx).into())).await?;
+                state.served_count.fetch_add(1, Ordering::Relaxed);
+            }
+            None => {
+                router.enqueue(make_message!(Payload::TransactionNotFound, TransactionNotFoundMessage { id: Some(id.into()) })).await?
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn drain_ignored(mut route: IncomingRoute) {
+    while route.recv().await.is_some() {}
+}
+
+/// Announces the txs of `source` to the node one by one at the configured rate
+async fn broadcast(state: Arc<BroadcastState>, router: Arc<Router>, source: TxSource) {
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / state.tps));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    for tx in source {
+        interval.tick().await;
+        let id = tx.id();
+        state.announced.write().insert(id, tx);
+        if let Err(err) =
+            router.enqueue(make_message!(Payload::InvTransactions, InvTransactionsMessage { ids: vec![id.into()] })).await
+        {
+            warn!("TxBroadcastPeer, broadcast stopped: {}", err);
+            return;
+        }
+        state.broadcast_count.fetch_add(1, Ordering::Relaxed);
+    }
+    debug!("TxBroadcastPeer, broadcast of {} txs completed", state.broadcast_count.load(Ordering::Relaxed));
+}
+
+/// A test-only P2P peer which connects to a node and only broadcasts a supplied sequence of transactions
+/// at a fixed rate, thus exercising the P2P tx relay path of the node. The peer disconnects when dropped
+pub struct TxBroadcastPeer {
+    adaptor: Arc<Adaptor>,
+    peer_key: PeerKey,
+    state: Arc<BroadcastState>,
+}
+
+impl TxBroadcastPeer {
+    /// Connects to the node listening on `address` and starts broadcasting `txs` at `tps` transactions per second.
+    /// The txs are expected to be in topological order
+    pub async fn connect<I>(address: String, network_id: NetworkId, txs: I, tps: f64) -> Result<Self, ConnectionError>
+    where
+        I: IntoIterator<Item = Arc<Transaction>>,
+        I::IntoIter: Send + 'static,
+    {
+        assert!(tps > 0.0, "broadcast rate must be positive");
+        let state = Arc::new(BroadcastState {
+            network_name: network_id.to_prefixed(),
+            tps,
+            source: Mutex::new(Some(Box::new(txs.into_iter()))),
+            announced: Default::default(),
+            broadcast_count: Default::default(),
+            served_count: Default::default(),
+        });
+        let initializer = Arc::new(TxBroadcastInitializer { state: state.clone() });
+        let adaptor = Adaptor::client_only(Hub::new(), initializer, Default::default());
+        let peer_key = adaptor.connect_peer(address).await?;
+        Ok(Self { adaptor, peer_key, state })
+    }
+
+    /// The number of txs announced to the node so far
+    pub fn broadcast_count(&self) -> u64 {
+        self.state.broadcast_count.load(Ordering::Relaxed)
+    }
+
+    /// The number of txs which were requested by the node and sent to it so far
+    pub fn served_count(&self) -> u64 {
+        self.state.served_count.load(Ordering::Relaxed)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.adaptor.has_peer(self.peer_key)
+    }
+
+    pub async fn disconnect(&self) {
+        self.adaptor.terminate_all_peers().await;
+    }
+}
+
+impl Drop for TxBroadcastPeer {
+    fn drop(&mut self) {
+        let adaptor = self.adaptor.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move { adaptor.close().await });
+            }
+            Err(_) => warn!("TxBroadcastPeer dropped outside of a runtime, the connection will close once the node times out"),
+        }
+    }
+}
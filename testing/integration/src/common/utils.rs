@@ -6,27 +6,13 @@ use kaspa_consensus_core::{
     header::Header,
     sign::sign,
     subnets::SUBNETWORK_ID_NATIVE,
-    tx::{
-        MutableTransaction, ScriptPublicKey, SignableTransaction, Transaction, TransactionId, TransactionInput, TransactionOutpoint,
-        TransactionOutput, UtxoEntry,
-    },
-    utxo::{
-        utxo_collection::{UtxoCollection, UtxoCollectionExtensions},
-        utxo_diff::UtxoDiff,
-    },
+    tx::{MutableTransaction, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
 };
-use kaspa_core::info;
 use kaspa_grpc_client::GrpcClient;
 use kaspa_rpc_core::{api::rpc::RpcApi, BlockAddedNotification, Notification, RpcUtxoEntry, VirtualDaaScoreChangedNotification};
 use kaspa_txscript::pay_to_address_script;
-use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use secp256k1::Keypair;
-use std::{
-    collections::{hash_map::Entry::Occupied, HashMap, HashSet},
-    future::Future,
-    sync::Arc,
-    time::Duration,
-};
+use std::{future::Future, time::Duration};
 use tokio::time::timeout;
 
 pub(crate) const EXPAND_FACTOR: u64 = 1;
@@ -41,83 +27,6 @@ pub const fn required_fee(num_inputs: usize, num_outputs: u64) -> u64 {
     FEE_RATE * estimated_mass(num_inputs, num_outputs)
 }
 
-/// Builds a TX DAG based on the initial UTXO set and on constant params
-pub fn generate_tx_dag(
-    mut utxoset: UtxoCollection,
-    schnorr_key: Keypair,
-    spk: ScriptPublicKey,
-    target_levels: usize,
-    target_width: usize,
-) -> Vec<Arc<Transaction>> {
-    /*
-    Algo:
-       perform level by level:
-           for target txs per level:
-               select random utxos (distinctly)
-               create and sign a tx
-               append tx to level txs
-               append tx to utxo diff
-           apply level utxo diff to the utxo collection
-    */
-
-    let num_inputs = CONTRACT_FACTOR as usize;
-    let num_outputs = EXPAND_FACTOR;
-
-    let mut txs = Vec::with_capacity(target_levels * target_width);
-
-    for i in 0..target_levels {
-        let mut utxo_diff = UtxoDiff::default();
-        utxoset
-            .iter()
-            .take(num_inputs * target_width)
-            .chunks(num_inputs)
-            .into_iter()
-            .map(|c| c.into_iter().map(|(o, e)| (TransactionInput::new(*o, vec![], 0, 1), e.clone())).unzip())
-            .collect::<Vec<(Vec<_>, Vec<_>)>>()
-            .into_par_iter()
-            .map(|(inputs, entries)| {
-                let total_in = entries.iter().map(|e| e.amount).sum::<u64>();
-                let total_out = total_in - required_fee(num_inputs, num_outputs);
-                let outputs = (0..num_outputs)
-                    .map(|_| TransactionOutput { value: total_out / num_outputs, script_public_key: spk.clone() })
-                    .collect_vec();
-                let unsigned_tx = Transaction::new(TX_VERSION, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
-                sign(SignableTransaction::with_entries(unsigned_tx, entries), schnorr_key)
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|signed_tx| {
-                utxo_diff.add_transaction(&signed_tx.as_verifiable(), 0).unwrap();
-                txs.push(Arc::new(signed_tx.tx));
-            });
-        utxoset.remove_collection(&utxo_diff.remove);
-        utxoset.add_collection(&utxo_diff.add);
-
-        if i % (target_levels / 10).max(1) == 0 {
-            info!("Generated {} txs", txs.len());
-        }
-    }
-
-    txs
-}
-
-/// Sanity test verifying that the generated TX DAG is valid, topologically ordered and has no double spends
-pub fn verify_tx_dag(initial_utxoset: &UtxoCollection, txs: &[Arc<Transaction>]) {
-    let mut prev_txs: HashMap<TransactionId, Arc<Transaction>> = HashMap::new();
-    let mut used_outpoints = HashSet::with_capacity(txs.len() * 2);
-    for tx in txs.iter() {
-        for input in tx.inputs.iter() {
-            assert!(used_outpoints.insert(input.previous_outpoint));
-            if let Occupied(e) = prev_txs.entry(input.previous_outpoint.transaction_id) {
-                assert!(e.get().outputs.len() > input.previous_outpoint.index as usize);
-            } else {
-                assert!(initial_utxoset.contains_key(&input.previous_outpoint));
-            }
-        }
-        assert!(prev_txs.insert(tx.id(), tx.clone()).is_none());
-    }
-}
-
 pub async fn wait_for<Fut>(sleep_millis: u64, max_iterations: u64, success: impl Fn() -> Fut, panic_message: &'static str)
 where
     Fut: Future<Output = bool>,
@@ -11,26 +11,30 @@ use kaspa_consensus::consensus::test_consensus::{TestConsensus, TestConsensusFac
 use kaspa_consensus::model::stores::block_transactions::{
     BlockTransactionsStore, BlockTransactionsStoreReader, DbBlockTransactionsStore,
 };
-use kaspa_consensus::model::stores::ghostdag::{GhostdagStoreReader, KType as GhostdagKType};
+use kaspa_consensus::model::stores::ghostdag::{GhostdagStore, GhostdagStoreReader, KType as GhostdagKType};
 use kaspa_consensus::model::stores::headers::HeaderStoreReader;
 use kaspa_consensus::model::stores::reachability::DbReachabilityStore;
-use kaspa_consensus::model::stores::relations::DbRelationsStore;
+use kaspa_consensus::model::stores::relations::{DbRelationsStore, RelationsStore};
 use kaspa_consensus::model::stores::selected_chain::SelectedChainStoreReader;
+use kaspa_consensus::model::stores::statuses::StatusesStore;
+use kaspa_consensus::model::stores::utxo_multisets::UtxoMultisetsStore;
 use kaspa_consensus::params::{
     ForkActivation, Params, CRESCENDO, DEVNET_PARAMS, MAINNET_PARAMS, MAX_DIFFICULTY_TARGET, MAX_DIFFICULTY_TARGET_AS_F64,
 };
 use kaspa_consensus::pipeline::monitor::ConsensusMonitor;
 use kaspa_consensus::pipeline::ProcessingCounters;
-use kaspa_consensus::processes::reachability::tests::{DagBlock, DagBuilder, StoreValidationExtensions};
+use kaspa_consensus::processes::difficulty::calc_work;
+use kaspa_consensus::processes::reachability::tests::{inclusive_past, DagBlock, DagBuilder, StoreValidationExtensions};
 use kaspa_consensus::processes::window::{WindowManager, WindowType};
 use kaspa_consensus_core::api::args::TransactionValidationArgs;
-use kaspa_consensus_core::api::{BlockValidationFutures, ConsensusApi};
+use kaspa_consensus_core::api::{BlockValidationFutures, ConsensusApi, DagIntegrityError, SelfCheckIssue, UtxoSetSummary};
 use kaspa_consensus_core::block::Block;
-use kaspa_consensus_core::blockhash::new_unique;
+use kaspa_consensus_core::blockhash::{new_unique, BlockHashes};
 use kaspa_consensus_core::blockstatus::BlockStatus;
 use kaspa_consensus_core::coinbase::MinerData;
 use kaspa_consensus_core::constants::{BLOCK_VERSION, SOMPI_PER_KASPA, STORAGE_MASS_PARAMETER, TRANSIENT_BYTE_TO_MASS_FACTOR};
 use kaspa_consensus_core::errors::block::{BlockProcessResult, RuleError};
+use kaspa_consensus_core::errors::consensus::ConsensusError;
 use kaspa_consensus_core::header::Header;
 use kaspa_consensus_core::mining_rules::MiningRules;
 use kaspa_consensus_core::network::{NetworkId, NetworkType::Mainnet};
@@ -39,7 +43,7 @@ use kaspa_consensus_core::trusted::{ExternalGhostdagData, TrustedBlock};
 use kaspa_consensus_core::tx::{
     MutableTransaction, ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry,
 };
-use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlueWorkType};
+use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlockHashSet, BlueWorkType, ReorgInfo};
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_consensus_notify::service::NotifyService;
 use kaspa_consensusmanager::ConsensusManager;
@@ -82,6 +86,7 @@ use std::{
     fs::File,
     future::Future,
     io::{BufRead, BufReader},
+    iter::once,
     str::{from_utf8, FromStr},
 };
 
@@ -215,6 +220,676 @@ async fn consensus_sanity_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn batch_block_insertion_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Blocks are built against the current DAG state, so use siblings which all point at genesis
+    let blocks = (2..7u64)
+        .map(|i| consensus.build_block_with_parents(i.into(), vec![MAINNET_PARAMS.genesis.hash]).to_immutable())
+        .collect_vec();
+    let hashes = blocks.iter().map(|b| b.hash()).collect_vec();
+    let futures = consensus.validate_and_insert_blocks(blocks);
+    assert_eq!(futures.len(), hashes.len());
+
+    let statuses = try_join_all(futures.into_iter().map(|f| f.virtual_state_task)).await.unwrap();
+    assert!(statuses.iter().all(|s| s.is_utxo_valid_or_pending()));
+    assert!(hashes.iter().all(|&hash| consensus.block_status(hash).is_utxo_valid_or_pending()));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn reorg_info_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let genesis = config.genesis.hash;
+    assert_eq!(consensus.last_reorg_info(), None);
+
+    // Chain A: genesis <- 2 <- 3. Each block is a simple extension reporting depth 0
+    consensus.add_block_with_parents(2.into(), vec![genesis]).await.unwrap();
+    assert_eq!(consensus.last_reorg_info(), Some(ReorgInfo { common_ancestor: genesis, removed_chain_len: 0, added_chain_len: 1 }));
+    consensus.add_block_with_parents(3.into(), vec![2.into()]).await.unwrap();
+    assert_eq!(consensus.last_reorg_info(), Some(ReorgInfo { common_ancestor: 2.into(), removed_chain_len: 0, added_chain_len: 1 }));
+
+    // Chain B: genesis <- 4 <- 5 <- 6. Block 4 is lighter than chain A so the chain is unaffected
+    consensus.add_block_with_parents(4.into(), vec![genesis]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 3.into());
+    assert_eq!(consensus.last_reorg_info(), Some(ReorgInfo { common_ancestor: 2.into(), removed_chain_len: 0, added_chain_len: 1 }));
+
+    // Block 5 ties with chain A, so whether it triggers the reorg depends on the hash tie-breaker
+    consensus.add_block_with_parents(5.into(), vec![4.into()]).await.unwrap();
+    let reorged_on_tie = consensus.get_sink() == 5.into();
+    if reorged_on_tie {
+        assert_eq!(
+            consensus.last_reorg_info(),
+            Some(ReorgInfo { common_ancestor: genesis, removed_chain_len: 2, added_chain_len: 2 })
+        );
+    } else {
+        assert_eq!(consensus.get_sink(), 3.into());
+    }
+
+    // Block 6 is heavier than chain A and must become the sink
+    consensus.add_block_with_parents(6.into(), vec![5.into()]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 6.into());
+    let expected = if reorged_on_tie {
+        ReorgInfo { common_ancestor: 5.into(), removed_chain_len: 0, added_chain_len: 1 }
+    } else {
+        ReorgInfo { common_ancestor: genesis, removed_chain_len: 2, added_chain_len: 3 }
+    };
+    assert_eq!(consensus.last_reorg_info(), Some(expected));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn backward_chain_iterator_to_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let genesis = config.genesis.hash;
+
+    // Chain: genesis <- 2 <- 3 <- 4, with 5 on a side branch off 2
+    consensus.add_block_with_parents(2.into(), vec![genesis]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![2.into()]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![3.into()]).await.unwrap();
+    consensus.add_block_with_parents(5.into(), vec![2.into()]).await.unwrap();
+
+    // Normal case: blocks from `from` (inclusive) down to `stop` (exclusive)
+    assert_eq!(consensus.backward_chain_iterator_to(4.into(), genesis).unwrap(), vec![4.into(), 3.into(), 2.into()]);
+    assert_eq!(consensus.backward_chain_iterator_to(4.into(), 2.into()).unwrap(), vec![4.into(), 3.into()]);
+
+    // Empty when stop == from
+    assert!(consensus.backward_chain_iterator_to(4.into(), 4.into()).unwrap().is_empty());
+
+    // Errors when stop is not a chain ancestor of from
+    assert_match!(
+        consensus.backward_chain_iterator_to(4.into(), 5.into()),
+        Err(ConsensusError::NotChainAncestor(stop, from)) if stop == Hash::from(5u64) && from == Hash::from(4u64)
+    );
+    assert_match!(consensus.backward_chain_iterator_to(2.into(), 4.into()), Err(ConsensusError::NotChainAncestor(_, _)));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn acceptance_depth_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let genesis = config.genesis.hash;
+
+    // The sink is only accepted by virtual
+    consensus.add_block_with_parents(2.into(), vec![genesis]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(2.into()), None);
+
+    // The depth increases as chain blocks are added on top of the accepting block
+    consensus.add_block_with_parents(3.into(), vec![2.into()]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(2.into()), Some(0));
+    consensus.add_block_with_parents(4.into(), vec![3.into()]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(2.into()), Some(1));
+    assert_eq!(consensus.acceptance_depth(3.into()), Some(0));
+
+    // A side block off genesis is not accepted until merged by a chain block
+    consensus.add_block_with_parents(10.into(), vec![genesis]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(10.into()), None);
+    consensus.add_block_with_parents(5.into(), vec![4.into(), 10.into()]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(10.into()), Some(0));
+    assert_eq!(consensus.acceptance_depth(2.into()), Some(2));
+    consensus.add_block_with_parents(6.into(), vec![5.into()]).await.unwrap();
+    assert_eq!(consensus.acceptance_depth(10.into()), Some(1));
+    assert_eq!(consensus.acceptance_depth(5.into()), Some(0));
+
+    // Unknown blocks have no acceptance depth
+    assert_eq!(consensus.acceptance_depth(100.into()), None);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn past_median_time_api_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let genesis = config.genesis.hash;
+    let genesis_timestamp = config.genesis.timestamp;
+
+    // A chain with non-monotonic timestamps (each still above the past median time of its block)
+    let offsets = [100, 500, 800, 600];
+    for (i, offset) in offsets.into_iter().enumerate() {
+        let hash = Hash::from(i as u64 + 1);
+        let parent = if i == 0 { genesis } else { Hash::from(i as u64) };
+        let mut block = consensus.build_block_with_parents(hash, vec![parent]);
+        block.header.timestamp = genesis_timestamp + offset;
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+    }
+    let mut block = consensus.build_block_with_parents(5.into(), vec![4.into()]);
+    block.header.timestamp = genesis_timestamp + 1_000;
+    consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+
+    // Genesis and blocks with an empty window (whose selected parent is genesis) fall back to the genesis timestamp
+    assert_eq!(consensus.past_median_time(genesis), Some(genesis_timestamp));
+    assert_eq!(consensus.past_median_time(1.into()), Some(genesis_timestamp));
+
+    // The window of a block is its past excluding genesis, and the median is taken over the sorted timestamps
+    let manual_median = |window_offsets: &[u64]| {
+        let mut sorted = window_offsets.to_vec();
+        sorted.sort_unstable();
+        genesis_timestamp + sorted[sorted.len() / 2]
+    };
+    for i in 2..=5 {
+        let expected = manual_median(&offsets[..i - 1]);
+        assert_eq!(consensus.past_median_time(Hash::from(i as u64)), Some(expected), "block {i}");
+    }
+    assert_eq!(consensus.past_median_time(5.into()), Some(genesis_timestamp + 600));
+
+    // Unknown blocks have no past median time
+    assert_eq!(consensus.past_median_time(100.into()), None);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn difficulty_info_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    for hash in [config.genesis.hash, 2.into()] {
+        let bits = consensus.get_header(hash).unwrap().bits;
+        let info = consensus.difficulty_info(hash).unwrap();
+        assert_eq!(info.bits, bits);
+        assert_eq!(info.target, Uint256::from_compact_target_bits(bits));
+        assert_eq!(info.work, calc_work(bits));
+        let expected_hps = calc_work(bits).as_f64() * 1000.0 / config.prior_target_time_per_block as f64;
+        assert!((info.estimated_network_hps - expected_hps).abs() <= expected_hps * 1e-12);
+    }
+
+    // Unknown (or pruned) headers are reported as missing
+    assert_match!(consensus.difficulty_info(100.into()), Err(ConsensusError::HeaderNotFound(_)));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn get_headers_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![2.into(), 3.into()]).await.unwrap();
+
+    // Input order is preserved, including duplicates and unknown hashes
+    let hashes: Vec<Hash> = vec![4.into(), 100.into(), config.genesis.hash, 2.into(), 3.into(), 2.into()];
+    let headers = consensus.get_headers(&hashes);
+    assert_eq!(headers.len(), hashes.len());
+    for (hash, header) in hashes.iter().copied().zip(headers) {
+        match consensus.get_header(hash) {
+            Ok(expected) => assert_eq!(header.unwrap().hash, expected.hash),
+            Err(_) => assert!(header.is_none()),
+        }
+    }
+    assert!(consensus.get_headers(&[]).is_empty());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn get_utxo_entries_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Build a short chain so that the coinbase outputs of the merged blocks populate the virtual UTXO set
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![OpTrue]), vec![]);
+    let mut parent = config.genesis.hash;
+    for i in 1..=4u64 {
+        let block = consensus.build_utxo_valid_block_with_parents(i.into(), vec![parent], miner_data.clone(), vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+        parent = i.into();
+    }
+
+    let virtual_utxos = consensus.get_virtual_utxos(None, usize::MAX, false);
+    assert!(!virtual_utxos.is_empty());
+
+    // Interleave existing outpoints with missing ones (unknown transaction ids and out-of-range output indices)
+    let mut outpoints = Vec::new();
+    let mut expected = Vec::new();
+    for (i, (outpoint, entry)) in virtual_utxos.iter().enumerate() {
+        outpoints.push(*outpoint);
+        expected.push(Some(entry.clone()));
+        outpoints.push(TransactionOutpoint::new(new_unique(), i as u32));
+        expected.push(None);
+        outpoints.push(TransactionOutpoint::new(outpoint.transaction_id, u32::MAX));
+        expected.push(None);
+    }
+
+    assert_eq!(consensus.get_utxo_entries(&outpoints), expected);
+    assert!(consensus.get_utxo_entries(&[]).is_empty());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn coinbase_maturity_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![OpTrue]), vec![]);
+    let mut parent = config.genesis.hash;
+    for i in 1..=4u64 {
+        let block = consensus.build_utxo_valid_block_with_parents(i.into(), vec![parent], miner_data.clone(), vec![]);
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+        parent = i.into();
+    }
+
+    let virtual_daa_score = consensus.get_virtual_daa_score();
+    let maturity = consensus.coinbase_maturity_daa();
+    assert_eq!(maturity, config.params.coinbase_maturity().get(virtual_daa_score));
+
+    let (outpoint, entry) =
+        consensus.get_virtual_utxos(None, usize::MAX, false).into_iter().find(|(_, entry)| entry.is_coinbase).unwrap();
+
+    // The coinbase utxo is immature at the current virtual DAA score and up to one score before its maturity
+    assert_eq!(consensus.is_coinbase_spendable(outpoint, virtual_daa_score), Some(false));
+    assert_eq!(consensus.is_coinbase_spendable(outpoint, entry.block_daa_score + maturity - 1), Some(false));
+
+    // The coinbase utxo becomes mature exactly `maturity` DAA scores after its block DAA score
+    assert_eq!(consensus.is_coinbase_spendable(outpoint, entry.block_daa_score + maturity), Some(true));
+    assert_eq!(consensus.is_coinbase_spendable(outpoint, entry.block_daa_score + maturity + 1), Some(true));
+
+    // Unknown outpoints are not answered
+    assert_eq!(consensus.is_coinbase_spendable(TransactionOutpoint::new(new_unique(), 0), virtual_daa_score), None);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn accepted_transactions_data_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.prior_coinbase_maturity = 0;
+            p.crescendo.coinbase_maturity = 0;
+            p.crescendo_activation = ForkActivation::always()
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![OpTrue]), vec![]);
+    let b = consensus.build_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], miner_data.clone(), vec![]);
+    consensus.validate_and_insert_block(b.to_immutable()).virtual_state_task.await.unwrap();
+    let funding_block = consensus.build_utxo_valid_block_with_parents(2.into(), vec![1.into()], miner_data.clone(), vec![]);
+    let (cb_id, cb_amount) = {
+        let mut cb = funding_block.transactions[0].clone();
+        cb.finalize();
+        (cb.id(), cb.outputs[0].value)
+    };
+    consensus.validate_and_insert_block(funding_block.to_immutable()).virtual_state_task.await.unwrap();
+
+    let tx = Transaction::new(
+        0,
+        vec![TransactionInput::new(TransactionOutpoint { transaction_id: cb_id, index: 0 }, vec![], 0, 0)],
+        vec![TransactionOutput::new(cb_amount / 2, ScriptPublicKey::from_vec(0, vec![OpTrue]))],
+        0,
+        SubnetworkId::default(),
+        0,
+        vec![],
+    );
+    let mut tx = MutableTransaction::from_tx(tx);
+    // This triggers storage mass population
+    consensus.validate_mempool_transaction(&mut tx, &TransactionValidationArgs::default()).unwrap();
+    let tx = tx.tx.unwrap_or_clone();
+
+    // Block 3 includes the tx, which is accepted by block 4. Blocks 5 and 6 add a merged sibling
+    consensus.add_utxo_valid_block_with_parents(3.into(), vec![2.into()], vec![tx.clone()]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(4.into(), vec![3.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(5.into(), vec![3.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(6.into(), vec![4.into(), 5.into()], vec![]).await.unwrap();
+
+    // Block 4 accepts the coinbase of its selected parent and the tx, both attributed to block 3
+    let data = consensus.get_block_accepted_transactions_data(4.into()).unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].block_hash, 3.into());
+    let block_3_transactions = consensus.get_block(3.into()).unwrap().transactions;
+    let accepted = data[0].accepted_transactions.iter().map(|entry| (entry.index_within_block, entry.transaction.id())).collect_vec();
+    assert_eq!(accepted, vec![(0, block_3_transactions[0].id()), (1, tx.id())]);
+
+    // The resolved transactions are consistent with the raw acceptance data of every chain block
+    for hash in [2.into(), 3.into(), 4.into(), 6.into()] {
+        let acceptance_data = consensus.get_block_acceptance_data(hash).unwrap();
+        let data = consensus.get_block_accepted_transactions_data(hash).unwrap();
+        assert_eq!(data.len(), acceptance_data.len());
+        for (resolved, raw) in data.iter().zip(acceptance_data.iter()) {
+            assert_eq!(resolved.block_hash, raw.block_hash);
+            let resolved_entries =
+                resolved.accepted_transactions.iter().map(|entry| (entry.index_within_block, entry.transaction.id()));
+            let raw_entries = raw.accepted_transactions.iter().map(|entry| (entry.index_within_block, entry.transaction_id));
+            assert!(resolved_entries.eq(raw_entries));
+        }
+    }
+    assert_eq!(consensus.get_block_accepted_transactions_data(6.into()).unwrap().len(), 2);
+
+    // Unknown blocks have no acceptance data
+    assert!(consensus.get_block_accepted_transactions_data(100.into()).is_none());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn accepting_block_of_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.prior_coinbase_maturity = 0;
+            p.crescendo.coinbase_maturity = 0;
+            p.crescendo_activation = ForkActivation::always()
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![OpTrue]), vec![]);
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    let funding_block = consensus.build_utxo_valid_block_with_parents(2.into(), vec![1.into()], miner_data, vec![]);
+    let (cb_id, cb_amount) = {
+        let mut cb = funding_block.transactions[0].clone();
+        cb.finalize();
+        (cb.id(), cb.outputs[0].value)
+    };
+    consensus.validate_and_insert_block(funding_block.to_immutable()).virtual_state_task.await.unwrap();
+
+    let tx = Transaction::new(
+        0,
+        vec![TransactionInput::new(TransactionOutpoint { transaction_id: cb_id, index: 0 }, vec![], 0, 0)],
+        vec![TransactionOutput::new(cb_amount / 2, ScriptPublicKey::from_vec(0, vec![OpTrue]))],
+        0,
+        SubnetworkId::default(),
+        0,
+        vec![],
+    );
+    let mut tx = MutableTransaction::from_tx(tx);
+    consensus.validate_mempool_transaction(&mut tx, &TransactionValidationArgs::default()).unwrap();
+    let tx = tx.tx.unwrap_or_clone();
+
+    // Block 3 includes the tx, which is accepted by block 4. Blocks 5 and 6 extend the chain so that 4 is below the sink
+    consensus.add_utxo_valid_block_with_parents(3.into(), vec![2.into()], vec![tx.clone()]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(4.into(), vec![3.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(5.into(), vec![4.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(6.into(), vec![5.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 6.into());
+
+    // The chain blocks scanned are 6, 5 and then 4
+    assert_eq!(consensus.accepting_block_of(tx.id(), 3), Some(4.into()));
+    assert_eq!(consensus.accepting_block_of(tx.id(), 10), Some(4.into()));
+    assert_eq!(consensus.accepting_block_of(tx.id(), 2), None);
+    assert_eq!(consensus.accepting_block_of(tx.id(), 0), None);
+
+    // The funding coinbase is accepted by the chain block following its block
+    assert_eq!(consensus.accepting_block_of(cb_id, 10), Some(3.into()));
+
+    // Unknown txs are not found
+    assert_eq!(consensus.accepting_block_of(100.into(), 10), None);
+
+    // Excessive depths are capped rather than rejected
+    assert_eq!(consensus.accepting_block_of(tx.id(), u64::MAX), Some(4.into()));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn pruning_point_utxo_set_summary_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Extend the genesis pruning point UTXO set with enough entries to span several scan chunks
+    let baseline = consensus.pruning_point_utxo_set_summary().unwrap();
+    let utxos = (0..2500u64)
+        .map(|i| {
+            let outpoint = TransactionOutpoint::new(new_unique(), (i % 3) as u32);
+            (outpoint, UtxoEntry::new(i + 1, ScriptPublicKey::from_vec(0, vec![OpTrue; (i % 40) as usize]), 0, false))
+        })
+        .collect_vec();
+    consensus.append_imported_pruning_point_utxos(&utxos, &mut MuHash::new());
+
+    let summary = consensus.pruning_point_utxo_set_summary().unwrap();
+    let added: UtxoSetSummary = utxos.iter().map(|(_, entry)| entry).collect();
+    assert_eq!(summary.count, baseline.count + added.count);
+    assert_eq!(summary.total_sompi, baseline.total_sompi + added.total_sompi);
+    assert_eq!(summary.bytes, baseline.bytes + added.bytes);
+
+    // The summary matches the set as streamed to syncing peers
+    let pruning_point = consensus.pruning_point();
+    let mut streamed = Vec::new();
+    let mut from_outpoint = None;
+    loop {
+        let chunk = consensus.get_pruning_point_utxos(pruning_point, from_outpoint, 1000, from_outpoint.is_some()).unwrap();
+        let Some((last, _)) = chunk.last() else { break };
+        from_outpoint = Some(*last);
+        streamed.extend(chunk.into_iter().map(|(_, entry)| entry));
+    }
+    assert_eq!(summary, streamed.iter().collect());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn dag_stats_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![2.into(), 3.into()]).await.unwrap();
+
+    // Block 4 merges both 2 and 3 (the latter as a blue), while 2 and 3 each merge only genesis
+    let stats = consensus.dag_stats(&[2.into(), 3.into(), 4.into()]);
+    assert_eq!((stats.blues_mean, stats.reds_mean, stats.parents_mean), (4.0 / 3.0, 0.0, 4.0 / 3.0));
+
+    // Unknown blocks are skipped rather than failing the whole query
+    assert_eq!(consensus.dag_stats(&[2.into(), 100.into(), 3.into(), 4.into(), 101.into()]), stats);
+    assert_eq!(consensus.dag_stats(&[100.into()]), Default::default());
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn dag_tips_info_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    //   G <- 2 <- 4 <- 5 (header only)
+    //     <- 3
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![2.into()]).await.unwrap();
+
+    let info = consensus.dag_tips_info();
+    assert_eq!(info.sink, 4.into());
+    assert_eq!(info.virtual_parents.iter().copied().collect::<BlockHashSet>(), BlockHashSet::from_iter([3.into(), 4.into()]));
+    assert_eq!(info.body_tips.iter().copied().collect::<BlockHashSet>(), BlockHashSet::from_iter([3.into(), 4.into()]));
+    assert_eq!(info.headers_selected_tip, 4.into());
+
+    // A header-only block advances the headers selected tip but not the body tips
+    let header = consensus.build_header_with_parents(5.into(), vec![4.into()]);
+    consensus.validate_and_insert_block(Block::from_header(header)).virtual_state_task.await.unwrap();
+    let info = consensus.dag_tips_info();
+    assert_eq!(info.sink, 4.into());
+    assert_eq!(info.body_tips.iter().copied().collect::<BlockHashSet>(), BlockHashSet::from_iter([3.into(), 4.into()]));
+    assert_eq!(info.headers_selected_tip, 5.into());
+
+    // Merging both tips leaves a single tip
+    consensus.add_block_with_parents(6.into(), vec![3.into(), 4.into()]).await.unwrap();
+    let info = consensus.dag_tips_info();
+    assert_eq!((info.sink, info.virtual_parents, info.body_tips), (6.into(), vec![6.into()], vec![6.into()]));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn self_check_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![2.into(), 3.into()]).await.unwrap();
+    consensus.add_block_with_parents(5.into(), vec![4.into()]).await.unwrap();
+
+    let report = consensus.self_check(10);
+    assert!(report.is_consistent(), "{:?}", report.issues);
+    assert_eq!(report.checked_blocks, 5);
+
+    // The check is bounded by depth
+    assert_eq!(consensus.self_check(2).checked_blocks, 2);
+    assert_eq!(consensus.self_check(0).checked_blocks, 0);
+
+    // Inject inconsistencies: a missing status and a parent which is not linked back to its child
+    consensus.statuses_store.write().delete(3.into()).unwrap();
+    {
+        let mut relations = consensus.reachability_relations_store.write();
+        let writer = relations.default_writer();
+        relations.set_parents(writer, 5.into(), BlockHashes::new(vec![4.into(), 2.into()])).unwrap();
+    }
+
+    let report = consensus.self_check(10);
+    assert!(!report.is_consistent());
+    assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+    assert!(report.issues.contains(&SelfCheckIssue::MissingChildLink { parent: 2.into(), child: 5.into() }));
+    assert!(report.issues.contains(&SelfCheckIssue::MissingStatus(3.into())));
+
+    // Issues below the checked depth are not reported
+    let report = consensus.self_check(1);
+    assert_eq!(report.issues, vec![SelfCheckIssue::MissingChildLink { parent: 2.into(), child: 5.into() }]);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn compute_ghostdag_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_block_with_parents(1.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(2.into(), vec![config.genesis.hash]).await.unwrap();
+    consensus.add_block_with_parents(3.into(), vec![1.into()]).await.unwrap();
+    consensus.add_block_with_parents(4.into(), vec![2.into()]).await.unwrap();
+
+    // The hypothetical data matches the data of an actually inserted block with the same parents
+    let parents = vec![3.into(), 4.into()];
+    let computed = consensus.compute_ghostdag(&parents).unwrap();
+    assert!(consensus.get_block_status(5.into()).is_none());
+    consensus.add_block_with_parents(5.into(), parents).await.unwrap();
+    let inserted = consensus.get_ghostdag_data(5.into()).unwrap();
+    assert_eq!(computed.selected_parent, inserted.selected_parent);
+    assert_eq!(computed.blue_score, inserted.blue_score);
+    assert_eq!(computed.blue_work, inserted.blue_work);
+    assert_eq!(computed.mergeset_blues, inserted.mergeset_blues);
+    assert_eq!(computed.mergeset_reds, inserted.mergeset_reds);
+    assert_eq!(computed.blues_anticone_sizes, inserted.blues_anticone_sizes);
+
+    // Invalid parent sets are rejected
+    assert!(matches!(consensus.compute_ghostdag(&[]), Err(ConsensusError::General(_))));
+    assert!(
+        matches!(consensus.compute_ghostdag(&[3.into(), 100.into()]), Err(ConsensusError::HeaderNotFound(hash)) if hash == Hash::from(100u64))
+    );
+    assert!(matches!(consensus.compute_ghostdag(&[1.into(), 3.into()]), Err(ConsensusError::GeneralOwned(_))));
+    assert!(matches!(consensus.compute_ghostdag(&[3.into(), 3.into()]), Err(ConsensusError::GeneralOwned(_))));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn estimate_past_size_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let dag: Vec<(u64, Vec<u64>)> =
+        vec![(1, vec![]), (2, vec![]), (3, vec![1]), (4, vec![1, 2]), (5, vec![3]), (6, vec![4, 5]), (7, vec![2]), (8, vec![6, 7])];
+    let to_hash = |id: u64| if id == 0 { config.genesis.hash } else { Hash::from(id) };
+    for (id, parents) in dag.iter() {
+        let parents = if parents.is_empty() { vec![config.genesis.hash] } else { parents.iter().copied().map(to_hash).collect() };
+        consensus.add_block_with_parents(to_hash(*id), parents).await.unwrap();
+    }
+
+    // The estimate matches the exact inclusive past size since no blocks are DAA-excluded in this small DAG
+    for id in 0..=8 {
+        let hash = to_hash(id);
+        let exact = inclusive_past(&*consensus.reachability_relations_store.read(), hash).len() as u64;
+        assert_eq!(consensus.estimate_past_size(hash).unwrap(), exact, "block {id}");
+    }
+
+    assert!(matches!(consensus.estimate_past_size(100.into()), Err(ConsensusError::HeaderNotFound(_))));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn validate_dag_integrity_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(2.into(), vec![1.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(3.into(), vec![1.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(4.into(), vec![2.into(), 3.into()], vec![]).await.unwrap();
+
+    assert_eq!(consensus.validate_dag_integrity(10).unwrap(), 5);
+    assert_eq!(consensus.validate_dag_integrity(2).unwrap(), 3);
+
+    // Inject inconsistencies: a chain block multiset which does not match its UTXO commitment and altered GHOSTDAG data
+    consensus.utxo_multisets_store.delete(1.into()).unwrap();
+    consensus.utxo_multisets_store.insert(1.into(), MuHash::new()).unwrap();
+    let mut ghostdag_data = consensus.ghostdag_store().get_data(4.into()).unwrap().as_ref().clone();
+    ghostdag_data.blue_score += 1;
+    consensus.ghostdag_store().delete(4.into()).unwrap();
+    consensus.ghostdag_store().insert(4.into(), Arc::new(ghostdag_data)).unwrap();
+
+    let DagIntegrityError(report) = consensus.validate_dag_integrity(10).unwrap_err();
+    assert_eq!(report.checked_blocks, 5);
+    assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+    assert!(report.issues.contains(&SelfCheckIssue::GhostdagMismatch(4.into())));
+    assert!(report.issues.contains(&SelfCheckIssue::UtxoCommitmentMismatch(1.into())));
+
+    // The structural self-check does not re-verify block data
+    assert!(consensus.self_check(10).is_consistent());
+
+    consensus.shutdown(wait_handles);
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct GhostdagTestDag {
     #[serde(rename = "K")]
@@ -1719,6 +2394,50 @@ async fn selected_chain_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn headers_by_blue_work_range_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_size = p.prior_difficulty_window_size;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    for i in 2..10 {
+        consensus.add_utxo_valid_block_with_parents(i.into(), vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+    // A side block which is merged but never on the selected chain
+    consensus.add_utxo_valid_block_with_parents(10.into(), vec![1.into()], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(11.into(), vec![9.into(), 10.into()], vec![]).await.unwrap();
+
+    let blue_work = |i: u64| consensus.get_header(i.into()).unwrap().blue_work;
+    let hashes = |headers: Vec<Arc<Header>>| headers.iter().map(|h| h.hash).collect_vec();
+
+    // Full range in ascending blue-work order, including genesis and excluding the side block
+    let all = consensus.headers_by_blue_work_range(BlueWorkType::ZERO, BlueWorkType::MAX, usize::MAX);
+    assert!(all.windows(2).all(|w| w[0].blue_work < w[1].blue_work));
+    assert_eq!(hashes(all), once(config.genesis.hash).chain([1, 2, 3, 4, 5, 6, 7, 8, 9, 11].map(Hash::from_u64_word)).collect_vec());
+
+    // Inclusive bounds
+    let range = consensus.headers_by_blue_work_range(blue_work(3), blue_work(6), usize::MAX);
+    assert_eq!(hashes(range), (3..=6).map(Hash::from_u64_word).collect_vec());
+
+    // The limit bounds the result from the low end
+    let limited = consensus.headers_by_blue_work_range(blue_work(2), BlueWorkType::MAX, 3);
+    assert_eq!(hashes(limited), (2..=4).map(Hash::from_u64_word).collect_vec());
+    assert!(consensus.headers_by_blue_work_range(blue_work(2), BlueWorkType::MAX, 0).is_empty());
+
+    // Empty and inverted ranges
+    assert!(consensus.headers_by_blue_work_range(blue_work(6), blue_work(3), usize::MAX).is_empty());
+    assert!(consensus.headers_by_blue_work_range(blue_work(11) + 1u64, BlueWorkType::MAX, usize::MAX).is_empty());
+
+    consensus.shutdown(wait_handles);
+}
+
 fn assert_selected_chain_store_matches_virtual_chain(consensus: &TestConsensus) {
     let pruning_point = consensus.pruning_point();
     let iter1 = selected_chain_store_iterator(consensus, pruning_point);
@@ -349,6 +349,102 @@ async fn daemon_utxos_propagation_test() {
     }
 }
 
+/// `cargo test --release --package kaspa-testing-integration --lib --features devnet-prealloc -- daemon_integration_tests::daemon_p2p_tx_relay_test`
+#[cfg(feature = "devnet-prealloc")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_p2p_tx_relay_test() {
+    use crate::common::{tx_dag::generate_prealloc_tx_dag, tx_peer::TxBroadcastPeer};
+    use kaspa_consensus_core::{constants::SOMPI_PER_KASPA, network::NetworkType};
+
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    // Two levels so that some of the relayed txs spend outputs of previously relayed ones
+    let dag = generate_prealloc_tx_dag(2, 10, 100 * SOMPI_PER_KASPA);
+    dag.verify();
+    let tx_count = dag.txs.len() as u64;
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        num_prealloc_utxos: Some(dag.initial_utxoset.len() as u64),
+        prealloc_address: Some(dag.prealloc_address(NetworkType::Simnet.into()).to_string()),
+        prealloc_amount: dag.prealloc_amount(),
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client1 = kaspad1.start().await;
+
+    // The node ignores relayed txs while it is not nearly synced, so mine a block to make the sink recent
+    let template = rpc_client1
+        .get_block_template(Address::new(kaspad1.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]), vec![])
+        .await
+        .unwrap();
+    rpc_client1.submit_block(template.block, false).await.unwrap();
+    let check_client = rpc_client1.clone();
+    wait_for(
+        50,
+        20,
+        move || {
+            async fn is_synced(client: GrpcClient) -> bool {
+                client.get_server_info().await.unwrap().is_synced
+            }
+            Box::pin(is_synced(check_client.clone()))
+        },
+        "the node did not become synced",
+    )
+    .await;
+
+    // Txs relayed by peers are inserted with low priority, which the mining counters track separately from RPC submissions
+    let mining_manager = kaspad1.rpc_core_service().mining_manager();
+    let counters_before = mining_manager.snapshot();
+
+    let peer = TxBroadcastPeer::connect(format!("127.0.0.1:{}", kaspad1.p2p_port), kaspad1.network, dag.txs, 100.0).await.unwrap();
+    assert!(peer.is_connected());
+
+    let check_mining_manager = mining_manager.clone();
+    let low_priority_tx_counts_before = counters_before.low_priority_tx_counts;
+    wait_for(
+        100,
+        50,
+        move || {
+            let low_priority_tx_counts = check_mining_manager.snapshot().low_priority_tx_counts - low_priority_tx_counts_before;
+            std::future::ready(low_priority_tx_counts == tx_count)
+        },
+        "the txs relayed via P2P did not reach the mempool",
+    )
+    .await;
+    let counters_delta = &mining_manager.snapshot() - &counters_before;
+    assert_eq!(counters_delta.low_priority_tx_counts, tx_count);
+    assert_eq!(counters_delta.high_priority_tx_counts, 0);
+    assert_eq!(peer.broadcast_count(), tx_count);
+    assert_eq!(peer.served_count(), tx_count);
+    assert_eq!(rpc_client1.get_connected_peer_info().await.unwrap().peer_info.len(), 1);
+
+    // Dropping the peer should disconnect it from the node
+    drop(peer);
+    let check_client = rpc_client1.clone();
+    wait_for(
+        50,
+        20,
+        move || {
+            async fn peer_disconnected(client: GrpcClient) -> bool {
+                client.get_connected_peer_info().await.unwrap().peer_info.is_empty()
+            }
+            Box::pin(peer_disconnected(check_client.clone()))
+        },
+        "the peer did not disconnect on drop",
+    )
+    .await;
+
+    rpc_client1.disconnect().await.unwrap();
+    drop(rpc_client1);
+    kaspad1.shutdown();
+}
+
 // The following test runtime parameters are required for a graceful shutdown of the gRPC server
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_cleaning_test() {
@@ -1,10 +1,10 @@
 use crate::{
     common::{
-        self,
         args::ArgsBuilder,
         client_notify::ChannelNotify,
         daemon::{ClientManager, Daemon},
-        utils::CONTRACT_FACTOR,
+        submitter::RateLimitedSubmitter,
+        tx_dag::{generate_prealloc_tx_dag, TxDag},
     },
     tasks::{block::group::MinerGroupTask, daemon::DaemonTask, tx::group::TxSenderGroupTask, Stopper, TasksRunner},
 };
@@ -18,7 +18,6 @@ use kaspa_notify::{
     scope::{NewBlockTemplateScope, Scope},
 };
 use kaspa_rpc_core::{api::rpc::RpcApi, Notification, RpcError};
-use kaspa_txscript::pay_to_address_script;
 use kaspa_utils::fd_budget;
 use kaspad_lib::args::Args;
 use parking_lot::Mutex;
@@ -30,7 +29,7 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::{Duration, Instant},
+    time::Duration,
 };
 use tokio::join;
 
@@ -68,35 +67,32 @@ async fn bench_bbt_latency() {
 
     TODO:
         1. More measurements with statistical aggregation
-        2. Save TX DAG dataset in a file for benchmark replication and stability
-        3. Add P2P TX traffic by implementing a custom P2P peer which only broadcasts txs
+        2. Add P2P TX traffic by implementing a custom P2P peer which only broadcasts txs
     */
 
     //
     // Setup
     //
-    let (prealloc_sk, prealloc_pk) = secp256k1::generate_keypair(&mut thread_rng());
-    let prealloc_address =
-        Address::new(NetworkType::Simnet.into(), kaspa_addresses::Version::PubKey, &prealloc_pk.x_only_public_key().0.serialize());
-    let schnorr_key = secp256k1::Keypair::from_secret_key(secp256k1::SECP256K1, &prealloc_sk);
-    let spk = pay_to_address_script(&prealloc_address);
+    let dag = TxDag::load_or_generate_from_env(|| {
+        generate_prealloc_tx_dag(TX_COUNT / TX_LEVEL_WIDTH, TX_LEVEL_WIDTH, 500 * SOMPI_PER_KASPA)
+    })
+    .unwrap();
+    dag.verify();
 
     let args = Args {
         simnet: true,
         disable_upnp: true, // UPnP registration might take some time and is not needed for this test
         enable_unsynced_mining: true,
-        num_prealloc_utxos: Some(TX_LEVEL_WIDTH as u64 * CONTRACT_FACTOR),
-        prealloc_address: Some(prealloc_address.to_string()),
-        prealloc_amount: 500 * SOMPI_PER_KASPA,
+        num_prealloc_utxos: Some(dag.initial_utxoset.len() as u64),
+        prealloc_address: Some(dag.prealloc_address(NetworkType::Simnet.into()).to_string()),
+        prealloc_amount: dag.prealloc_amount(),
         block_template_cache_lifetime: Some(0),
         ..Default::default()
     };
     let network = args.network();
     let params: Params = network.into();
 
-    let utxoset = args.generate_prealloc_utxos(args.num_prealloc_utxos.unwrap());
-    let txs = common::utils::generate_tx_dag(utxoset.clone(), schnorr_key, spk, TX_COUNT / TX_LEVEL_WIDTH, TX_LEVEL_WIDTH);
-    common::utils::verify_tx_dag(&utxoset, &txs);
+    let txs = dag.txs;
     info!("Generated overall {} txs", txs.len());
 
     let fd_total_budget = fd_budget::limit();
@@ -211,44 +207,9 @@ async fn bench_bbt_latency() {
     let tx_sender = submit_tx_pool.sender();
     let exec = executing.clone();
     let cc = client.clone();
-    let mut tps_pressure = if MEMPOOL_TARGET < u64::MAX { u64::MAX } else { TPS_PRESSURE };
-    let mut last_log_time = Instant::now() - Duration::from_secs(5);
-    let mut log_index = 0;
+    let submitter = RateLimitedSubmitter::new(TPS_PRESSURE).with_mempool_target(MEMPOOL_TARGET, Arc::new(client.clone()));
     let tx_sender_task = tokio::spawn(async move {
-        for (i, tx) in txs.into_iter().enumerate() {
-            if tps_pressure != u64::MAX {
-                tokio::time::sleep(std::time::Duration::from_secs_f64(1.0 / tps_pressure as f64)).await;
-            }
-            if last_log_time.elapsed() > Duration::from_millis(200) {
-                let mut mempool_size = cc.get_info().await.unwrap().mempool_size;
-                if log_index % 10 == 0 {
-                    info!("Mempool size: {:#?}, txs submitted: {}", mempool_size, i);
-                }
-                log_index += 1;
-                last_log_time = Instant::now();
-
-                if mempool_size > (MEMPOOL_TARGET as f32 * 1.05) as u64 {
-                    tps_pressure = TPS_PRESSURE;
-                    while mempool_size > MEMPOOL_TARGET {
-                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                        mempool_size = cc.get_info().await.unwrap().mempool_size;
-                        if log_index % 10 == 0 {
-                            info!("Mempool size: {:#?}, txs submitted: {}", mempool_size, i);
-                        }
-                        log_index += 1;
-                    }
-                }
-            }
-            match tx_sender.send((i, tx)).await {
-                Ok(_) => {}
-                Err(_) => {
-                    break;
-                }
-            }
-            if !exec.load(Ordering::Relaxed) {
-                break;
-            }
-        }
+        submitter.submit(txs.into_iter().enumerate(), &tx_sender, || exec.load(Ordering::Relaxed)).await;
 
         kaspa_core::warn!("Tx sender task, waiting for mempool to drain..");
         loop {
@@ -316,30 +277,27 @@ async fn bench_bbt_latency_2() {
 
     TODO:
         1. More measurements with statistical aggregation
-        2. Save TX DAG dataset in a file for benchmark replication and stability
-        3. Add P2P TX traffic by implementing a custom P2P peer which only broadcasts txs
+        2. Add P2P TX traffic by implementing a custom P2P peer which only broadcasts txs
     */
 
     //
     // Setup
     //
-    let (prealloc_sk, prealloc_pk) = secp256k1::generate_keypair(&mut thread_rng());
-    let prealloc_address =
-        Address::new(NetworkType::Simnet.into(), kaspa_addresses::Version::PubKey, &prealloc_pk.x_only_public_key().0.serialize());
-    let schnorr_key = secp256k1::Keypair::from_secret_key(secp256k1::SECP256K1, &prealloc_sk);
-    let spk = pay_to_address_script(&prealloc_address);
-
-    let args = ArgsBuilder::simnet(TX_LEVEL_WIDTH as u64 * CONTRACT_FACTOR, 500)
-        .prealloc_address(prealloc_address)
+    let dag = TxDag::load_or_generate_from_env(|| {
+        generate_prealloc_tx_dag(TX_COUNT / TX_LEVEL_WIDTH, TX_LEVEL_WIDTH, 500 * SOMPI_PER_KASPA)
+    })
+    .unwrap();
+    dag.verify();
+
+    let args = ArgsBuilder::simnet(dag.initial_utxoset.len() as u64, dag.prealloc_amount() / SOMPI_PER_KASPA)
+        .prealloc_address(dag.prealloc_address(NetworkType::Simnet.into()))
         .apply_args(Daemon::fill_args_with_random_ports)
         .build();
 
     let network = args.network();
     let params: Params = network.into();
 
-    let utxoset = args.generate_prealloc_utxos(args.num_prealloc_utxos.unwrap());
-    let txs = common::utils::generate_tx_dag(utxoset.clone(), schnorr_key, spk, TX_COUNT / TX_LEVEL_WIDTH, TX_LEVEL_WIDTH);
-    common::utils::verify_tx_dag(&utxoset, &txs);
+    let txs = dag.txs;
     info!("Generated overall {} txs", txs.len());
 
     let client_manager = Arc::new(ClientManager::new(args));
@@ -1,8 +1,8 @@
 use crate::{
     common::{
-        self,
         args::ArgsBuilder,
         daemon::{ClientManager, Daemon},
+        tx_dag::TxDagGenerator,
         utils::CONTRACT_FACTOR,
     },
     tasks::{
@@ -189,14 +189,10 @@ async fn utxos_changed_subscriptions_client(address_cycle_seconds: u64, address_
     let params: Params = network.into();
 
     let utxoset = args.generate_prealloc_utxos(args.num_prealloc_utxos.unwrap());
-    let txs = common::utils::generate_tx_dag(
-        utxoset.clone(),
-        schnorr_key,
-        spk,
-        (TX_COUNT + TX_LEVEL_WIDTH - 1) / TX_LEVEL_WIDTH,
-        TX_LEVEL_WIDTH,
-    );
-    common::utils::verify_tx_dag(&utxoset, &txs);
+    let dag =
+        TxDagGenerator::new(schnorr_key, spk, (TX_COUNT + TX_LEVEL_WIDTH - 1) / TX_LEVEL_WIDTH, TX_LEVEL_WIDTH).generate(utxoset);
+    dag.verify();
+    let txs = dag.txs;
     info!("Generated overall {} txs", txs.len());
 
     // Start the daemon
@@ -1,17 +1,28 @@
 use super::semaphore::Semaphore;
-use std::sync::Arc;
+use std::{
+    cell::UnsafeCell,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
 
 /// Readers-first Reader-writer Lock. If the lock is acquired by readers, then additional readers
 /// will always be able to acquire the lock as well even if a writer is already in the queue. Note
 /// that this makes it safe to make recursive read calls.
 ///
-/// We currently only use this lock over an empty tuple, however it can easily contain data by
-/// using `UnsafeCell<T>` and passing it to the various guards with or without mutable access
-pub struct RfRwLock {
+/// The lock protects data of type `T` which is accessible through the various guards, with mutable
+/// access provided only by write guards. By default the lock is over an empty tuple, in which case it
+/// is used purely for synchronization
+pub struct RfRwLock<T: ?Sized = ()> {
     // The low-level "non-fair" semaphore used to prioritize readers
     ll_sem: Semaphore,
+    data: UnsafeCell<T>,
 }
 
+// Safety: same bounds as `std::sync::RwLock`. The semaphore guarantees that data is either shared by
+// readers or exclusively accessed by a single writer
+unsafe impl<T: ?Sized + Send> Send for RfRwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RfRwLock<T> {}
+
 impl Default for RfRwLock {
     fn default() -> Self {
         Self::new()
@@ -20,25 +31,38 @@ impl Default for RfRwLock {
 
 impl RfRwLock {
     pub fn new() -> Self {
-        Self { ll_sem: Semaphore::new(Semaphore::MAX_PERMITS) }
+        Self::with_data(())
+    }
+}
+
+impl<T> RfRwLock<T> {
+    pub fn with_data(data: T) -> Self {
+        Self { ll_sem: Semaphore::new(Semaphore::MAX_PERMITS), data: UnsafeCell::new(data) }
     }
 
-    pub async fn read(&self) -> RfRwLockReadGuard<'_> {
+    /// Consumes the lock, returning the underlying data
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RfRwLock<T> {
+    pub async fn read(&self) -> RfRwLockReadGuard<'_, T> {
         self.ll_sem.acquire(1).await;
         RfRwLockReadGuard(self)
     }
 
-    pub fn blocking_read(&self) -> RfRwLockReadGuard<'_> {
+    pub fn blocking_read(&self) -> RfRwLockReadGuard<'_, T> {
         self.ll_sem.blocking_acquire(1);
         RfRwLockReadGuard(self)
     }
 
-    pub async fn read_owned(self: Arc<Self>) -> RfRwLockOwnedReadGuard {
+    pub async fn read_owned(self: Arc<Self>) -> RfRwLockOwnedReadGuard<T> {
         self.ll_sem.acquire(1).await;
         RfRwLockOwnedReadGuard(self)
     }
 
-    pub async fn write(&self) -> RfRwLockWriteGuard<'_> {
+    pub async fn write(&self) -> RfRwLockWriteGuard<'_, T> {
         // Writes acquire all possible permits, hence they ensure exclusiveness. On the other hand, this allows
         // late readers to get in front of them since readers request only a single permit and the semaphore is
         // non-fair
@@ -46,16 +70,22 @@ impl RfRwLock {
         RfRwLockWriteGuard(self)
     }
 
-    pub fn blocking_write(&self) -> RfRwLockWriteGuard<'_> {
+    pub fn blocking_write(&self) -> RfRwLockWriteGuard<'_, T> {
         self.ll_sem.blocking_acquire(Semaphore::MAX_PERMITS);
         RfRwLockWriteGuard(self)
     }
 
-    pub async fn write_owned(self: Arc<Self>) -> RfRwLockOwnedWriteGuard {
+    pub async fn write_owned(self: Arc<Self>) -> RfRwLockOwnedWriteGuard<T> {
         self.ll_sem.acquire(Semaphore::MAX_PERMITS).await;
         RfRwLockOwnedWriteGuard(self)
     }
 
+    /// Returns a mutable reference to the underlying data. No locking is needed since
+    /// the mutable borrow statically guarantees exclusive access
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
     fn release_read(&self) {
         self.ll_sem.release(1);
     }
@@ -67,33 +97,78 @@ impl RfRwLock {
     fn blocking_yield_writer(&self) {
         self.ll_sem.blocking_yield(Semaphore::MAX_PERMITS);
     }
+
+    /// Safety: the caller must hold a read or write permit
+    unsafe fn data(&self) -> &T {
+        &*self.data.get()
+    }
+
+    /// Safety: the caller must hold the write permits
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn data_mut(&self) -> &mut T {
+        &mut *self.data.get()
+    }
 }
 
-pub struct RfRwLockReadGuard<'a>(&'a RfRwLock);
+pub struct RfRwLockReadGuard<'a, T: ?Sized = ()>(&'a RfRwLock<T>);
 
-impl Drop for RfRwLockReadGuard<'_> {
+impl<T: ?Sized> Deref for RfRwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard holds a read permit
+        unsafe { self.0.data() }
+    }
+}
+
+impl<T: ?Sized> Drop for RfRwLockReadGuard<'_, T> {
     fn drop(&mut self) {
         self.0.release_read();
     }
 }
 
-pub struct RfRwLockOwnedReadGuard(Arc<RfRwLock>);
+pub struct RfRwLockOwnedReadGuard<T: ?Sized = ()>(Arc<RfRwLock<T>>);
+
+impl<T: ?Sized> Deref for RfRwLockOwnedReadGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard holds a read permit
+        unsafe { self.0.data() }
+    }
+}
 
-impl Drop for RfRwLockOwnedReadGuard {
+impl<T: ?Sized> Drop for RfRwLockOwnedReadGuard<T> {
     fn drop(&mut self) {
         self.0.release_read();
     }
 }
 
-pub struct RfRwLockWriteGuard<'a>(&'a RfRwLock);
+pub struct RfRwLockWriteGuard<'a, T: ?Sized = ()>(&'a RfRwLock<T>);
+
+impl<T: ?Sized> Deref for RfRwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard holds the write permits
+        unsafe { self.0.data() }
+    }
+}
 
-impl Drop for RfRwLockWriteGuard<'_> {
+impl<T: ?Sized> DerefMut for RfRwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the guard holds the write permits
+        unsafe { self.0.data_mut() }
+    }
+}
+
+impl<T: ?Sized> Drop for RfRwLockWriteGuard<'_, T> {
     fn drop(&mut self) {
         self.0.release_write();
     }
 }
 
-impl RfRwLockWriteGuard<'_> {
+impl<T: ?Sized> RfRwLockWriteGuard<'_, T> {
     /// Releases and recaptures the write lock. Makes sure that other pending readers/writers get a
     /// chance to capture the lock before this thread does so.
     pub fn blocking_yield(&mut self) {
@@ -101,9 +176,25 @@ impl RfRwLockWriteGuard<'_> {
     }
 }
 
-pub struct RfRwLockOwnedWriteGuard(Arc<RfRwLock>);
+pub struct RfRwLockOwnedWriteGuard<T: ?Sized = ()>(Arc<RfRwLock<T>>);
+
+impl<T: ?Sized> Deref for RfRwLockOwnedWriteGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard holds the write permits
+        unsafe { self.0.data() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RfRwLockOwnedWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the guard holds the write permits
+        unsafe { self.0.data_mut() }
+    }
+}
 
-impl Drop for RfRwLockOwnedWriteGuard {
+impl<T: ?Sized> Drop for RfRwLockOwnedWriteGuard<T> {
     fn drop(&mut self) {
         self.0.release_write();
     }
@@ -189,4 +280,38 @@ mod tests {
         tx_back.send(()).unwrap();
         timeout(ACQUIRE_TIMEOUT, h).await.unwrap().unwrap();
     }
+
+    #[tokio::test]
+    async fn test_concurrent_readers_with_pending_writer() {
+        const READERS: usize = 32;
+        let l = Arc::new(RfRwLock::with_data(0u64));
+        let first_read = l.clone().read_owned().await;
+
+        let (tx, rx) = oneshot::channel();
+        let l_clone = l.clone();
+        let writer = tokio::spawn(async move {
+            let fut = l_clone.write_owned();
+            tx.send(()).unwrap();
+            let mut write = fut.await;
+            *write += 1;
+        });
+
+        // Wait for the writer to request writing before registering more readers
+        rx.await.unwrap();
+
+        // All readers are expected to acquire the lock concurrently although a writer is pending
+        let mut reads = Vec::with_capacity(READERS);
+        for _ in 0..READERS {
+            let read = timeout(ACQUIRE_TIMEOUT, l.clone().read_owned()).await.unwrap();
+            assert_eq!(0, *read, "writer acquired while readers hold the lock");
+            reads.push(read);
+        }
+        assert!(!writer.is_finished());
+
+        // Release all readers and make sure the writer eventually acquires
+        drop(first_read);
+        drop(reads);
+        timeout(ACQUIRE_TIMEOUT, writer).await.unwrap().unwrap();
+        assert_eq!(1, *timeout(ACQUIRE_TIMEOUT, l.read()).await.unwrap());
+    }
 }
@@ -65,7 +65,7 @@ pub(crate) fn get_module_path() -> &'static str {
 /// A low-level non-fair semaphore. The semaphore is non-fair in the sense that clients acquiring
 /// a lower number of permits might get their allocation before earlier clients which requested more
 /// permits -- if the semaphore can provide the lower allocation but not the larger. This non-fairness
-/// is especially useful for implementing a strict readers-preferred reader-writer lock. See [`RfRwLock`].
+/// is especially useful for implementing a strict readers-preferred reader-writer lock. See [`RfRwLock`](super::rwlock::RfRwLock).
 /// Additionally it is possible that a new client immediately acquires if it happens to arrive right after
 /// a release and before others were awaked. Otherwise the semaphore is usually fair in the sense that
 /// waiters are awaked in the order they arrived at.
@@ -73,6 +73,9 @@ pub(crate) fn get_module_path() -> &'static str {
 pub(crate) struct Semaphore {
     counter: AtomicUsize,
     signal: Event,
+    /// The configured capacity of the semaphore, used for asserting against permit over-release
+    #[cfg(debug_assertions)]
+    max_permits: usize,
     #[cfg(feature = "semaphore-trace")]
     trace_inner: TraceInner,
 }
@@ -86,12 +89,16 @@ impl Semaphore {
                 Semaphore {
                     counter: AtomicUsize::new(available_permits),
                     signal: Event::new(),
+                    #[cfg(debug_assertions)]
+                    max_permits: available_permits,
                     trace_inner: Default::default(),
                 }
             } else {
                 Semaphore {
                     counter: AtomicUsize::new(available_permits),
                     signal: Event::new(),
+                    #[cfg(debug_assertions)]
+                    max_permits: available_permits,
                 }
             }
         }
@@ -153,8 +160,25 @@ impl Semaphore {
 
     /// Releases a number of `permits` previously acquired by a call to [`acquire`] or [`acquire_blocking`].
     /// Returns the released slot
+    ///
+    /// In debug builds, panics if the release exceeds the configured capacity of the semaphore (i.e., if
+    /// more permits are released than were previously acquired). The counter is left untouched in such a case
     pub fn release(&self, permits: usize) -> usize {
-        let slot = self.counter.fetch_add(permits, Ordering::AcqRel) + permits;
+        #[cfg(debug_assertions)]
+        let prev = self
+            .counter
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |prev| {
+                prev.checked_add(permits).filter(|&slot| slot <= self.max_permits)
+            })
+            .unwrap_or_else(|prev| {
+                panic!(
+                    "semaphore over-release: releasing {} permits with {} available exceeds the maximum of {} permits",
+                    permits, prev, self.max_permits
+                )
+            });
+        #[cfg(not(debug_assertions))]
+        let prev = self.counter.fetch_add(permits, Ordering::AcqRel);
+        let slot = prev.wrapping_add(permits);
 
         #[cfg(feature = "semaphore-trace")]
         if permits == 1 && slot == Self::MAX_PERMITS {
@@ -183,3 +207,51 @@ impl Semaphore {
         self.blocking_acquire(permits)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balanced_release() {
+        let sem = Semaphore::new(3);
+        assert_eq!(Some(3), sem.try_acquire(2));
+        assert_eq!(None, sem.try_acquire(2));
+        assert_eq!(3, sem.release(2));
+        assert_eq!(Some(3), sem.try_acquire(3));
+        assert_eq!(3, sem.release(3));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "semaphore over-release")]
+    fn test_over_release_panics() {
+        let sem = Semaphore::new(3);
+        sem.try_acquire(1).unwrap();
+        sem.release(1);
+        // Double release
+        sem.release(1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "semaphore over-release")]
+    fn test_max_permits_over_release_panics() {
+        let sem = Semaphore::new(Semaphore::MAX_PERMITS);
+        sem.try_acquire(Semaphore::MAX_PERMITS).unwrap();
+        sem.release(Semaphore::MAX_PERMITS);
+        // Releasing beyond usize::MAX must not silently wrap around
+        sem.release(1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_over_release_keeps_counter() {
+        let sem = Semaphore::new(3);
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| sem.release(1)));
+        assert!(res.is_err());
+        // The failed release must not have added permits
+        assert_eq!(Some(3), sem.try_acquire(3));
+        assert_eq!(None, sem.try_acquire(1));
+    }
+}